@@ -129,6 +129,14 @@ client_request_definitions! {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
     },
+    ThreadFork => "thread/fork" {
+        params: v2::ThreadForkParams,
+        response: v2::ThreadForkResponse,
+    },
+    ThreadRollback => "thread/rollback" {
+        params: v2::ThreadRollbackParams,
+        response: v2::ThreadRollbackResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -137,6 +145,10 @@ client_request_definitions! {
         params: v2::TurnInterruptParams,
         response: v2::TurnInterruptResponse,
     },
+    TurnUndo => "turn/undo" {
+        params: v2::TurnUndoParams,
+        response: v2::TurnUndoResponse,
+    },
 
     ModelList => "model/list" {
         params: v2::ModelListParams,
@@ -499,8 +511,12 @@ pub struct FuzzyFileSearchResponse {
 server_notification_definitions! {
     /// NEW NOTIFICATIONS
     ThreadStarted => "thread/started" (v2::ThreadStartedNotification),
+    ThreadForked => "thread/forked" (v2::ThreadForkedNotification),
+    ThreadRolledBack => "thread/rolledBack" (v2::ThreadRolledBackNotification),
     TurnStarted => "turn/started" (v2::TurnStartedNotification),
     TurnCompleted => "turn/completed" (v2::TurnCompletedNotification),
+    TurnUndone => "turn/undone" (v2::TurnUndoneNotification),
+    ThreadTokenUsageUpdated => "thread/tokenUsage/updated" (v2::ThreadTokenUsageUpdatedNotification),
     ItemStarted => "item/started" (v2::ItemStartedNotification),
     ItemCompleted => "item/completed" (v2::ItemCompletedNotification),
     AgentMessageDelta => "item/agentMessage/delta" (v2::AgentMessageDeltaNotification),
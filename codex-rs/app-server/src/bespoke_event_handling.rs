@@ -19,7 +19,9 @@ use codex_app_server_protocol::ReasoningTextDeltaNotification;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
 use codex_app_server_protocol::TurnInterruptResponse;
+use codex_app_server_protocol::TurnUndoneNotification;
 use codex_core::CodexConversation;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::Event;
@@ -148,6 +150,22 @@ pub(crate) async fn apply_bespoke_event_handling(
             });
         }
         EventMsg::TokenCount(token_count_event) => {
+            if let Some(info) = &token_count_event.info {
+                let usage = &info.total_token_usage;
+                let notification = ThreadTokenUsageUpdatedNotification {
+                    thread_id: conversation_id.to_string(),
+                    input_tokens: usage.input_tokens,
+                    cached_input_tokens: usage.cached_input_tokens,
+                    output_tokens: usage.output_tokens,
+                    turn_cost_usd: token_count_event.cost.map(|cost| cost.turn_usd),
+                    session_cost_usd: token_count_event.cost.map(|cost| cost.session_usd),
+                };
+                outgoing
+                    .send_server_notification(ServerNotification::ThreadTokenUsageUpdated(
+                        notification,
+                    ))
+                    .await;
+            }
             if let Some(rate_limits) = token_count_event.rate_limits {
                 outgoing
                     .send_server_notification(ServerNotification::AccountRateLimitsUpdated(
@@ -172,6 +190,16 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ItemCompleted(notification))
                 .await;
         }
+        EventMsg::UndoCompleted(undo_completed_event) => {
+            let notification = TurnUndoneNotification {
+                thread_id: conversation_id.to_string(),
+                success: undo_completed_event.success,
+                message: undo_completed_event.message,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::TurnUndone(notification))
+                .await;
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -427,6 +455,8 @@ mod tests {
             },
             duration: Duration::from_nanos(92708),
             result: Ok(result),
+            cached: false,
+            throttled: None,
         };
 
         let notification = construct_mcp_tool_call_end_notification(end_event.clone()).await;
@@ -460,6 +490,8 @@ mod tests {
             },
             duration: Duration::from_millis(1),
             result: Err("boom".to_string()),
+            cached: false,
+            throttled: None,
         };
 
         let notification = construct_mcp_tool_call_end_notification(end_event.clone()).await;
@@ -72,11 +72,17 @@ use codex_app_server_protocol::SetDefaultModelResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadForkParams;
+use codex_app_server_protocol::ThreadForkResponse;
+use codex_app_server_protocol::ThreadForkedNotification;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
+use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadRollbackResponse;
+use codex_app_server_protocol::ThreadRolledBackNotification;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
@@ -86,6 +92,8 @@ use codex_app_server_protocol::TurnStartParams;
 use codex_app_server_protocol::TurnStartResponse;
 use codex_app_server_protocol::TurnStartedNotification;
 use codex_app_server_protocol::TurnStatus;
+use codex_app_server_protocol::TurnUndoParams;
+use codex_app_server_protocol::TurnUndoResponse;
 use codex_app_server_protocol::UserInfoResponse;
 use codex_app_server_protocol::UserInput as V2UserInput;
 use codex_app_server_protocol::UserSavedConfig;
@@ -106,6 +114,7 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
 use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::config_loader::load_config_as_toml;
+use codex_core::count_user_turns;
 use codex_core::default_client::get_codex_user_agent;
 use codex_core::exec::ExecParams;
 use codex_core::exec_env::create_env;
@@ -116,6 +125,7 @@ use codex_core::parse_cursor;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::read_head_for_summary;
+use codex_core::resolve_rollout_path;
 use codex_feedback::CodexFeedback;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
@@ -257,12 +267,21 @@ impl CodexMessageProcessor {
                 self.send_unimplemented_error(request_id, "thread/compact")
                     .await;
             }
+            ClientRequest::ThreadFork { request_id, params } => {
+                self.thread_fork(request_id, params).await;
+            }
+            ClientRequest::ThreadRollback { request_id, params } => {
+                self.thread_rollback(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
             ClientRequest::TurnInterrupt { request_id, params } => {
                 self.turn_interrupt(request_id, params).await;
             }
+            ClientRequest::TurnUndo { request_id, params } => {
+                self.turn_undo(request_id, params).await;
+            }
             ClientRequest::NewConversation { request_id, params } => {
                 // Do not tokio::spawn() to process new_conversation()
                 // asynchronously because we need to ensure the conversation is
@@ -1566,6 +1585,239 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn thread_fork(&mut self, request_id: RequestId, params: ThreadForkParams) {
+        let ThreadForkParams {
+            thread_id,
+            turn_index,
+        } = params;
+
+        let (source_id, rollout_path, history) = match self.load_thread_history(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let keep_turns = turn_index.map(|index| index as usize);
+        match self
+            .spawn_forked_thread(&rollout_path, history, keep_turns)
+            .await
+        {
+            Ok(thread) => {
+                let response = ThreadForkResponse {
+                    thread: thread.clone(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+
+                let notif = ThreadForkedNotification {
+                    thread,
+                    source_thread_id: source_id.to_string(),
+                };
+                self.outgoing
+                    .send_server_notification(ServerNotification::ThreadForked(notif))
+                    .await;
+            }
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_rollback(&mut self, request_id: RequestId, params: ThreadRollbackParams) {
+        let ThreadRollbackParams {
+            thread_id,
+            num_turns,
+        } = params;
+
+        let (source_id, rollout_path, history) = match self.load_thread_history(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // Shutting the source down would cut a running turn short, and its
+        // rollout does not hold that turn yet.
+        if let Ok(conversation) = self.conversation_manager.get_conversation(source_id).await
+            && conversation.has_active_turn().await
+        {
+            self.send_invalid_request_error(
+                request_id,
+                format!(
+                    "cannot roll back thread {source_id} while a turn is running; interrupt it first"
+                ),
+            )
+            .await;
+            return;
+        }
+
+        let total_turns = count_user_turns(&history);
+        let num_turns_usize = num_turns as usize;
+        if num_turns_usize == 0 || num_turns_usize > total_turns {
+            self.send_invalid_request_error(
+                request_id,
+                format!(
+                    "numTurns must be between 1 and {total_turns} for thread {source_id}, got {num_turns}"
+                ),
+            )
+            .await;
+            return;
+        }
+
+        let keep_turns = total_turns - num_turns_usize;
+        let thread = match self
+            .spawn_forked_thread(&rollout_path, history, Some(keep_turns))
+            .await
+        {
+            Ok(thread) => thread,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // The rolled-back thread replaces the source, so stop the source
+        // session. Any listener attached to it exits once its event stream
+        // closes.
+        if let Some(conversation) = self
+            .conversation_manager
+            .remove_conversation(&source_id)
+            .await
+        {
+            let _ = conversation.submit(Op::Shutdown).await;
+        }
+
+        let response = ThreadRollbackResponse {
+            thread: thread.clone(),
+        };
+        self.outgoing.send_response(request_id, response).await;
+
+        let notif = ThreadRolledBackNotification {
+            thread,
+            source_thread_id: source_id.to_string(),
+            num_turns,
+        };
+        self.outgoing
+            .send_server_notification(ServerNotification::ThreadRolledBack(notif))
+            .await;
+    }
+
+    /// Resolves a v2 thread id to its rollout file and recorded history.
+    async fn load_thread_history(
+        &self,
+        thread_id: &str,
+    ) -> Result<(ConversationId, PathBuf, InitialHistory), JSONRPCErrorError> {
+        let conversation_id =
+            ConversationId::from_string(thread_id).map_err(|err| JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("invalid thread id: {err}"),
+                data: None,
+            })?;
+
+        let rollout_path = match find_conversation_path_by_id_str(
+            &self.config.codex_home,
+            &conversation_id.to_string(),
+        )
+        .await
+        {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                return Err(JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("no rollout found for conversation id {conversation_id}"),
+                    data: None,
+                });
+            }
+            Err(err) => {
+                return Err(JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("failed to locate conversation id {conversation_id}: {err}"),
+                    data: None,
+                });
+            }
+        };
+
+        let history = RolloutRecorder::get_rollout_history(&rollout_path)
+            .await
+            .map_err(|err| JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("failed to load rollout `{}`: {err}", rollout_path.display()),
+                data: None,
+            })?;
+
+        Ok((conversation_id, rollout_path, history))
+    }
+
+    /// Starts a new thread holding the first `keep_turns` turns of `history`
+    /// (all of them when `None`) and attaches a listener to it.
+    async fn spawn_forked_thread(
+        &mut self,
+        rollout_path: &Path,
+        history: InitialHistory,
+        keep_turns: Option<usize>,
+    ) -> Result<Thread, JSONRPCErrorError> {
+        let config = self.config.as_ref().clone();
+        let fallback_model_provider = config.model_provider_id.clone();
+
+        let new_conv = match keep_turns {
+            // `fork_conversation` yields an empty history once the cut point
+            // is past the last turn, so copy the full history instead.
+            Some(keep_turns) if keep_turns < count_user_turns(&history) => {
+                self.conversation_manager
+                    .fork_conversation(keep_turns, config, rollout_path.to_path_buf())
+                    .await
+            }
+            _ => {
+                let items = history.get_rollout_items();
+                let history = if items.is_empty() {
+                    InitialHistory::New
+                } else {
+                    InitialHistory::Forked(items)
+                };
+                self.conversation_manager
+                    .resume_conversation_with_history(config, history, self.auth_manager.clone())
+                    .await
+            }
+        };
+        let NewConversation {
+            conversation_id,
+            session_configured,
+            ..
+        } = new_conv.map_err(|err| JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!("error forking thread: {err}"),
+            data: None,
+        })?;
+
+        if let Err(err) = self
+            .attach_conversation_listener(conversation_id, false)
+            .await
+        {
+            tracing::warn!(
+                "failed to attach listener for conversation {}: {}",
+                conversation_id,
+                err.message
+            );
+        }
+
+        let summary = read_summary_from_rollout(
+            session_configured.rollout_path.as_path(),
+            fallback_model_provider.as_str(),
+        )
+        .await
+        .map_err(|err| JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!(
+                "failed to load rollout `{}` for conversation {conversation_id}: {err}",
+                session_configured.rollout_path.display()
+            ),
+            data: None,
+        })?;
+        Ok(summary_to_thread(summary))
+    }
+
     async fn get_conversation_summary(
         &self,
         request_id: RequestId,
@@ -2020,7 +2272,8 @@ impl CodexMessageProcessor {
                 });
             }
         };
-        let canonical_rollout_path = tokio::fs::canonicalize(rollout_path).await;
+        let canonical_rollout_path =
+            tokio::fs::canonicalize(resolve_rollout_path(rollout_path).await).await;
         let canonical_rollout_path = if let Ok(path) = canonical_rollout_path
             && path.starts_with(&canonical_sessions_dir)
         {
@@ -2048,8 +2301,10 @@ impl CodexMessageProcessor {
                 data: None,
             });
         };
-        if !file_name
-            .to_string_lossy()
+        let name = file_name.to_string_lossy();
+        if !name
+            .strip_suffix(".zst")
+            .unwrap_or(&name)
             .ends_with(required_suffix.as_str())
         {
             return Err(JSONRPCErrorError {
@@ -2125,7 +2380,10 @@ impl CodexMessageProcessor {
                 .codex_home
                 .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
             tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
+            // Shutting the conversation down may have compressed the rollout.
+            let source = resolve_rollout_path(&canonical_rollout_path).await;
+            let file_name = source.file_name().unwrap_or(file_name.as_os_str());
+            tokio::fs::rename(&source, &archive_folder.join(file_name)).await?;
             Ok(())
         }
         .await;
@@ -2169,6 +2427,7 @@ impl CodexMessageProcessor {
         let _ = conversation
             .submit(Op::UserInput {
                 items: mapped_items,
+                final_output_json_schema: None,
             })
             .await;
 
@@ -2263,6 +2522,31 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
+    async fn turn_undo(&self, request_id: RequestId, params: TurnUndoParams) {
+        let TurnUndoParams { thread_id } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // The outcome is reported via `turn/undone` once UndoCompleted arrives.
+        match conversation.submit(Op::Undo).await {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, TurnUndoResponse {})
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to undo turn: {err}"))
+                    .await;
+            }
+        }
+    }
+
     async fn turn_start(&self, request_id: RequestId, params: TurnStartParams) {
         let (_, conversation) = match self.conversation_from_thread_id(&params.thread_id).await {
             Ok(v) => v,
@@ -2307,6 +2591,7 @@ impl CodexMessageProcessor {
         let turn_id = conversation
             .submit(Op::UserInput {
                 items: mapped_items,
+                final_output_json_schema: params.output_schema,
             })
             .await;
 
@@ -2,6 +2,7 @@ mod account;
 mod model_list;
 mod rate_limits;
 mod thread_archive;
+mod thread_fork;
 mod thread_list;
 mod thread_resume;
 mod thread_start;
@@ -27,10 +27,14 @@ use std::path::PathBuf;
 use supports_color::Stream;
 
 mod mcp_cmd;
+mod queue_cmd;
+mod sessions_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
 use crate::mcp_cmd::McpCli;
+use crate::queue_cmd::QueueCli;
+use crate::sessions_cmd::SessionsCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -81,7 +85,7 @@ enum Subcommand {
     Mcp(McpCli),
 
     /// [experimental] Run the Codex MCP server (stdio transport).
-    McpServer,
+    McpServer(McpServerCommand),
 
     /// [experimental] Run the app server or related tooling.
     AppServer(AppServerCommand),
@@ -100,6 +104,12 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// List, search, show, export and prune recorded sessions.
+    Sessions(SessionsCli),
+
+    /// Queue prompts and run them unattended, optionally in parallel git worktrees.
+    Queue(QueueCli),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -116,6 +126,15 @@ enum Subcommand {
     Features(FeaturesCli),
 }
 
+#[derive(Debug, Parser)]
+struct McpServerCommand {
+    /// Serve Codex's built-in tools (shell, apply_patch, read_file,
+    /// grep_files, list_dir) directly instead of the `codex` agent tools.
+    /// Calls honor the configured sandbox and approval policies.
+    #[arg(long = "tools-only", default_value_t = false)]
+    tools_only: bool,
+}
+
 #[derive(Debug, Parser)]
 struct CompletionCommand {
     /// Shell to generate completions for
@@ -423,14 +442,34 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
         }
-        Some(Subcommand::McpServer) => {
-            codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
+        Some(Subcommand::McpServer(mcp_server_cli)) => {
+            let mode = if mcp_server_cli.tools_only {
+                codex_mcp_server::McpServerMode::ToolsOnly
+            } else {
+                codex_mcp_server::McpServerMode::Codex
+            };
+            codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides, mode)
+                .await?;
         }
         Some(Subcommand::Mcp(mut mcp_cli)) => {
             // Propagate any root-level config overrides (e.g. `-c key=value`).
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Queue(mut queue_cli)) => {
+            prepend_config_flags(
+                &mut queue_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            queue_cli.run(codex_linux_sandbox_exe).await?;
+        }
         Some(Subcommand::AppServer(app_server_cli)) => match app_server_cli.subcommand {
             None => {
                 codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
@@ -666,6 +705,9 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
     if !resume_cli.add_dir.is_empty() {
         interactive.add_dir.extend(resume_cli.add_dir);
     }
+    if let Some(output_schema) = resume_cli.output_schema {
+        interactive.output_schema = Some(output_schema);
+    }
     if let Some(prompt) = resume_cli.prompt {
         interactive.prompt = Some(prompt);
     }
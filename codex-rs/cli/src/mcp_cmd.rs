@@ -261,6 +261,10 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
+        sandbox: None,
+        env_policy: None,
+        cache: None,
+        rate_limit: None,
     };
 
     servers.insert(name.clone(), new_entry);
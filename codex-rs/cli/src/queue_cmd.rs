@@ -0,0 +1,430 @@
+use std::io::IsTerminal;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveTime;
+use chrono::Utc;
+use codex_common::CliConfigOverrides;
+use codex_common::SandboxModeCliArg;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::job_queue::JobQueue;
+use codex_core::job_queue::JobStatus;
+use codex_core::job_queue::NewJob;
+use codex_core::job_queue::QueueRunOptions;
+use codex_core::job_queue::QueuedJob;
+use codex_core::job_queue::run_queue;
+use codex_core::protocol::SessionSource;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_RETRY_DELAY_SECS: u64 = 30;
+const PROMPT_PREVIEW_CHARS: usize = 60;
+
+/// Queue prompts and run them unattended.
+///
+/// Subcommands:
+/// - `add`    — enqueue a prompt with its cwd, profile and sandbox settings
+/// - `list`   — show queued, running and finished jobs (with `--json`)
+/// - `run`    — execute queued jobs, optionally several at a time
+/// - `remove` — delete a job and its git worktree
+#[derive(Debug, clap::Parser)]
+pub struct QueueCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: QueueSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum QueueSubcommand {
+    /// Add a prompt to the queue.
+    Add(AddArgs),
+
+    /// List jobs in the queue, oldest first.
+    List(ListArgs),
+
+    /// Run queued jobs until none is left.
+    Run(RunArgs),
+
+    /// Remove a job from the queue together with its git worktree.
+    #[clap(visible_alias = "rm")]
+    Remove(RemoveArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct AddArgs {
+    /// Prompt to run. If omitted or `-`, the prompt is read from stdin.
+    #[arg(value_name = "PROMPT")]
+    pub prompt: Option<String>,
+
+    /// Directory the job runs in. Defaults to the current directory.
+    #[arg(long, short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Configuration profile from config.toml to run the job with.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Model the job should use.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Sandbox policy for commands the job runs.
+    #[arg(long = "sandbox", short = 's', value_enum)]
+    pub sandbox_mode: Option<SandboxModeCliArg>,
+
+    /// Attempts before the job is marked failed.
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_ATTEMPTS)]
+    pub max_attempts: u32,
+
+    /// Do not start the job before TIME: an RFC 3339 timestamp, or HH:MM for
+    /// the next time the local clock shows it.
+    #[arg(long = "at", value_name = "TIME", value_parser = parse_run_after)]
+    pub run_after: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Output the jobs as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RunArgs {
+    /// Number of jobs to run at the same time.
+    #[arg(long, short = 'j', value_name = "N", default_value_t = 1)]
+    pub parallel: usize,
+
+    /// Run jobs directly in their directory instead of in a separate git
+    /// worktree per job.
+    #[arg(long)]
+    pub no_worktrees: bool,
+
+    /// Append finished jobs to FILE as JSON Lines. Defaults to
+    /// `CODEX_HOME/queue/summary.jsonl`.
+    #[arg(long, value_name = "FILE")]
+    pub summary: Option<PathBuf>,
+
+    /// Seconds to wait before retrying a failed job; doubled on every
+    /// further attempt.
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_RETRY_DELAY_SECS)]
+    pub retry_delay: u64,
+
+    /// Stop once no job is ready instead of waiting for scheduled jobs and
+    /// retries.
+    #[arg(long)]
+    pub no_wait: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RemoveArgs {
+    /// Job id or a unique prefix of it.
+    #[arg(value_name = "ID")]
+    pub id: String,
+
+    /// Keep the job's git worktree on disk.
+    #[arg(long, conflicts_with = "force")]
+    pub keep_worktree: bool,
+
+    /// Remove the job's git worktree even if it has uncommitted changes.
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl QueueCli {
+    pub async fn run(self, codex_linux_sandbox_exe: Option<PathBuf>) -> Result<()> {
+        let QueueCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides.clone(), ConfigOverrides::default())
+            .await
+            .context("failed to load configuration")?;
+        let queue = JobQueue::new(&config.codex_home);
+
+        match subcommand {
+            QueueSubcommand::Add(args) => run_add(&queue, args)?,
+            QueueSubcommand::List(args) => run_list(&queue, args)?,
+            QueueSubcommand::Run(args) => {
+                let options = QueueRunOptions {
+                    parallel: args.parallel,
+                    use_worktrees: !args.no_worktrees,
+                    summary_path: args.summary.unwrap_or_else(|| queue.summary_path()),
+                    retry_base_delay: Duration::from_secs(args.retry_delay),
+                    wait_for_scheduled: !args.no_wait,
+                    cli_overrides: overrides,
+                    codex_linux_sandbox_exe,
+                };
+                run_worker(&config, &queue, options).await?;
+            }
+            QueueSubcommand::Remove(args) => run_remove(&queue, args)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn run_add(queue: &JobQueue, args: AddArgs) -> Result<()> {
+    let AddArgs {
+        prompt,
+        cwd,
+        config_profile,
+        model,
+        sandbox_mode,
+        max_attempts,
+        run_after,
+    } = args;
+    let prompt = match prompt.filter(|prompt| prompt != "-") {
+        Some(prompt) => prompt,
+        None => read_prompt_from_stdin()?,
+    };
+    let cwd = match cwd {
+        Some(cwd) => std::path::absolute(cwd).context("failed to resolve --cwd")?,
+        None => std::env::current_dir().context("failed to read the current directory")?,
+    };
+    if !cwd.is_dir() {
+        bail!("{} is not a directory", cwd.display());
+    }
+
+    let job = queue.add(NewJob {
+        prompt,
+        cwd,
+        profile: config_profile,
+        model,
+        sandbox_mode: sandbox_mode.map(Into::into),
+        max_attempts,
+        run_after,
+    })?;
+    match job.run_after {
+        Some(at) => println!(
+            "Queued job {} to run after {}.",
+            job.id,
+            at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ),
+        None => println!("Queued job {}.", job.id),
+    }
+    Ok(())
+}
+
+fn read_prompt_from_stdin() -> Result<String> {
+    if std::io::stdin().is_terminal() {
+        bail!("no prompt given; pass one as an argument or pipe it on stdin");
+    }
+    let mut prompt = String::new();
+    std::io::stdin()
+        .read_to_string(&mut prompt)
+        .context("failed to read the prompt from stdin")?;
+    Ok(prompt)
+}
+
+fn run_list(queue: &JobQueue, args: ListArgs) -> Result<()> {
+    let jobs = queue.list()?;
+
+    if args.json {
+        let output = serde_json::to_string_pretty(&jobs)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if jobs.is_empty() {
+        println!("The queue is empty.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 5]> = jobs
+        .iter()
+        .map(|job| {
+            [
+                short_id(&job.id).to_string(),
+                job.status.to_string(),
+                format!("{}/{}", job.attempts, job.max_attempts),
+                job.cwd.display().to_string(),
+                prompt_preview(&job.prompt),
+            ]
+        })
+        .collect();
+    let mut widths = ["Id".len(), "Status".len(), "Tries".len(), "Cwd".len()];
+    for row in &rows {
+        for (i, cell) in row.iter().take(widths.len()).enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    println!(
+        "{id:<id_w$}  {status:<status_w$}  {tries:<tries_w$}  {cwd:<cwd_w$}  {prompt}",
+        id = "Id",
+        status = "Status",
+        tries = "Tries",
+        cwd = "Cwd",
+        prompt = "Prompt",
+        id_w = widths[0],
+        status_w = widths[1],
+        tries_w = widths[2],
+        cwd_w = widths[3],
+    );
+    for row in &rows {
+        println!(
+            "{id:<id_w$}  {status:<status_w$}  {tries:<tries_w$}  {cwd:<cwd_w$}  {prompt}",
+            id = row[0].as_str(),
+            status = row[1].as_str(),
+            tries = row[2].as_str(),
+            cwd = row[3].as_str(),
+            prompt = row[4].as_str(),
+            id_w = widths[0],
+            status_w = widths[1],
+            tries_w = widths[2],
+            cwd_w = widths[3],
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_worker(config: &Config, queue: &JobQueue, options: QueueRunOptions) -> Result<()> {
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        true,
+        config.cli_auth_credentials_store_mode,
+    );
+    let conversation_manager =
+        Arc::new(ConversationManager::new(auth_manager, SessionSource::Exec));
+
+    let stats = run_queue(queue, conversation_manager, &options, print_job_update).await?;
+    println!(
+        "Finished: {} succeeded, {} failed, {} retried. Summary: {}",
+        stats.succeeded,
+        stats.failed,
+        stats.retried,
+        options.summary_path.display()
+    );
+    if stats.failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_job_update(job: &QueuedJob) {
+    let id = short_id(&job.id);
+    match job.status {
+        JobStatus::Running => println!(
+            "[{id}] attempt {}/{}: {}",
+            job.attempts,
+            job.max_attempts,
+            prompt_preview(&job.prompt)
+        ),
+        JobStatus::Succeeded => {
+            match &job.rollout_path {
+                Some(rollout_path) => println!("[{id}] succeeded ({})", rollout_path.display()),
+                None => println!("[{id}] succeeded"),
+            }
+            if let Some(result_ref) = &job.result_ref {
+                println!("[{id}] commits kept at {result_ref}");
+            }
+        }
+        JobStatus::Pending => {
+            let error = job.last_error.as_deref().unwrap_or("unknown error");
+            match job.run_after {
+                Some(at) => println!(
+                    "[{id}] failed: {error}; retrying at {}",
+                    at.with_timezone(&Local).format("%H:%M:%S")
+                ),
+                None => println!("[{id}] failed: {error}; retrying"),
+            }
+        }
+        JobStatus::Failed => {
+            let error = job.last_error.as_deref().unwrap_or("unknown error");
+            println!(
+                "[{id}] failed after {} attempt{}: {error}",
+                job.attempts,
+                if job.attempts == 1 { "" } else { "s" }
+            );
+        }
+    }
+}
+
+fn run_remove(queue: &JobQueue, args: RemoveArgs) -> Result<()> {
+    let RemoveArgs {
+        id,
+        keep_worktree,
+        force,
+    } = args;
+    // A live worker could start the job, or save it again, while it is removed.
+    let Some(_worker) = queue.try_lock_worker()? else {
+        bail!("a worker is running the queue; stop it before removing jobs");
+    };
+    let Some(job) = queue.find(&id)? else {
+        bail!("no queued job matches `{id}`");
+    };
+
+    let worktree_root = queue.worktree_path(&job.id);
+    if !keep_worktree
+        && worktree_root.exists()
+        && let Err(err) = codex_git::remove_worktree(&worktree_root, force)
+    {
+        bail!(
+            "failed to remove the worktree at {}: {err}\n\
+             Pass --force to discard its changes or --keep-worktree to keep it.",
+            worktree_root.display()
+        );
+    }
+    queue.remove(&job.id)?;
+    println!("Removed job {}.", job.id);
+    Ok(())
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+fn prompt_preview(prompt: &str) -> String {
+    let line = prompt
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let line = line.trim();
+    if line.chars().count() > PROMPT_PREVIEW_CHARS {
+        let truncated: String = line.chars().take(PROMPT_PREVIEW_CHARS - 1).collect();
+        format!("{truncated}…")
+    } else {
+        line.to_string()
+    }
+}
+
+fn parse_run_after(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    let time = NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| {
+        format!("expected an RFC 3339 timestamp or a local time as HH:MM, got '{value}'")
+    })?;
+    let now = Local::now();
+    let today = now
+        .date_naive()
+        .and_time(time)
+        .and_local_timezone(Local)
+        .earliest();
+    let at = match today {
+        Some(at) if at > now => Some(at),
+        _ => now
+            .date_naive()
+            .succ_opt()
+            .and_then(|tomorrow| tomorrow.and_time(time).and_local_timezone(Local).earliest()),
+    };
+    at.map(|at| at.with_timezone(&Utc))
+        .ok_or_else(|| format!("{value} does not exist in the local time zone"))
+}
@@ -0,0 +1,857 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use clap::ArgGroup;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::ARCHIVED_SESSIONS_SUBDIR;
+use codex_core::RolloutRecorder;
+use codex_core::SESSION_BUNDLE_EXTENSION;
+use codex_core::SessionBundleImportOptions;
+use codex_core::SessionTranscript;
+use codex_core::TranscriptRole;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::export_session_bundle;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::import_session_bundle;
+use codex_core::list_rollout_paths;
+use codex_core::resolve_rollout_path;
+use regex_lite::RegexBuilder;
+use serde_json::json;
+
+const LIST_PAGE_SIZE: usize = 100;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Search, inspect, export and prune recorded sessions.
+///
+/// Subcommands:
+/// - `list`   — list sessions, newest first (with `--json`)
+/// - `grep`   — search user and agent messages
+/// - `show`   — print a session transcript
+/// - `export` — write a transcript as Markdown, HTML or JSON
+/// - `prune`  — delete or archive old sessions
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// List recorded sessions, newest first.
+    List(ListArgs),
+
+    /// Search the user and agent messages of recorded sessions.
+    Grep(GrepArgs),
+
+    /// Print the transcript of a session.
+    Show(ShowArgs),
+
+    /// Export the transcript of a session, or a bundle that can be imported
+    /// on another machine.
+    Export(ExportArgs),
+
+    /// Import a session bundle so it can be resumed with `codex resume`.
+    Import(ImportArgs),
+
+    /// Delete or archive sessions according to retention rules.
+    Prune(PruneArgs),
+
+    /// Rebuild the session index used to list sessions.
+    Reindex(ReindexArgs),
+}
+
+/// Filters shared by the subcommands that scan every session.
+#[derive(Debug, Default, clap::Args)]
+pub struct SessionFilterArgs {
+    /// Only sessions started in DIR or one of its subdirectories.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Only sessions started on or after DATE (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub since: Option<String>,
+
+    /// Only sessions started on or before DATE (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub until: Option<String>,
+
+    /// Only sessions with at least one turn on MODEL.
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<String>,
+
+    /// Only sessions recorded with this model provider id.
+    #[arg(long, value_name = "PROVIDER")]
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub filters: SessionFilterArgs,
+
+    /// Show at most N sessions.
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Output the sessions as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct GrepArgs {
+    /// Regular expression to search for.
+    pub pattern: String,
+
+    /// Match case-insensitively.
+    #[arg(short = 'i', long)]
+    pub ignore_case: bool,
+
+    #[command(flatten)]
+    pub filters: SessionFilterArgs,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Session id or path to a rollout file.
+    pub session: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExportArgs {
+    /// Session id or path to a rollout file.
+    pub session: String,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Markdown)]
+    pub format: ExportFormat,
+
+    /// Write the export to FILE instead of stdout.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Write a portable bundle with the rollout, its ghost snapshots and
+    /// referenced images instead of a transcript. Defaults to
+    /// `<SESSION_ID>.codex-bundle` in the current directory.
+    #[arg(long, conflicts_with = "format")]
+    pub bundle: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ImportArgs {
+    /// Bundle written by `codex sessions export --bundle`.
+    #[arg(value_name = "BUNDLE")]
+    pub bundle: PathBuf,
+
+    /// Checkout to use in place of the session's original working directory.
+    /// Defaults to the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Import without restoring the session's ghost snapshots, for example
+    /// when the commits they were taken on are not in this checkout.
+    #[arg(long)]
+    pub skip_snapshots: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    /// A standalone HTML page.
+    Html,
+    /// Session metadata and messages as a single JSON document.
+    Json,
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(
+    group(
+        ArgGroup::new("retention")
+            .args(["older_than", "keep_last"])
+            .required(true)
+            .multiple(true)
+    )
+)]
+pub struct PruneArgs {
+    /// Prune sessions whose rollout file was not written to in DAYS days.
+    #[arg(long, value_name = "DAYS")]
+    pub older_than: Option<u64>,
+
+    /// Never prune the N most recent sessions.
+    #[arg(long, value_name = "N")]
+    pub keep_last: Option<usize>,
+
+    /// Move pruned sessions to the archived sessions directory instead of
+    /// deleting them.
+    #[arg(long)]
+    pub archive: bool,
+
+    /// Print the sessions that would be pruned without touching them.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub filters: SessionFilterArgs,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ReindexArgs {
+    /// Also compress rollouts with zstd once they have not been written to
+    /// for a day.
+    #[arg(long)]
+    pub compress: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+            .await
+            .context("failed to load configuration")?;
+
+        match subcommand {
+            SessionsSubcommand::List(args) => run_list(&config, args).await?,
+            SessionsSubcommand::Grep(args) => run_grep(&config, args).await?,
+            SessionsSubcommand::Show(args) => run_show(&config, args).await?,
+            SessionsSubcommand::Export(args) => run_export(&config, args).await?,
+            SessionsSubcommand::Import(args) => run_import(&config, args).await?,
+            SessionsSubcommand::Prune(args) => run_prune(&config, args).await?,
+            SessionsSubcommand::Reindex(args) => run_reindex(&config, args).await?,
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_list(config: &Config, args: ListArgs) -> Result<()> {
+    let ListArgs {
+        filters,
+        limit,
+        json,
+    } = args;
+    let sessions = load_sessions(config, &filters, limit, SessionDetail::Head).await?;
+
+    if json {
+        let entries: Vec<_> = sessions
+            .iter()
+            .map(|session| {
+                json!({
+                    "id": session.meta.id,
+                    "path": session.path,
+                    "timestamp": session.meta.timestamp,
+                    "cwd": session.meta.cwd,
+                    "models": session.models,
+                    "model_providers": session.model_providers,
+                    "title": session.title(),
+                })
+            })
+            .collect();
+        let output = serde_json::to_string_pretty(&entries)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if sessions.is_empty() {
+        println!("No sessions found.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 5]> = sessions
+        .iter()
+        .map(|session| {
+            [
+                session.meta.id.to_string(),
+                started_display(session),
+                join_or_dash(&session.models),
+                session.meta.cwd.display().to_string(),
+                session.title().unwrap_or("-").to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = ["Id".len(), "Started".len(), "Model".len(), "Cwd".len()];
+    for row in &rows {
+        for (i, cell) in row.iter().take(widths.len()).enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    println!(
+        "{id:<id_w$}  {started:<started_w$}  {model:<model_w$}  {cwd:<cwd_w$}  {title}",
+        id = "Id",
+        started = "Started",
+        model = "Model",
+        cwd = "Cwd",
+        title = "Title",
+        id_w = widths[0],
+        started_w = widths[1],
+        model_w = widths[2],
+        cwd_w = widths[3],
+    );
+    for row in &rows {
+        println!(
+            "{id:<id_w$}  {started:<started_w$}  {model:<model_w$}  {cwd:<cwd_w$}  {title}",
+            id = row[0].as_str(),
+            started = row[1].as_str(),
+            model = row[2].as_str(),
+            cwd = row[3].as_str(),
+            title = row[4].as_str(),
+            id_w = widths[0],
+            started_w = widths[1],
+            model_w = widths[2],
+            cwd_w = widths[3],
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_grep(config: &Config, args: GrepArgs) -> Result<()> {
+    let GrepArgs {
+        pattern,
+        ignore_case,
+        filters,
+    } = args;
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .with_context(|| format!("invalid pattern '{pattern}'"))?;
+
+    let sessions = load_sessions(config, &filters, None, SessionDetail::Full).await?;
+    let mut matches = 0usize;
+    for session in &sessions {
+        for message in &session.messages {
+            for line in message.text.lines().filter(|line| regex.is_match(line)) {
+                matches += 1;
+                println!(
+                    "{}  {}: {}",
+                    session.meta.id,
+                    role_label(message.role),
+                    line.trim()
+                );
+            }
+        }
+    }
+
+    if matches == 0 {
+        println!("No matches found.");
+    }
+
+    Ok(())
+}
+
+async fn run_show(config: &Config, args: ShowArgs) -> Result<()> {
+    let path = resolve_session(config, &args.session).await?;
+    let session = SessionTranscript::load(&path)
+        .await
+        .with_context(|| format!("failed to read session from {}", path.display()))?;
+    print!("{}", render_text(&session));
+    Ok(())
+}
+
+async fn run_export(config: &Config, args: ExportArgs) -> Result<()> {
+    let ExportArgs {
+        session,
+        format,
+        output,
+        bundle,
+    } = args;
+    let path = resolve_session(config, &session).await?;
+    let session = SessionTranscript::load(&path)
+        .await
+        .with_context(|| format!("failed to read session from {}", path.display()))?;
+
+    if bundle {
+        let output = output.unwrap_or_else(|| {
+            PathBuf::from(format!("{}.{SESSION_BUNDLE_EXTENSION}", session.meta.id))
+        });
+        let exported = export_session_bundle(&path, &output)
+            .await
+            .with_context(|| format!("failed to write {}", output.display()))?;
+        println!(
+            "Exported session {} to {} ({} snapshot{}, {} image{}).",
+            exported.session_id,
+            output.display(),
+            exported.ghost_commits,
+            if exported.ghost_commits == 1 { "" } else { "s" },
+            exported.images,
+            if exported.images == 1 { "" } else { "s" }
+        );
+        if exported.skipped_ghost_commits > 0 {
+            eprintln!(
+                "Skipped {} snapshot{} that could not be read from the session's repository.",
+                exported.skipped_ghost_commits,
+                if exported.skipped_ghost_commits == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            );
+        }
+        return Ok(());
+    }
+
+    let rendered = match format {
+        ExportFormat::Markdown => render_markdown(&session),
+        ExportFormat::Html => render_html(&session),
+        ExportFormat::Json => serde_json::to_string_pretty(&session)? + "\n",
+    };
+
+    match output {
+        Some(output) => {
+            tokio::fs::write(&output, rendered)
+                .await
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            println!(
+                "Exported session {} to {}.",
+                session.meta.id,
+                output.display()
+            );
+        }
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
+
+async fn run_import(config: &Config, args: ImportArgs) -> Result<()> {
+    let ImportArgs {
+        bundle,
+        cwd,
+        skip_snapshots,
+    } = args;
+    let cwd = match cwd {
+        Some(cwd) => std::path::absolute(cwd).context("failed to resolve --cwd")?,
+        None => std::env::current_dir().context("failed to read the current directory")?,
+    };
+    let imported = import_session_bundle(
+        &config.codex_home,
+        &bundle,
+        SessionBundleImportOptions {
+            cwd: cwd.clone(),
+            skip_snapshots,
+        },
+    )
+    .await
+    .with_context(|| format!("failed to import {}", bundle.display()))?;
+
+    println!(
+        "Imported session {} into {} ({} snapshot{}, {} image{}).",
+        imported.session_id,
+        cwd.display(),
+        imported.ghost_commits,
+        if imported.ghost_commits == 1 { "" } else { "s" },
+        imported.images,
+        if imported.images == 1 { "" } else { "s" }
+    );
+    println!("Resume it with: codex resume {}", imported.session_id);
+    Ok(())
+}
+
+async fn run_prune(config: &Config, args: PruneArgs) -> Result<()> {
+    let PruneArgs {
+        older_than,
+        keep_last,
+        archive,
+        dry_run,
+        filters,
+    } = args;
+
+    let sessions = load_prunable_sessions(config, &filters).await?;
+    let cutoff = older_than.map(|days| {
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)))
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    });
+
+    let mut pruned = Vec::new();
+    for session in sessions.iter().skip(keep_last.unwrap_or(0)) {
+        if let Some(cutoff) = cutoff {
+            let modified = tokio::fs::metadata(&session.path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .with_context(|| format!("failed to stat {}", session.path.display()))?;
+            if modified >= cutoff {
+                continue;
+            }
+        }
+        pruned.push(session);
+    }
+
+    if pruned.is_empty() {
+        println!("No sessions to prune.");
+        return Ok(());
+    }
+
+    let archive_dir = config.codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    let verb = match (dry_run, archive) {
+        (true, true) => "Would archive",
+        (true, false) => "Would delete",
+        (false, true) => "Archived",
+        (false, false) => "Deleted",
+    };
+    for session in &pruned {
+        if !dry_run {
+            prune_session(&session.path, archive.then_some(archive_dir.as_path())).await?;
+        }
+        println!("{verb} {} ({})", session.meta.id, session.path.display());
+    }
+    println!(
+        "{verb} {} session{}.",
+        pruned.len(),
+        if pruned.len() == 1 { "" } else { "s" }
+    );
+
+    Ok(())
+}
+
+async fn run_reindex(config: &Config, args: ReindexArgs) -> Result<()> {
+    let compress_idle_after = args.compress.then(|| Duration::from_secs(SECONDS_PER_DAY));
+    let stats = RolloutRecorder::rebuild_index(&config.codex_home, compress_idle_after)
+        .await
+        .context("failed to rebuild the session index")?;
+    println!(
+        "Indexed {} session{}.",
+        stats.sessions,
+        if stats.sessions == 1 { "" } else { "s" }
+    );
+    if args.compress {
+        println!(
+            "Compressed {} rollout{}.",
+            stats.compressed,
+            if stats.compressed == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+async fn prune_session(path: &Path, archive_dir: Option<&Path>) -> Result<()> {
+    match archive_dir {
+        Some(archive_dir) => {
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| anyhow!("invalid rollout path {}", path.display()))?;
+            tokio::fs::create_dir_all(archive_dir)
+                .await
+                .with_context(|| format!("failed to create {}", archive_dir.display()))?;
+            tokio::fs::rename(path, archive_dir.join(file_name))
+                .await
+                .with_context(|| format!("failed to archive {}", path.display()))?;
+        }
+        None => tokio::fs::remove_file(path)
+            .await
+            .with_context(|| format!("failed to delete {}", path.display()))?,
+    }
+    Ok(())
+}
+
+/// How much of each rollout [`load_sessions`] reads.
+#[derive(Debug, Clone, Copy)]
+enum SessionDetail {
+    /// Only the start of the file; see [`SessionTranscript::load_head`].
+    Head,
+    /// The whole transcript.
+    Full,
+}
+
+/// Reads every session with a user message matching `filters`, newest first.
+/// Files that fail to parse are reported on stderr and skipped.
+async fn load_sessions(
+    config: &Config,
+    filters: &SessionFilterArgs,
+    limit: Option<usize>,
+    detail: SessionDetail,
+) -> Result<Vec<SessionTranscript>> {
+    let cwd = resolve_cwd_filter(filters)?;
+    let providers = filters.provider.clone().map(|provider| vec![provider]);
+    let detail = filters.detail_needed(detail);
+
+    let mut sessions = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = RolloutRecorder::list_conversations(
+            &config.codex_home,
+            LIST_PAGE_SIZE,
+            cursor.as_ref(),
+            &[],
+            providers.as_deref(),
+            &config.model_provider_id,
+        )
+        .await
+        .context("failed to list sessions")?;
+
+        for item in page.items {
+            let Some(session) = load_session(&item.path, detail).await else {
+                continue;
+            };
+            if !matches_filters(&session, filters, cwd.as_deref()) {
+                continue;
+            }
+            sessions.push(session);
+            if limit.is_some_and(|limit| sessions.len() >= limit) {
+                return Ok(sessions);
+            }
+        }
+
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(sessions),
+        }
+    }
+}
+
+/// Reads the head of every rollout matching `filters`, newest first,
+/// including sessions that never got a user message.
+async fn load_prunable_sessions(
+    config: &Config,
+    filters: &SessionFilterArgs,
+) -> Result<Vec<SessionTranscript>> {
+    let cwd = resolve_cwd_filter(filters)?;
+    let paths = list_rollout_paths(&config.codex_home)
+        .await
+        .context("failed to list sessions")?;
+
+    let mut sessions = Vec::new();
+    for path in paths {
+        let Some(session) = load_session(&path, filters.detail_needed(SessionDetail::Head)).await
+        else {
+            continue;
+        };
+        if !matches_filters(&session, filters, cwd.as_deref())
+            || !matches_provider(&session, filters, &config.model_provider_id)
+        {
+            continue;
+        }
+        sessions.push(session);
+    }
+    Ok(sessions)
+}
+
+async fn load_session(path: &Path, detail: SessionDetail) -> Option<SessionTranscript> {
+    let result = match detail {
+        SessionDetail::Head => SessionTranscript::load_head(path).await,
+        SessionDetail::Full => SessionTranscript::load(path).await,
+    };
+    match result {
+        Ok(session) => Some(session),
+        Err(err) => {
+            eprintln!("Skipping {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+impl SessionFilterArgs {
+    /// `--model` matches any turn, so it needs the whole transcript.
+    fn detail_needed(&self, detail: SessionDetail) -> SessionDetail {
+        if self.model.is_some() {
+            SessionDetail::Full
+        } else {
+            detail
+        }
+    }
+}
+
+fn resolve_cwd_filter(filters: &SessionFilterArgs) -> Result<Option<PathBuf>> {
+    filters
+        .cwd
+        .as_deref()
+        .map(std::path::absolute)
+        .transpose()
+        .context("failed to resolve --cwd")
+}
+
+/// Rollouts without a provider were recorded with the default one, matching
+/// [`RolloutRecorder::list_conversations`].
+fn matches_provider(
+    session: &SessionTranscript,
+    filters: &SessionFilterArgs,
+    default_provider: &str,
+) -> bool {
+    let Some(provider) = filters.provider.as_deref() else {
+        return true;
+    };
+    if session.model_providers.is_empty() {
+        provider == default_provider
+    } else {
+        session.model_providers.iter().any(|used| used == provider)
+    }
+}
+
+fn matches_filters(
+    session: &SessionTranscript,
+    filters: &SessionFilterArgs,
+    cwd: Option<&Path>,
+) -> bool {
+    if let Some(cwd) = cwd
+        && !session.meta.cwd.starts_with(cwd)
+    {
+        return false;
+    }
+    if let Some(model) = &filters.model
+        && !session.models.iter().any(|used| used == model)
+    {
+        return false;
+    }
+    if filters.since.is_some() || filters.until.is_some() {
+        let Some(date) = session.meta.timestamp.get(..10) else {
+            return false;
+        };
+        if filters.since.as_deref().is_some_and(|since| date < since)
+            || filters.until.as_deref().is_some_and(|until| date > until)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Accepts either a session id or the path to a rollout file.
+async fn resolve_session(config: &Config, session: &str) -> Result<PathBuf> {
+    let path = resolve_rollout_path(Path::new(session)).await;
+    if path.is_file() {
+        return Ok(path);
+    }
+    find_conversation_path_by_id_str(&config.codex_home, session)
+        .await
+        .context("failed to search sessions")?
+        .ok_or_else(|| anyhow!("No session found with id or path '{session}'."))
+}
+
+fn parse_date(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let valid = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "expected a date in YYYY-MM-DD format, got '{value}'"
+        ))
+    }
+}
+
+fn started_display(session: &SessionTranscript) -> String {
+    // RFC 3339 timestamps; drop the fractional seconds for display.
+    let timestamp = session.meta.timestamp.as_str();
+    timestamp
+        .get(..19)
+        .map(|prefix| prefix.replace('T', " "))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn join_or_dash(values: &[String]) -> String {
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+fn role_label(role: TranscriptRole) -> &'static str {
+    match role {
+        TranscriptRole::User => "user",
+        TranscriptRole::Agent => "codex",
+    }
+}
+
+fn render_text(session: &SessionTranscript) -> String {
+    let mut out = format!(
+        "Session:   {}\nStarted:   {}\nCwd:       {}\nModel:     {}\nProvider:  {}\n",
+        session.meta.id,
+        started_display(session),
+        session.meta.cwd.display(),
+        join_or_dash(&session.models),
+        join_or_dash(&session.model_providers),
+    );
+    for message in &session.messages {
+        out.push_str(&format!(
+            "\n{}:\n{}\n",
+            role_label(message.role),
+            message.text.trim_end()
+        ));
+    }
+    out
+}
+
+fn render_markdown(session: &SessionTranscript) -> String {
+    let mut out = format!(
+        "# {}\n\n- Session: `{}`\n- Started: {}\n- Cwd: `{}`\n- Model: {}\n- Provider: {}\n",
+        session.title().unwrap_or("Codex session"),
+        session.meta.id,
+        started_display(session),
+        session.meta.cwd.display(),
+        join_or_dash(&session.models),
+        join_or_dash(&session.model_providers),
+    );
+    for message in &session.messages {
+        let heading = match message.role {
+            TranscriptRole::User => "User",
+            TranscriptRole::Agent => "Codex",
+        };
+        out.push_str(&format!("\n## {heading}\n\n{}\n", message.text.trim()));
+    }
+    out
+}
+
+fn render_html(session: &SessionTranscript) -> String {
+    let title = escape_html(session.title().unwrap_or("Codex session"));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>body{{font-family:sans-serif;max-width:50em;margin:2em auto}}\
+         .message{{white-space:pre-wrap;border-left:3px solid #ccc;padding-left:1em}}\
+         .user{{border-color:#3b82f6}}.agent{{border-color:#10b981}}</style>\n\
+         </head>\n<body>\n<h1>{title}</h1>\n<dl>\n\
+         <dt>Session</dt><dd><code>{}</code></dd>\n\
+         <dt>Started</dt><dd>{}</dd>\n\
+         <dt>Cwd</dt><dd><code>{}</code></dd>\n\
+         <dt>Model</dt><dd>{}</dd>\n\
+         <dt>Provider</dt><dd>{}</dd>\n</dl>\n",
+        session.meta.id,
+        escape_html(&started_display(session)),
+        escape_html(&session.meta.cwd.display().to_string()),
+        escape_html(&join_or_dash(&session.models)),
+        escape_html(&join_or_dash(&session.model_providers)),
+    );
+    for message in &session.messages {
+        let (class, heading) = match message.role {
+            TranscriptRole::User => ("user", "User"),
+            TranscriptRole::Agent => ("agent", "Codex"),
+        };
+        out.push_str(&format!(
+            "<h2>{heading}</h2>\n<div class=\"message {class}\">{}</div>\n",
+            escape_html(message.text.trim())
+        ));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn list_jobs(codex_home: &Path) -> Result<Vec<JsonValue>> {
+    let output = codex_command(codex_home)?
+        .args(["queue", "list", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let jobs: Vec<JsonValue> = serde_json::from_slice(&output.stdout)?;
+    Ok(jobs)
+}
+
+#[test]
+fn add_list_and_remove_jobs() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let workdir = TempDir::new()?;
+
+    codex_command(codex_home.path())?
+        .args(["queue", "add", "Fix the flaky login test", "--cwd"])
+        .arg(workdir.path())
+        .args(["--profile", "ci", "--sandbox", "workspace-write"])
+        .args(["--max-attempts", "5"])
+        .assert()
+        .success()
+        .stdout(contains("Queued job"));
+    codex_command(codex_home.path())?
+        .args(["queue", "add", "--at", "2030-01-01T09:00:00Z", "--cwd"])
+        .arg(workdir.path())
+        .write_stdin("Update the changelog\n")
+        .assert()
+        .success()
+        .stdout(contains("to run after"));
+
+    let jobs = list_jobs(codex_home.path())?;
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0]["prompt"], json!("Fix the flaky login test"));
+    assert_eq!(jobs[0]["profile"], json!("ci"));
+    assert_eq!(jobs[0]["sandbox_mode"], json!("workspace-write"));
+    assert_eq!(jobs[0]["max_attempts"], json!(5));
+    assert_eq!(jobs[0]["status"], json!("pending"));
+    assert_eq!(jobs[1]["prompt"], json!("Update the changelog\n"));
+    assert_eq!(jobs[1]["run_after"], json!("2030-01-01T09:00:00Z"));
+
+    codex_command(codex_home.path())?
+        .args(["queue", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Fix the flaky login test"))
+        .stdout(contains("0/5"));
+
+    let first_id = jobs[0]["id"].as_str().expect("job id").to_string();
+    codex_command(codex_home.path())?
+        .args(["queue", "remove", &first_id[..8]])
+        .assert()
+        .success()
+        .stdout(contains(format!("Removed job {first_id}.")));
+    let remaining = list_jobs(codex_home.path())?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0]["prompt"], json!("Update the changelog\n"));
+
+    codex_command(codex_home.path())?
+        .args(["queue", "remove", &first_id])
+        .assert()
+        .failure()
+        .stderr(contains("no queued job matches"));
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=Tester",
+            "-c",
+            "user.email=test@example.com",
+        ])
+        .args(args)
+        .status()?;
+    assert!(status.success(), "git {args:?} failed");
+    Ok(())
+}
+
+#[test]
+fn remove_keeps_a_dirty_worktree_unless_forced() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let repo = TempDir::new()?;
+    git(repo.path(), &["init", "--initial-branch=main"])?;
+    std::fs::write(repo.path().join("README.md"), "hello\n")?;
+    git(repo.path(), &["add", "."])?;
+    git(repo.path(), &["commit", "-m", "init"])?;
+
+    codex_command(codex_home.path())?
+        .args(["queue", "add", "Update the README", "--cwd"])
+        .arg(repo.path())
+        .assert()
+        .success();
+    let jobs = list_jobs(codex_home.path())?;
+    let id = jobs[0]["id"].as_str().expect("job id").to_string();
+    let worktree = codex_home.path().join("queue/worktrees").join(&id);
+    git(
+        repo.path(),
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            worktree.to_str().expect("utf-8 path"),
+        ],
+    )?;
+    std::fs::write(worktree.join("README.md"), "edited by the job\n")?;
+
+    // Stand in for a running `codex queue run`.
+    let lock = std::fs::File::create(codex_home.path().join("queue/worker.lock"))?;
+    lock.try_lock()?;
+    codex_command(codex_home.path())?
+        .args(["queue", "remove", &id])
+        .assert()
+        .failure()
+        .stderr(contains("a worker is running the queue"));
+    drop(lock);
+
+    codex_command(codex_home.path())?
+        .args(["queue", "remove", &id])
+        .assert()
+        .failure()
+        .stderr(contains("--force"));
+    assert!(worktree.exists());
+    assert_eq!(list_jobs(codex_home.path())?.len(), 1);
+
+    codex_command(codex_home.path())?
+        .args(["queue", "remove", &id, "--force"])
+        .assert()
+        .success()
+        .stdout(contains(format!("Removed job {id}.")));
+    assert!(!worktree.exists());
+    assert!(list_jobs(codex_home.path())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn add_rejects_an_empty_prompt() -> Result<()> {
+    let codex_home = TempDir::new()?;
+
+    codex_command(codex_home.path())?
+        .args(["queue", "add", "-"])
+        .write_stdin("   \n")
+        .assert()
+        .failure()
+        .stderr(contains("the prompt is empty"));
+    assert!(list_jobs(codex_home.path())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn run_with_an_empty_queue_finishes_immediately() -> Result<()> {
+    let codex_home = TempDir::new()?;
+
+    codex_command(codex_home.path())?
+        .args(["queue", "run", "--no-wait"])
+        .assert()
+        .success()
+        .stdout(contains("Finished: 0 succeeded, 0 failed, 0 retried."));
+    Ok(())
+}
@@ -0,0 +1,358 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_core::ARCHIVED_SESSIONS_SUBDIR;
+use codex_core::SESSION_INDEX_FILE;
+use codex_core::SESSIONS_SUBDIR;
+use core_test_support::rollout;
+use core_test_support::rollout::session_meta_line;
+use core_test_support::rollout::turn_context_line;
+use core_test_support::rollout::user_message_line;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+/// Writes a rollout with one user and one agent message under
+/// `CODEX_HOME/sessions/YYYY/MM/DD/` and returns its path.
+fn write_rollout(
+    codex_home: &Path,
+    id: &str,
+    filename_ts: &str,
+    cwd: &str,
+    model: &str,
+    user: &str,
+    agent: &str,
+) -> Result<PathBuf> {
+    let timestamp = format!(
+        "{}T{}.000Z",
+        &filename_ts[..10],
+        filename_ts[11..].replace('-', ":")
+    );
+    let cwd = Path::new(cwd);
+    let lines = [
+        session_meta_line(id, &timestamp, cwd),
+        turn_context_line(&timestamp, cwd, model),
+        user_message_line(&timestamp, user),
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": { "type": "agent_message", "message": agent }
+        }),
+    ];
+    Ok(rollout::write_rollout(codex_home, filename_ts, id, &lines)?)
+}
+
+const OLD_ID: &str = "11111111-1111-4111-8111-111111111111";
+const NEW_ID: &str = "22222222-2222-4222-8222-222222222222";
+
+fn write_two_sessions(codex_home: &Path) -> Result<(PathBuf, PathBuf)> {
+    let old = write_rollout(
+        codex_home,
+        OLD_ID,
+        "2025-01-10T09-00-00",
+        "/work/api",
+        "gpt-5",
+        "Fix the flaky login test",
+        "The <retry> loop now waits for the token.",
+    )?;
+    let new = write_rollout(
+        codex_home,
+        NEW_ID,
+        "2025-03-02T18-30-00",
+        "/work/web",
+        "gpt-5-codex",
+        "Add a dark mode toggle",
+        "Added the toggle to the settings page.",
+    )?;
+    Ok((old, new))
+}
+
+#[test]
+fn list_filters_by_model_cwd_and_date() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_two_sessions(codex_home.path())?;
+
+    let output = codex_command(codex_home.path())?
+        .args(["sessions", "list", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let sessions: JsonValue = serde_json::from_slice(&output.stdout)?;
+    let ids: Vec<&str> = sessions
+        .as_array()
+        .expect("array")
+        .iter()
+        .filter_map(|session| session["id"].as_str())
+        .collect();
+    assert_eq!(ids, vec![NEW_ID, OLD_ID]);
+    assert_eq!(sessions[1]["title"], json!("Fix the flaky login test"));
+    assert_eq!(sessions[1]["models"], json!(["gpt-5"]));
+
+    for filter in [
+        &["--model", "gpt-5"][..],
+        &["--cwd", "/work/api"][..],
+        &["--until", "2025-02-01"][..],
+    ] {
+        codex_command(codex_home.path())?
+            .args(["sessions", "list"])
+            .args(filter)
+            .assert()
+            .success()
+            .stdout(contains(OLD_ID))
+            .stdout(contains(NEW_ID).not());
+    }
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "list", "--since", "2025-04-01"])
+        .assert()
+        .success()
+        .stdout(contains("No sessions found."));
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "list", "--since", "April"])
+        .assert()
+        .failure()
+        .stderr(contains("YYYY-MM-DD"));
+
+    Ok(())
+}
+
+#[test]
+fn grep_searches_user_and_agent_messages() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_two_sessions(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "grep", "-i", "TOGGLE"])
+        .assert()
+        .success()
+        .stdout(contains(format!("{NEW_ID}  user: Add a dark mode toggle")))
+        .stdout(contains(format!(
+            "{NEW_ID}  codex: Added the toggle to the settings page."
+        )))
+        .stdout(contains(OLD_ID).not());
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "grep", "toggle", "--cwd", "/work/api"])
+        .assert()
+        .success()
+        .stdout(contains("No matches found."));
+
+    Ok(())
+}
+
+#[test]
+fn show_and_export_render_the_transcript() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_two_sessions(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "show", OLD_ID])
+        .assert()
+        .success()
+        .stdout(contains("Model:     gpt-5"))
+        .stdout(contains("user:\nFix the flaky login test"))
+        .stdout(contains(
+            "codex:\nThe <retry> loop now waits for the token.",
+        ));
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "export", OLD_ID])
+        .assert()
+        .success()
+        .stdout(contains("# Fix the flaky login test"))
+        .stdout(contains("## Codex\n\nThe <retry> loop"));
+
+    let html_path = codex_home.path().join("session.html");
+    codex_command(codex_home.path())?
+        .args(["sessions", "export", OLD_ID, "--format", "html", "-o"])
+        .arg(&html_path)
+        .assert()
+        .success();
+    let html = fs::read_to_string(&html_path)?;
+    assert!(html.contains("The &lt;retry&gt; loop now waits for the token."));
+
+    let output = codex_command(codex_home.path())?
+        .args(["sessions", "export", OLD_ID, "--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+    let exported: JsonValue = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(exported["meta"]["id"], json!(OLD_ID));
+    assert_eq!(
+        exported["messages"],
+        json!([
+            { "role": "user", "text": "Fix the flaky login test" },
+            { "role": "agent", "text": "The <retry> loop now waits for the token." },
+        ])
+    );
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "show", "33333333-3333-4333-8333-333333333333"])
+        .assert()
+        .failure()
+        .stderr(contains("No session found"));
+
+    Ok(())
+}
+
+#[test]
+fn prune_keeps_recent_sessions_and_archives_the_rest() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let (old, new) = write_two_sessions(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--keep-last", "1", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains(format!("Would delete {OLD_ID}")));
+    assert!(old.exists());
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--older-than", "1"])
+        .assert()
+        .success()
+        .stdout(contains("No sessions to prune."));
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--keep-last", "1", "--archive"])
+        .assert()
+        .success()
+        .stdout(contains("Archived 1 session."));
+    assert!(!old.exists());
+    assert!(new.exists());
+    let archived = codex_home
+        .path()
+        .join(ARCHIVED_SESSIONS_SUBDIR)
+        .join(old.file_name().expect("file name"));
+    assert!(archived.exists());
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune"])
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn prune_includes_sessions_without_a_user_message() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let (old, new) = write_two_sessions(codex_home.path())?;
+    let empty_id = "33333333-3333-4333-8333-333333333333";
+    let empty = write_rollout(
+        codex_home.path(),
+        empty_id,
+        "2024-12-24T08-00-00",
+        "/work/api",
+        "gpt-5",
+        "unused",
+        "unused",
+    )?;
+    // Keep only the session meta line.
+    let meta = fs::read_to_string(&empty)?
+        .lines()
+        .next()
+        .map(|line| format!("{line}\n"))
+        .unwrap_or_default();
+    fs::write(&empty, meta)?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "list"])
+        .assert()
+        .success()
+        .stdout(contains(empty_id).not());
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "prune", "--keep-last", "2"])
+        .assert()
+        .success()
+        .stdout(contains(format!("Deleted {empty_id}")))
+        .stdout(contains("Deleted 1 session."));
+    assert!(!empty.exists());
+    assert!(old.exists());
+    assert!(new.exists());
+
+    Ok(())
+}
+
+#[test]
+fn reindex_writes_the_session_index() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_two_sessions(codex_home.path())?;
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "reindex", "--compress"])
+        .assert()
+        .success()
+        .stdout(contains("Indexed 2 sessions."))
+        .stdout(contains("Compressed 0 rollouts."));
+
+    let index_path = codex_home
+        .path()
+        .join(SESSIONS_SUBDIR)
+        .join(SESSION_INDEX_FILE);
+    let index: JsonValue = serde_json::from_str(&fs::read_to_string(index_path)?)?;
+    assert_eq!(
+        index["days"]["2025/01/10"]["sessions"][0]["first_user_message"],
+        json!("Fix the flaky login test")
+    );
+
+    codex_command(codex_home.path())?
+        .args(["sessions", "list", "--model", "gpt-5"])
+        .assert()
+        .success()
+        .stdout(contains(OLD_ID));
+
+    Ok(())
+}
+
+#[test]
+fn bundle_export_and_import_moves_a_session_between_homes() -> Result<()> {
+    let source_home = TempDir::new()?;
+    write_two_sessions(source_home.path())?;
+    let bundle = source_home.path().join("old.codex-bundle");
+
+    codex_command(source_home.path())?
+        .args(["sessions", "export", OLD_ID, "--bundle", "-o"])
+        .arg(&bundle)
+        .assert()
+        .success()
+        .stdout(contains(format!("Exported session {OLD_ID}")));
+
+    let target_home = TempDir::new()?;
+    let checkout = target_home.path().join("api");
+    codex_command(target_home.path())?
+        .args(["sessions", "import"])
+        .arg(&bundle)
+        .arg("--cwd")
+        .arg(&checkout)
+        .assert()
+        .success()
+        .stdout(contains(format!("codex resume {OLD_ID}")));
+
+    let output = codex_command(target_home.path())?
+        .args(["sessions", "export", OLD_ID, "--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+    let exported: JsonValue = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(exported["meta"]["cwd"], json!(checkout));
+
+    codex_command(target_home.path())?
+        .args(["sessions", "import"])
+        .arg(&bundle)
+        .assert()
+        .failure()
+        .stderr(contains("already exists"));
+
+    Ok(())
+}
@@ -0,0 +1,973 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::ModelProviderInfo;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::default_client::CodexHttpClient;
+use crate::error::CodexErr;
+use crate::error::ConnectionFailedError;
+use crate::error::ResponseStreamFailed;
+use crate::error::Result;
+use crate::error::RetryLimitReachedError;
+use crate::error::UnexpectedResponseError;
+use crate::model_family::ModelFamily;
+use crate::protocol::TokenUsage;
+use crate::tools::spec::create_tools_json_for_messages_api;
+use crate::util::backoff;
+use bytes::Bytes;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::SubAgentSource;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+/// `max_tokens` is mandatory for the Messages API; used when
+/// `model_max_output_tokens` is not configured.
+const DEFAULT_MAX_OUTPUT_TOKENS: i64 = 32_000;
+
+/// Smallest thinking budget the Messages API accepts.
+const MIN_THINKING_BUDGET_TOKENS: i64 = 1_024;
+
+/// Reasoning items produced from Messages API `thinking` blocks carry this id,
+/// with the block signature stored in `encrypted_content`, so they can be sent
+/// back verbatim on the next request.
+const THINKING_ITEM_ID: &str = "anthropic_thinking";
+
+/// Like [`THINKING_ITEM_ID`] for `redacted_thinking` blocks, whose opaque
+/// payload is stored in `encrypted_content`.
+const REDACTED_THINKING_ITEM_ID: &str = "anthropic_redacted_thinking";
+
+/// Implementation for the Anthropic Messages API.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn stream_messages(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<i64>,
+    client: &CodexHttpClient,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+    session_source: &SessionSource,
+) -> Result<ResponseStream> {
+    if prompt.output_schema.is_some() {
+        return Err(CodexErr::UnsupportedOperation(
+            "output_schema is not supported for Messages API".to_string(),
+        ));
+    }
+
+    let payload = build_messages_payload(prompt, model_family, effort, max_output_tokens)?;
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None),
+        payload.to_string()
+    );
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let mut req_builder = provider.create_request_builder(client, &None).await?;
+
+        // Include subagent header only for subagent sessions.
+        if let SessionSource::SubAgent(sub) = session_source.clone() {
+            let subagent = if let SubAgentSource::Other(label) = sub {
+                label
+            } else {
+                serde_json::to_value(&sub)
+                    .ok()
+                    .and_then(|v| v.as_str().map(std::string::ToString::to_string))
+                    .unwrap_or_else(|| "other".to_string())
+            };
+            req_builder = req_builder.header("x-openai-subagent", subagent);
+        }
+
+        let res = otel_event_manager
+            .log_request(attempt, || {
+                req_builder
+                    .header(reqwest::header::ACCEPT, "text/event-stream")
+                    .json(&payload)
+                    .send()
+            })
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let request_id = resp
+                    .headers()
+                    .get("request-id")
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = resp.bytes_stream().map_err(move |e| {
+                    CodexErr::ResponseStreamFailed(ResponseStreamFailed {
+                        source: e,
+                        request_id: request_id.clone(),
+                    })
+                });
+                tokio::spawn(process_messages_sse(
+                    stream,
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                    otel_event_manager.clone(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                        status,
+                        body,
+                        request_id: None,
+                    }));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(RetryLimitReachedError {
+                        status,
+                        request_id: None,
+                    }));
+                }
+
+                let retry_after_secs = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+
+                let delay = retry_after_secs
+                    .map(|s| Duration::from_millis(s * 1_000))
+                    .unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(CodexErr::ConnectionFailed(ConnectionFailedError {
+                        source: e,
+                    }));
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Builds the JSON body for a streaming `POST /v1/messages` request.
+///
+/// The system prompt, the tool list and the last message each carry an
+/// ephemeral `cache_control` marker so the provider can reuse the prompt
+/// prefix across turns.
+fn build_messages_payload(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    effort: Option<ReasoningEffortConfig>,
+    max_output_tokens: Option<i64>,
+) -> Result<Value> {
+    let full_instructions = prompt.get_full_instructions(model_family);
+    let system = json!([{
+        "type": "text",
+        "text": full_instructions,
+        "cache_control": {"type": "ephemeral"},
+    }]);
+
+    let mut messages = build_messages(&prompt.get_formatted_input());
+    if let Some(last_block) = messages
+        .last_mut()
+        .and_then(|msg| msg.get_mut("content"))
+        .and_then(Value::as_array_mut)
+        .and_then(|content| content.last_mut())
+        .and_then(Value::as_object_mut)
+    {
+        last_block.insert("cache_control".to_string(), json!({"type": "ephemeral"}));
+    }
+
+    let mut tools_json = create_tools_json_for_messages_api(&prompt.tools)?;
+    if let Some(last_tool) = tools_json.last_mut().and_then(Value::as_object_mut) {
+        last_tool.insert("cache_control".to_string(), json!({"type": "ephemeral"}));
+    }
+
+    let max_tokens = max_output_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS);
+    let mut payload = json!({
+        "model": model_family.slug,
+        "system": system,
+        "messages": messages,
+        "max_tokens": max_tokens,
+        "stream": true,
+    });
+    let Some(obj) = payload.as_object_mut() else {
+        return Ok(payload);
+    };
+
+    if !tools_json.is_empty() {
+        obj.insert("tools".to_string(), json!(tools_json));
+        obj.insert(
+            "tool_choice".to_string(),
+            json!({
+                "type": "auto",
+                "disable_parallel_tool_use": !prompt.parallel_tool_calls,
+            }),
+        );
+    }
+
+    if model_family.supports_reasoning_summaries
+        && let Some(budget_tokens) = thinking_budget(effort, max_tokens)
+    {
+        obj.insert(
+            "thinking".to_string(),
+            json!({"type": "enabled", "budget_tokens": budget_tokens}),
+        );
+    }
+
+    Ok(payload)
+}
+
+/// Maps the configured reasoning effort to an extended-thinking budget that
+/// fits under `max_tokens`.
+fn thinking_budget(effort: Option<ReasoningEffortConfig>, max_tokens: i64) -> Option<i64> {
+    let budget = match effort? {
+        ReasoningEffortConfig::None => return None,
+        ReasoningEffortConfig::Minimal => MIN_THINKING_BUDGET_TOKENS,
+        ReasoningEffortConfig::Low => 4_096,
+        ReasoningEffortConfig::Medium => 10_000,
+        ReasoningEffortConfig::High => 24_000,
+    };
+    let budget = budget.min(max_tokens - 1);
+    (budget >= MIN_THINKING_BUDGET_TOKENS).then_some(budget)
+}
+
+/// Converts conversation history into Messages API `messages`. Items are
+/// mapped to content blocks and consecutive blocks with the same role are
+/// merged into a single message, since the API expects roles to alternate
+/// and tool results to follow the assistant turn that requested them.
+fn build_messages(input: &[ResponseItem]) -> Vec<Value> {
+    let mut messages: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    let mut push_block = |role: &'static str, block: Value| match messages.last_mut() {
+        Some((last_role, blocks)) if *last_role == role => blocks.push(block),
+        _ => messages.push((role, vec![block])),
+    };
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    // System and developer messages have no Messages API role
+                    // of their own; they are sent as user content.
+                    "user"
+                };
+                for c in content {
+                    match c {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            if !text.is_empty() {
+                                push_block(role, json!({"type": "text", "text": text}));
+                            }
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            push_block(role, image_block(image_url));
+                        }
+                    }
+                }
+            }
+            ResponseItem::Reasoning {
+                id,
+                content,
+                encrypted_content: Some(payload),
+                ..
+            } => {
+                // Only thinking blocks produced by this API can be replayed;
+                // reasoning from other providers is dropped.
+                if id == THINKING_ITEM_ID {
+                    let thinking: String = content
+                        .iter()
+                        .flatten()
+                        .map(|entry| match entry {
+                            ReasoningItemContent::ReasoningText { text }
+                            | ReasoningItemContent::Text { text } => text.as_str(),
+                        })
+                        .collect();
+                    push_block(
+                        "assistant",
+                        json!({"type": "thinking", "thinking": thinking, "signature": payload}),
+                    );
+                } else if id == REDACTED_THINKING_ITEM_ID {
+                    push_block(
+                        "assistant",
+                        json!({"type": "redacted_thinking", "data": payload}),
+                    );
+                }
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let input = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push_block(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": input}),
+                );
+            }
+            ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action,
+                ..
+            } => {
+                let Some(call_id) = call_id.as_ref().or(id.as_ref()) else {
+                    continue;
+                };
+                // The Messages API declares `shell` in place of the local
+                // shell tool, so replay the call in that tool's shape.
+                let LocalShellAction::Exec(exec) = action;
+                let mut input = json!({"command": exec.command});
+                if let Some(workdir) = &exec.working_directory {
+                    input["workdir"] = json!(workdir);
+                }
+                if let Some(timeout_ms) = exec.timeout_ms {
+                    input["timeout_ms"] = json!(timeout_ms);
+                }
+                push_block(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": "shell", "input": input}),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let content = if let Some(items) = &output.content_items {
+                    json!(
+                        items
+                            .iter()
+                            .map(|it| match it {
+                                FunctionCallOutputContentItem::InputText { text } => {
+                                    json!({"type": "text", "text": text})
+                                }
+                                FunctionCallOutputContentItem::InputImage { image_url } => {
+                                    image_block(image_url)
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    )
+                } else {
+                    json!(output.content)
+                };
+                push_block(
+                    "user",
+                    json!({
+                        "type": "tool_result",
+                        "tool_use_id": call_id,
+                        "content": content,
+                        "is_error": output.success == Some(false),
+                    }),
+                );
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                push_block(
+                    "assistant",
+                    json!({
+                        "type": "tool_use",
+                        "id": call_id,
+                        "name": name,
+                        "input": {"input": input},
+                    }),
+                );
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                push_block(
+                    "user",
+                    json!({"type": "tool_result", "tool_use_id": call_id, "content": output}),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Other => {
+                // Omit these items from the conversation history.
+                continue;
+            }
+        }
+    }
+
+    messages
+        .into_iter()
+        .map(|(role, content)| json!({"role": role, "content": content}))
+        .collect()
+}
+
+/// Maps an image URL to an image content block, inlining `data:` URLs as
+/// base64 sources.
+fn image_block(image_url: &str) -> Value {
+    if let Some(rest) = image_url.strip_prefix("data:")
+        && let Some((media_type, data)) = rest.split_once(";base64,")
+    {
+        return json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        });
+    }
+    json!({
+        "type": "image",
+        "source": {"type": "url", "url": image_url},
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessagesUsage {
+    input_tokens: Option<i64>,
+    cache_creation_input_tokens: Option<i64>,
+    cache_read_input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+}
+
+impl MessagesUsage {
+    /// `message_delta` carries cumulative counts that supersede the ones
+    /// reported by `message_start`.
+    fn merge(&mut self, other: MessagesUsage) {
+        self.input_tokens = other.input_tokens.or(self.input_tokens);
+        self.cache_creation_input_tokens = other
+            .cache_creation_input_tokens
+            .or(self.cache_creation_input_tokens);
+        self.cache_read_input_tokens = other
+            .cache_read_input_tokens
+            .or(self.cache_read_input_tokens);
+        self.output_tokens = other.output_tokens.or(self.output_tokens);
+    }
+}
+
+impl From<MessagesUsage> for TokenUsage {
+    fn from(usage: MessagesUsage) -> Self {
+        // `input_tokens` excludes tokens read from or written to the prompt
+        // cache, whereas `TokenUsage::input_tokens` counts all of them.
+        let cached_input_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let input_tokens = usage.input_tokens.unwrap_or(0)
+            + usage.cache_creation_input_tokens.unwrap_or(0)
+            + cached_input_tokens;
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MessagesStreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: Value,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: Value,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        #[serde(default)]
+        usage: Option<MessagesUsage>,
+    },
+    MessageStop,
+    Ping,
+    Error {
+        error: MessagesError,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    id: String,
+    #[serde(default)]
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesError {
+    r#type: Option<String>,
+    message: Option<String>,
+}
+
+/// A content block that is still streaming.
+enum BlockState {
+    Text(String),
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    RedactedThinking(String),
+    ToolUse {
+        id: String,
+        name: String,
+        input: String,
+    },
+}
+
+/// SSE processor for the Messages API streaming format. Each content block
+/// becomes a [`ResponseItem`] announced with `OutputItemAdded` (text and
+/// thinking) and finalized with `OutputItemDone` once the block stops, so the
+/// output matches what the Responses API produces.
+async fn process_messages_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    otel_event_manager: OtelEventManager,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut blocks: HashMap<usize, BlockState> = HashMap::new();
+    let mut response_id = String::new();
+    let mut usage = MessagesUsage::default();
+
+    loop {
+        let start = std::time::Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        let duration = start.elapsed();
+        otel_event_manager.log_sse_event(&response, duration);
+
+        let sse = match response {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before message_stop".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        // Events without a payload (e.g. `message_stop`) are identified by
+        // the SSE event name alone.
+        let data = if sse.data.trim().is_empty() {
+            json!({"type": sse.event})
+        } else {
+            match serde_json::from_str::<Value>(&sse.data) {
+                Ok(v) => v,
+                Err(e) => {
+                    debug!(
+                        "Failed to parse Messages SSE event: {e}, data: {}",
+                        &sse.data
+                    );
+                    continue;
+                }
+            }
+        };
+        trace!("messages received SSE event: {data:?}");
+
+        let event = match serde_json::from_value::<MessagesStreamEvent>(data) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!("Ignoring unrecognized Messages SSE event: {e}");
+                continue;
+            }
+        };
+
+        match event {
+            MessagesStreamEvent::MessageStart { message } => {
+                response_id = message.id;
+                if let Some(start_usage) = message.usage {
+                    usage.merge(start_usage);
+                }
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+            MessagesStreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let block_type = content_block.get("type").and_then(Value::as_str);
+                let text_field = |field: &str| {
+                    content_block
+                        .get(field)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let state = match block_type {
+                    Some("text") => {
+                        let item = ResponseItem::Message {
+                            id: None,
+                            role: "assistant".to_string(),
+                            content: vec![],
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        let text = text_field("text");
+                        if !text.is_empty() {
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::OutputTextDelta(text.clone())))
+                                .await;
+                        }
+                        BlockState::Text(text)
+                    }
+                    Some("thinking") => {
+                        let item = ResponseItem::Reasoning {
+                            id: THINKING_ITEM_ID.to_string(),
+                            summary: Vec::new(),
+                            content: Some(vec![]),
+                            encrypted_content: None,
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        BlockState::Thinking {
+                            thinking: text_field("thinking"),
+                            signature: text_field("signature"),
+                        }
+                    }
+                    Some("redacted_thinking") => BlockState::RedactedThinking(text_field("data")),
+                    Some("tool_use") => {
+                        // Non-streamed input arrives complete on the start event.
+                        let input = content_block
+                            .get("input")
+                            .filter(|v| v.as_object().is_some_and(|o| !o.is_empty()))
+                            .map(Value::to_string)
+                            .unwrap_or_default();
+                        BlockState::ToolUse {
+                            id: text_field("id"),
+                            name: text_field("name"),
+                            input,
+                        }
+                    }
+                    _ => continue,
+                };
+                blocks.insert(index, state);
+            }
+            MessagesStreamEvent::ContentBlockDelta { index, delta } => {
+                let Some(state) = blocks.get_mut(&index) else {
+                    continue;
+                };
+                let delta_type = delta.get("type").and_then(Value::as_str);
+                let delta_text = |field: &str| {
+                    delta
+                        .get(field)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                match (state, delta_type) {
+                    (BlockState::Text(text), Some("text_delta")) => {
+                        let fragment = delta_text("text");
+                        text.push_str(&fragment);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputTextDelta(fragment)))
+                            .await;
+                    }
+                    (BlockState::Thinking { thinking, .. }, Some("thinking_delta")) => {
+                        let fragment = delta_text("thinking");
+                        thinking.push_str(&fragment);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::ReasoningContentDelta {
+                                delta: fragment,
+                                content_index: 0,
+                            }))
+                            .await;
+                    }
+                    (BlockState::Thinking { signature, .. }, Some("signature_delta")) => {
+                        signature.push_str(&delta_text("signature"));
+                    }
+                    (BlockState::ToolUse { input, .. }, Some("input_json_delta")) => {
+                        input.push_str(&delta_text("partial_json"));
+                    }
+                    _ => {}
+                }
+            }
+            MessagesStreamEvent::ContentBlockStop { index } => {
+                let Some(state) = blocks.remove(&index) else {
+                    continue;
+                };
+                let item = match state {
+                    BlockState::Text(text) => ResponseItem::Message {
+                        id: None,
+                        role: "assistant".to_string(),
+                        content: vec![ContentItem::OutputText { text }],
+                    },
+                    BlockState::Thinking {
+                        thinking,
+                        signature,
+                    } => ResponseItem::Reasoning {
+                        id: THINKING_ITEM_ID.to_string(),
+                        summary: Vec::new(),
+                        content: Some(vec![ReasoningItemContent::ReasoningText { text: thinking }]),
+                        encrypted_content: Some(signature),
+                    },
+                    BlockState::RedactedThinking(data) => ResponseItem::Reasoning {
+                        id: REDACTED_THINKING_ITEM_ID.to_string(),
+                        summary: Vec::new(),
+                        content: None,
+                        encrypted_content: Some(data),
+                    },
+                    BlockState::ToolUse { id, name, input } => ResponseItem::FunctionCall {
+                        id: None,
+                        name,
+                        arguments: if input.is_empty() {
+                            "{}".to_string()
+                        } else {
+                            input
+                        },
+                        call_id: id,
+                    },
+                };
+                let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+            }
+            MessagesStreamEvent::MessageDelta { usage: delta_usage } => {
+                if let Some(delta_usage) = delta_usage {
+                    usage.merge(delta_usage);
+                }
+            }
+            MessagesStreamEvent::MessageStop => {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(usage.into()),
+                    }))
+                    .await;
+                return;
+            }
+            MessagesStreamEvent::Ping => {}
+            MessagesStreamEvent::Error { error } => {
+                let message = error
+                    .message
+                    .or(error.r#type)
+                    .unwrap_or_else(|| "unknown Messages API error".to_string());
+                let _ = tx_event.send(Err(CodexErr::Stream(message, None))).await;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_family::find_family_for_model;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::LocalShellExecAction;
+    use codex_protocol::models::LocalShellStatus;
+    use pretty_assertions::assert_eq;
+
+    fn user_msg(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn groups_tool_calls_and_results_by_role() {
+        let input = vec![
+            user_msg("list files"),
+            ResponseItem::Reasoning {
+                id: THINKING_ITEM_ID.to_string(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: "use ls".to_string(),
+                }]),
+                encrypted_content: Some("sig".to_string()),
+            },
+            ResponseItem::Reasoning {
+                id: "rs_openai".to_string(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some("opaque".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "a.txt".to_string(),
+                    content_items: None,
+                    success: Some(true),
+                },
+            },
+            user_msg("thanks"),
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![
+                json!({"role": "user", "content": [{"type": "text", "text": "list files"}]}),
+                json!({"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "use ls", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                ]}),
+                json!({"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "a.txt", "is_error": false},
+                    {"type": "text", "text": "thanks"},
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn replays_local_shell_calls_as_the_declared_shell_tool() {
+        let input = vec![
+            user_msg("list files"),
+            ResponseItem::LocalShellCall {
+                id: None,
+                call_id: Some("toolu_1".to_string()),
+                status: LocalShellStatus::Completed,
+                action: LocalShellAction::Exec(LocalShellExecAction {
+                    command: vec!["ls".to_string()],
+                    timeout_ms: Some(1000),
+                    working_directory: Some("/repo".to_string()),
+                    env: None,
+                    user: None,
+                }),
+            },
+        ];
+
+        assert_eq!(
+            build_messages(&input)[1],
+            json!({"role": "assistant", "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {
+                    "command": ["ls"],
+                    "workdir": "/repo",
+                    "timeout_ms": 1000,
+                }},
+            ]})
+        );
+    }
+
+    #[test]
+    fn inlines_data_url_images() {
+        assert_eq!(
+            image_block("data:image/png;base64,AAAA"),
+            json!({"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}})
+        );
+        assert_eq!(
+            image_block("https://example.com/a.png"),
+            json!({"type": "image", "source": {"type": "url", "url": "https://example.com/a.png"}})
+        );
+    }
+
+    #[test]
+    fn marks_prompt_prefix_for_caching() {
+        let prompt = Prompt {
+            input: vec![user_msg("hi")],
+            ..Default::default()
+        };
+        let family = find_family_for_model("gpt-4.1").expect("known model family");
+        let payload = build_messages_payload(&prompt, &family, None, Some(1_000)).expect("payload");
+
+        assert_eq!(payload["max_tokens"], json!(1_000));
+        assert_eq!(
+            payload["system"][0]["cache_control"],
+            json!({"type": "ephemeral"})
+        );
+        assert_eq!(
+            payload["messages"][0]["content"][0]["cache_control"],
+            json!({"type": "ephemeral"})
+        );
+        assert_eq!(payload.get("thinking"), None);
+    }
+
+    #[test]
+    fn thinking_budget_fits_under_max_tokens() {
+        assert_eq!(thinking_budget(None, 32_000), None);
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::None), 32_000),
+            None
+        );
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::High), 32_000),
+            Some(24_000)
+        );
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::High), 8_000),
+            Some(7_999)
+        );
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::Low), 1_000),
+            None
+        );
+    }
+
+    #[test]
+    fn usage_counts_cached_input() {
+        let mut usage = MessagesUsage {
+            input_tokens: Some(10),
+            cache_creation_input_tokens: Some(5),
+            cache_read_input_tokens: Some(100),
+            output_tokens: Some(1),
+        };
+        usage.merge(MessagesUsage {
+            output_tokens: Some(20),
+            ..Default::default()
+        });
+
+        let usage = TokenUsage::from(usage);
+        assert_eq!(
+            (
+                usage.input_tokens,
+                usage.cached_input_tokens,
+                usage.output_tokens,
+                usage.total_tokens
+            ),
+            (115, 100, 20, 135)
+        );
+    }
+}
@@ -3,12 +3,14 @@ use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
 use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
+use crate::protocol::TurnPhase;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Instant;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
 
@@ -69,7 +71,11 @@ pub(crate) async fn apply_patch(
                     None,
                 )
                 .await;
-            match rx_approve.await.unwrap_or_default() {
+            let started = Instant::now();
+            let decision = rx_approve.await.unwrap_or_default();
+            sess.record_turn_phase(TurnPhase::ApprovalWait, started.elapsed())
+                .await;
+            match decision {
                 ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
                     InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                         action,
@@ -0,0 +1,439 @@
+//! Record-and-replay of model transcripts ("cassettes") for deterministic
+//! offline runs.
+//!
+//! With `CODEX_RS_RECORD_CASSETTE=<path>`, every request sent through
+//! [`crate::ModelClient`] is written to `<path>` together with the events
+//! streamed back. With `CODEX_RS_REPLAY_CASSETTE=<path>`, requests are served
+//! from the cassette in order instead of the provider, and a request that
+//! differs from the recorded one fails the turn. Cassettes are JSON Lines
+//! with one [`CassetteEntry`] per request and work with every wire API.
+//!
+//! Requests are normalized before they are recorded or compared: the session
+//! cwd and `CODEX_HOME` are replaced by placeholders and command timings are
+//! zeroed, so a cassette recorded on one machine replays on another.
+
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::flags::CODEX_RS_RECORD_CASSETTE;
+use crate::flags::CODEX_RS_REPLAY_CASSETTE;
+
+const CWD_PLACEHOLDER: &str = "<cwd>";
+const CODEX_HOME_PLACEHOLDER: &str = "<codex_home>";
+
+/// Longest excerpt of a mismatched value included in error messages.
+const MAX_MISMATCH_EXCERPT: usize = 200;
+
+/// One model request and the events streamed back for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CassetteEntry {
+    pub(crate) request: Value,
+    pub(crate) events: Vec<ResponseEvent>,
+    /// Set when the stream ended with an error after `events`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+pub(crate) enum Cassette {
+    Record(CassetteRecorder),
+    Replay(CassettePlayer),
+}
+
+static ACTIVE_CASSETTE: OnceLock<std::result::Result<Option<Cassette>, String>> = OnceLock::new();
+
+/// Returns the cassette selected by the environment, if any. The file is
+/// opened on first use and shared by every client in the process.
+pub(crate) fn active_cassette() -> Result<Option<&'static Cassette>> {
+    match ACTIVE_CASSETTE.get_or_init(load_from_env) {
+        Ok(cassette) => Ok(cassette.as_ref()),
+        Err(message) => Err(CodexErr::Fatal(message.clone())),
+    }
+}
+
+fn load_from_env() -> std::result::Result<Option<Cassette>, String> {
+    match (*CODEX_RS_RECORD_CASSETTE, *CODEX_RS_REPLAY_CASSETTE) {
+        (Some(_), Some(_)) => Err(
+            "CODEX_RS_RECORD_CASSETTE and CODEX_RS_REPLAY_CASSETTE are mutually exclusive"
+                .to_string(),
+        ),
+        (Some(path), None) => CassetteRecorder::create(Path::new(path))
+            .map(|recorder| Some(Cassette::Record(recorder)))
+            .map_err(|err| format!("failed to create cassette `{path}`: {err}")),
+        (None, Some(path)) => CassettePlayer::load(Path::new(path))
+            .map(|player| Some(Cassette::Replay(player)))
+            .map_err(|err| format!("failed to load cassette `{path}`: {err}")),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Rewrites the parts of a request that vary between machines or runs.
+pub(crate) fn normalize_request(request: &Value, cwd: &Path, codex_home: &Path) -> Value {
+    static TIMING: OnceLock<Regex> = OnceLock::new();
+    static DURATION_FIELD: OnceLock<Regex> = OnceLock::new();
+    #[expect(clippy::unwrap_used)]
+    let timing = TIMING
+        .get_or_init(|| Regex::new(r"(Wall time|Duration): [0-9]+(\.[0-9]+)? seconds").unwrap());
+    #[expect(clippy::unwrap_used)]
+    let duration_field = DURATION_FIELD
+        .get_or_init(|| Regex::new(r#"(duration_seconds\\*"\s*:\s*)[0-9]+(\.[0-9]+)?"#).unwrap());
+
+    let mut text = request.to_string();
+    // Replace the longer path first in case one is nested in the other.
+    let mut paths = [(cwd, CWD_PLACEHOLDER), (codex_home, CODEX_HOME_PLACEHOLDER)];
+    paths.sort_by_key(|(path, _)| std::cmp::Reverse(path.as_os_str().len()));
+    for (path, placeholder) in paths {
+        let Some(path) = path.to_str().filter(|p| !p.is_empty() && *p != "/") else {
+            continue;
+        };
+        // Match the path as it appears inside a JSON string.
+        let escaped = Value::String(path.to_string()).to_string();
+        let escaped = &escaped[1..escaped.len() - 1];
+        text = text.replace(escaped, placeholder);
+    }
+    let text = timing.replace_all(&text, "$1: 0 seconds");
+    let text = duration_field.replace_all(&text, "${1}0");
+
+    serde_json::from_str(&text).unwrap_or_else(|_| request.clone())
+}
+
+struct RecorderState {
+    file: File,
+    next_sequence: usize,
+    next_to_write: usize,
+    finished: BTreeMap<usize, CassetteEntry>,
+}
+
+/// Writes entries in the order their requests were made, even when streams
+/// finish out of order.
+pub(crate) struct CassetteRecorder {
+    state: Mutex<RecorderState>,
+}
+
+impl CassetteRecorder {
+    pub(crate) fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            state: Mutex::new(RecorderState {
+                file: File::create(path)?,
+                next_sequence: 0,
+                next_to_write: 0,
+                finished: BTreeMap::new(),
+            }),
+        })
+    }
+
+    /// Forwards `stream` to the caller while capturing its events, then writes
+    /// them to the cassette once the stream ends.
+    pub(crate) fn record(&'static self, request: Value, stream: ResponseStream) -> ResponseStream {
+        let sequence = {
+            let mut state = self.lock_state();
+            let sequence = state.next_sequence;
+            state.next_sequence += 1;
+            sequence
+        };
+
+        let ResponseStream { mut rx_event } = stream;
+        let (tx_event, rx) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(async move {
+            let mut entry = Some(CassetteEntry {
+                request,
+                events: Vec::new(),
+                error: None,
+            });
+            while let Some(event) = rx_event.recv().await {
+                if let Some(pending) = entry.as_mut() {
+                    match &event {
+                        Ok(event) => pending.events.push(event.clone()),
+                        Err(CodexErr::Stream(message, _)) => pending.error = Some(message.clone()),
+                        Err(err) => pending.error = Some(err.to_string()),
+                    }
+                    // Write as soon as the response is complete so the entry
+                    // lands on disk even if the session exits right away.
+                    if matches!(event, Ok(ResponseEvent::Completed { .. }) | Err(_))
+                        && let Some(done) = entry.take()
+                    {
+                        self.finish(sequence, done);
+                    }
+                }
+                if tx_event.send(event).await.is_err() {
+                    break;
+                }
+            }
+            if let Some(done) = entry {
+                self.finish(sequence, done);
+            }
+        });
+        ResponseStream { rx_event: rx }
+    }
+
+    fn finish(&self, sequence: usize, entry: CassetteEntry) {
+        let mut state = self.lock_state();
+        state.finished.insert(sequence, entry);
+        loop {
+            let next = state.next_to_write;
+            let Some(entry) = state.finished.remove(&next) else {
+                break;
+            };
+            state.next_to_write += 1;
+            let result = serde_json::to_string(&entry)
+                .map_err(std::io::Error::from)
+                .and_then(|line| writeln!(state.file, "{line}"))
+                .and_then(|()| state.file.flush());
+            if let Err(err) = result {
+                warn!("failed to write cassette entry: {err}");
+            }
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+struct PlayerState {
+    entries: VecDeque<CassetteEntry>,
+    served: usize,
+}
+
+/// Serves recorded entries in order.
+pub(crate) struct CassettePlayer {
+    path: PathBuf,
+    state: Mutex<PlayerState>,
+}
+
+impl CassettePlayer {
+    pub(crate) fn load(path: &Path) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = VecDeque::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push_back(serde_json::from_str(&line)?);
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(PlayerState { entries, served: 0 }),
+        })
+    }
+
+    /// Returns the next recorded response, or an error if `request` is not
+    /// the next recorded request.
+    pub(crate) fn serve(&self, request: &Value) -> Result<ResponseStream> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let number = state.served + 1;
+        let Some(entry) = state.entries.front() else {
+            return Err(CodexErr::Fatal(format!(
+                "cassette `{}` has no response for request #{number}",
+                self.path.display()
+            )));
+        };
+        if let Some(mismatch) = describe_mismatch("request", &entry.request, request) {
+            return Err(CodexErr::Fatal(format!(
+                "request #{number} does not match cassette `{}`: {mismatch}",
+                self.path.display()
+            )));
+        }
+        let Some(entry) = state.entries.pop_front() else {
+            unreachable!("front entry checked above");
+        };
+        state.served += 1;
+
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(entry.events.len() + 1);
+        for event in entry.events {
+            let _ = tx_event.try_send(Ok(event));
+        }
+        if let Some(message) = entry.error {
+            let _ = tx_event.try_send(Err(CodexErr::Stream(message, None)));
+        }
+        Ok(ResponseStream { rx_event })
+    }
+}
+
+/// Describes the first difference between `expected` and `actual`, if any.
+fn describe_mismatch(location: &str, expected: &Value, actual: &Value) -> Option<String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter().find_map(|key| {
+                describe_mismatch(
+                    &format!("{location}.{key}"),
+                    expected.get(key).unwrap_or(&Value::Null),
+                    actual.get(key).unwrap_or(&Value::Null),
+                )
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if let Some(mismatch) = expected
+                .iter()
+                .zip(actual)
+                .enumerate()
+                .find_map(|(i, (e, a))| describe_mismatch(&format!("{location}[{i}]"), e, a))
+            {
+                return Some(mismatch);
+            }
+            (expected.len() != actual.len()).then(|| {
+                format!(
+                    "{location} has {} items, expected {}",
+                    actual.len(),
+                    expected.len()
+                )
+            })
+        }
+        _ if expected == actual => None,
+        _ => Some(format!(
+            "{location} is {}, expected {}",
+            excerpt(actual),
+            excerpt(expected)
+        )),
+    }
+}
+
+fn excerpt(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() <= MAX_MISMATCH_EXCERPT {
+        return text;
+    }
+    let truncated: String = text.chars().take(MAX_MISMATCH_EXCERPT).collect();
+    format!("{truncated}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::ResponseItem;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn assistant_message(text: &str) -> ResponseEvent {
+        ResponseEvent::OutputItemDone(ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        })
+    }
+
+    #[test]
+    fn normalizes_paths_and_timings() {
+        let request = json!({
+            "input": [
+                {"cwd": "/work/repo", "home": "/work/repo/.codex"},
+                {"output": "{\"output\":\"ok\",\"metadata\":{\"exit_code\":0,\"duration_seconds\":1.2}}"},
+                {"output": "Exit code: 0\nWall time: 0.5 seconds\nOutput:\nok"},
+            ]
+        });
+
+        let normalized = normalize_request(
+            &request,
+            Path::new("/work/repo"),
+            Path::new("/work/repo/.codex"),
+        );
+
+        assert_eq!(
+            normalized,
+            json!({
+                "input": [
+                    {"cwd": "<cwd>", "home": "<codex_home>"},
+                    {"output": "{\"output\":\"ok\",\"metadata\":{\"exit_code\":0,\"duration_seconds\":0}}"},
+                    {"output": "Exit code: 0\nWall time: 0 seconds\nOutput:\nok"},
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn describes_first_mismatch() {
+        let expected = json!({"model": "gpt-5", "input": [{"text": "a"}, {"text": "b"}]});
+        let actual = json!({"model": "gpt-5", "input": [{"text": "a"}, {"text": "c"}]});
+
+        assert_eq!(describe_mismatch("request", &expected, &expected), None);
+        assert_eq!(
+            describe_mismatch("request", &expected, &actual),
+            Some(r#"request.input[1].text is "c", expected "b""#.to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_entries_in_order() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("session.cassette.jsonl");
+        let entries = [
+            CassetteEntry {
+                request: json!({"turn": 1}),
+                events: vec![assistant_message("first")],
+                error: None,
+            },
+            CassetteEntry {
+                request: json!({"turn": 2}),
+                events: Vec::new(),
+                error: Some("disconnected".to_string()),
+            },
+        ];
+        let lines: Vec<String> = entries
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<std::result::Result<_, _>>()?;
+        std::fs::write(&path, lines.join("\n"))?;
+
+        let player = CassettePlayer::load(&path)?;
+        let mut first = player.serve(&json!({"turn": 1}))?;
+        assert!(matches!(
+            first.next().await,
+            Some(Ok(ResponseEvent::OutputItemDone(
+                ResponseItem::Message { .. }
+            )))
+        ));
+        assert!(first.next().await.is_none());
+
+        let err = player
+            .serve(&json!({"turn": 3}))
+            .err()
+            .expect("mismatched request should fail");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Fatal error: request #2 does not match cassette `{}`: request.turn is 3, expected 2",
+                path.display()
+            )
+        );
+
+        let mut second = player.serve(&json!({"turn": 2}))?;
+        assert!(matches!(
+            second.next().await,
+            Some(Err(CodexErr::Stream(..)))
+        ));
+        assert!(player.serve(&json!({"turn": 3})).is_err());
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@ use crate::error::Result;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::model_family::ModelFamily;
+use crate::protocol::TokenUsage;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::util::backoff;
 use bytes::Bytes;
@@ -27,6 +28,7 @@ use futures::Stream;
 use futures::StreamExt;
 use futures::TryStreamExt;
 use reqwest::StatusCode;
+use serde::Deserialize;
 use serde_json::json;
 use std::pin::Pin;
 use std::task::Context;
@@ -36,6 +38,9 @@ use tokio::time::timeout;
 use tracing::debug;
 use tracing::trace;
 
+/// How long to wait for the usage chunk or `[DONE]` after `finish_reason`.
+const USAGE_CHUNK_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Implementation for the classic Chat Completions API.
 pub(crate) async fn stream_chat_completions(
     prompt: &Prompt,
@@ -328,12 +333,18 @@ pub(crate) async fn stream_chat_completions(
     }
 
     let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-    let payload = json!({
+    let mut payload = json!({
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
         "tools": tools_json,
     });
+    if provider.stream_include_usage
+        && let Some(payload) = payload.as_object_mut()
+    {
+        // Ask for a final chunk with token usage (including cached prompt tokens).
+        payload.insert("stream_options".to_string(), json!({"include_usage": true}));
+    }
 
     debug!(
         "POST to {}: {}",
@@ -517,10 +528,22 @@ async fn process_chat_sse<S>(
     let mut fn_call_state = FunctionCallState::default();
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
+    // With `stream_options.include_usage`, usage arrives in a chunk after the
+    // one carrying `finish_reason`, so `Completed` is held back until the
+    // stream ends.
+    let mut finished = false;
+    let mut token_usage: Option<TokenUsage> = None;
 
     loop {
         let start = std::time::Instant::now();
-        let response = timeout(idle_timeout, stream.next()).await;
+        // Once the turn has finished, only a trailing usage chunk or `[DONE]`
+        // is expected; don't hold the turn for a provider that sends neither.
+        let wait = if finished {
+            idle_timeout.min(USAGE_CHUNK_TIMEOUT)
+        } else {
+            idle_timeout
+        };
+        let response = timeout(wait, stream.next()).await;
         let duration = start.elapsed();
         otel_event_manager.log_sse_event(&response, duration);
 
@@ -537,7 +560,17 @@ async fn process_chat_sse<S>(
                 let _ = tx_event
                     .send(Ok(ResponseEvent::Completed {
                         response_id: String::new(),
-                        token_usage: None,
+                        token_usage,
+                    }))
+                    .await;
+                return;
+            }
+            Err(_) if finished => {
+                // The turn already finished; don't fail it over a missing usage chunk.
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id: String::new(),
+                        token_usage,
                     }))
                     .await;
                 return;
@@ -568,7 +601,7 @@ async fn process_chat_sse<S>(
             let _ = tx_event
                 .send(Ok(ResponseEvent::Completed {
                     response_id: String::new(),
-                    token_usage: None,
+                    token_usage,
                 }))
                 .await;
             return;
@@ -581,6 +614,26 @@ async fn process_chat_sse<S>(
         };
         trace!("chat_completions received SSE chunk: {chunk:?}");
 
+        if let Some(usage) = chunk
+            .get("usage")
+            .filter(|usage| !usage.is_null())
+            .and_then(|usage| serde_json::from_value::<ChatUsage>(usage.clone()).ok())
+        {
+            token_usage = Some(usage.into());
+        }
+        if finished {
+            if token_usage.is_some() {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id: String::new(),
+                        token_usage,
+                    }))
+                    .await;
+                return;
+            }
+            continue;
+        }
+
         let choice_opt = chunk.get("choices").and_then(|c| c.get(0));
 
         if let Some(choice) = choice_opt {
@@ -706,19 +759,51 @@ async fn process_chat_sse<S>(
                     _ => {}
                 }
 
-                // Emit Completed regardless of reason so the agent can advance.
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::Completed {
-                        response_id: String::new(),
-                        token_usage: None,
-                    }))
-                    .await;
+                // Completed is emitted regardless of reason so the agent can
+                // advance, once any trailing usage chunk has been read.
+                assistant_item = None;
+                reasoning_item = None;
+                finished = true;
+            }
+        }
+    }
+}
 
-                // Prepare for potential next turn (should not happen in same stream).
-                // fn_call_state = FunctionCallState::default();
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: i64,
+    completion_tokens: i64,
+    total_tokens: i64,
+    prompt_tokens_details: Option<ChatPromptTokensDetails>,
+    completion_tokens_details: Option<ChatCompletionTokensDetails>,
+}
 
-                return; // End processing for this SSE stream.
-            }
+#[derive(Debug, Deserialize)]
+struct ChatPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: i64,
+}
+
+impl From<ChatUsage> for TokenUsage {
+    fn from(val: ChatUsage) -> Self {
+        TokenUsage {
+            input_tokens: val.prompt_tokens,
+            cached_input_tokens: val
+                .prompt_tokens_details
+                .map(|d| d.cached_tokens)
+                .unwrap_or(0),
+            output_tokens: val.completion_tokens,
+            reasoning_output_tokens: val
+                .completion_tokens_details
+                .map(|d| d.reasoning_tokens)
+                .unwrap_or(0),
+            total_tokens: val.total_tokens,
         }
     }
 }
@@ -927,6 +1012,9 @@ where
                 Poll::Ready(Some(Ok(ResponseEvent::OutputItemAdded(item)))) => {
                     return Poll::Ready(Some(Ok(ResponseEvent::OutputItemAdded(item))));
                 }
+                Poll::Ready(Some(Ok(ResponseEvent::ModelFailover(failover)))) => {
+                    return Poll::Ready(Some(Ok(ResponseEvent::ModelFailover(failover))));
+                }
             }
         }
     }
@@ -975,3 +1063,62 @@ impl<S> AggregatedChatStream<S> {
         Self::new(inner, AggregateMode::Streaming)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn completes_shortly_after_finish_reason_without_done() {
+        let chunks = vec![
+            Ok(Bytes::from(
+                "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+            )),
+            Ok(Bytes::from(
+                "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+            )),
+        ];
+        // The provider never sends usage or `[DONE]` and keeps the connection open.
+        let stream = futures::stream::iter(chunks).chain(futures::stream::pending());
+        let otel_event_manager = OtelEventManager::new(
+            ConversationId::new(),
+            "test",
+            "test",
+            None,
+            None,
+            None,
+            false,
+            "test".to_string(),
+        );
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent>>(16);
+        tokio::spawn(process_chat_sse(
+            Box::pin(stream),
+            tx,
+            Duration::from_secs(300),
+            otel_event_manager,
+        ));
+
+        let mut events = Vec::new();
+        let collect = async {
+            while let Some(event) = rx.recv().await {
+                let completed = matches!(event, Ok(ResponseEvent::Completed { .. }));
+                events.push(event);
+                if completed {
+                    break;
+                }
+            }
+        };
+        timeout(USAGE_CHUNK_TIMEOUT * 3, collect)
+            .await
+            .expect("Completed should not wait for the idle timeout");
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, Ok(ResponseEvent::Completed { .. })))
+                .count(),
+            1
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::io::BufRead;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use bytes::Bytes;
@@ -30,10 +32,15 @@ use tracing::trace;
 use tracing::warn;
 
 use crate::AuthManager;
+use crate::anthropic_messages::stream_messages;
 use crate::auth::CodexAuth;
 use crate::auth::RefreshTokenError;
+use crate::cassette::Cassette;
+use crate::cassette::active_cassette;
+use crate::cassette::normalize_request;
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
+use crate::client_common::ModelFailover;
 use crate::client_common::Prompt;
 use crate::client_common::Reasoning;
 use crate::client_common::ResponseEvent;
@@ -52,6 +59,7 @@ use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_model_info::get_model_info;
@@ -89,6 +97,12 @@ pub struct ModelClient {
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
     session_source: SessionSource,
+    /// Position in `config.model_fallbacks` of the entry in use, where 0 is
+    /// the primary provider. Shared with the session so a switch also sticks
+    /// for later turns.
+    active_fallback: Arc<AtomicUsize>,
+    /// Clients for the `model_fallbacks` entries, built on first use.
+    fallback_clients: Arc<[OnceLock<ModelClient>]>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -104,6 +118,11 @@ impl ModelClient {
         session_source: SessionSource,
     ) -> Self {
         let client = create_client();
+        let fallback_clients = config
+            .model_fallbacks
+            .iter()
+            .map(|_| OnceLock::new())
+            .collect();
 
         Self {
             config,
@@ -115,20 +134,31 @@ impl ModelClient {
             effort,
             summary,
             session_source,
+            active_fallback: Arc::new(AtomicUsize::new(0)),
+            fallback_clients,
         }
     }
 
+    /// Shares `active_fallback`, normally owned by the session, so a failover
+    /// outlives the turn this client was built for.
+    pub(crate) fn with_active_fallback(mut self, active_fallback: Arc<AtomicUsize>) -> Self {
+        self.active_fallback = active_fallback;
+        self
+    }
+
     pub fn get_model_context_window(&self) -> Option<i64> {
-        let pct = self.config.model_family.effective_context_window_percent;
-        self.config
+        let config = &self.current().config;
+        let pct = config.model_family.effective_context_window_percent;
+        config
             .model_context_window
-            .or_else(|| get_model_info(&self.config.model_family).map(|info| info.context_window))
+            .or_else(|| get_model_info(&config.model_family).map(|info| info.context_window))
             .map(|w| w.saturating_mul(pct) / 100)
     }
 
     pub fn get_auto_compact_token_limit(&self) -> Option<i64> {
-        self.config.model_auto_compact_token_limit.or_else(|| {
-            get_model_info(&self.config.model_family).and_then(|info| info.auto_compact_token_limit)
+        let config = &self.current().config;
+        config.model_auto_compact_token_limit.or_else(|| {
+            get_model_info(&config.model_family).and_then(|info| info.auto_compact_token_limit)
         })
     }
 
@@ -140,7 +170,169 @@ impl ModelClient {
         &self.provider
     }
 
+    /// Streams a response for `prompt`. When the active provider fails with
+    /// a quota error or exhausts its retries, the request moves on to the
+    /// next `model_fallbacks` entry; each switch is reported to the caller as
+    /// a leading [`ResponseEvent::ModelFailover`].
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let mut failovers = Vec::new();
+        let stream = loop {
+            match self.current().stream_with_cassette(prompt).await {
+                Ok(stream) => break stream,
+                Err(err) => match self.fail_over(&err) {
+                    Some(failover) => failovers.push(failover),
+                    None => return Err(err),
+                },
+            }
+        };
+        if failovers.is_empty() {
+            return Ok(stream);
+        }
+
+        let ResponseStream { mut rx_event } = stream;
+        let (tx_event, rx) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(async move {
+            for failover in failovers {
+                if tx_event
+                    .send(Ok(ResponseEvent::ModelFailover(failover)))
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            while let Some(event) = rx_event.recv().await {
+                if tx_event.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(ResponseStream { rx_event: rx })
+    }
+
+    /// Switches to the next `model_fallbacks` entry if `err` means the active
+    /// provider cannot serve requests right now. Returns `None` when the error
+    /// is not eligible or the chain is exhausted.
+    pub fn fail_over(&self, err: &CodexErr) -> Option<ModelFailover> {
+        if !is_failover_error(err) {
+            return None;
+        }
+        let from_index = self.active_fallback.load(Ordering::SeqCst);
+        if from_index >= self.config.model_fallbacks.len() {
+            return None;
+        }
+        self.active_fallback.store(from_index + 1, Ordering::SeqCst);
+
+        let (from_provider, from_model) = match from_index {
+            0 => (&self.config.model_provider_id, &self.config.model),
+            n => {
+                let from = &self.config.model_fallbacks[n - 1];
+                (&from.model_provider_id, &from.model)
+            }
+        };
+        let to = &self.config.model_fallbacks[from_index];
+        let failover = ModelFailover {
+            from_provider: from_provider.clone(),
+            from_model: from_model.clone(),
+            to_provider: to.model_provider_id.clone(),
+            to_model: to.model.clone(),
+            reason: err.to_string(),
+        };
+        warn!(
+            "model provider `{}` failed ({}); falling back to `{}` with model `{}`",
+            failover.from_provider, failover.reason, failover.to_provider, failover.to_model
+        );
+        self.otel_event_manager.model_failover(
+            &failover.from_provider,
+            &failover.to_provider,
+            &failover.to_model,
+            &failover.reason,
+        );
+        Some(failover)
+    }
+
+    /// Returns this client configured for the active `model_fallbacks` entry.
+    fn current(&self) -> &ModelClient {
+        let index = self.active_fallback.load(Ordering::SeqCst);
+        let Some(slot) = index
+            .checked_sub(1)
+            .and_then(|i| self.fallback_clients.get(i))
+        else {
+            return self;
+        };
+        slot.get_or_init(|| self.fallback_client(index - 1))
+    }
+
+    fn fallback_client(&self, index: usize) -> ModelClient {
+        let fallback = &self.config.model_fallbacks[index];
+        let mut config = (*self.config).clone();
+        config.model = fallback.model.clone();
+        config.model_family = config
+            .model_family_for(&fallback.model)
+            .unwrap_or_else(|| derive_default_model_family(&fallback.model));
+        if let Some(info) = get_model_info(&config.model_family) {
+            config.model_context_window = Some(info.context_window);
+            config.model_max_output_tokens = Some(info.max_output_tokens);
+            config.model_auto_compact_token_limit = info.auto_compact_token_limit;
+        }
+        if let Some((_, family)) = config.model_family_override(&fallback.model) {
+            let family = family.clone();
+            config.model_context_window = family.context_window.or(config.model_context_window);
+            config.model_max_output_tokens =
+                family.max_output_tokens.or(config.model_max_output_tokens);
+            config.model_auto_compact_token_limit = family
+                .auto_compact_token_limit
+                .or(config.model_auto_compact_token_limit);
+        }
+        config.model_provider_id = fallback.model_provider_id.clone();
+        config.model_provider = fallback.model_provider.clone();
+
+        Self {
+            otel_event_manager: self
+                .otel_event_manager
+                .with_model(config.model.as_str(), config.model_family.slug.as_str()),
+            provider: fallback.model_provider.clone(),
+            config: Arc::new(config),
+            // The fallback client is only used through `current()`, so it
+            // does not need its own cache; sharing ours would be a cycle.
+            fallback_clients: Arc::from([]),
+            ..self.clone()
+        }
+    }
+
+    async fn stream_with_cassette(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let Some(cassette) = active_cassette()? else {
+            return self.stream_from_provider(prompt).await;
+        };
+        let request = self.cassette_request(prompt)?;
+        match cassette {
+            Cassette::Replay(player) => player.serve(&request),
+            Cassette::Record(recorder) => {
+                let stream = self.stream_from_provider(prompt).await?;
+                Ok(recorder.record(request, stream))
+            }
+        }
+    }
+
+    /// Wire-independent view of a request used to match cassette entries.
+    fn cassette_request(&self, prompt: &Prompt) -> Result<Value> {
+        let request = serde_json::json!({
+            "wire_api": self.provider.wire_api,
+            "model": self.config.model,
+            "instructions": prompt.get_full_instructions(&self.config.model_family),
+            "input": prompt.get_formatted_input(),
+            "tools": create_tools_json_for_responses_api(&prompt.tools)?,
+            "parallel_tool_calls": prompt.parallel_tool_calls,
+            "output_schema": prompt.output_schema,
+        });
+        Ok(normalize_request(
+            &request,
+            &self.config.cwd,
+            &self.config.codex_home,
+        ))
+    }
+
+    async fn stream_from_provider(&self, prompt: &Prompt) -> Result<ResponseStream> {
         match self.provider.wire_api {
             WireApi::Responses => self.stream_responses(prompt).await,
             WireApi::Chat => {
@@ -180,6 +372,19 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Messages => {
+                stream_messages(
+                    prompt,
+                    &self.config.model_family,
+                    self.effort,
+                    self.config.model_max_output_tokens,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                    &self.session_source,
+                )
+                .await
+            }
         }
     }
 
@@ -473,11 +678,16 @@ impl ModelClient {
     }
 
     pub fn get_provider(&self) -> ModelProviderInfo {
-        self.provider.clone()
+        self.current().provider.clone()
+    }
+
+    /// Returns the key of the provider currently serving requests.
+    pub fn get_model_provider_id(&self) -> String {
+        self.current().config.model_provider_id.clone()
     }
 
     pub fn get_otel_event_manager(&self) -> OtelEventManager {
-        self.otel_event_manager.clone()
+        self.current().otel_event_manager.clone()
     }
 
     pub fn get_session_source(&self) -> SessionSource {
@@ -486,12 +696,12 @@ impl ModelClient {
 
     /// Returns the currently configured model slug.
     pub fn get_model(&self) -> String {
-        self.config.model.clone()
+        self.current().config.model.clone()
     }
 
     /// Returns the currently configured model family.
     pub fn get_model_family(&self) -> ModelFamily {
-        self.config.model_family.clone()
+        self.current().config.model_family.clone()
     }
 
     /// Returns the current reasoning effort setting.
@@ -996,6 +1206,20 @@ fn is_context_window_error(error: &Error) -> bool {
     error.code.as_deref() == Some("context_length_exceeded")
 }
 
+/// Errors after which retrying the same provider is pointless for now.
+fn is_failover_error(err: &CodexErr) -> bool {
+    matches!(
+        err,
+        CodexErr::QuotaExceeded
+            | CodexErr::UsageLimitReached(_)
+            | CodexErr::RetryLimit(_)
+            | CodexErr::InternalServerError
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::ResponseStreamFailed(_)
+            | CodexErr::Stream(..)
+    )
+}
+
 fn is_quota_exceeded_error(error: &Error) -> bool {
     error.code.as_deref() == Some("insufficient_quota")
 }
@@ -1141,6 +1365,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1205,6 +1430,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1242,6 +1468,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1281,6 +1508,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1316,6 +1544,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1351,6 +1580,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1455,6 +1685,7 @@ mod tests {
                 stream_max_retries: Some(0),
                 stream_idle_timeout_ms: Some(1000),
                 requires_openai_auth: false,
+                stream_include_usage: false,
             };
 
             let otel_event_manager = otel_event_manager();
@@ -193,7 +193,8 @@ fn strip_total_output_header(output: &str) -> Option<&str> {
     Some(remainder)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ResponseEvent {
     Created,
     OutputItemDone(ResponseItem),
@@ -215,6 +216,19 @@ pub enum ResponseEvent {
         summary_index: i64,
     },
     RateLimits(RateLimitSnapshot),
+    /// The request was moved to the next `model_fallbacks` entry.
+    ModelFailover(ModelFailover),
+}
+
+/// A switch from one `model_fallbacks` entry to the next.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelFailover {
+    pub from_provider: String,
+    pub from_model: String,
+    pub to_provider: String,
+    pub to_model: String,
+    /// The error that triggered the switch.
+    pub reason: String,
 }
 
 #[derive(Debug, Serialize)]
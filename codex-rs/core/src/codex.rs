@@ -2,7 +2,11 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Weak;
 use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::AuthManager;
 use crate::client_common::REVIEW_PROMPT;
@@ -21,6 +25,7 @@ use async_channel::Receiver;
 use async_channel::Sender;
 use codex_protocol::ConversationId;
 use codex_protocol::items::TurnItem;
+use codex_protocol::num_format::format_with_separators;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
@@ -35,7 +40,6 @@ use codex_protocol::protocol::TurnContextItem;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
-use mcp_types::CallToolResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
@@ -54,9 +58,11 @@ use tracing::warn;
 
 use crate::ModelProviderInfo;
 use crate::client::ModelClient;
+use crate::client_common::ModelFailover;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
+use crate::config::types::Compaction;
 use crate::config::types::McpServerTransportConfig;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
@@ -71,9 +77,11 @@ use crate::compact::build_compacted_history;
 use crate::compact::collect_user_messages;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
-use crate::model_family::find_family_for_model;
+use crate::mcp_connection_manager::McpToolCallOutcome;
 use crate::openai_model_info::get_model_info;
 use crate::project_doc::get_user_instructions;
+use crate::project_doc::read_review_docs;
+use crate::prompt_cache::PromptFingerprint;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
@@ -94,13 +102,19 @@ use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
 use crate::protocol::StreamErrorEvent;
 use crate::protocol::Submission;
+use crate::protocol::TokenCost;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::TurnPhase;
 use crate::protocol::WarningEvent;
+use crate::review_rubrics::available_review_rubrics;
+use crate::review_rubrics::build_review_instructions;
+use crate::review_rubrics::resolve_review_rubrics;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
+use crate::rollout::policy::Redactor;
 use crate::shell;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
@@ -109,6 +123,7 @@ use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tasks::shadow_git_dir;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
@@ -141,6 +156,8 @@ pub struct Codex {
     pub(crate) next_id: AtomicU64,
     pub(crate) tx_sub: Sender<Submission>,
     pub(crate) rx_event: Receiver<Event>,
+    /// Weak so that the event stream still closes once the session ends.
+    pub(crate) session: Weak<Session>,
 }
 
 /// Wrapper returned by [`Codex::spawn`] containing the spawned [`Codex`],
@@ -178,6 +195,7 @@ impl Codex {
             user_instructions,
             base_instructions: config.base_instructions.clone(),
             compact_prompt: config.compact_prompt.clone(),
+            compaction: config.compaction.clone(),
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
@@ -203,12 +221,15 @@ impl Codex {
         })?;
         let conversation_id = session.conversation_id;
 
+        let weak_session = Arc::downgrade(&session);
+
         // This task will run until Op::Shutdown is received.
         tokio::spawn(submission_loop(session, config, rx_sub));
         let codex = Codex {
             next_id: AtomicU64::new(0),
             tx_sub,
             rx_event,
+            session: weak_session,
         };
 
         Ok(CodexSpawnOk {
@@ -228,6 +249,14 @@ impl Codex {
         Ok(id)
     }
 
+    /// Whether the session is running a turn. False once it has shut down.
+    pub(crate) async fn has_active_turn(&self) -> bool {
+        match self.session.upgrade() {
+            Some(session) => session.active_turn.lock().await.is_some(),
+            None => false,
+        }
+    }
+
     /// Use sparingly: prefer `submit()` so Codex is responsible for generating
     /// unique IDs for each submission.
     pub async fn submit_with_id(&self, sub: Submission) -> CodexResult<()> {
@@ -272,6 +301,7 @@ pub(crate) struct TurnContext {
     pub(crate) developer_instructions: Option<String>,
     pub(crate) base_instructions: Option<String>,
     pub(crate) compact_prompt: Option<String>,
+    pub(crate) compaction: Compaction,
     pub(crate) user_instructions: Option<String>,
     pub(crate) approval_policy: AskForApproval,
     pub(crate) sandbox_policy: SandboxPolicy,
@@ -320,6 +350,9 @@ pub(crate) struct SessionConfiguration {
     /// Compact prompt override.
     compact_prompt: Option<String>,
 
+    /// Compaction strategy and pinned files.
+    compaction: Compaction,
+
     /// When to escalate for approval for execution
     approval_policy: AskForApproval,
     /// How to sandbox commands executed in the system
@@ -386,18 +419,24 @@ impl Session {
         provider: ModelProviderInfo,
         session_configuration: &SessionConfiguration,
         conversation_id: ConversationId,
+        active_model_fallback: Arc<AtomicUsize>,
         sub_id: String,
     ) -> TurnContext {
         let config = session_configuration.original_config_do_not_use.clone();
-        let model_family = find_family_for_model(&session_configuration.model)
+        let model_family = config
+            .model_family_for(&session_configuration.model)
             .unwrap_or_else(|| config.model_family.clone());
         let mut per_turn_config = (*config).clone();
         per_turn_config.model = session_configuration.model.clone();
         per_turn_config.model_family = model_family.clone();
         per_turn_config.model_reasoning_effort = session_configuration.model_reasoning_effort;
         per_turn_config.model_reasoning_summary = session_configuration.model_reasoning_summary;
-        if let Some(model_info) = get_model_info(&model_family) {
-            per_turn_config.model_context_window = Some(model_info.context_window);
+        if let Some(context_window) = config
+            .model_family_override(&session_configuration.model)
+            .and_then(|(_, family)| family.context_window)
+            .or_else(|| get_model_info(&model_family).map(|info| info.context_window))
+        {
+            per_turn_config.model_context_window = Some(context_window);
         }
 
         let otel_event_manager = otel_event_manager.clone().with_model(
@@ -414,7 +453,8 @@ impl Session {
             session_configuration.model_reasoning_summary,
             conversation_id,
             session_configuration.session_source.clone(),
-        );
+        )
+        .with_active_fallback(active_model_fallback);
 
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
@@ -428,6 +468,7 @@ impl Session {
             developer_instructions: session_configuration.developer_instructions.clone(),
             base_instructions: session_configuration.base_instructions.clone(),
             compact_prompt: session_configuration.compact_prompt.clone(),
+            compaction: session_configuration.compaction.clone(),
             user_instructions: session_configuration.user_instructions.clone(),
             approval_policy: session_configuration.approval_policy,
             sandbox_policy: session_configuration.sandbox_policy.clone(),
@@ -490,6 +531,8 @@ impl Session {
         let mcp_fut = McpConnectionManager::new(
             config.mcp_servers.clone(),
             config.mcp_oauth_credentials_store_mode,
+            config.codex_linux_sandbox_exe.clone(),
+            config.cwd.clone(),
         );
         let default_shell_fut = shell::default_user_shell();
         let history_meta_fut = crate::message_history::history_metadata(&config);
@@ -604,6 +647,8 @@ impl Session {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            active_model_fallback: Arc::new(AtomicUsize::new(0)),
+            history_redactor: Arc::new(Redactor::new(&config.redaction_patterns)),
         };
 
         let sess = Arc::new(Session {
@@ -642,6 +687,68 @@ impl Session {
         Ok(sess)
     }
 
+    /// Builds a session that only hosts tool calls (see
+    /// [`crate::tools::standalone::StandaloneToolHost`]). It records no rollout,
+    /// starts no MCP servers and never talks to a model, but keeps an active
+    /// turn open so approval requests can be routed back through
+    /// [`Session::notify_approval`].
+    pub(crate) async fn new_standalone(
+        config: Arc<Config>,
+        auth_manager: Arc<AuthManager>,
+        tx_event: Sender<Event>,
+    ) -> Arc<Self> {
+        let conversation_id = ConversationId::default();
+        let session_configuration = SessionConfiguration {
+            provider: config.model_provider.clone(),
+            model: config.model.clone(),
+            model_reasoning_effort: config.model_reasoning_effort,
+            model_reasoning_summary: config.model_reasoning_summary,
+            developer_instructions: None,
+            user_instructions: None,
+            base_instructions: None,
+            compact_prompt: None,
+            compaction: config.compaction.clone(),
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            cwd: config.cwd.clone(),
+            original_config_do_not_use: Arc::clone(&config),
+            features: config.features.clone(),
+            session_source: SessionSource::Mcp,
+        };
+        let otel_event_manager = OtelEventManager::new(
+            conversation_id,
+            config.model.as_str(),
+            config.model_family.slug.as_str(),
+            auth_manager.auth().and_then(|a| a.get_account_id()),
+            auth_manager.auth().and_then(|a| a.get_account_email()),
+            auth_manager.auth().map(|a| a.mode),
+            config.otel.log_user_prompt,
+            terminal::user_agent(),
+        );
+        let services = SessionServices {
+            mcp_connection_manager: McpConnectionManager::default(),
+            unified_exec_manager: UnifiedExecSessionManager::default(),
+            notifier: UserNotifier::new(None),
+            rollout: Mutex::new(None),
+            user_shell: shell::default_user_shell().await,
+            show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            auth_manager,
+            otel_event_manager,
+            tool_approvals: Mutex::new(ApprovalStore::default()),
+            active_model_fallback: Arc::new(AtomicUsize::new(0)),
+            history_redactor: Arc::new(Redactor::new(&config.redaction_patterns)),
+        };
+
+        Arc::new(Session {
+            conversation_id,
+            tx_event,
+            state: Mutex::new(SessionState::new(session_configuration)),
+            active_turn: Mutex::new(Some(ActiveTurn::default())),
+            services,
+            next_internal_sub_id: AtomicU64::new(0),
+        })
+    }
+
     pub(crate) fn get_tx_event(&self) -> Sender<Event> {
         self.tx_event.clone()
     }
@@ -708,6 +815,13 @@ impl Session {
                     }
                 }
 
+                // Restore pins recorded by `Op::PinMessage`.
+                for item in &rollout_items {
+                    if let RolloutItem::EventMsg(EventMsg::MessagePinned(pinned)) = item {
+                        self.pin_message(pinned.text.clone()).await;
+                    }
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history =
                     self.reconstruct_history_from_rollout(&turn_context, &rollout_items);
@@ -744,6 +858,12 @@ impl Session {
         let session_configuration = {
             let mut state = self.state.lock().await;
             let session_configuration = state.session_configuration.clone().apply(&updates);
+            if session_configuration.model != state.session_configuration.model {
+                // A newly selected model starts over at the top of its chain.
+                self.services
+                    .active_model_fallback
+                    .store(0, std::sync::atomic::Ordering::SeqCst);
+            }
             state.session_configuration = session_configuration.clone();
             session_configuration
         };
@@ -754,6 +874,7 @@ impl Session {
             session_configuration.provider.clone(),
             &session_configuration,
             self.conversation_id,
+            Arc::clone(&self.services.active_model_fallback),
             sub_id,
         );
         if let Some(final_schema) = updates.final_output_json_schema {
@@ -901,7 +1022,11 @@ impl Session {
             parsed_cmd,
         });
         self.send_event(turn_context, event).await;
-        rx_approve.await.unwrap_or_default()
+        let started = Instant::now();
+        let decision = rx_approve.await.unwrap_or_default();
+        self.record_turn_phase(TurnPhase::ApprovalWait, started.elapsed())
+            .await;
+        decision
     }
 
     pub async fn request_patch_approval(
@@ -985,6 +1110,10 @@ impl Session {
                     history.record_items(std::iter::once(response_item));
                 }
                 RolloutItem::Compacted(compacted) => {
+                    if let Some(replacement_history) = &compacted.replacement_history {
+                        history.replace(replacement_history.clone());
+                        continue;
+                    }
                     let snapshot = history.get_history();
                     let user_messages = collect_user_messages(&snapshot);
                     let rebuilt = build_compacted_history(
@@ -1070,23 +1199,84 @@ impl Session {
         state.clone_history()
     }
 
+    pub(crate) async fn pin_message(&self, text: String) -> bool {
+        let mut state = self.state.lock().await;
+        state.pin_message(text)
+    }
+
+    pub(crate) async fn pinned_messages(&self) -> Vec<String> {
+        let state = self.state.lock().await;
+        state.pinned_messages.clone()
+    }
+
     pub(crate) async fn update_token_usage_info(
         &self,
         turn_context: &TurnContext,
         token_usage: Option<&TokenUsage>,
     ) {
-        {
-            let mut state = self.state.lock().await;
-            if let Some(token_usage) = token_usage {
+        if let Some(token_usage) = token_usage {
+            let cost_usd = turn_context
+                .client
+                .config()
+                .model_price_for(&turn_context.client.get_model())
+                .map(|price| price.cost_usd(token_usage));
+            {
+                let mut state = self.state.lock().await;
                 state.update_token_info_from_usage(
                     token_usage,
                     turn_context.client.get_model_context_window(),
                 );
+                state.record_spend(&turn_context.sub_id, token_usage, cost_usd);
+            }
+            let active = self.active_turn.lock().await;
+            if let Some(at) = active.as_ref() {
+                let mut ts = at.turn_state.lock().await;
+                ts.profile.record_usage(token_usage, cost_usd);
             }
         }
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Adds one occurrence of `phase` to the profile of the running turn.
+    pub(crate) async fn record_turn_phase(&self, phase: TurnPhase, elapsed: Duration) {
+        let active = self.active_turn.lock().await;
+        if let Some(at) = active.as_ref() {
+            let mut ts = at.turn_state.lock().await;
+            ts.profile.record(phase, elapsed);
+        }
+    }
+
+    /// Describes the exceeded limit once the session has used up
+    /// `max_session_tokens` or `max_session_cost`.
+    pub(crate) async fn session_budget_exceeded(
+        &self,
+        turn_context: &TurnContext,
+    ) -> Option<String> {
+        let config = turn_context.client.config();
+        let spend = {
+            let state = self.state.lock().await;
+            state.spend().clone()
+        };
+        if let Some(limit) = config.max_session_tokens
+            && spend.total_tokens >= limit
+        {
+            return Some(format!(
+                "Session token budget exceeded: used {} tokens (max_session_tokens = {}).",
+                format_with_separators(spend.total_tokens),
+                format_with_separators(limit)
+            ));
+        }
+        if let Some(limit) = config.max_session_cost
+            && let Some(cost_usd) = spend.cost_usd
+            && cost_usd >= limit
+        {
+            return Some(format!(
+                "Session cost budget exceeded: spent ${cost_usd:.2} (max_session_cost = ${limit:.2})."
+            ));
+        }
+        None
+    }
+
     pub(crate) async fn override_last_token_usage_estimate(
         &self,
         turn_context: &TurnContext,
@@ -1130,14 +1320,57 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, cost, prompt_cache) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            let spend = state.spend();
+            let cost = spend.cost_usd.map(|session_usd| TokenCost {
+                turn_usd: spend.turn_cost_usd,
+                session_usd,
+            });
+            (info, rate_limits, cost, spend.prompt_cache())
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info,
+            rate_limits,
+            cost,
+            prompt_cache,
+        });
         self.send_event(turn_context, event).await;
     }
 
+    /// Remembers the shape of `prompt` and warns when it rewrote part of the
+    /// prefix sent with the previous request, which defeats prompt caching.
+    async fn check_prompt_cache_prefix(&self, turn_context: &TurnContext, prompt: &Prompt) {
+        let model_family = turn_context.client.get_model_family();
+        let fingerprint = PromptFingerprint::new(
+            &turn_context.client.get_model(),
+            &prompt.get_full_instructions(&model_family),
+            &prompt.tools,
+            &prompt.input,
+        );
+        let prefix_break = {
+            let mut state = self.state.lock().await;
+            let previous = state.last_prompt.replace(fingerprint);
+            previous.and_then(|previous| {
+                state
+                    .last_prompt
+                    .as_ref()
+                    .and_then(|next| previous.prefix_break(next))
+            })
+        };
+        if let Some(prefix_break) = prefix_break {
+            warn!("prompt cache prefix rewritten: {prefix_break:?}");
+            self.send_event(
+                turn_context,
+                EventMsg::Warning(WarningEvent {
+                    message: prefix_break.warning_message(),
+                }),
+            )
+            .await;
+        }
+    }
+
     pub(crate) async fn set_total_tokens_full(&self, turn_context: &TurnContext) {
         let context_window = turn_context.client.get_model_context_window();
         if let Some(context_window) = context_window {
@@ -1181,6 +1414,37 @@ impl Session {
         self.send_event(turn_context, event).await;
     }
 
+    /// Announces a switch to the next `model_fallbacks` entry and records
+    /// the new provider and model in the rollout.
+    pub(crate) async fn record_model_failover(
+        &self,
+        turn_context: &TurnContext,
+        failover: &ModelFailover,
+    ) {
+        self.notify_background_event(
+            turn_context,
+            format!(
+                "{} ({}) failed: {}. Switching to {} ({}).",
+                failover.from_model,
+                failover.from_provider,
+                failover.reason,
+                failover.to_model,
+                failover.to_provider
+            ),
+        )
+        .await;
+        let rollout_item = RolloutItem::TurnContext(TurnContextItem {
+            cwd: turn_context.cwd.clone(),
+            approval_policy: turn_context.approval_policy,
+            sandbox_policy: turn_context.sandbox_policy.clone(),
+            model: failover.to_model.clone(),
+            model_provider: Some(failover.to_provider.clone()),
+            effort: turn_context.client.get_reasoning_effort(),
+            summary: turn_context.client.get_reasoning_summary(),
+        });
+        self.persist_rollout_items(&[rollout_item]).await;
+    }
+
     pub(crate) async fn notify_stream_error(
         &self,
         turn_context: &TurnContext,
@@ -1197,16 +1461,21 @@ impl Session {
         turn_context: Arc<TurnContext>,
         cancellation_token: CancellationToken,
     ) {
-        if !self
-            .state
-            .lock()
-            .await
-            .session_configuration
-            .features
-            .enabled(Feature::GhostCommit)
-        {
-            return;
-        }
+        let codex_home = {
+            let state = self.state.lock().await;
+            if !state
+                .session_configuration
+                .features
+                .enabled(Feature::GhostCommit)
+            {
+                return;
+            }
+            state
+                .session_configuration
+                .original_config_do_not_use
+                .codex_home
+                .clone()
+        };
         let token = match turn_context.tool_call_gate.subscribe().await {
             Ok(token) => token,
             Err(err) => {
@@ -1216,7 +1485,7 @@ impl Session {
         };
 
         info!("spawning ghost snapshot task");
-        let task = GhostSnapshotTask::new(token);
+        let task = GhostSnapshotTask::new(token, shadow_git_dir(&codex_home, &turn_context.cwd));
         Arc::new(task)
             .run(
                 Arc::new(SessionTaskContext::new(self.clone())),
@@ -1289,7 +1558,7 @@ impl Session {
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
-    ) -> anyhow::Result<CallToolResult> {
+    ) -> McpToolCallOutcome {
         self.services
             .mcp_connection_manager
             .call_tool(server, tool, arguments)
@@ -1377,9 +1646,18 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
+            Op::ListSnapshots => {
+                handlers::list_snapshots(&sess, sub.id.clone()).await;
+            }
+            Op::RestoreSnapshot { id, paths } => {
+                handlers::restore_snapshot(&sess, sub.id.clone(), id, paths).await;
+            }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
             }
+            Op::PinMessage { text } => {
+                handlers::pin_message(&sess, sub.id.clone(), text).await;
+            }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
                     &sess,
@@ -1414,18 +1692,24 @@ mod handlers {
     use crate::mcp::auth::compute_auth_statuses;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
+    use crate::tasks::RestoreSnapshotTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
+    use crate::tasks::snapshot_summaries;
     use codex_protocol::custom_prompts::CustomPrompt;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::ListSnapshotsResponseEvent;
+    use codex_protocol::protocol::MessagePinnedEvent;
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::TurnAbortedEvent;
     use codex_protocol::user_input::UserInput;
+    use std::path::PathBuf;
     use std::sync::Arc;
     use tracing::info;
     use tracing::warn;
@@ -1466,7 +1750,16 @@ mod handlers {
                     final_output_json_schema: Some(final_output_json_schema),
                 },
             ),
-            Op::UserInput { items } => (items, SessionSettingsUpdate::default()),
+            Op::UserInput {
+                items,
+                final_output_json_schema,
+            } => (
+                items,
+                SessionSettingsUpdate {
+                    final_output_json_schema: Some(final_output_json_schema),
+                    ..Default::default()
+                },
+            ),
             _ => unreachable!(),
         };
 
@@ -1476,6 +1769,16 @@ mod handlers {
             .get_otel_event_manager()
             .user_prompt(&items);
 
+        if let Some(message) = sess.session_budget_exceeded(&current_context).await {
+            sess.send_event(&current_context, EventMsg::Error(ErrorEvent { message }))
+                .await;
+            let event = EventMsg::TurnAborted(TurnAbortedEvent {
+                reason: TurnAbortReason::BudgetExceeded,
+            });
+            sess.send_event(&current_context, event).await;
+            return;
+        }
+
         // Attempt to inject input into current task
         if let Err(items) = sess.inject_input(items).await {
             if let Some(env_item) =
@@ -1530,8 +1833,11 @@ mod handlers {
     pub async fn add_to_history(sess: &Arc<Session>, config: &Arc<Config>, text: String) {
         let id = sess.conversation_id;
         let config = Arc::clone(config);
+        let redactor = Arc::clone(&sess.services.history_redactor);
         tokio::spawn(async move {
-            if let Err(e) = crate::message_history::append_entry(&text, &id, &config).await {
+            if let Err(e) =
+                crate::message_history::append_entry(&text, &id, &config, &redactor).await
+            {
                 warn!("failed to append to message history: {e}");
             }
         });
@@ -1620,6 +1926,18 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn pin_message(sess: &Session, sub_id: String, text: String) {
+        if !sess.pin_message(text.clone()).await {
+            return;
+        }
+        // The event is what records the pin in the rollout.
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::MessagePinned(MessagePinnedEvent { text }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1628,6 +1946,42 @@ mod handlers {
             .await;
     }
 
+    pub async fn list_snapshots(sess: &Session, sub_id: String) {
+        let cwd = sess.state.lock().await.session_configuration.cwd.clone();
+        let items = sess.clone_history().await.get_history();
+        let snapshots =
+            match tokio::task::spawn_blocking(move || snapshot_summaries(&cwd, &items)).await {
+                Ok(snapshots) => snapshots,
+                Err(err) => {
+                    warn!("failed to summarize ghost snapshots: {err}");
+                    Vec::new()
+                }
+            };
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListSnapshotsResponse(ListSnapshotsResponseEvent { snapshots }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn restore_snapshot(
+        sess: &Arc<Session>,
+        sub_id: String,
+        id: String,
+        paths: Vec<PathBuf>,
+    ) {
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
+            .await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            RestoreSnapshotTask::new(id, paths),
+        )
+        .await;
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1704,7 +2058,8 @@ async fn spawn_review_thread(
     review_request: ReviewRequest,
 ) {
     let model = config.review_model.clone();
-    let review_model_family = find_family_for_model(&model)
+    let review_model_family = config
+        .model_family_for(&model)
         .unwrap_or_else(|| parent_turn_context.client.get_model_family());
     // For reviews, disable web_search and view_image regardless of global settings.
     let mut review_features = config.features.clone();
@@ -1716,7 +2071,31 @@ async fn spawn_review_thread(
         features: &review_features,
     });
 
-    let base_instructions = REVIEW_PROMPT.to_string();
+    let (rubrics, unknown_rubrics) = resolve_review_rubrics(&config, &review_request.rubrics);
+    if !unknown_rubrics.is_empty() {
+        let message = format!(
+            "Ignoring unknown review rubric(s): {}. Available rubrics: {}",
+            unknown_rubrics.join(", "),
+            available_review_rubrics(&config).join(", ")
+        );
+        sess.send_event(
+            &parent_turn_context,
+            EventMsg::Warning(WarningEvent { message }),
+        )
+        .await;
+    }
+    // REVIEW.md files are looked up from the turn's cwd, like AGENTS.md.
+    let mut review_doc_config = (*config).clone();
+    review_doc_config.cwd = parent_turn_context.cwd.clone();
+    let repo_review_instructions = match read_review_docs(&review_doc_config).await {
+        Ok(instructions) => instructions,
+        Err(e) => {
+            error!("error trying to find review doc: {e:#}");
+            None
+        }
+    };
+    let base_instructions =
+        build_review_instructions(REVIEW_PROMPT, repo_review_instructions.as_deref(), &rubrics);
     let review_prompt = review_request.prompt.clone();
     let provider = parent_turn_context.client.get_provider();
     let auth_manager = parent_turn_context.client.get_auth_manager();
@@ -1760,6 +2139,7 @@ async fn spawn_review_thread(
         user_instructions: None,
         base_instructions: Some(base_instructions.clone()),
         compact_prompt: parent_turn_context.compact_prompt.clone(),
+        compaction: parent_turn_context.compaction.clone(),
         approval_policy: parent_turn_context.approval_policy,
         sandbox_policy: parent_turn_context.sandbox_policy.clone(),
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
@@ -1832,12 +2212,39 @@ pub(crate) async fn run_task(
             .collect::<Vec<ResponseItem>>();
 
         // Construct the input that we will send to the model.
-        let turn_input: Vec<ResponseItem> = {
+        let (turn_input, estimated_request_tokens) = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
-            sess.clone_history().await.get_history_for_prompt()
+            let mut history = sess.clone_history().await;
+            let estimated_request_tokens = history.estimate_next_request_tokens(&turn_context);
+            (history.get_history_for_prompt(), estimated_request_tokens)
         };
 
+        // Compact before sending when the items recorded since the model last
+        // reported usage (e.g. a large tool output) would push the request
+        // over the limit, instead of waiting for a context window error.
+        if let Some(limit) = turn_context.client.get_auto_compact_token_limit()
+            && let Some(tokens) = estimated_request_tokens.filter(|tokens| *tokens >= limit)
+        {
+            compact::run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
+            // A failed compaction leaves the history, and so the estimate,
+            // unchanged; retrying would compact forever.
+            let tokens_after = sess
+                .clone_history()
+                .await
+                .estimate_next_request_tokens(&turn_context);
+            if tokens_after.is_none_or(|after| after < tokens) {
+                continue;
+            }
+            let event = EventMsg::Error(ErrorEvent {
+                message: format!(
+                    "The next request needs about {tokens} tokens, over the auto-compact limit of {limit}, and compacting the conversation did not free any space. Start a new conversation to continue."
+                ),
+            });
+            sess.send_event(&turn_context, event).await;
+            break;
+        }
+
         let turn_input_messages = turn_input
             .iter()
             .filter_map(|item| match parse_turn_item(item) {
@@ -1873,6 +2280,14 @@ pub(crate) async fn run_task(
                 let (responses, items_to_record_in_conversation_history) =
                     process_items(processed_items, &sess, &turn_context).await;
 
+                // Stop before sending another request once the session is over budget.
+                if (token_limit_reached || !responses.is_empty())
+                    && let Some(message) = sess.session_budget_exceeded(&turn_context).await
+                {
+                    abort_over_budget(&sess, &turn_context, message, &cancellation_token).await;
+                    return None;
+                }
+
                 // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
                 if token_limit_reached {
                     compact::run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
@@ -1917,6 +2332,25 @@ pub(crate) async fn run_task(
     last_agent_message
 }
 
+/// Reports the exceeded budget and aborts the running task with
+/// [`TurnAbortReason::BudgetExceeded`]. The abort runs on its own task because
+/// `abort_all_tasks` waits for this one to wind down; we return once it has
+/// cancelled us so no `TaskComplete` is emitted.
+async fn abort_over_budget(
+    sess: &Arc<Session>,
+    turn_context: &TurnContext,
+    message: String,
+    cancellation_token: &CancellationToken,
+) {
+    sess.send_event(turn_context, EventMsg::Error(ErrorEvent { message }))
+        .await;
+    let sess = Arc::clone(sess);
+    tokio::spawn(async move {
+        sess.abort_all_tasks(TurnAbortReason::BudgetExceeded).await;
+    });
+    cancellation_token.cancelled().await;
+}
+
 async fn run_turn(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
@@ -1942,6 +2376,7 @@ async fn run_turn(
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: turn_context.final_output_json_schema.clone(),
     };
+    sess.check_prompt_cache_prefix(&turn_context, &prompt).await;
 
     let mut retries = 0;
     loop {
@@ -1975,15 +2410,31 @@ async fn run_turn(
                 if let Some(rate_limits) = rate_limits {
                     sess.update_rate_limits(&turn_context, rate_limits).await;
                 }
-                return Err(CodexErr::UsageLimitReached(e));
+                let e = CodexErr::UsageLimitReached(e);
+                let Some(failover) = turn_context.client.fail_over(&e) else {
+                    return Err(e);
+                };
+                sess.record_model_failover(&turn_context, &failover).await;
+                retries = 0;
             }
             Err(CodexErr::UsageNotIncluded) => return Err(CodexErr::UsageNotIncluded),
-            Err(e @ CodexErr::QuotaExceeded) => return Err(e),
+            Err(e @ CodexErr::QuotaExceeded) => {
+                let Some(failover) = turn_context.client.fail_over(&e) else {
+                    return Err(e);
+                };
+                sess.record_model_failover(&turn_context, &failover).await;
+                retries = 0;
+            }
             Err(e @ CodexErr::RefreshTokenFailed(_)) => return Err(e),
             Err(e) => {
                 // Use the configured provider-specific stream retry budget.
                 let max_retries = turn_context.client.get_provider().stream_max_retries();
-                if retries < max_retries {
+                if retries >= max_retries
+                    && let Some(failover) = turn_context.client.fail_over(&e)
+                {
+                    sess.record_model_failover(&turn_context, &failover).await;
+                    retries = 0;
+                } else if retries < max_retries {
                     retries += 1;
                     let delay = match e {
                         CodexErr::Stream(_, Some(delay)) => delay,
@@ -2041,11 +2492,13 @@ async fn try_run_turn(
         approval_policy: turn_context.approval_policy,
         sandbox_policy: turn_context.sandbox_policy.clone(),
         model: turn_context.client.get_model(),
+        model_provider: Some(turn_context.client.get_model_provider_id()),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
     });
 
     sess.persist_rollout_items(&[rollout_item]).await;
+    let request_started = Instant::now();
     let mut stream = turn_context
         .client
         .clone()
@@ -2063,6 +2516,7 @@ async fn try_run_turn(
         FuturesOrdered::new();
 
     let mut active_item: Option<TurnItem> = None;
+    let mut first_output_at: Option<Instant> = None;
 
     loop {
         // Poll the next item from the model stream. We must inspect *both* Ok and Err
@@ -2088,6 +2542,23 @@ async fn try_run_turn(
             }
         };
 
+        if first_output_at.is_none()
+            && !matches!(
+                event,
+                ResponseEvent::Created
+                    | ResponseEvent::RateLimits(_)
+                    | ResponseEvent::ModelFailover(_)
+            )
+        {
+            let now = Instant::now();
+            sess.record_turn_phase(
+                TurnPhase::ModelTimeToFirstToken,
+                now.duration_since(request_started),
+            )
+            .await;
+            first_output_at = Some(now);
+        }
+
         let add_completed = &mut |response_item: ProcessedResponseItem| {
             output.push_back(future::ready(Ok(response_item)).boxed());
         };
@@ -2181,10 +2652,17 @@ async fn try_run_turn(
                 // token usage is available to avoid duplicate TokenCount events.
                 sess.update_rate_limits(&turn_context, snapshot).await;
             }
+            ResponseEvent::ModelFailover(failover) => {
+                sess.record_model_failover(&turn_context, &failover).await;
+            }
             ResponseEvent::Completed {
                 response_id: _,
                 token_usage,
             } => {
+                if let Some(first_output_at) = first_output_at {
+                    sess.record_turn_phase(TurnPhase::ModelStreaming, first_output_at.elapsed())
+                        .await;
+                }
                 sess.update_token_usage_info(&turn_context, token_usage.as_ref())
                     .await;
                 let processed_items = output.try_collect().await?;
@@ -2374,9 +2852,11 @@ mod tests {
     use crate::exec::ExecToolCallOutput;
     use crate::mcp::auth::McpAuthStatusEntry;
     use crate::tools::format_exec_output_str;
+    use mcp_types::CallToolResult;
 
     use crate::protocol::CompactedItem;
     use crate::protocol::InitialHistory;
+    use crate::protocol::MessagePinnedEvent;
     use crate::protocol::ResumedHistory;
     use crate::state::TaskKind;
     use crate::tasks::SessionTask;
@@ -2415,6 +2895,27 @@ mod tests {
         assert_eq!(expected, reconstructed);
     }
 
+    #[test]
+    fn reconstruct_history_uses_recorded_replacement_history() {
+        let (session, turn_context) = make_session_and_context();
+        let (mut rollout_items, _) = sample_rollout(&session, &turn_context);
+        let kept = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "kept verbatim".to_string(),
+            }],
+        };
+        rollout_items.push(RolloutItem::Compacted(CompactedItem {
+            message: String::new(),
+            replacement_history: Some(vec![kept.clone()]),
+        }));
+
+        let reconstructed = session.reconstruct_history_from_rollout(&turn_context, &rollout_items);
+
+        assert_eq!(vec![kept], reconstructed);
+    }
+
     #[test]
     fn record_initial_history_reconstructs_resumed_transcript() {
         let (session, turn_context) = make_session_and_context();
@@ -2447,6 +2948,32 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn record_initial_history_restores_pinned_messages() {
+        let (session, turn_context) = make_session_and_context();
+        let (mut rollout_items, _) = sample_rollout(&session, &turn_context);
+        for text in ["keep the API stable", "keep the API stable", "use tabs"] {
+            rollout_items.push(RolloutItem::EventMsg(EventMsg::MessagePinned(
+                MessagePinnedEvent {
+                    text: text.to_string(),
+                },
+            )));
+        }
+
+        tokio_test::block_on(session.record_initial_history(InitialHistory::Resumed(
+            ResumedHistory {
+                conversation_id: ConversationId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            },
+        )));
+
+        assert_eq!(
+            vec!["keep the API stable".to_string(), "use tabs".to_string()],
+            tokio_test::block_on(session.pinned_messages())
+        );
+    }
+
     #[test]
     fn prefers_structured_content_when_present() {
         let ctr = CallToolResult {
@@ -2595,6 +3122,7 @@ mod tests {
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
             compact_prompt: config.compact_prompt.clone(),
+            compaction: config.compaction.clone(),
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
@@ -2615,6 +3143,8 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            active_model_fallback: Arc::new(AtomicUsize::new(0)),
+            history_redactor: Arc::new(Redactor::new(&config.redaction_patterns)),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2623,6 +3153,7 @@ mod tests {
             session_configuration.provider.clone(),
             &session_configuration,
             conversation_id,
+            Arc::clone(&services.active_model_fallback),
             "turn_id".to_string(),
         );
 
@@ -2671,6 +3202,7 @@ mod tests {
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
             compact_prompt: config.compact_prompt.clone(),
+            compaction: config.compaction.clone(),
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
@@ -2691,6 +3223,8 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            active_model_fallback: Arc::new(AtomicUsize::new(0)),
+            history_redactor: Arc::new(Redactor::new(&config.redaction_patterns)),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -2699,6 +3233,7 @@ mod tests {
             session_configuration.provider.clone(),
             &session_configuration,
             conversation_id,
+            Arc::clone(&services.active_model_fallback),
             "turn_id".to_string(),
         ));
 
@@ -2941,6 +3476,7 @@ mod tests {
         live_history.replace(rebuilt1);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary1.to_string(),
+            replacement_history: None,
         }));
 
         let user2 = ResponseItem::Message {
@@ -2974,6 +3510,7 @@ mod tests {
         live_history.replace(rebuilt2);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
             message: summary2.to_string(),
+            replacement_history: None,
         }));
 
         let user3 = ResponseItem::Message {
@@ -3184,6 +3721,10 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -3228,6 +3769,10 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -33,6 +33,11 @@ impl CodexConversation {
         self.codex.next_event().await
     }
 
+    /// Whether a turn is running in this conversation.
+    pub async fn has_active_turn(&self) -> bool {
+        self.codex.has_active_turn().await
+    }
+
     pub fn rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
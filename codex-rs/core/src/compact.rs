@@ -1,3 +1,5 @@
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::Prompt;
@@ -9,6 +11,7 @@ use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::protocol::AgentMessageEvent;
 use crate::protocol::CompactedItem;
+use crate::protocol::ContextCompactedEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::TaskStartedEvent;
@@ -16,23 +19,46 @@ use crate::protocol::TurnContextItem;
 use crate::protocol::WarningEvent;
 use crate::truncate::truncate_middle;
 use crate::util::backoff;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
 use futures::prelude::*;
 use tracing::error;
+use tracing::warn;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../templates/compact/prompt.md");
 pub const SUMMARY_PREFIX: &str = include_str!("../templates/compact/summary_prefix.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
+const PINNED_FILE_MAX_BYTES: usize = 32 * 1024;
+const PINNED_FILE_OPEN: &str = "<pinned_file";
+const PINNED_FILE_CLOSE: &str = "</pinned_file>";
+const OMITTED_TOOL_OUTPUT: &str = "[tool output omitted during compaction]";
 
 pub(crate) async fn run_inline_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
 ) {
+    let strategy = turn_context.compaction.strategy;
+    if strategy != CompactionStrategy::SummarizeAll {
+        compact_without_model(&sess, &turn_context, strategy).await;
+        // Dropping content may not be enough to get under the limit, in which
+        // case summarizing is the only way to keep the conversation going.
+        let Some(limit) = turn_context.client.get_auto_compact_token_limit() else {
+            return;
+        };
+        let estimated_tokens = sess
+            .clone_history()
+            .await
+            .estimate_token_count(&turn_context);
+        if estimated_tokens < limit {
+            return;
+        }
+    }
     let prompt = turn_context.compact_prompt().to_string();
     let input = vec![UserInput::Text { text: prompt }];
     run_compact_task_inner(sess, turn_context, input).await;
@@ -47,10 +73,47 @@ pub(crate) async fn run_compact_task(
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, start_event).await;
-    run_compact_task_inner(sess.clone(), turn_context, input).await;
+    match turn_context.compaction.strategy {
+        CompactionStrategy::SummarizeAll => {
+            run_compact_task_inner(sess.clone(), turn_context, input).await;
+        }
+        strategy @ (CompactionStrategy::DropOldToolOutputs | CompactionStrategy::SlidingWindow) => {
+            compact_without_model(&sess, &turn_context, strategy).await;
+        }
+    }
     None
 }
 
+/// Compacts the history with a strategy that does not call the model.
+async fn compact_without_model(
+    sess: &Session,
+    turn_context: &TurnContext,
+    strategy: CompactionStrategy,
+) {
+    let mut history = sess.clone_history().await;
+    let tokens_before = history.estimate_token_count(turn_context);
+    let items = history.get_history();
+    let keep_recent_turns = turn_context.compaction.keep_recent_turns;
+    let new_history = if strategy == CompactionStrategy::SlidingWindow {
+        sliding_window(
+            sess.build_initial_context(turn_context),
+            items,
+            keep_recent_turns,
+        )
+    } else {
+        drop_old_tool_outputs(items, keep_recent_turns)
+    };
+    finish_compaction(
+        sess,
+        turn_context,
+        strategy,
+        tokens_before,
+        String::new(),
+        new_history,
+    )
+    .await;
+}
+
 async fn run_compact_task_inner(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
@@ -59,6 +122,7 @@ async fn run_compact_task_inner(
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
 
     let mut history = sess.clone_history().await;
+    let tokens_before = history.estimate_token_count(&turn_context);
     history.record_items(&[initial_input_for_turn.into()]);
 
     let mut truncated_count = 0usize;
@@ -71,6 +135,7 @@ async fn run_compact_task_inner(
         approval_policy: turn_context.approval_policy,
         sandbox_policy: turn_context.sandbox_policy.clone(),
         model: turn_context.client.get_model(),
+        model_provider: Some(turn_context.client.get_model_provider_id()),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
     });
@@ -154,21 +219,15 @@ async fn run_compact_task_inner(
         .cloned()
         .collect();
     new_history.extend(ghost_snapshots);
-    sess.replace_history(new_history).await;
-
-    if let Some(estimated_tokens) = sess
-        .clone_history()
-        .await
-        .estimate_token_count(&turn_context)
-    {
-        sess.override_last_token_usage_estimate(&turn_context, estimated_tokens)
-            .await;
-    }
-
-    let rollout_item = RolloutItem::Compacted(CompactedItem {
-        message: summary_text.clone(),
-    });
-    sess.persist_rollout_items(&[rollout_item]).await;
+    finish_compaction(
+        &sess,
+        &turn_context,
+        CompactionStrategy::SummarizeAll,
+        tokens_before,
+        summary_text,
+        new_history,
+    )
+    .await;
 
     let event = EventMsg::AgentMessage(AgentMessageEvent {
         message: "Compact task completed".to_string(),
@@ -181,6 +240,206 @@ async fn run_compact_task_inner(
     sess.send_event(&turn_context, warning).await;
 }
 
+/// Adds the pins to `new_history`, installs it, records the compaction in the
+/// rollout and reports it with `ContextCompacted`.
+async fn finish_compaction(
+    sess: &Session,
+    turn_context: &TurnContext,
+    strategy: CompactionStrategy,
+    tokens_before: i64,
+    summary_text: String,
+    new_history: Vec<ResponseItem>,
+) {
+    let pinned_messages = sess.pinned_messages().await;
+    let pinned_files = read_pinned_files(turn_context).await;
+    // A plain summary can be rebuilt from the rollout on resume; anything
+    // else needs the resulting history recorded verbatim.
+    let replayable = strategy == CompactionStrategy::SummarizeAll
+        && pinned_messages.is_empty()
+        && pinned_files.is_empty();
+    let pinned_file_items = pinned_files
+        .iter()
+        .map(|(path, contents)| pinned_file_item(path, contents))
+        .collect();
+    let new_history = apply_pins(new_history, &pinned_messages, pinned_file_items);
+    sess.replace_history(new_history.clone()).await;
+
+    let tokens_after = sess
+        .clone_history()
+        .await
+        .estimate_token_count(turn_context);
+    sess.override_last_token_usage_estimate(turn_context, tokens_after)
+        .await;
+
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message: summary_text,
+        replacement_history: (!replayable).then_some(new_history),
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+
+    let event = EventMsg::ContextCompacted(ContextCompactedEvent {
+        strategy,
+        tokens_before,
+        tokens_after,
+        pinned_messages: pinned_messages.len(),
+        pinned_files: pinned_files.into_iter().map(|(path, _)| path).collect(),
+    });
+    sess.send_event(turn_context, event).await;
+}
+
+/// Reads the configured pinned files, skipping those that cannot be read.
+async fn read_pinned_files(turn_context: &TurnContext) -> Vec<(PathBuf, String)> {
+    let mut files = Vec::new();
+    for path in &turn_context.compaction.pinned_files {
+        match tokio::fs::read_to_string(turn_context.cwd.join(path)).await {
+            Ok(contents) => {
+                let (contents, _) = truncate_middle(&contents, PINNED_FILE_MAX_BYTES);
+                files.push((path.clone(), contents));
+            }
+            Err(err) => warn!("failed to read pinned file {}: {err}", path.display()),
+        }
+    }
+    files
+}
+
+fn pinned_file_item(path: &Path, contents: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!(
+                "{PINNED_FILE_OPEN} path=\"{}\">\n{contents}\n{PINNED_FILE_CLOSE}",
+                path.display()
+            ),
+        }],
+    }
+}
+
+pub(crate) fn is_pinned_file_text(text: &str) -> bool {
+    text.trim_start().starts_with(PINNED_FILE_OPEN)
+}
+
+/// Puts the pins right before the first user message of `history`: the
+/// current contents of the pinned files, replacing any older copies, then the
+/// pinned messages the strategy did not already keep.
+fn apply_pins(
+    history: Vec<ResponseItem>,
+    pinned_messages: &[String],
+    pinned_file_items: Vec<ResponseItem>,
+) -> Vec<ResponseItem> {
+    let mut history: Vec<ResponseItem> = history
+        .into_iter()
+        .filter(|item| !is_pinned_file_item(item))
+        .collect();
+    let kept_messages = collect_user_messages(&history);
+    let missing_messages = pinned_messages
+        .iter()
+        .filter(|message| !kept_messages.contains(message))
+        .map(|message| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: message.clone(),
+            }],
+        });
+    let insert_at = history
+        .iter()
+        .position(is_user_turn_item)
+        .unwrap_or(history.len());
+    let pins: Vec<ResponseItem> = pinned_file_items
+        .into_iter()
+        .chain(missing_messages)
+        .collect();
+    history.splice(insert_at..insert_at, pins);
+    history
+}
+
+fn is_pinned_file_item(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "user" => {
+            content_items_to_text(content).is_some_and(|text| is_pinned_file_text(&text))
+        }
+        _ => false,
+    }
+}
+
+fn is_user_turn_item(item: &ResponseItem) -> bool {
+    matches!(
+        crate::event_mapping::parse_turn_item(item),
+        Some(TurnItem::UserMessage(user)) if !is_summary_message(&user.message())
+    )
+}
+
+/// Index of the user message that starts the `keep_recent_turns`-th most
+/// recent turn, or `None` when the history has no more turns than that.
+fn recent_turns_start(items: &[ResponseItem], keep_recent_turns: usize) -> Option<usize> {
+    let turn_starts: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| is_user_turn_item(item))
+        .map(|(index, _)| index)
+        .collect();
+    let first_kept = turn_starts.len().checked_sub(keep_recent_turns.max(1))?;
+    turn_starts.get(first_kept).copied()
+}
+
+/// Replaces the output of tool calls made before the most recent turns with a
+/// placeholder. The calls themselves are kept so every output keeps its call.
+fn drop_old_tool_outputs(items: Vec<ResponseItem>, keep_recent_turns: usize) -> Vec<ResponseItem> {
+    let Some(cutoff) = recent_turns_start(&items, keep_recent_turns) else {
+        return items;
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            if index >= cutoff {
+                return item;
+            }
+            match item {
+                ResponseItem::FunctionCallOutput { call_id, output } => {
+                    ResponseItem::FunctionCallOutput {
+                        call_id,
+                        output: FunctionCallOutputPayload {
+                            content: OMITTED_TOOL_OUTPUT.to_string(),
+                            content_items: None,
+                            success: output.success,
+                        },
+                    }
+                }
+                ResponseItem::CustomToolCallOutput { call_id, .. } => {
+                    ResponseItem::CustomToolCallOutput {
+                        call_id,
+                        output: OMITTED_TOOL_OUTPUT.to_string(),
+                    }
+                }
+                other => other,
+            }
+        })
+        .collect()
+}
+
+/// Keeps the initial context and the most recent turns. Ghost snapshots from
+/// the dropped turns are kept so `/undo` and `/timeline` still work.
+fn sliding_window(
+    initial_context: Vec<ResponseItem>,
+    mut items: Vec<ResponseItem>,
+    keep_recent_turns: usize,
+) -> Vec<ResponseItem> {
+    let Some(cutoff) = recent_turns_start(&items, keep_recent_turns) else {
+        return items;
+    };
+    let mut history = initial_context;
+    let recent = items.split_off(cutoff);
+    history.extend(
+        items
+            .into_iter()
+            .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. })),
+    );
+    history.extend(recent);
+    history
+}
+
 pub fn content_items_to_text(content: &[ContentItem]) -> Option<String> {
     let mut pieces = Vec::new();
     for item in content {
@@ -304,6 +563,9 @@ async fn drain_to_completed(
             Ok(ResponseEvent::RateLimits(snapshot)) => {
                 sess.update_rate_limits(turn_context, snapshot).await;
             }
+            Ok(ResponseEvent::ModelFailover(failover)) => {
+                sess.record_model_failover(turn_context, &failover).await;
+            }
             Ok(ResponseEvent::Completed { token_usage, .. }) => {
                 sess.update_token_usage_info(turn_context, token_usage.as_ref())
                     .await;
@@ -470,4 +732,126 @@ mod tests {
         };
         assert_eq!(summary, summary_text);
     }
+
+    fn user(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn drop_old_tool_outputs_keeps_recent_turns_intact() {
+        let items = vec![
+            user("first"),
+            call("call-1"),
+            output("call-1", "old output"),
+            user("second"),
+            call("call-2"),
+            output("call-2", "recent output"),
+        ];
+
+        let compacted = drop_old_tool_outputs(items, 1);
+
+        assert_eq!(
+            compacted,
+            vec![
+                user("first"),
+                call("call-1"),
+                output("call-1", OMITTED_TOOL_OUTPUT),
+                user("second"),
+                call("call-2"),
+                output("call-2", "recent output"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sliding_window_keeps_initial_context_recent_turns_and_snapshots() {
+        let snapshot = ResponseItem::GhostSnapshot {
+            ghost_commit: codex_git::GhostCommit::new(
+                "ghost".to_string(),
+                None,
+                Vec::new(),
+                Vec::new(),
+            ),
+        };
+        let items = vec![
+            user("<environment_context>old</environment_context>"),
+            user("first"),
+            snapshot.clone(),
+            call("call-1"),
+            output("call-1", "old output"),
+            user("second"),
+            user("third"),
+        ];
+
+        let compacted = sliding_window(
+            vec![user("<environment_context>new</environment_context>")],
+            items.clone(),
+            2,
+        );
+
+        assert_eq!(
+            compacted,
+            vec![
+                user("<environment_context>new</environment_context>"),
+                snapshot,
+                user("second"),
+                user("third"),
+            ]
+        );
+        assert_eq!(sliding_window(Vec::new(), items.clone(), 4), items);
+    }
+
+    #[test]
+    fn apply_pins_adds_missing_messages_and_refreshes_files() {
+        let history = vec![
+            user("<environment_context>cwd</environment_context>"),
+            pinned_file_item(Path::new("NOTES.md"), "stale"),
+            user("recent"),
+        ];
+
+        let pinned = apply_pins(
+            history,
+            &["never use unwrap".to_string(), "recent".to_string()],
+            vec![pinned_file_item(Path::new("NOTES.md"), "fresh")],
+        );
+
+        assert_eq!(
+            pinned,
+            vec![
+                user("<environment_context>cwd</environment_context>"),
+                user("<pinned_file path=\"NOTES.md\">\nfresh\n</pinned_file>"),
+                user("never use unwrap"),
+                user("recent"),
+            ]
+        );
+        assert_eq!(
+            collect_user_messages(&pinned),
+            vec!["never use unwrap".to_string(), "recent".to_string()]
+        );
+    }
 }
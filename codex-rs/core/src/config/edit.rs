@@ -50,7 +50,10 @@ pub enum ConfigEdit {
 // TODO(jif) move to a dedicated file
 mod document_helpers {
     use crate::config::types::McpServerConfig;
+    use crate::config::types::McpServerSandboxMode;
     use crate::config::types::McpServerTransportConfig;
+    use crate::config::types::ShellEnvironmentPolicyInherit;
+    use crate::config::types::ShellEnvironmentPolicyToml;
     use toml_edit::Array as TomlArray;
     use toml_edit::InlineTable;
     use toml_edit::Item as TomlItem;
@@ -159,10 +162,76 @@ mod document_helpers {
         {
             entry["disabled_tools"] = array_from_iter(disabled_tools.iter().cloned());
         }
+        if let Some(sandbox) = &config.sandbox {
+            let mut table = TomlTable::new();
+            table.set_implicit(false);
+            table["mode"] = value(match sandbox.mode {
+                McpServerSandboxMode::ReadOnly => "read-only",
+                McpServerSandboxMode::WorkspaceWrite => "workspace-write",
+                McpServerSandboxMode::None => "none",
+            });
+            if sandbox.network_access {
+                table["network_access"] = value(true);
+            }
+            entry["sandbox"] = TomlItem::Table(table);
+        }
+        if let Some(env_policy) = &config.env_policy {
+            entry["env_policy"] = serialize_env_policy(env_policy);
+        }
+        if let Some(cache) = &config.cache {
+            let mut table = TomlTable::new();
+            table.set_implicit(false);
+            table["ttl_sec"] = value(cache.ttl.as_secs_f64());
+            if let Some(max_entries) = cache.max_entries {
+                table["max_entries"] = value(max_entries.get() as i64);
+            }
+            entry["cache"] = TomlItem::Table(table);
+        }
+        if let Some(rate_limit) = &config.rate_limit {
+            let mut table = TomlTable::new();
+            table.set_implicit(false);
+            if let Some(max_concurrent_calls) = rate_limit.max_concurrent_calls {
+                table["max_concurrent_calls"] = value(max_concurrent_calls.get() as i64);
+            }
+            if let Some(calls_per_minute) = rate_limit.calls_per_minute {
+                table["calls_per_minute"] = value(i64::from(calls_per_minute.get()));
+            }
+            entry["rate_limit"] = TomlItem::Table(table);
+        }
 
         TomlItem::Table(entry)
     }
 
+    fn serialize_env_policy(policy: &ShellEnvironmentPolicyToml) -> TomlItem {
+        let mut table = TomlTable::new();
+        table.set_implicit(false);
+        if let Some(inherit) = &policy.inherit {
+            table["inherit"] = value(match inherit {
+                ShellEnvironmentPolicyInherit::Core => "core",
+                ShellEnvironmentPolicyInherit::All => "all",
+                ShellEnvironmentPolicyInherit::None => "none",
+            });
+        }
+        if let Some(ignore_default_excludes) = policy.ignore_default_excludes {
+            table["ignore_default_excludes"] = value(ignore_default_excludes);
+        }
+        if let Some(exclude) = &policy.exclude {
+            table["exclude"] = array_from_iter(exclude.iter().cloned());
+        }
+        if let Some(include_only) = &policy.include_only {
+            table["include_only"] = array_from_iter(include_only.iter().cloned());
+        }
+        if let Some(use_profile) = policy.experimental_use_profile {
+            table["experimental_use_profile"] = value(use_profile);
+        }
+        if let Some(set) = &policy.r#set
+            && !set.is_empty()
+        {
+            table["set"] = table_from_pairs(set.iter());
+        }
+        TomlItem::Table(table)
+    }
+
     fn table_from_inline(inline: &InlineTable) -> TomlTable {
         let mut table = new_implicit_table();
         for (key, value) in inline.iter() {
@@ -568,7 +637,13 @@ impl ConfigEditsBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::McpRateLimitConfig;
+    use crate::config::types::McpServerSandboxConfig;
+    use crate::config::types::McpServerSandboxMode;
     use crate::config::types::McpServerTransportConfig;
+    use crate::config::types::McpToolCacheConfig;
+    use crate::config::types::ShellEnvironmentPolicyInherit;
+    use crate::config::types::ShellEnvironmentPolicyToml;
     use codex_protocol::config_types::ReasoningEffort;
     use pretty_assertions::assert_eq;
     use tempfile::tempdir;
@@ -864,6 +939,17 @@ hide_gpt5_1_migration_prompt = true
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
+                sandbox: Some(McpServerSandboxConfig {
+                    mode: McpServerSandboxMode::ReadOnly,
+                    network_access: false,
+                }),
+                env_policy: Some(ShellEnvironmentPolicyToml {
+                    inherit: Some(ShellEnvironmentPolicyInherit::Core),
+                    exclude: Some(vec!["AWS_*".to_string()]),
+                    ..Default::default()
+                }),
+                cache: None,
+                rate_limit: None,
             },
         );
 
@@ -885,6 +971,16 @@ hide_gpt5_1_migration_prompt = true
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
+                sandbox: None,
+                env_policy: None,
+                cache: Some(McpToolCacheConfig {
+                    ttl: std::time::Duration::from_secs(30),
+                    max_entries: None,
+                }),
+                rate_limit: Some(McpRateLimitConfig {
+                    max_concurrent_calls: None,
+                    calls_per_minute: std::num::NonZeroU32::new(10),
+                }),
             },
         );
 
@@ -907,6 +1003,12 @@ disabled_tools = [\"forbidden\"]
 [mcp_servers.http.http_headers]
 Z-Header = \"z\"
 
+[mcp_servers.http.cache]
+ttl_sec = 30.0
+
+[mcp_servers.http.rate_limit]
+calls_per_minute = 10
+
 [mcp_servers.stdio]
 command = \"cmd\"
 args = [\"--flag\"]
@@ -916,6 +1018,13 @@ enabled_tools = [\"one\", \"two\"]
 [mcp_servers.stdio.env]
 A = \"1\"
 B = \"2\"
+
+[mcp_servers.stdio.sandbox]
+mode = \"read-only\"
+
+[mcp_servers.stdio.env_policy]
+inherit = \"core\"
+exclude = [\"AWS_*\"]
 ";
         assert_eq!(raw, expected);
     }
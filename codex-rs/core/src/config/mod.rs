@@ -1,14 +1,25 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::Compaction;
+use crate::config::types::CompactionToml;
+use crate::config::types::DEFAULT_COMPACTION_KEEP_RECENT_TURNS;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelFallback;
+use crate::config::types::ModelFallbackToml;
+use crate::config::types::ModelFamilyToml;
+use crate::config::types::ModelPrice;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
 use crate::config::types::ReasoningSummaryFormat;
+use crate::config::types::RedactionToml;
+use crate::config::types::ReviewRubric;
+use crate::config::types::RolloutCompression;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::SessionEncryption;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::Tui;
@@ -24,7 +35,8 @@ use crate::features::FeaturesToml;
 use crate::git_info::resolve_root_git_project_for_trust;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
-use crate::model_family::find_family_for_model;
+use crate::model_family::find_family_for_model_with_overrides;
+use crate::model_family::find_model_family_override;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::built_in_model_providers;
 use crate::openai_model_info::get_model_info;
@@ -82,6 +94,9 @@ pub struct Config {
     /// Model used specifically for review sessions. Defaults to "gpt-5-codex".
     pub review_model: String,
 
+    /// User-defined `[review_rubrics]`, keyed by rubric name.
+    pub review_rubrics: HashMap<String, ReviewRubric>,
+
     pub model_family: ModelFamily,
 
     /// Size of the context window for the model, in tokens.
@@ -99,6 +114,10 @@ pub struct Config {
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Ordered fallbacks used when `model_provider` runs out of quota or keeps
+    /// failing after retries.
+    pub model_fallbacks: Vec<ModelFallback>,
+
     /// Approval policy for executing commands.
     pub approval_policy: AskForApproval,
 
@@ -187,6 +206,21 @@ pub struct Config {
     /// Combined provider map (defaults merged with user-defined overrides).
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// User-defined `[model_families]` overrides, keyed by slug prefix.
+    pub model_families: HashMap<String, ModelFamilyToml>,
+
+    /// Per-million-token prices from `[model_prices]`, keyed by slug prefix.
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// Abort the session once its model requests have used this many tokens.
+    pub max_session_tokens: Option<i64>,
+
+    /// Abort the session once its estimated cost, in USD, reaches this amount.
+    pub max_session_cost: Option<f64>,
+
+    /// How the history is compacted and what survives compaction.
+    pub compaction: Compaction,
+
     /// Maximum number of bytes to include from an AGENTS.md project doc file.
     pub project_doc_max_bytes: usize,
 
@@ -200,6 +234,17 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// How session rollouts are stored once the session shuts down.
+    pub rollout_compression: RolloutCompression,
+
+    /// Whether rollouts and `~/.codex/history.jsonl` are encrypted at rest.
+    pub session_encryption: SessionEncryption,
+
+    /// Regular expressions whose matches are replaced with `[REDACTED]`
+    /// before rollouts and the message history are written. Built-in rules
+    /// from `[redaction]` are expanded into their patterns.
+    pub redaction_patterns: Vec<String>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -517,9 +562,18 @@ pub struct ConfigToml {
     /// Review model override used by the `/review` feature.
     pub review_model: Option<String>,
 
+    /// Named rubrics that review requests can select, in addition to the
+    /// built-in ones.
+    #[serde(default)]
+    pub review_rubrics: HashMap<String, ReviewRubric>,
+
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Providers to fail over to, in order, when `model_provider` runs out of
+    /// quota or keeps failing after retries.
+    pub model_fallbacks: Option<Vec<ModelFallbackToml>>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
@@ -586,6 +640,25 @@ pub struct ConfigToml {
     #[serde(default)]
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// Declares or overrides model family capabilities and token limits for
+    /// models whose slug starts with the key.
+    #[serde(default)]
+    pub model_families: HashMap<String, ModelFamilyToml>,
+
+    /// Price, in USD per million tokens, of models whose slug starts with the
+    /// key. Used to report costs and enforce `max_session_cost`.
+    #[serde(default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// Token budget for a session; the running turn is aborted once exceeded.
+    pub max_session_tokens: Option<i64>,
+
+    /// Cost budget for a session, in USD; requires a `model_prices` entry.
+    pub max_session_cost: Option<f64>,
+
+    /// Compaction strategy and pinned files.
+    pub compaction: Option<CompactionToml>,
+
     /// Maximum number of bytes to include from an AGENTS.md project doc file.
     pub project_doc_max_bytes: Option<usize>,
 
@@ -603,6 +676,15 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Compress session rollouts when their session shuts down.
+    pub rollout_compression: Option<RolloutCompression>,
+
+    /// Encrypt rollouts and the message history at rest.
+    pub session_encryption: Option<SessionEncryption>,
+
+    /// Redact secrets before rollouts and the message history are written.
+    pub redaction: Option<RedactionToml>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -1003,6 +1085,7 @@ impl Config {
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let history = cfg.history.unwrap_or_default();
+        let redaction_patterns = resolve_redaction_patterns(cfg.redaction.as_ref())?;
 
         let include_apply_patch_tool_flag = features.enabled(Feature::ApplyPatchFreeform);
         let tools_web_search_request = features.enabled(Feature::WebSearchRequest);
@@ -1028,8 +1111,74 @@ impl Config {
             .or(cfg.model)
             .unwrap_or_else(default_model);
 
-        let mut model_family =
-            find_family_for_model(&model).unwrap_or_else(|| derive_default_model_family(&model));
+        let model_fallbacks = config_profile
+            .model_fallbacks
+            .or(cfg.model_fallbacks)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|fallback| {
+                let model_provider = model_providers
+                    .get(&fallback.model_provider)
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!(
+                                "Model provider `{}` in model_fallbacks not found",
+                                fallback.model_provider
+                            ),
+                        )
+                    })?
+                    .clone();
+                Ok(ModelFallback {
+                    model: fallback.model.unwrap_or_else(|| model.clone()),
+                    model_provider_id: fallback.model_provider,
+                    model_provider,
+                })
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        validate_model_families(&cfg.model_families)?;
+        validate_model_prices(&cfg.model_prices)?;
+        let max_session_tokens = config_profile.max_session_tokens.or(cfg.max_session_tokens);
+        let max_session_cost = config_profile.max_session_cost.or(cfg.max_session_cost);
+        if max_session_tokens.is_some_and(|tokens| tokens <= 0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "max_session_tokens must be positive",
+            ));
+        }
+        if max_session_cost.is_some_and(|cost| !(cost.is_finite() && cost > 0.0)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "max_session_cost must be a positive number",
+            ));
+        }
+        let profile_compaction = config_profile.compaction.clone().unwrap_or_default();
+        let base_compaction = cfg.compaction.clone().unwrap_or_default();
+        let compaction = Compaction {
+            strategy: profile_compaction
+                .strategy
+                .or(base_compaction.strategy)
+                .unwrap_or_default(),
+            keep_recent_turns: profile_compaction
+                .keep_recent_turns
+                .or(base_compaction.keep_recent_turns)
+                .unwrap_or(DEFAULT_COMPACTION_KEEP_RECENT_TURNS),
+            pinned_files: profile_compaction
+                .pinned_files
+                .or(base_compaction.pinned_files)
+                .unwrap_or_default(),
+        };
+        if compaction.keep_recent_turns == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "compaction.keep_recent_turns must be positive",
+            ));
+        }
+        let model_family_override =
+            find_model_family_override(&model, &cfg.model_families).map(|(_, value)| value);
+        let mut model_family = find_family_for_model_with_overrides(&model, &cfg.model_families)
+            .unwrap_or_else(|| derive_default_model_family(&model));
 
         if let Some(supports_reasoning_summaries) = cfg.model_supports_reasoning_summaries {
             model_family.supports_reasoning_summaries = supports_reasoning_summaries;
@@ -1041,17 +1190,24 @@ impl Config {
         let openai_model_info = get_model_info(&model_family);
         let model_context_window = cfg
             .model_context_window
+            .or_else(|| model_family_override.and_then(|value| value.context_window))
             .or_else(|| openai_model_info.as_ref().map(|info| info.context_window));
-        let model_max_output_tokens = cfg.model_max_output_tokens.or_else(|| {
-            openai_model_info
-                .as_ref()
-                .map(|info| info.max_output_tokens)
-        });
-        let model_auto_compact_token_limit = cfg.model_auto_compact_token_limit.or_else(|| {
-            openai_model_info
-                .as_ref()
-                .and_then(|info| info.auto_compact_token_limit)
-        });
+        let model_max_output_tokens = cfg
+            .model_max_output_tokens
+            .or_else(|| model_family_override.and_then(|value| value.max_output_tokens))
+            .or_else(|| {
+                openai_model_info
+                    .as_ref()
+                    .map(|info| info.max_output_tokens)
+            });
+        let model_auto_compact_token_limit = cfg
+            .model_auto_compact_token_limit
+            .or_else(|| model_family_override.and_then(|value| value.auto_compact_token_limit))
+            .or_else(|| {
+                openai_model_info
+                    .as_ref()
+                    .and_then(|info| info.auto_compact_token_limit)
+            });
 
         let compact_prompt = compact_prompt.or(cfg.compact_prompt).and_then(|value| {
             let trimmed = value.trim();
@@ -1096,12 +1252,14 @@ impl Config {
         let config = Self {
             model,
             review_model,
+            review_rubrics: cfg.review_rubrics,
             model_family,
             model_context_window,
             model_max_output_tokens,
             model_auto_compact_token_limit,
             model_provider_id,
             model_provider,
+            model_fallbacks,
             cwd: resolved_cwd,
             approval_policy,
             sandbox_policy,
@@ -1121,6 +1279,11 @@ impl Config {
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
             model_providers,
+            model_families: cfg.model_families,
+            model_prices: cfg.model_prices,
+            max_session_tokens,
+            max_session_cost,
+            compaction,
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             project_doc_fallback_filenames: cfg
                 .project_doc_fallback_filenames
@@ -1137,6 +1300,9 @@ impl Config {
                 .collect(),
             codex_home,
             history,
+            rollout_compression: cfg.rollout_compression.unwrap_or_default(),
+            session_encryption: cfg.session_encryption.unwrap_or_default(),
+            redaction_patterns,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1207,6 +1373,28 @@ impl Config {
         None
     }
 
+    /// Returns the model family for `model`, applying any `[model_families]`
+    /// entry that matches it.
+    pub fn model_family_for(&self, model: &str) -> Option<ModelFamily> {
+        find_family_for_model_with_overrides(model, &self.model_families)
+    }
+
+    /// Returns the key and value of the `[model_families]` entry that applies
+    /// to `model`, if any.
+    pub fn model_family_override(&self, model: &str) -> Option<(&str, &ModelFamilyToml)> {
+        find_model_family_override(model, &self.model_families)
+    }
+
+    /// Returns the `[model_prices]` entry for `model`. When several keys are
+    /// prefixes of the slug, the longest wins.
+    pub fn model_price_for(&self, model: &str) -> Option<&ModelPrice> {
+        self.model_prices
+            .iter()
+            .filter(|(slug, _)| model.starts_with(slug.as_str()))
+            .max_by_key(|(slug, _)| slug.len())
+            .map(|(_, price)| price)
+    }
+
     fn load_override_from_file(
         path: Option<&PathBuf>,
         cwd: &Path,
@@ -1241,6 +1429,99 @@ impl Config {
     }
 }
 
+/// Expands `[redaction]` into the list of patterns to apply and checks that
+/// each one compiles.
+fn resolve_redaction_patterns(redaction: Option<&RedactionToml>) -> std::io::Result<Vec<String>> {
+    let Some(redaction) = redaction else {
+        return Ok(Vec::new());
+    };
+    let patterns: Vec<String> = redaction
+        .builtin
+        .iter()
+        .flat_map(|rule| rule.patterns().iter().map(|pattern| (*pattern).to_string()))
+        .chain(redaction.patterns.iter().cloned())
+        .collect();
+    for pattern in &patterns {
+        if let Err(err) = regex_lite::Regex::new(pattern) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid redaction pattern `{pattern}`: {err}"),
+            ));
+        }
+    }
+    Ok(patterns)
+}
+
+/// Rejects `[model_prices]` entries with negative or non-finite prices.
+fn validate_model_prices(model_prices: &HashMap<String, ModelPrice>) -> std::io::Result<()> {
+    for (slug, price) in model_prices {
+        for (name, value) in [
+            ("input", Some(price.input)),
+            ("cached_input", price.cached_input),
+            ("output", Some(price.output)),
+        ] {
+            if value.is_some_and(|value| !(value.is_finite() && value >= 0.0)) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("model_prices.{slug}: {name} must be a non-negative number"),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rejects `[model_families]` entries whose values cannot work.
+fn validate_model_families(
+    model_families: &HashMap<String, ModelFamilyToml>,
+) -> std::io::Result<()> {
+    let invalid = |slug: &str, message: &str| {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("model_families.{slug}: {message}"),
+        ))
+    };
+    for (slug, family) in model_families {
+        if slug.trim().is_empty() {
+            return invalid(slug, "slug must not be empty");
+        }
+        if let Some(percent) = family.effective_context_window_percent
+            && !(1..=100).contains(&percent)
+        {
+            return invalid(
+                slug,
+                "effective_context_window_percent must be between 1 and 100",
+            );
+        }
+        for (name, value) in [
+            ("context_window", family.context_window),
+            ("max_output_tokens", family.max_output_tokens),
+            ("auto_compact_token_limit", family.auto_compact_token_limit),
+        ] {
+            if value.is_some_and(|value| value <= 0) {
+                return invalid(slug, &format!("{name} must be positive"));
+            }
+        }
+        if let (Some(limit), Some(window)) =
+            (family.auto_compact_token_limit, family.context_window)
+            && limit > window
+        {
+            return invalid(
+                slug,
+                "auto_compact_token_limit must not exceed context_window",
+            );
+        }
+        if family
+            .base_instructions
+            .as_ref()
+            .is_some_and(|instructions| instructions.trim().is_empty())
+        {
+            return invalid(slug, "base_instructions must not be empty");
+        }
+    }
+    Ok(())
+}
+
 fn default_model() -> String {
     OPENAI_DEFAULT_MODEL.to_string()
 }
@@ -1289,10 +1570,15 @@ mod tests {
     use crate::config::edit::ConfigEdit;
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
+    use crate::config::types::BuiltinRedaction;
     use crate::config::types::HistoryPersistence;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
     use crate::features::Feature;
+    use crate::model_family::find_family_for_model;
+    use crate::protocol::TokenUsage;
+    use crate::tools::handlers::apply_patch::ApplyPatchToolType;
+    use codex_protocol::config_types::CompactionStrategy;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -1652,6 +1938,286 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn profile_model_fallbacks_resolve_providers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "gpt-5-codex"
+profile = "work"
+
+[profiles.work]
+model_fallbacks = [
+  { model_provider = "oss", model = "gpt-oss:20b" },
+  { model_provider = "openai" },
+]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        let fallbacks: Vec<(&str, &str)> = config
+            .model_fallbacks
+            .iter()
+            .map(|fallback| (fallback.model_provider_id.as_str(), fallback.model.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            vec![("oss", "gpt-oss:20b"), ("openai", "gpt-5-codex")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn model_families_override_capabilities_and_limits() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "qwen3-coder:30b"
+
+[model_families.qwen3-coder]
+family = "qwen3"
+apply_patch_tool_type = "function"
+supports_parallel_tool_calls = true
+context_window = 262144
+auto_compact_token_limit = 200000
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.model_family.family, "qwen3");
+        assert_eq!(
+            config.model_family.apply_patch_tool_type,
+            Some(ApplyPatchToolType::Function)
+        );
+        assert!(config.model_family.supports_parallel_tool_calls);
+        assert_eq!(config.model_context_window, Some(262_144));
+        assert_eq!(config.model_auto_compact_token_limit, Some(200_000));
+        assert_eq!(
+            config
+                .model_family_override("qwen3-coder:30b")
+                .map(|(slug, _)| slug),
+            Some("qwen3-coder")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn model_families_reject_invalid_limits() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[model_families.local]
+context_window = 8192
+auto_compact_token_limit = 16384
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("auto-compact limit above the window should be rejected");
+
+        assert_eq!(
+            err.to_string(),
+            "model_families.local: auto_compact_token_limit must not exceed context_window"
+        );
+        assert!(
+            toml::from_str::<ConfigToml>("[model_families.local]\nsupports_tools = true\n")
+                .is_err(),
+            "unknown model family fields should be rejected"
+        );
+    }
+
+    #[test]
+    fn redaction_expands_builtin_rules_and_rejects_invalid_patterns() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+session_encryption = "keyring"
+
+[redaction]
+builtin = ["emails"]
+patterns = ['internal-[0-9a-f]{8}']
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.session_encryption, SessionEncryption::Keyring);
+        let mut expected: Vec<String> = BuiltinRedaction::Emails
+            .patterns()
+            .iter()
+            .map(|pattern| (*pattern).to_string())
+            .collect();
+        expected.push("internal-[0-9a-f]{8}".to_string());
+        assert_eq!(config.redaction_patterns, expected);
+
+        let cfg = toml::from_str::<ConfigToml>("[redaction]\npatterns = ['(unclosed']\n")
+            .expect("TOML deserialization should succeed");
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("invalid pattern should be rejected");
+        assert!(
+            err.to_string()
+                .contains("invalid redaction pattern `(unclosed`")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn model_fallbacks_reject_unknown_provider() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg =
+            toml::from_str::<ConfigToml>(r#"model_fallbacks = [{ model_provider = "missing" }]"#)
+                .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown fallback provider should be rejected");
+
+        assert_eq!(
+            err.to_string(),
+            "Model provider `missing` in model_fallbacks not found"
+        );
+    }
+
+    #[test]
+    fn model_prices_and_session_budgets_resolve() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "gpt-5-codex"
+profile = "ci"
+max_session_tokens = 1000000
+max_session_cost = 50.0
+
+[model_prices.gpt-5]
+input = 1.25
+output = 10.0
+
+[model_prices.gpt-5-codex]
+input = 1.5
+cached_input = 0.15
+output = 12.0
+
+[profiles.ci]
+max_session_cost = 2.5
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            (config.max_session_tokens, config.max_session_cost),
+            (Some(1_000_000), Some(2.5))
+        );
+        let price = config
+            .model_price_for("gpt-5-codex")
+            .expect("longest prefix should match");
+        assert_eq!(price.cached_input, Some(0.15));
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: 500_000,
+            output_tokens: 100_000,
+            reasoning_output_tokens: 50_000,
+            total_tokens: 1_100_000,
+        };
+        assert_eq!(price.cost_usd(&usage), 2.025);
+        assert_eq!(config.model_price_for("o3"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_compaction_overrides_top_level_fields() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+profile = "long"
+
+[compaction]
+strategy = "drop-old-tool-outputs"
+pinned_files = ["docs/ARCHITECTURE.md"]
+
+[profiles.long.compaction]
+strategy = "sliding-window"
+keep_recent_turns = 8
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.compaction,
+            Compaction {
+                strategy: CompactionStrategy::SlidingWindow,
+                keep_recent_turns: 8,
+                pinned_files: vec![PathBuf::from("docs/ARCHITECTURE.md")],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn model_prices_reject_negative_values() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg = toml::from_str::<ConfigToml>(
+            "[model_prices.local]
+input = -1.0
+output = 0.0
+",
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("negative price should be rejected");
+
+        assert_eq!(
+            err.to_string(),
+            "model_prices.local: input must be a non-negative number"
+        );
+    }
+
     #[test]
     fn cli_override_takes_precedence_over_profile_sandbox_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1834,6 +2400,10 @@ trust_level = "trusted"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         );
 
@@ -1980,6 +2550,10 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2048,6 +2622,10 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2096,6 +2674,10 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2142,6 +2724,10 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2204,6 +2790,10 @@ startup_timeout_sec = 2.0
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
         apply_blocking(
@@ -2278,6 +2868,10 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2305,6 +2899,10 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         );
         apply_blocking(
@@ -2370,6 +2968,10 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             ),
             (
@@ -2387,6 +2989,10 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             ),
         ]);
@@ -2467,6 +3073,10 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2509,6 +3119,10 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         )]);
 
@@ -2812,6 +3426,7 @@ model_verbosity = "high"
             stream_max_retries: Some(10),
             stream_idle_timeout_ms: Some(300_000),
             requires_openai_auth: false,
+            stream_include_usage: false,
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -2867,12 +3482,14 @@ model_verbosity = "high"
             Config {
                 model: "o3".to_string(),
                 review_model: OPENAI_DEFAULT_REVIEW_MODEL.to_string(),
+                review_rubrics: HashMap::new(),
                 model_family: find_family_for_model("o3").expect("known model slug"),
                 model_context_window: Some(200_000),
                 model_max_output_tokens: Some(100_000),
                 model_auto_compact_token_limit: Some(180_000),
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                model_fallbacks: Vec::new(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -2885,10 +3502,18 @@ model_verbosity = "high"
                 mcp_servers: HashMap::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
+                model_families: HashMap::new(),
+                model_prices: HashMap::new(),
+                max_session_tokens: None,
+                max_session_cost: None,
+                compaction: Compaction::default(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 codex_home: fixture.codex_home(),
                 history: History::default(),
+                rollout_compression: RolloutCompression::default(),
+                session_encryption: SessionEncryption::default(),
+                redaction_patterns: Vec::new(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -2938,12 +3563,14 @@ model_verbosity = "high"
         let expected_gpt3_profile_config = Config {
             model: "gpt-3.5-turbo".to_string(),
             review_model: OPENAI_DEFAULT_REVIEW_MODEL.to_string(),
+            review_rubrics: HashMap::new(),
             model_family: find_family_for_model("gpt-3.5-turbo").expect("known model slug"),
             model_context_window: Some(16_385),
             model_max_output_tokens: Some(4_096),
             model_auto_compact_token_limit: Some(14_746),
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -2956,10 +3583,18 @@ model_verbosity = "high"
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            model_families: HashMap::new(),
+            model_prices: HashMap::new(),
+            max_session_tokens: None,
+            max_session_cost: None,
+            compaction: Compaction::default(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            rollout_compression: RolloutCompression::default(),
+            session_encryption: SessionEncryption::default(),
+            redaction_patterns: Vec::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3024,12 +3659,14 @@ model_verbosity = "high"
         let expected_zdr_profile_config = Config {
             model: "o3".to_string(),
             review_model: OPENAI_DEFAULT_REVIEW_MODEL.to_string(),
+            review_rubrics: HashMap::new(),
             model_family: find_family_for_model("o3").expect("known model slug"),
             model_context_window: Some(200_000),
             model_max_output_tokens: Some(100_000),
             model_auto_compact_token_limit: Some(180_000),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -3042,10 +3679,18 @@ model_verbosity = "high"
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            model_families: HashMap::new(),
+            model_prices: HashMap::new(),
+            max_session_tokens: None,
+            max_session_cost: None,
+            compaction: Compaction::default(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            rollout_compression: RolloutCompression::default(),
+            session_encryption: SessionEncryption::default(),
+            redaction_patterns: Vec::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3096,12 +3741,14 @@ model_verbosity = "high"
         let expected_gpt5_profile_config = Config {
             model: "gpt-5".to_string(),
             review_model: OPENAI_DEFAULT_REVIEW_MODEL.to_string(),
+            review_rubrics: HashMap::new(),
             model_family: find_family_for_model("gpt-5").expect("known model slug"),
             model_context_window: Some(272_000),
             model_max_output_tokens: Some(128_000),
             model_auto_compact_token_limit: Some(244_800),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -3114,10 +3761,18 @@ model_verbosity = "high"
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            model_families: HashMap::new(),
+            model_prices: HashMap::new(),
+            max_session_tokens: None,
+            max_session_cost: None,
+            compaction: Compaction::default(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            rollout_compression: RolloutCompression::default(),
+            session_encryption: SessionEncryption::default(),
+            redaction_patterns: Vec::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -15,6 +15,15 @@ pub struct ConfigProfile {
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
     pub model_provider: Option<String>,
+    /// Providers to fail over to, in order; see `Config::model_fallbacks`.
+    pub model_fallbacks: Option<Vec<crate::config::types::ModelFallbackToml>>,
+    /// Session token budget; see `Config::max_session_tokens`.
+    pub max_session_tokens: Option<i64>,
+    /// Session cost budget in USD; see `Config::max_session_cost`.
+    pub max_session_cost: Option<f64>,
+    /// Compaction settings; unset fields fall back to the top-level
+    /// `[compaction]` table.
+    pub compaction: Option<crate::config::types::CompactionToml>,
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
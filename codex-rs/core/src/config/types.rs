@@ -3,8 +3,18 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use crate::model_provider_info::ModelProviderInfo;
+use crate::tools::handlers::apply_patch::ApplyPatchToolType;
+use crate::tools::spec::ConfigShellToolType;
+use codex_protocol::config_types::CompactionStrategy;
+use codex_protocol::config_types::ReasoningEffort;
+use codex_protocol::config_types::Verbosity;
+use codex_protocol::protocol::TokenUsage;
+use codex_utils_string::redaction;
 use serde::Deserializer;
 use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::time::Duration;
 use wildmatch::WildMatchPattern;
@@ -43,6 +53,26 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// Sandbox for a stdio server process. When unset, the server runs with
+    /// the same privileges as Codex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<McpServerSandboxConfig>,
+
+    /// Policy for building the environment of a stdio server process. When
+    /// unset, only a small set of core variables plus `env_vars` and `env`
+    /// are passed through.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_policy: Option<ShellEnvironmentPolicyToml>,
+
+    /// Opt-in cache of successful tool results, keyed on tool name and
+    /// arguments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<McpToolCacheConfig>,
+
+    /// Limits on how often tools on this server may be called.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<McpRateLimitConfig>,
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -84,6 +114,14 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled_tools: Option<Vec<String>>,
             #[serde(default)]
             disabled_tools: Option<Vec<String>>,
+            #[serde(default)]
+            sandbox: Option<McpServerSandboxConfig>,
+            #[serde(default)]
+            env_policy: Option<ShellEnvironmentPolicyToml>,
+            #[serde(default)]
+            cache: Option<McpToolCacheConfig>,
+            #[serde(default)]
+            rate_limit: Option<McpRateLimitConfig>,
         }
 
         let mut raw = RawMcpServerConfig::deserialize(deserializer)?;
@@ -100,6 +138,19 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        let sandbox = raw.sandbox;
+        let env_policy = raw.env_policy.clone();
+        let cache = raw.cache;
+        let rate_limit = raw.rate_limit;
+
+        if let Some(sandbox) = sandbox
+            && sandbox.network_access
+            && sandbox.mode != McpServerSandboxMode::WorkspaceWrite
+        {
+            return Err(SerdeError::custom(
+                "sandbox.network_access is only supported with mode = \"workspace-write\"",
+            ));
+        }
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -136,6 +187,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             throw_if_set("streamable_http", "env_vars", raw.env_vars.as_ref())?;
             throw_if_set("streamable_http", "cwd", raw.cwd.as_ref())?;
             throw_if_set("streamable_http", "bearer_token", raw.bearer_token.as_ref())?;
+            throw_if_set("streamable_http", "sandbox", raw.sandbox.as_ref())?;
+            throw_if_set("streamable_http", "env_policy", raw.env_policy.as_ref())?;
             McpServerTransportConfig::StreamableHttp {
                 url,
                 bearer_token_env_var: raw.bearer_token_env_var.clone(),
@@ -153,6 +206,10 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled,
             enabled_tools,
             disabled_tools,
+            sandbox,
+            env_policy,
+            cache,
+            rate_limit,
         })
     }
 }
@@ -161,6 +218,50 @@ const fn default_enabled() -> bool {
     true
 }
 
+/// Sandbox applied to a stdio MCP server, enforced with the same
+/// Landlock/seccomp helper that sandboxes shell commands on Linux.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct McpServerSandboxConfig {
+    pub mode: McpServerSandboxMode,
+
+    /// Allow outbound network access. Only valid with `workspace-write`.
+    #[serde(default)]
+    pub network_access: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpServerSandboxMode {
+    /// Read-only access to the entire file-system, no network.
+    ReadOnly,
+    /// Write access to the server's `cwd` (or the session's) and temp dirs.
+    WorkspaceWrite,
+    /// Run the server unsandboxed.
+    None,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct McpToolCacheConfig {
+    /// How long a cached result stays valid, in seconds.
+    #[serde(rename = "ttl_sec", with = "duration_secs")]
+    pub ttl: Duration,
+
+    /// Maximum number of cached results kept for the server. Defaults to 64.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<NonZeroUsize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct McpRateLimitConfig {
+    /// Maximum number of calls to the server that may run at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_calls: Option<NonZeroUsize>,
+
+    /// Maximum number of calls started within any 60 second window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calls_per_minute: Option<NonZeroU32>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged, deny_unknown_fields, rename_all = "snake_case")]
 pub enum McpServerTransportConfig {
@@ -193,6 +294,28 @@ pub enum McpServerTransportConfig {
     },
 }
 
+mod duration_secs {
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+    use std::time::Duration;
+
+    pub fn serialize<S>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(value.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let secs = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+    }
+}
+
 mod option_duration_secs {
     use serde::Deserialize;
     use serde::Deserializer;
@@ -250,6 +373,128 @@ impl UriBasedFileOpener {
     }
 }
 
+/// One entry of a `model_fallbacks` chain as written in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModelFallbackToml {
+    /// Key into the `model_providers` map.
+    pub model_provider: String,
+
+    /// Model to request from the provider. Defaults to the primary `model`.
+    pub model: Option<String>,
+}
+
+/// A `model_fallbacks` entry resolved against the `model_providers` map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelFallback {
+    pub model: String,
+    pub model_provider_id: String,
+    pub model_provider: ModelProviderInfo,
+}
+
+/// A `[model_families.<slug>]` table that declares or overrides the
+/// capabilities Codex assumes for models whose slug starts with `<slug>`.
+/// Unset fields keep the built-in value for the model, or the defaults for
+/// unknown models.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ModelFamilyToml {
+    /// Family name, used to look up built-in context window limits.
+    pub family: Option<String>,
+    pub needs_special_apply_patch_instructions: Option<bool>,
+    pub supports_reasoning_summaries: Option<bool>,
+    pub default_reasoning_effort: Option<ReasoningEffort>,
+    pub reasoning_summary_format: Option<ReasoningSummaryFormat>,
+    pub supports_parallel_tool_calls: Option<bool>,
+    pub apply_patch_tool_type: Option<ApplyPatchToolType>,
+    pub base_instructions: Option<String>,
+    pub experimental_supported_tools: Option<Vec<String>>,
+    pub effective_context_window_percent: Option<i64>,
+    pub support_verbosity: Option<bool>,
+    pub default_verbosity: Option<Verbosity>,
+    pub shell_type: Option<ConfigShellToolType>,
+
+    /// Size of the context window, in tokens.
+    pub context_window: Option<i64>,
+
+    /// Maximum number of output tokens.
+    pub max_output_tokens: Option<i64>,
+
+    /// Token usage threshold triggering auto-compaction.
+    pub auto_compact_token_limit: Option<i64>,
+}
+
+/// A `[review_rubrics.<name>]` table defining a rubric that review requests
+/// can select by name. Entries override built-in rubrics of the same name.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ReviewRubric {
+    /// What the reviewer should check for under this rubric.
+    pub instructions: String,
+}
+
+/// Turns left intact by the strategies that do not summarize.
+pub const DEFAULT_COMPACTION_KEEP_RECENT_TURNS: usize = 3;
+
+/// The `[compaction]` table. Profiles accept the same table; fields they
+/// leave unset fall back to the top-level table.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CompactionToml {
+    /// Defaults to `summarize-all`.
+    pub strategy: Option<CompactionStrategy>,
+
+    /// Number of most recent user turns left intact by the
+    /// `drop-old-tool-outputs` and `sliding-window` strategies.
+    pub keep_recent_turns: Option<usize>,
+
+    /// Files, relative to the session cwd, whose current contents are added
+    /// back to the history after every compaction.
+    pub pinned_files: Option<Vec<PathBuf>>,
+}
+
+/// Effective compaction settings after profile and defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compaction {
+    pub strategy: CompactionStrategy,
+    pub keep_recent_turns: usize,
+    pub pinned_files: Vec<PathBuf>,
+}
+
+impl Default for Compaction {
+    fn default() -> Self {
+        Self {
+            strategy: CompactionStrategy::default(),
+            keep_recent_turns: DEFAULT_COMPACTION_KEEP_RECENT_TURNS,
+            pinned_files: Vec::new(),
+        }
+    }
+}
+
+/// A `[model_prices.<slug>]` table giving the price, in USD per million
+/// tokens, of models whose slug starts with `<slug>`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPrice {
+    pub input: f64,
+
+    /// Price of input tokens served from the prompt cache. Defaults to `input`.
+    pub cached_input: Option<f64>,
+
+    /// Price of output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
+impl ModelPrice {
+    /// Cost of `usage` in USD.
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        let cached_input = self.cached_input.unwrap_or(self.input);
+        (usage.non_cached_input() as f64 * self.input
+            + usage.cached_input() as f64 * cached_input
+            + usage.output_tokens.max(0) as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
 /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct History {
@@ -271,6 +516,61 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// How session rollouts are stored once their session shuts down.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RolloutCompression {
+    /// Keep plain `.jsonl` files.
+    #[default]
+    None,
+    /// Replace the file with a zstd-compressed `.jsonl.zst`.
+    Zstd,
+}
+
+/// Whether rollouts and the message history are encrypted at rest.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionEncryption {
+    #[default]
+    None,
+    /// Encrypt every record with a key kept in the OS keyring.
+    Keyring,
+}
+
+/// `[redaction]`: text matching these rules is replaced with `[REDACTED]`
+/// before rollouts and the message history are written to disk.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct RedactionToml {
+    /// Built-in rule sets to enable.
+    #[serde(default)]
+    pub builtin: Vec<BuiltinRedaction>,
+
+    /// Additional regular expressions.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuiltinRedaction {
+    /// Bearer tokens, JWTs and GitHub/Slack tokens.
+    Tokens,
+    /// API keys for common providers and PEM private keys.
+    Keys,
+    /// Email addresses.
+    Emails,
+}
+
+impl BuiltinRedaction {
+    pub fn patterns(self) -> &'static [&'static str] {
+        match self {
+            BuiltinRedaction::Tokens => redaction::TOKEN_PATTERNS,
+            BuiltinRedaction::Keys => redaction::KEY_PATTERNS,
+            BuiltinRedaction::Emails => redaction::EMAIL_PATTERNS,
+        }
+    }
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -394,7 +694,7 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
     }
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum ShellEnvironmentPolicyInherit {
     /// "Core" environment variables for the platform. On UNIX, this would
@@ -410,21 +710,27 @@ pub enum ShellEnvironmentPolicyInherit {
 }
 
 /// Policy for building the `env` when spawning a process via either the
-/// `shell` or `local_shell` tool.
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+/// `shell` or `local_shell` tool (also used for stdio MCP servers).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct ShellEnvironmentPolicyToml {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub inherit: Option<ShellEnvironmentPolicyInherit>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ignore_default_excludes: Option<bool>,
 
     /// List of regular expressions.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude: Option<Vec<String>>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub r#set: Option<HashMap<String, String>>,
 
     /// List of regular expressions.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub include_only: Option<Vec<String>>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub experimental_use_profile: Option<bool>,
 }
 
@@ -770,4 +1076,103 @@ mod tests {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn deserialize_stdio_server_config_with_sandbox_and_env_policy() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+
+            [sandbox]
+            mode = "workspace-write"
+            network_access = true
+
+            [env_policy]
+            inherit = "core"
+            include_only = ["PATH", "HOME"]
+        "#,
+        )
+        .expect("should deserialize sandboxed command config");
+
+        assert_eq!(
+            cfg.sandbox,
+            Some(McpServerSandboxConfig {
+                mode: McpServerSandboxMode::WorkspaceWrite,
+                network_access: true,
+            })
+        );
+        assert_eq!(
+            cfg.env_policy,
+            Some(ShellEnvironmentPolicyToml {
+                inherit: Some(ShellEnvironmentPolicyInherit::Core),
+                include_only: Some(vec!["PATH".to_string(), "HOME".to_string()]),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_network_access_without_workspace_write() {
+        let err = toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            sandbox = { mode = "read-only", network_access = true }
+        "#,
+        )
+        .expect_err("should reject network access for read-only sandbox");
+
+        assert!(
+            err.to_string().contains("sandbox.network_access"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_sandbox_for_http_transport() {
+        toml::from_str::<McpServerConfig>(
+            r#"
+            url = "https://example.com"
+            sandbox = { mode = "read-only" }
+        "#,
+        )
+        .expect_err("should reject sandbox for http transport");
+    }
+
+    #[test]
+    fn deserialize_server_config_with_cache_and_rate_limit() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            url = "https://example.com/mcp"
+            cache = { ttl_sec = 1.5 }
+            rate_limit = { max_concurrent_calls = 2, calls_per_minute = 30 }
+        "#,
+        )
+        .expect("should deserialize cache and rate_limit");
+
+        assert_eq!(
+            cfg.cache,
+            Some(McpToolCacheConfig {
+                ttl: Duration::from_millis(1500),
+                max_entries: None,
+            })
+        );
+        assert_eq!(
+            cfg.rate_limit,
+            Some(McpRateLimitConfig {
+                max_concurrent_calls: NonZeroUsize::new(2),
+                calls_per_minute: NonZeroU32::new(30),
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_zero_rate_limit() {
+        toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            rate_limit = { calls_per_minute = 0 }
+        "#,
+        )
+        .expect_err("should reject a zero rate limit");
+    }
 }
@@ -1,5 +1,6 @@
 use crate::codex::TurnContext;
 use crate::context_manager::normalize;
+use crate::context_manager::token_count::TokenCounter;
 use crate::truncate;
 use crate::truncate::format_output_for_model_body;
 use crate::truncate::globally_truncate_function_output_items;
@@ -7,7 +8,6 @@ use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
-use codex_utils_tokenizer::Tokenizer;
 use std::ops::Deref;
 
 const CONTEXT_WINDOW_HARD_LIMIT_FACTOR: f64 = 1.1;
@@ -22,6 +22,9 @@ pub(crate) struct ContextManager {
     /// The oldest items are at the beginning of the vector.
     items: Vec<ResponseItem>,
     token_info: Option<TokenUsageInfo>,
+    /// Number of items that were in the history when the model last reported
+    /// token usage for it. Cleared when the history is rewritten.
+    items_at_last_usage: Option<usize>,
 }
 
 impl ContextManager {
@@ -29,6 +32,7 @@ impl ContextManager {
         Self {
             items: Vec::new(),
             token_info: TokenUsageInfo::new_or_append(&None, &None, None),
+            items_at_last_usage: None,
         }
     }
 
@@ -80,26 +84,35 @@ impl ContextManager {
         history
     }
 
-    // Estimate the number of tokens in the history. Return None if no tokenizer
-    // is available. This does not consider the reasoning traces.
+    // Estimate the number of tokens in the history, counted with the model's
+    // tokenizer when one is available. This does not consider the reasoning
+    // traces.
     // /!\ The value is a lower bound estimate and does not represent the exact
     // context length.
-    pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
-        let model = turn_context.client.get_model();
-        let tokenizer = Tokenizer::for_model(model.as_str()).ok()?;
+    pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> i64 {
+        let counter = TokenCounter::for_model(turn_context.client.get_model().as_str());
         let model_family = turn_context.client.get_model_family();
 
-        Some(
-            self.items
-                .iter()
-                .map(|item| {
-                    serde_json::to_string(&item)
-                        .map(|item| tokenizer.count(&item))
-                        .unwrap_or_default()
-                })
-                .sum::<i64>()
-                + tokenizer.count(model_family.base_instructions.as_str()),
-        )
+        count_items(&counter, &self.items)
+            + counter.count_text(model_family.base_instructions.as_str())
+    }
+
+    /// Estimates how much of the context window the next request will use:
+    /// the usage the model last reported, plus the items recorded since.
+    /// Returns `None` until the model has reported usage for this history.
+    pub(crate) fn estimate_next_request_tokens(&self, turn_context: &TurnContext) -> Option<i64> {
+        let items_at_last_usage = self.items_at_last_usage?;
+        let last_usage = self
+            .token_info
+            .as_ref()?
+            .last_token_usage
+            .tokens_in_context_window();
+        let new_items = self.items.get(items_at_last_usage..).unwrap_or_default();
+        if new_items.is_empty() {
+            return Some(last_usage);
+        }
+        let counter = TokenCounter::for_model(turn_context.client.get_model().as_str());
+        Some(last_usage + count_items(&counter, new_items))
     }
 
     pub(crate) fn remove_first_item(&mut self) {
@@ -107,6 +120,7 @@ impl ContextManager {
             // Remove the oldest item (front of the list). Items are ordered from
             // oldest → newest, so index 0 is the first entry recorded.
             let removed = self.items.remove(0);
+            self.items_at_last_usage = None;
             // If the removed item participates in a call/output pair, also remove
             // its corresponding counterpart to keep the invariants intact without
             // running a full normalization pass.
@@ -116,6 +130,7 @@ impl ContextManager {
 
     pub(crate) fn replace(&mut self, items: Vec<ResponseItem>) {
         self.items = items;
+        self.items_at_last_usage = None;
     }
 
     pub(crate) fn update_token_info(
@@ -128,6 +143,7 @@ impl ContextManager {
             &Some(usage.clone()),
             model_context_window,
         );
+        self.items_at_last_usage = Some(self.items.len());
     }
 
     /// This function enforces a couple of invariants on the in-memory history:
@@ -194,6 +210,15 @@ impl ContextManager {
     }
 }
 
+/// Ghost snapshots are never sent to the model, so they do not count.
+fn count_items(counter: &TokenCounter, items: &[ResponseItem]) -> i64 {
+    items
+        .iter()
+        .filter(|item| !matches!(item, ResponseItem::GhostSnapshot { .. }))
+        .map(|item| counter.count_item(item))
+        .sum()
+}
+
 /// API messages include every non-system item (user/assistant messages, reasoning,
 /// tool calls, tool outputs, shell calls, and web-search calls).
 fn is_api_message(message: &ResponseItem) -> bool {
@@ -113,6 +113,41 @@ fn get_history_for_prompt_drops_ghost_commits() {
     assert_eq!(filtered, vec![]);
 }
 
+#[test]
+fn estimate_next_request_tokens_adds_items_recorded_since_last_usage() {
+    let (_session, turn_context) = crate::codex::make_session_and_context();
+    let mut history = create_history_with_items(vec![user_msg("hello")]);
+    assert_eq!(history.estimate_next_request_tokens(&turn_context), None);
+
+    let usage = TokenUsage {
+        input_tokens: 1_000,
+        total_tokens: 1_000,
+        ..Default::default()
+    };
+    history.update_token_info(&usage, None);
+    assert_eq!(
+        history.estimate_next_request_tokens(&turn_context),
+        Some(1_000)
+    );
+
+    let output = ResponseItem::FunctionCallOutput {
+        call_id: "call-1".to_string(),
+        output: FunctionCallOutputPayload {
+            content: "x ".repeat(500),
+            ..Default::default()
+        },
+    };
+    history.record_items([&output]);
+    let counter = TokenCounter::for_model(turn_context.client.get_model().as_str());
+    assert_eq!(
+        history.estimate_next_request_tokens(&turn_context),
+        Some(1_000 + counter.count_item(&output))
+    );
+
+    history.replace(vec![user_msg("summary")]);
+    assert_eq!(history.estimate_next_request_tokens(&turn_context), None);
+}
+
 #[test]
 fn remove_first_item_removes_matching_output_for_function_call() {
     let items = vec![
@@ -1,5 +1,6 @@
 mod history;
 mod normalize;
+mod token_count;
 
 pub(crate) use crate::truncate::MODEL_FORMAT_MAX_BYTES;
 pub(crate) use crate::truncate::MODEL_FORMAT_MAX_LINES;
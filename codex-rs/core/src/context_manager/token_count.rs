@@ -0,0 +1,62 @@
+//! Local token counts for conversation history items.
+//!
+//! The same items are counted again every time the history is estimated, so
+//! counts are cached per item, keyed by the model and the item's serialized
+//! form.
+
+use std::num::NonZeroUsize;
+use std::sync::LazyLock;
+
+use codex_protocol::models::ResponseItem;
+use codex_utils_cache::BlockingLruCache;
+use codex_utils_cache::sha1_digest;
+use codex_utils_tokenizer::Tokenizer;
+
+const ITEM_TOKEN_CACHE_CAPACITY: usize = 4096;
+
+/// Used when no tokenizer can be loaded.
+const APPROX_BYTES_PER_TOKEN: usize = 4;
+
+static ITEM_TOKEN_COUNTS: LazyLock<BlockingLruCache<[u8; 20], i64>> = LazyLock::new(|| {
+    BlockingLruCache::new(NonZeroUsize::new(ITEM_TOKEN_CACHE_CAPACITY).unwrap_or(NonZeroUsize::MIN))
+});
+
+pub(crate) struct TokenCounter {
+    model: String,
+    tokenizer: Option<Tokenizer>,
+}
+
+impl TokenCounter {
+    pub(crate) fn for_model(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            tokenizer: Tokenizer::for_model(model).ok(),
+        }
+    }
+
+    pub(crate) fn count_text(&self, text: &str) -> i64 {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.count(text),
+            None => approx_token_count(text),
+        }
+    }
+
+    pub(crate) fn count_item(&self, item: &ResponseItem) -> i64 {
+        let Ok(serialized) = serde_json::to_string(item) else {
+            return 0;
+        };
+        if self.tokenizer.is_none() {
+            return approx_token_count(&serialized);
+        }
+        let mut key_bytes = Vec::with_capacity(self.model.len() + 1 + serialized.len());
+        key_bytes.extend_from_slice(self.model.as_bytes());
+        key_bytes.push(0);
+        key_bytes.extend_from_slice(serialized.as_bytes());
+        ITEM_TOKEN_COUNTS
+            .get_or_insert_with(sha1_digest(&key_bytes), || self.count_text(&serialized))
+    }
+}
+
+fn approx_token_count(text: &str) -> i64 {
+    i64::try_from(text.len().div_ceil(APPROX_BYTES_PER_TOKEN)).unwrap_or(i64::MAX)
+}
@@ -191,13 +191,14 @@ impl ConversationManager {
     }
 }
 
-/// Return a prefix of `items` obtained by cutting strictly before the nth user message
-/// (0-based) and all items that follow it.
-fn truncate_before_nth_user_message(history: InitialHistory, n: usize) -> InitialHistory {
-    // Work directly on rollout items, and cut the vector at the nth user message input.
-    let items: Vec<RolloutItem> = history.get_rollout_items();
+/// Number of user turns (user message inputs) recorded in `history`. Forking
+/// at `n` with [`ConversationManager::fork_conversation`] keeps the first `n`.
+pub fn count_user_turns(history: &InitialHistory) -> usize {
+    user_message_positions(&history.get_rollout_items()).len()
+}
 
-    // Find indices of user message inputs in rollout order.
+/// Indices of user message inputs in rollout order.
+fn user_message_positions(items: &[RolloutItem]) -> Vec<usize> {
     let mut user_positions: Vec<usize> = Vec::new();
     for (idx, item) in items.iter().enumerate() {
         if let RolloutItem::ResponseItem(item @ ResponseItem::Message { .. }) = item
@@ -209,6 +210,15 @@ fn truncate_before_nth_user_message(history: InitialHistory, n: usize) -> Initia
             user_positions.push(idx);
         }
     }
+    user_positions
+}
+
+/// Return a prefix of `items` obtained by cutting strictly before the nth user message
+/// (0-based) and all items that follow it.
+fn truncate_before_nth_user_message(history: InitialHistory, n: usize) -> InitialHistory {
+    // Work directly on rollout items, and cut the vector at the nth user message input.
+    let items: Vec<RolloutItem> = history.get_rollout_items();
+    let user_positions = user_message_positions(&items);
 
     // If fewer than or equal to n user messages exist, treat as empty (out of range).
     if user_positions.len() <= n {
@@ -307,6 +317,22 @@ mod tests {
         assert_matches!(truncated2, InitialHistory::New);
     }
 
+    #[test]
+    fn counts_user_turns() {
+        let items: Vec<RolloutItem> = [
+            user_msg("u1"),
+            assistant_msg("a1"),
+            user_msg("u2"),
+            assistant_msg("a2"),
+        ]
+        .into_iter()
+        .map(RolloutItem::ResponseItem)
+        .collect();
+
+        assert_eq!(count_user_turns(&InitialHistory::Forked(items)), 2);
+        assert_eq!(count_user_turns(&InitialHistory::New), 0);
+    }
+
     #[test]
     fn ignores_session_prefix_messages_when_truncating() {
         let (session, turn_context) = make_session_and_context();
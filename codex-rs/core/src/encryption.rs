@@ -0,0 +1,251 @@
+//! Encryption at rest for rollouts and the message history.
+//!
+//! Both files are JSON Lines that are appended to while a session runs, so
+//! encryption works line by line: with `session_encryption = "keyring"` each
+//! record is written as `{"encrypted":"<base64>"}`, where the payload is a
+//! random XChaCha20-Poly1305 nonce followed by the ciphertext of the original
+//! line. Readers pass every line through [`decode_line`], so plaintext and
+//! encrypted records can be mixed in one file and older files stay readable.
+//!
+//! The key is generated on first use and kept in the OS keyring through
+//! `codex-keyring-store`; it never touches `CODEX_HOME`.
+
+use std::borrow::Cow;
+use std::io;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::KeyInit;
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use serde::Deserialize;
+use serde::Serialize;
+
+const KEYRING_SERVICE: &str = "Codex Session Encryption";
+const KEYRING_ACCOUNT: &str = "session-key";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+
+/// Prefix of every encrypted line, used to skip JSON parsing of plaintext.
+const ENCRYPTED_LINE_PREFIX: &str = "{\"encrypted\":";
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedLine {
+    encrypted: String,
+}
+
+/// Encrypts and decrypts individual JSONL records.
+pub(crate) struct LineCipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for LineCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LineCipher").finish_non_exhaustive()
+    }
+}
+
+impl LineCipher {
+    fn from_key(key: &[u8]) -> io::Result<Self> {
+        let cipher = XChaCha20Poly1305::new_from_slice(key).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "session key has the wrong length",
+            )
+        })?;
+        Ok(Self { cipher })
+    }
+
+    /// Reads the key from `store`, returning `None` if none was created yet.
+    pub(crate) fn load(store: &dyn KeyringStore) -> io::Result<Option<Self>> {
+        let encoded = store.load(KEYRING_SERVICE, KEYRING_ACCOUNT).map_err(|e| {
+            io::Error::other(format!(
+                "failed to load the session key from the keyring: {}",
+                e.message()
+            ))
+        })?;
+        let Some(encoded) = encoded else {
+            return Ok(None);
+        };
+        let key = BASE64_STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Self::from_key(&key).map(Some)
+    }
+
+    /// Reads the key from `store`, generating and saving one if needed.
+    pub(crate) fn load_or_create(store: &dyn KeyringStore) -> io::Result<Self> {
+        if let Some(cipher) = Self::load(store)? {
+            return Ok(cipher);
+        }
+        let key: [u8; KEY_LEN] = rand::random();
+        store
+            .save(
+                KEYRING_SERVICE,
+                KEYRING_ACCOUNT,
+                &BASE64_STANDARD.encode(key),
+            )
+            .map_err(|e| {
+                io::Error::other(format!(
+                    "failed to save the session key to the keyring: {}",
+                    e.message()
+                ))
+            })?;
+        Self::from_key(&key)
+    }
+
+    /// Encrypts one JSONL record (without its trailing newline).
+    pub(crate) fn encrypt_line(&self, line: &str) -> io::Result<String> {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), line.as_bytes())
+            .map_err(|_| io::Error::other("failed to encrypt record"))?;
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+        let wrapped = EncryptedLine {
+            encrypted: BASE64_STANDARD.encode(payload),
+        };
+        serde_json::to_string(&wrapped).map_err(io::Error::from)
+    }
+
+    fn decrypt_line(&self, line: &str) -> io::Result<String> {
+        let wrapped: EncryptedLine = serde_json::from_str(line)?;
+        let payload = BASE64_STANDARD
+            .decode(wrapped.encrypted)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if payload.len() < NONCE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "encrypted record is truncated",
+            ));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "failed to decrypt record; was it written with a different session key?",
+                )
+            })?;
+        String::from_utf8(plaintext).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Key loaded from the OS keyring, shared by every writer and reader in the
+/// process so the keyring is queried at most once.
+static SHARED_CIPHER: Mutex<Option<Arc<LineCipher>>> = Mutex::new(None);
+
+/// Returns the process-wide cipher. With `create`, a key is generated when the
+/// keyring does not hold one yet.
+pub(crate) fn shared_cipher(create: bool) -> io::Result<Option<Arc<LineCipher>>> {
+    let mut guard = SHARED_CIPHER.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(cipher) = guard.as_ref() {
+        return Ok(Some(cipher.clone()));
+    }
+    let store = DefaultKeyringStore;
+    let cipher = if create {
+        Some(LineCipher::load_or_create(&store)?)
+    } else {
+        LineCipher::load(&store)?
+    };
+    let cipher = cipher.map(Arc::new);
+    guard.clone_from(&cipher);
+    Ok(cipher)
+}
+
+/// Makes the key in `store` the process-wide cipher, so tests do not touch the
+/// OS keyring. Keeps a cipher that is already installed.
+#[cfg(test)]
+pub(crate) fn install_shared_cipher(store: &dyn KeyringStore) -> io::Result<Arc<LineCipher>> {
+    let mut guard = SHARED_CIPHER.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(cipher) = guard.as_ref() {
+        return Ok(cipher.clone());
+    }
+    let cipher = Arc::new(LineCipher::load_or_create(store)?);
+    *guard = Some(cipher.clone());
+    Ok(cipher)
+}
+
+pub(crate) fn is_encrypted_line(line: &str) -> bool {
+    line.trim_start().starts_with(ENCRYPTED_LINE_PREFIX)
+}
+
+/// Returns the plaintext of a JSONL record, decrypting it with the shared
+/// cipher if it was written encrypted.
+pub(crate) fn decode_line(line: &str) -> io::Result<Cow<'_, str>> {
+    if !is_encrypted_line(line) {
+        return Ok(Cow::Borrowed(line));
+    }
+    let cipher = shared_cipher(false)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "found an encrypted record but no session key in the keyring",
+        )
+    })?;
+    cipher.decrypt_line(line.trim()).map(Cow::Owned)
+}
+
+/// Applies [`decode_line`] to every line of `text`.
+pub(crate) fn decode_text(text: String) -> io::Result<String> {
+    if !text.lines().any(is_encrypted_line) {
+        return Ok(text);
+    }
+    let mut decoded = String::with_capacity(text.len());
+    for line in text.lines() {
+        decoded.push_str(&decode_line(line)?);
+        decoded.push('\n');
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn key_is_created_once_and_reused() -> io::Result<()> {
+        let store = MockKeyringStore::default();
+        assert!(LineCipher::load(&store)?.is_none());
+
+        let writer = LineCipher::load_or_create(&store)?;
+        let saved = store.saved_value(KEYRING_ACCOUNT);
+        let reader = LineCipher::load_or_create(&store)?;
+        assert_eq!(store.saved_value(KEYRING_ACCOUNT), saved);
+
+        let line = r#"{"timestamp":"2025-01-01T00:00:00Z","type":"event_msg"}"#;
+        let encrypted = writer.encrypt_line(line)?;
+        assert!(is_encrypted_line(&encrypted));
+        assert!(!encrypted.contains("event_msg"));
+        assert_eq!(reader.decrypt_line(&encrypted)?, line);
+        Ok(())
+    }
+
+    #[test]
+    fn decrypting_with_another_key_fails() -> io::Result<()> {
+        let writer = LineCipher::load_or_create(&MockKeyringStore::default())?;
+        let other = LineCipher::load_or_create(&MockKeyringStore::default())?;
+        let encrypted = writer.encrypt_line("{}")?;
+        let err = other.decrypt_line(&encrypted).expect_err("wrong key");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        Ok(())
+    }
+
+    #[test]
+    fn plaintext_lines_pass_through() -> io::Result<()> {
+        let text = "{\"a\":1}\n{\"b\":2}\n".to_string();
+        assert_eq!(decode_text(text.clone())?, text);
+        assert_eq!(decode_line("{\"a\":1}")?, "{\"a\":1}");
+        Ok(())
+    }
+}
@@ -94,23 +94,26 @@ impl EnvironmentContext {
             && self.writable_roots == *writable_roots
     }
 
+    /// Only the fields that changed between the two turns, so the update
+    /// appended to the history stays small and the earlier context (and the
+    /// cached prompt prefix) is left untouched.
     pub fn diff(before: &TurnContext, after: &TurnContext) -> Self {
-        let cwd = if before.cwd != after.cwd {
-            Some(after.cwd.clone())
-        } else {
-            None
-        };
-        let approval_policy = if before.approval_policy != after.approval_policy {
-            Some(after.approval_policy)
-        } else {
-            None
-        };
-        let sandbox_policy = if before.sandbox_policy != after.sandbox_policy {
-            Some(after.sandbox_policy.clone())
-        } else {
-            None
-        };
-        EnvironmentContext::new(cwd, approval_policy, sandbox_policy, None)
+        Self::from(before).changed_fields(Self::from(after))
+    }
+
+    fn changed_fields(self, next: EnvironmentContext) -> Self {
+        fn changed<T: PartialEq>(before: Option<T>, after: Option<T>) -> Option<T> {
+            if before == after { None } else { after }
+        }
+
+        Self {
+            cwd: changed(self.cwd, next.cwd),
+            approval_policy: changed(self.approval_policy, next.approval_policy),
+            sandbox_mode: changed(self.sandbox_mode, next.sandbox_mode),
+            network_access: changed(self.network_access, next.network_access),
+            writable_roots: changed(self.writable_roots, next.writable_roots),
+            shell: None,
+        }
     }
 }
 
@@ -342,4 +345,26 @@ mod tests {
 
         assert!(context1.equals_except_shell(&context2));
     }
+
+    #[test]
+    fn changed_fields_only_includes_updated_settings() {
+        let before = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], false)),
+            None,
+        );
+        let after = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], true)),
+            None,
+        );
+
+        let expected = r#"<environment_context>
+  <network_access>enabled</network_access>
+</environment_context>"#;
+
+        assert_eq!(before.changed_fields(after).serialize_to_xml(), expected);
+    }
 }
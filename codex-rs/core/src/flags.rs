@@ -3,4 +3,10 @@ use env_flags::env_flags;
 env_flags! {
     /// Fixture path for offline tests (see client.rs).
     pub CODEX_RS_SSE_FIXTURE: Option<&str> = None;
+
+    /// Record every model request and response to this cassette file (see cassette.rs).
+    pub CODEX_RS_RECORD_CASSETTE: Option<&str> = None;
+
+    /// Serve model responses from this cassette file instead of the provider (see cassette.rs).
+    pub CODEX_RS_REPLAY_CASSETTE: Option<&str> = None;
 }
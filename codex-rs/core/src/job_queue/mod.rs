@@ -0,0 +1,446 @@
+//! Persistent queue of prompts that run unattended, used by `codex queue`.
+//!
+//! Each job is stored as `<CODEX_HOME>/queue/jobs/<id>.json`, so a worker can
+//! update one job without rewriting the others. [`run_queue`] drains the queue
+//! through a [`crate::ConversationManager`]; every attempt is a regular
+//! session, so its transcript ends up in the usual rollout files.
+
+mod runner;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::ConversationId;
+use codex_protocol::config_types::SandboxMode;
+use serde::Deserialize;
+use serde::Serialize;
+use uuid::Uuid;
+
+pub use runner::QueueRunOptions;
+pub use runner::QueueRunStats;
+pub use runner::retry_delay;
+pub use runner::run_queue;
+
+/// Directory under `CODEX_HOME` that holds the queue.
+pub const QUEUE_SUBDIR: &str = "queue";
+/// File under [`QUEUE_SUBDIR`] that finished jobs are appended to.
+pub const QUEUE_SUMMARY_FILE: &str = "summary.jsonl";
+const JOBS_SUBDIR: &str = "jobs";
+const WORKTREES_SUBDIR: &str = "worktrees";
+const WORKER_LOCK_FILE: &str = "worker.lock";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting to run, possibly until [`QueuedJob::run_after`].
+    Pending,
+    Running,
+    Succeeded,
+    /// Every attempt failed.
+    Failed,
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A prompt in the queue together with the settings it runs with and the
+/// result of its latest attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub id: String,
+    pub prompt: String,
+    pub cwd: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_mode: Option<SandboxMode>,
+    pub max_attempts: u32,
+    pub created_at: DateTime<Utc>,
+    /// Earliest time the job may start: the scheduled time until the first
+    /// attempt, then the end of the retry backoff.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_after: Option<DateTime<Utc>>,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    /// Working directory inside the job's git worktree, once one was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<PathBuf>,
+    /// Commit the worktree was created at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_head: Option<String>,
+    /// Ref pointing at the commits the job made, written when its worktree is
+    /// removed after it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<ConversationId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollout_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_agent_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl QueuedJob {
+    /// Whether a worker may start the job at `now`.
+    pub fn is_ready(&self, now: DateTime<Utc>) -> bool {
+        self.status == JobStatus::Pending && self.run_after.is_none_or(|at| at <= now)
+    }
+}
+
+/// Settings for a job added with [`JobQueue::add`].
+#[derive(Debug, Clone, Default)]
+pub struct NewJob {
+    pub prompt: String,
+    pub cwd: PathBuf,
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub sandbox_mode: Option<SandboxMode>,
+    /// Attempts before the job is marked failed; at least one.
+    pub max_attempts: u32,
+    /// Do not start the job before this time.
+    pub run_after: Option<DateTime<Utc>>,
+}
+
+/// One line of the summary file, written when a job succeeds or runs out of
+/// attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobSummary {
+    pub id: String,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub prompt: String,
+    pub cwd: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<ConversationId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollout_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_agent_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl From<&QueuedJob> for JobSummary {
+    fn from(job: &QueuedJob) -> Self {
+        Self {
+            id: job.id.clone(),
+            status: job.status,
+            attempts: job.attempts,
+            prompt: job.prompt.clone(),
+            cwd: job.cwd.clone(),
+            worktree: job.worktree.clone(),
+            result_ref: job.result_ref.clone(),
+            conversation_id: job.conversation_id,
+            rollout_path: job.rollout_path.clone(),
+            last_agent_message: job.last_agent_message.clone(),
+            error: job.last_error.clone(),
+            finished_at: job.finished_at.unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+/// Jobs stored under `<CODEX_HOME>/queue`.
+#[derive(Debug, Clone)]
+pub struct JobQueue {
+    codex_home: PathBuf,
+    root: PathBuf,
+}
+
+impl JobQueue {
+    pub fn new(codex_home: &Path) -> Self {
+        Self {
+            codex_home: codex_home.to_path_buf(),
+            root: codex_home.join(QUEUE_SUBDIR),
+        }
+    }
+
+    /// `CODEX_HOME` the queue lives in; jobs load their configuration from it.
+    pub fn codex_home(&self) -> &Path {
+        &self.codex_home
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Default location of the summary file.
+    pub fn summary_path(&self) -> PathBuf {
+        self.root.join(QUEUE_SUMMARY_FILE)
+    }
+
+    /// Where the git worktree of job `id` is created.
+    pub fn worktree_path(&self, id: &str) -> PathBuf {
+        self.root.join(WORKTREES_SUBDIR).join(id)
+    }
+
+    fn jobs_dir(&self) -> PathBuf {
+        self.root.join(JOBS_SUBDIR)
+    }
+
+    fn job_path(&self, id: &str) -> PathBuf {
+        self.jobs_dir().join(format!("{id}.json"))
+    }
+
+    pub fn add(&self, job: NewJob) -> io::Result<QueuedJob> {
+        if job.prompt.trim().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the prompt is empty",
+            ));
+        }
+        let job = QueuedJob {
+            id: Uuid::new_v4().to_string(),
+            prompt: job.prompt,
+            cwd: job.cwd,
+            profile: job.profile,
+            model: job.model,
+            sandbox_mode: job.sandbox_mode,
+            max_attempts: job.max_attempts.max(1),
+            created_at: Utc::now(),
+            run_after: job.run_after,
+            status: JobStatus::Pending,
+            attempts: 0,
+            worktree: None,
+            worktree_head: None,
+            result_ref: None,
+            conversation_id: None,
+            rollout_path: None,
+            last_agent_message: None,
+            last_error: None,
+            finished_at: None,
+        };
+        self.save(&job)?;
+        Ok(job)
+    }
+
+    /// Every job, oldest first.
+    pub fn list(&self) -> io::Result<Vec<QueuedJob>> {
+        let entries = match fs::read_dir(self.jobs_dir()) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut jobs = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .and_then(|text| serde_json::from_str::<QueuedJob>(&text).map_err(io::Error::from))
+            {
+                Ok(job) => jobs.push(job),
+                Err(err) => tracing::warn!("skipping unreadable queue job {path:?}: {err}"),
+            }
+        }
+        jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(jobs)
+    }
+
+    /// Looks a job up by its id or a unique prefix of it.
+    pub fn find(&self, id_or_prefix: &str) -> io::Result<Option<QueuedJob>> {
+        let mut matches = self
+            .list()?
+            .into_iter()
+            .filter(|job| job.id.starts_with(id_or_prefix));
+        let Some(job) = matches.next() else {
+            return Ok(None);
+        };
+        if job.id != id_or_prefix && matches.next().is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{id_or_prefix}` matches more than one job"),
+            ));
+        }
+        Ok(Some(job))
+    }
+
+    /// Writes `job`, replacing the stored copy atomically.
+    pub fn save(&self, job: &QueuedJob) -> io::Result<()> {
+        let dir = self.jobs_dir();
+        fs::create_dir_all(&dir)?;
+        let mut tmp = tempfile::NamedTempFile::new_in(&dir)?;
+        serde_json::to_writer_pretty(&mut tmp, job)?;
+        tmp.persist(self.job_path(&job.id))
+            .map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// Takes the lock that makes the caller the only worker of this queue, or
+    /// returns `None` when another worker holds it. The lock is released when
+    /// the returned guard is dropped or the process exits.
+    pub fn try_lock_worker(&self) -> io::Result<Option<QueueWorkerLock>> {
+        fs::create_dir_all(&self.root)?;
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.root.join(WORKER_LOCK_FILE))?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(QueueWorkerLock { _file: file })),
+            Err(fs::TryLockError::WouldBlock) => Ok(None),
+            Err(fs::TryLockError::Error(err)) => Err(err),
+        }
+    }
+
+    /// Deletes the job record. Its worktree and rollouts are left alone.
+    pub fn remove(&self, id: &str) -> io::Result<bool> {
+        match fs::remove_file(self.job_path(id)) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Puts jobs left `running` by a worker that did not shut down cleanly
+    /// back into the queue. The interrupted attempt still counts; jobs that
+    /// run out of attempts fail and are appended to `summary_path`. Holding
+    /// the worker lock guarantees that no live worker owns those jobs.
+    pub fn requeue_interrupted(
+        &self,
+        _worker: &QueueWorkerLock,
+        summary_path: &Path,
+    ) -> io::Result<usize> {
+        let mut requeued = 0;
+        for mut job in self.list()? {
+            if job.status != JobStatus::Running {
+                continue;
+            }
+            job.status = if job.attempts >= job.max_attempts {
+                job.finished_at = Some(Utc::now());
+                JobStatus::Failed
+            } else {
+                JobStatus::Pending
+            };
+            job.last_error = Some("the worker stopped while the job was running".to_string());
+            self.save(&job)?;
+            if job.status == JobStatus::Failed {
+                append_job_summary(summary_path, &JobSummary::from(&job))?;
+            }
+            requeued += 1;
+        }
+        Ok(requeued)
+    }
+}
+
+/// Proof that the holder is the only worker draining a [`JobQueue`], taken
+/// with [`JobQueue::try_lock_worker`].
+#[derive(Debug)]
+pub struct QueueWorkerLock {
+    _file: fs::File,
+}
+
+/// Appends `summary` as one JSON line to `path`.
+pub fn append_job_summary(path: &Path, summary: &JobSummary) -> io::Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(summary)?;
+    line.push('\n');
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn new_job(prompt: &str) -> NewJob {
+        NewJob {
+            prompt: prompt.to_string(),
+            cwd: PathBuf::from("/work/api"),
+            max_attempts: 2,
+            ..NewJob::default()
+        }
+    }
+
+    #[test]
+    fn jobs_round_trip_and_are_listed_oldest_first() -> io::Result<()> {
+        let home = tempfile::tempdir()?;
+        let queue = JobQueue::new(home.path());
+        assert!(queue.list()?.is_empty());
+
+        let first = queue.add(new_job("fix the flaky test"))?;
+        let second = queue.add(NewJob {
+            profile: Some("ci".to_string()),
+            sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+            ..new_job("update the changelog")
+        })?;
+        assert_eq!(queue.list()?, vec![first.clone(), second.clone()]);
+        assert_eq!(queue.find(&second.id[..8])?, Some(second.clone()));
+        assert!(queue.add(new_job("  ")).is_err());
+
+        assert!(queue.remove(&first.id)?);
+        assert!(!queue.remove(&first.id)?);
+        assert_eq!(queue.list()?, vec![second]);
+        Ok(())
+    }
+
+    #[test]
+    fn interrupted_jobs_are_requeued_until_out_of_attempts() -> io::Result<()> {
+        let home = tempfile::tempdir()?;
+        let queue = JobQueue::new(home.path());
+        let mut retry = queue.add(new_job("first"))?;
+        retry.status = JobStatus::Running;
+        retry.attempts = 1;
+        queue.save(&retry)?;
+        let mut exhausted = queue.add(new_job("second"))?;
+        exhausted.status = JobStatus::Running;
+        exhausted.attempts = 2;
+        queue.save(&exhausted)?;
+
+        let worker = queue.try_lock_worker()?.expect("no other worker");
+        assert!(queue.try_lock_worker()?.is_none());
+        assert_eq!(
+            queue.requeue_interrupted(&worker, &queue.summary_path())?,
+            2
+        );
+        let statuses: Vec<JobStatus> = queue.list()?.iter().map(|job| job.status).collect();
+        assert_eq!(statuses, vec![JobStatus::Pending, JobStatus::Failed]);
+
+        let summary = fs::read_to_string(queue.summary_path())?;
+        let summaries: Vec<JobSummary> = summary
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].id, exhausted.id);
+        assert_eq!(summaries[0].status, JobStatus::Failed);
+
+        drop(worker);
+        assert!(queue.try_lock_worker()?.is_some());
+        Ok(())
+    }
+}
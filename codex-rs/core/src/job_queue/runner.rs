@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::user_input::UserInput;
+use tokio::task::JoinSet;
+use toml::Value as TomlValue;
+use tracing::warn;
+
+use super::JobQueue;
+use super::JobStatus;
+use super::JobSummary;
+use super::QueuedJob;
+use super::append_job_summary;
+use crate::ConversationManager;
+use crate::NewConversation;
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::load_config_as_toml_with_cli_overrides;
+use crate::git_info::get_git_repo_root;
+use crate::protocol::EventMsg;
+use crate::protocol::Op;
+
+/// Longest wait between two attempts of the same job.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// How a [`run_queue`] worker executes jobs.
+#[derive(Debug, Clone)]
+pub struct QueueRunOptions {
+    /// Number of jobs that may run at the same time.
+    pub parallel: usize,
+    /// Run each job in its own detached git worktree when its directory is
+    /// inside a git repository.
+    pub use_worktrees: bool,
+    /// File that finished jobs are appended to as JSON Lines.
+    pub summary_path: PathBuf,
+    /// Delay before the second attempt; doubled for every further attempt.
+    pub retry_base_delay: Duration,
+    /// Keep running until jobs scheduled for later, or waiting to be retried,
+    /// have run. Otherwise the worker stops once nothing is ready.
+    pub wait_for_scheduled: bool,
+    /// `-c key=value` overrides applied on top of each job's settings.
+    pub cli_overrides: Vec<(String, TomlValue)>,
+    pub codex_linux_sandbox_exe: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueRunStats {
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Failed attempts that were put back into the queue.
+    pub retried: usize,
+}
+
+/// Backoff before attempt `attempt + 1`, after `attempt` failed attempts.
+pub fn retry_delay(attempt: u32, base: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    base.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+/// Runs queued jobs, at most `options.parallel` at a time, until the queue
+/// has no pending job left (or, without `wait_for_scheduled`, none that is
+/// ready). `on_update` is called whenever a job changes status. Fails when
+/// another worker is already draining the queue.
+pub async fn run_queue<F>(
+    queue: &JobQueue,
+    conversation_manager: Arc<ConversationManager>,
+    options: &QueueRunOptions,
+    on_update: F,
+) -> io::Result<QueueRunStats>
+where
+    F: Fn(&QueuedJob),
+{
+    let worker_queue = queue.clone();
+    let summary_path = options.summary_path.clone();
+    let (_worker, requeued) = blocking(move || {
+        let Some(worker) = worker_queue.try_lock_worker()? else {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!(
+                    "another worker is already running the queue in {}",
+                    worker_queue.root().display()
+                ),
+            ));
+        };
+        let requeued = worker_queue.requeue_interrupted(&worker, &summary_path)?;
+        Ok((worker, requeued))
+    })
+    .await?;
+    if requeued > 0 {
+        warn!("requeued {requeued} job(s) interrupted by a previous worker");
+    }
+
+    let parallel = options.parallel.max(1);
+    let mut stats = QueueRunStats::default();
+    let mut running: JoinSet<AttemptResult> = JoinSet::new();
+    let mut in_flight: HashMap<tokio::task::Id, QueuedJob> = HashMap::new();
+
+    loop {
+        while running.len() < parallel {
+            let now = Utc::now();
+            let next = list_jobs(queue).await?.into_iter().find(|job| {
+                job.is_ready(now) && !in_flight.values().any(|running| running.id == job.id)
+            });
+            let Some(mut job) = next else {
+                break;
+            };
+            job.status = JobStatus::Running;
+            job.attempts += 1;
+            job.run_after = None;
+            job.last_error = None;
+            save_job(queue, &job).await?;
+            on_update(&job);
+
+            let task = run_attempt(
+                queue.clone(),
+                job.clone(),
+                Arc::clone(&conversation_manager),
+                options.clone(),
+            );
+            let handle = running.spawn(task);
+            in_flight.insert(handle.id(), job);
+        }
+
+        let next_wakeup = list_jobs(queue)
+            .await?
+            .into_iter()
+            .filter(|job| job.status == JobStatus::Pending)
+            .filter_map(|job| job.run_after)
+            .min();
+        if running.is_empty() {
+            match next_wakeup {
+                Some(at) if options.wait_for_scheduled => {
+                    let wait = (at - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                _ => break,
+            }
+        }
+
+        let wait = next_wakeup
+            .filter(|_| running.len() < parallel)
+            .map(|at| (at - Utc::now()).to_std().unwrap_or(Duration::ZERO));
+        let joined = match wait {
+            Some(wait) => tokio::select! {
+                joined = running.join_next_with_id() => joined,
+                _ = tokio::time::sleep(wait) => continue,
+            },
+            None => running.join_next_with_id().await,
+        };
+        let Some(joined) = joined else {
+            continue;
+        };
+        let (task_id, result) = match joined {
+            Ok((task_id, result)) => (task_id, result),
+            Err(err) => {
+                let result = AttemptResult::failed(format!("the job panicked: {err}"));
+                (err.id(), result)
+            }
+        };
+        let Some(mut job) = in_flight.remove(&task_id) else {
+            continue;
+        };
+        job.worktree = result.worktree.or(job.worktree);
+        job.worktree_head = result.worktree_head.or(job.worktree_head);
+        job.conversation_id = result.conversation_id.or(job.conversation_id);
+        job.rollout_path = result.rollout_path.or(job.rollout_path);
+        match result.outcome {
+            Ok(last_agent_message) => {
+                job.status = JobStatus::Succeeded;
+                job.last_agent_message = last_agent_message;
+                job.finished_at = Some(Utc::now());
+                if job.worktree.is_some() {
+                    remove_worktree(queue, &mut job).await;
+                }
+                stats.succeeded += 1;
+            }
+            Err(error) => {
+                job.last_error = Some(error);
+                if job.attempts < job.max_attempts {
+                    let delay = retry_delay(job.attempts, options.retry_base_delay);
+                    job.status = JobStatus::Pending;
+                    job.run_after = chrono::Duration::from_std(delay)
+                        .ok()
+                        .map(|delay| Utc::now() + delay);
+                    stats.retried += 1;
+                } else {
+                    job.status = JobStatus::Failed;
+                    job.finished_at = Some(Utc::now());
+                    stats.failed += 1;
+                }
+            }
+        }
+        save_job(queue, &job).await?;
+        if matches!(job.status, JobStatus::Succeeded | JobStatus::Failed) {
+            let summary_path = options.summary_path.clone();
+            let summary = JobSummary::from(&job);
+            blocking(move || append_job_summary(&summary_path, &summary)).await?;
+        }
+        on_update(&job);
+    }
+
+    Ok(stats)
+}
+
+/// Runs blocking queue I/O off the async runtime.
+async fn blocking<T, F>(f: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(io::Error::other)?
+}
+
+async fn list_jobs(queue: &JobQueue) -> io::Result<Vec<QueuedJob>> {
+    let queue = queue.clone();
+    blocking(move || queue.list()).await
+}
+
+async fn save_job(queue: &JobQueue, job: &QueuedJob) -> io::Result<()> {
+    let queue = queue.clone();
+    let job = job.clone();
+    blocking(move || queue.save(&job)).await
+}
+
+/// Removes the worktree of a job that succeeded. Commits the job made on the
+/// detached `HEAD` are pinned under [`result_ref`] first so they stay
+/// reachable. Git refuses to remove a worktree with uncommitted changes, so
+/// those are kept until `codex queue remove --force` discards them.
+async fn remove_worktree(queue: &JobQueue, job: &mut QueuedJob) {
+    let worktree_root = queue.worktree_path(&job.id);
+    // Without the commit the worktree started from there is no telling
+    // whether the job committed anything.
+    let Some(base) = job.worktree_head.clone() else {
+        return;
+    };
+
+    let reference = result_ref(&job.id);
+    let pin_root = worktree_root.clone();
+    let pin_reference = reference.clone();
+    let pinned = blocking(move || {
+        codex_git::pin_worktree_head(&pin_root, &base, &pin_reference).map_err(io::Error::other)
+    })
+    .await;
+    match pinned {
+        Ok(true) => job.result_ref = Some(reference),
+        Ok(false) => {}
+        Err(err) => {
+            warn!(
+                "kept the worktree of job {} at {}: {err}",
+                job.id,
+                worktree_root.display()
+            );
+            return;
+        }
+    }
+
+    let removal_root = worktree_root.clone();
+    let removed = blocking(move || {
+        codex_git::remove_worktree(&removal_root, false).map_err(io::Error::other)
+    })
+    .await;
+    match removed {
+        Ok(()) => job.worktree = None,
+        Err(err) => warn!(
+            "kept the worktree of job {} at {}: {err}",
+            job.id,
+            worktree_root.display()
+        ),
+    }
+}
+
+/// Ref that keeps the commits of job `id` once its worktree is gone.
+fn result_ref(id: &str) -> String {
+    format!("refs/codex/queue/{id}")
+}
+
+#[derive(Debug)]
+struct AttemptResult {
+    worktree: Option<PathBuf>,
+    worktree_head: Option<String>,
+    conversation_id: Option<ConversationId>,
+    rollout_path: Option<PathBuf>,
+    /// The final agent message on success, or why the attempt failed.
+    outcome: Result<Option<String>, String>,
+}
+
+impl AttemptResult {
+    fn failed(error: String) -> Self {
+        Self {
+            worktree: None,
+            worktree_head: None,
+            conversation_id: None,
+            rollout_path: None,
+            outcome: Err(error),
+        }
+    }
+}
+
+async fn run_attempt(
+    queue: JobQueue,
+    job: QueuedJob,
+    conversation_manager: Arc<ConversationManager>,
+    options: QueueRunOptions,
+) -> AttemptResult {
+    let worktree = if options.use_worktrees {
+        match prepare_worktree(&queue, &job).await {
+            Ok(worktree) => worktree,
+            Err(err) => {
+                return AttemptResult::failed(format!("failed to create a git worktree: {err}"));
+            }
+        }
+    } else {
+        None
+    };
+    let mut result = AttemptResult {
+        worktree: worktree.as_ref().map(|worktree| worktree.cwd.clone()),
+        worktree_head: worktree.as_ref().and_then(|worktree| worktree.head.clone()),
+        conversation_id: None,
+        rollout_path: None,
+        outcome: Ok(None),
+    };
+    let cwd = worktree.map_or_else(|| job.cwd.clone(), |worktree| worktree.cwd);
+
+    let overrides = ConfigOverrides {
+        model: job.model.clone(),
+        config_profile: job.profile.clone(),
+        // Nobody is around to answer approval requests.
+        approval_policy: Some(AskForApproval::Never),
+        sandbox_mode: job.sandbox_mode,
+        cwd: Some(cwd),
+        codex_linux_sandbox_exe: options.codex_linux_sandbox_exe.clone(),
+        ..ConfigOverrides::default()
+    };
+    let codex_home = queue.codex_home().to_path_buf();
+    let config =
+        match load_config_as_toml_with_cli_overrides(&codex_home, options.cli_overrides.clone())
+            .await
+            .and_then(|cfg| {
+                Config::load_from_base_config_with_overrides(cfg, overrides, codex_home)
+            }) {
+            Ok(config) => config,
+            Err(err) => {
+                result.outcome = Err(format!("failed to load the configuration: {err}"));
+                return result;
+            }
+        };
+
+    let NewConversation {
+        conversation_id,
+        conversation,
+        session_configured,
+    } = match conversation_manager.new_conversation(config).await {
+        Ok(new_conversation) => new_conversation,
+        Err(err) => {
+            result.outcome = Err(format!("failed to start a session: {err}"));
+            return result;
+        }
+    };
+    result.conversation_id = Some(conversation_id);
+    result.rollout_path = Some(session_configured.rollout_path);
+
+    result.outcome = async {
+        conversation
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: job.prompt.clone(),
+                }],
+                final_output_json_schema: None,
+            })
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let mut last_error = None;
+        loop {
+            let event = conversation
+                .next_event()
+                .await
+                .map_err(|err| err.to_string())?;
+            match event.msg {
+                EventMsg::Error(ev) => last_error = Some(ev.message),
+                EventMsg::TaskComplete(ev) => {
+                    return match last_error {
+                        Some(error) => Err(error),
+                        None => Ok(ev.last_agent_message),
+                    };
+                }
+                EventMsg::TurnAborted(ev) => {
+                    return Err(last_error
+                        .unwrap_or_else(|| format!("the turn was aborted ({:?})", ev.reason)));
+                }
+                EventMsg::ShutdownComplete => {
+                    return Err("the session shut down before the turn completed".to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+    .await;
+
+    // Flush the rollout before the session is dropped.
+    if conversation.submit(Op::Shutdown).await.is_ok() {
+        while let Ok(event) = conversation.next_event().await {
+            if matches!(event.msg, EventMsg::ShutdownComplete) {
+                break;
+            }
+        }
+    }
+    conversation_manager
+        .remove_conversation(&conversation_id)
+        .await;
+
+    result
+}
+
+/// Working directory inside a job's worktree and the commit the worktree was
+/// created at.
+struct JobWorktree {
+    cwd: PathBuf,
+    head: Option<String>,
+}
+
+/// Returns the job's worktree, creating it on the first attempt. Later
+/// attempts reuse it so they can build on earlier progress. Jobs outside a git
+/// repository run in place.
+async fn prepare_worktree(queue: &JobQueue, job: &QueuedJob) -> io::Result<Option<JobWorktree>> {
+    if let Some(worktree) = &job.worktree
+        && worktree.exists()
+    {
+        return Ok(Some(JobWorktree {
+            cwd: worktree.clone(),
+            head: job.worktree_head.clone(),
+        }));
+    }
+    if get_git_repo_root(&job.cwd).is_none() {
+        return Ok(None);
+    }
+    let repo_path = job.cwd.clone();
+    let worktree_path = queue.worktree_path(&job.id);
+    tokio::task::spawn_blocking(move || {
+        codex_git::create_detached_worktree(&repo_path, &worktree_path)
+    })
+    .await
+    .map_err(io::Error::other)?
+    .map(|worktree| {
+        Some(JobWorktree {
+            cwd: worktree.cwd,
+            head: Some(worktree.head),
+        })
+    })
+    .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        let base = Duration::from_secs(30);
+        assert_eq!(retry_delay(1, base), Duration::from_secs(30));
+        assert_eq!(retry_delay(2, base), Duration::from_secs(60));
+        assert_eq!(retry_delay(3, base), Duration::from_secs(120));
+        assert_eq!(retry_delay(40, base), MAX_RETRY_DELAY);
+    }
+}
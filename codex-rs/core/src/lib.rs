@@ -5,9 +5,11 @@
 // the TUI or the tracing stack).
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
+mod anthropic_messages;
 mod apply_patch;
 pub mod auth;
 pub mod bash;
+mod cassette;
 mod chat_completions;
 mod client;
 mod client_common;
@@ -20,6 +22,7 @@ pub mod config;
 pub mod config_loader;
 mod context_manager;
 pub mod custom_prompts;
+mod encryption;
 mod environment_context;
 pub mod error;
 pub mod exec;
@@ -27,6 +30,7 @@ pub mod exec_env;
 pub mod features;
 mod flags;
 pub mod git_info;
+pub mod job_queue;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
@@ -34,6 +38,7 @@ mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
 pub mod parse_command;
+mod prompt_cache;
 mod response_processing;
 pub mod sandboxing;
 pub mod token_data;
@@ -48,9 +53,12 @@ pub use model_provider_info::create_oss_provider_with_base_url;
 mod conversation_manager;
 mod event_mapping;
 pub mod review_format;
+pub mod review_prompts;
+pub mod review_rubrics;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
+pub use conversation_manager::count_user_turns;
 // Re-export common auth types for workspace consumers
 pub use auth::AuthManager;
 pub use auth::CodexAuth;
@@ -65,18 +73,35 @@ pub mod shell;
 pub mod spawn;
 pub mod terminal;
 mod tools;
+pub use tools::standalone::StandaloneToolHost;
+pub use tools::standalone::StandaloneToolOutput;
+pub use tools::standalone::StandaloneToolSpec;
 pub mod turn_diff_tracker;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
+pub use rollout::SESSION_INDEX_FILE;
 pub use rollout::SESSIONS_SUBDIR;
+pub use rollout::SessionIndexStats;
 pub use rollout::SessionMeta;
+pub use rollout::bundle::SESSION_ATTACHMENTS_SUBDIR;
+pub use rollout::bundle::SESSION_BUNDLE_EXTENSION;
+pub use rollout::bundle::SessionBundleExport;
+pub use rollout::bundle::SessionBundleImport;
+pub use rollout::bundle::SessionBundleImportOptions;
+pub use rollout::bundle::export_session_bundle;
+pub use rollout::bundle::import_session_bundle;
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
+pub use rollout::list_rollout_paths;
+pub use rollout::resolve_rollout_path;
+pub use rollout::transcript::SessionTranscript;
+pub use rollout::transcript::TranscriptMessage;
+pub use rollout::transcript::TranscriptRole;
 mod function_tool;
 mod state;
 mod tasks;
@@ -96,6 +121,7 @@ pub use codex_protocol::protocol;
 pub use codex_protocol::config_types as protocol_config_types;
 
 pub use client::ModelClient;
+pub use client_common::ModelFailover;
 pub use client_common::Prompt;
 pub use client_common::REVIEW_PROMPT;
 pub use client_common::ResponseEvent;
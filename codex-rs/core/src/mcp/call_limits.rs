@@ -0,0 +1,290 @@
+//! Per-server result cache and rate limits for MCP tool calls, configured via
+//! the `cache` and `rate_limit` tables of an `[mcp_servers.<name>]` entry.
+
+use std::collections::VecDeque;
+use std::num::NonZeroU32;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_utils_cache::BlockingLruCache;
+use codex_utils_cache::sha1_digest;
+use mcp_types::CallToolResult;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+use crate::config::types::McpRateLimitConfig;
+use crate::config::types::McpToolCacheConfig;
+
+const DEFAULT_MAX_CACHE_ENTRIES: usize = 64;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct CachedResult {
+    stored_at: Instant,
+    result: CallToolResult,
+}
+
+/// Successful tool results keyed on tool name and (canonicalized) arguments.
+pub(crate) struct ToolResultCache {
+    ttl: Duration,
+    entries: BlockingLruCache<[u8; 20], CachedResult>,
+}
+
+impl ToolResultCache {
+    pub(crate) fn new(config: McpToolCacheConfig) -> Self {
+        let capacity = config
+            .max_entries
+            .unwrap_or(NonZeroUsize::new(DEFAULT_MAX_CACHE_ENTRIES).unwrap_or(NonZeroUsize::MIN));
+        Self {
+            ttl: config.ttl,
+            entries: BlockingLruCache::new(capacity),
+        }
+    }
+
+    /// Returns the cached result for the call if it has not expired.
+    pub(crate) fn get(&self, tool: &str, arguments: Option<&Value>) -> Option<CallToolResult> {
+        let key = cache_key(tool, arguments);
+        let cached = self.entries.get(&key)?;
+        if cached.stored_at.elapsed() < self.ttl {
+            Some(cached.result)
+        } else {
+            self.entries.remove(&key);
+            None
+        }
+    }
+
+    /// Caches `result` unless the tool reported an error.
+    pub(crate) fn insert(&self, tool: &str, arguments: Option<&Value>, result: &CallToolResult) {
+        if result.is_error.unwrap_or(false) {
+            return;
+        }
+        self.entries.insert(
+            cache_key(tool, arguments),
+            CachedResult {
+                stored_at: Instant::now(),
+                result: result.clone(),
+            },
+        );
+    }
+}
+
+fn cache_key(tool: &str, arguments: Option<&Value>) -> [u8; 20] {
+    let mut key = tool.to_string();
+    key.push('\0');
+    if let Some(arguments) = arguments {
+        write_canonical_json(arguments, &mut key);
+    }
+    sha1_digest(key.as_bytes())
+}
+
+/// Serializes `value` with object keys sorted so that argument order does not
+/// affect the cache key.
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Held for the duration of a rate-limited call.
+pub(crate) struct RateLimitPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+    /// Set when the call had to wait for the limiter before it could start.
+    pub(crate) waited: Option<Duration>,
+}
+
+pub(crate) struct RateLimiter {
+    concurrency: Option<Arc<Semaphore>>,
+    calls_per_minute: Option<NonZeroU32>,
+    recent_calls: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: McpRateLimitConfig) -> Self {
+        Self {
+            concurrency: config
+                .max_concurrent_calls
+                .map(|max| Arc::new(Semaphore::new(max.get()))),
+            calls_per_minute: config.calls_per_minute,
+            recent_calls: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Waits until both the concurrency and the per-minute limit allow
+    /// another call to start.
+    pub(crate) async fn acquire(&self) -> RateLimitPermit {
+        let start = Instant::now();
+        let mut throttled = false;
+
+        let permit = match &self.concurrency {
+            Some(semaphore) => match Arc::clone(semaphore).try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    throttled = true;
+                    Arc::clone(semaphore).acquire_owned().await.ok()
+                }
+            },
+            None => None,
+        };
+
+        if let Some(limit) = self.calls_per_minute {
+            loop {
+                let wait = {
+                    let mut recent_calls = self.recent_calls.lock().await;
+                    reserve_call_slot(&mut recent_calls, Instant::now(), limit)
+                };
+                match wait {
+                    Some(wait) => {
+                        throttled = true;
+                        tokio::time::sleep(wait).await;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        RateLimitPermit {
+            _permit: permit,
+            waited: throttled.then(|| start.elapsed()),
+        }
+    }
+}
+
+/// Records a call at `now` if fewer than `limit` calls started within the
+/// last minute; otherwise returns how long to wait for the oldest to expire.
+fn reserve_call_slot(
+    recent_calls: &mut VecDeque<Instant>,
+    now: Instant,
+    limit: NonZeroU32,
+) -> Option<Duration> {
+    while recent_calls
+        .front()
+        .is_some_and(|started| now.duration_since(*started) >= RATE_LIMIT_WINDOW)
+    {
+        recent_calls.pop_front();
+    }
+    if recent_calls.len() < limit.get() as usize {
+        recent_calls.push_back(now);
+        return None;
+    }
+    recent_calls
+        .front()
+        .map(|oldest| RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(*oldest)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ContentBlock;
+    use mcp_types::TextContent;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn text_result(text: &str, is_error: Option<bool>) -> CallToolResult {
+        CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            })],
+            is_error,
+            structured_content: None,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cache_ignores_argument_order_and_skips_errors() {
+        let cache = ToolResultCache::new(McpToolCacheConfig {
+            ttl: Duration::from_secs(60),
+            max_entries: None,
+        });
+        let result = text_result("found", None);
+        cache.insert("search", Some(&json!({"q": "x", "limit": 1})), &result);
+        cache.insert("fail", None, &text_result("boom", Some(true)));
+
+        assert_eq!(
+            cache.get("search", Some(&json!({"limit": 1, "q": "x"}))),
+            Some(result)
+        );
+        assert_eq!(cache.get("search", Some(&json!({"q": "y"}))), None);
+        assert_eq!(cache.get("fail", None), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cache_entries_expire_after_ttl() {
+        let cache = ToolResultCache::new(McpToolCacheConfig {
+            ttl: Duration::ZERO,
+            max_entries: None,
+        });
+        cache.insert("search", None, &text_result("found", None));
+        assert_eq!(cache.get("search", None), None);
+    }
+
+    #[test]
+    fn call_slots_are_limited_per_minute() {
+        let limit = NonZeroU32::new(2).expect("non-zero");
+        let start = Instant::now();
+        let mut recent_calls = VecDeque::new();
+
+        assert_eq!(reserve_call_slot(&mut recent_calls, start, limit), None);
+        assert_eq!(
+            reserve_call_slot(&mut recent_calls, start + Duration::from_secs(10), limit),
+            None
+        );
+        assert_eq!(
+            reserve_call_slot(&mut recent_calls, start + Duration::from_secs(20), limit),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(
+            reserve_call_slot(&mut recent_calls, start + RATE_LIMIT_WINDOW, limit),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_reports_wait() {
+        let limiter = Arc::new(RateLimiter::new(McpRateLimitConfig {
+            max_concurrent_calls: NonZeroUsize::new(1),
+            calls_per_minute: None,
+        }));
+        let first = limiter.acquire().await;
+        assert_eq!(first.waited, None);
+
+        let waiter = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.acquire().await.waited }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(first);
+
+        let waited = waiter.await.expect("join");
+        assert!(waited.is_some(), "second call should have been throttled");
+    }
+}
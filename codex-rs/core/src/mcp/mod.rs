@@ -1 +1,2 @@
 pub mod auth;
+pub(crate) mod call_limits;
@@ -10,6 +10,8 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -18,6 +20,8 @@ use anyhow::Result;
 use anyhow::anyhow;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::StdioLauncher;
+use codex_rmcp_client::create_env_for_mcp_server;
 use mcp_types::ClientCapabilities;
 use mcp_types::Implementation;
 use mcp_types::ListResourceTemplatesRequestParams;
@@ -37,8 +41,18 @@ use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
 
+use crate::config::types::McpRateLimitConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerSandboxConfig;
+use crate::config::types::McpServerSandboxMode;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::McpToolCacheConfig;
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::exec_env::create_env;
+use crate::landlock::create_linux_sandbox_command_args;
+use crate::mcp::call_limits::RateLimiter;
+use crate::mcp::call_limits::ToolResultCache;
+use crate::protocol::SandboxPolicy;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -100,6 +114,17 @@ struct ManagedClient {
     client: Arc<RmcpClient>,
     startup_timeout: Duration,
     tool_timeout: Option<Duration>,
+    result_cache: Option<ToolResultCache>,
+    rate_limiter: Option<RateLimiter>,
+}
+
+/// Result of [`McpConnectionManager::call_tool`] and how the call was served.
+pub(crate) struct McpToolCallOutcome {
+    pub result: Result<mcp_types::CallToolResult>,
+    /// The result was served from the server's result cache.
+    pub cached: bool,
+    /// Time spent waiting on the server's rate limit before the call ran.
+    pub throttled: Option<Duration>,
 }
 
 /// A thin wrapper around a set of running [`RmcpClient`] instances.
@@ -125,11 +150,17 @@ impl McpConnectionManager {
     ///   are human-readable server identifiers and *values* are the spawn
     ///   instructions.
     ///
+    /// Stdio servers with a `sandbox` setting are launched through
+    /// `codex_linux_sandbox_exe`; `cwd` is the writable workspace for servers
+    /// that do not set their own `cwd`.
+    ///
     /// Servers that fail to start are reported in `ClientStartErrors`: the
     /// user should be informed about these errors.
     pub async fn new(
         mcp_servers: HashMap<String, McpServerConfig>,
         store_mode: OAuthCredentialsStoreMode,
+        codex_linux_sandbox_exe: Option<PathBuf>,
+        cwd: PathBuf,
     ) -> Result<(Self, ClientStartErrors)> {
         // Early exit if no servers are configured.
         if mcp_servers.is_empty() {
@@ -140,6 +171,10 @@ impl McpConnectionManager {
         let mut join_set = JoinSet::new();
         let mut errors = ClientStartErrors::new();
         let mut tool_filters: HashMap<String, ToolFilter> = HashMap::new();
+        let mut call_limits: HashMap<
+            String,
+            (Option<McpToolCacheConfig>, Option<McpRateLimitConfig>),
+        > = HashMap::new();
 
         for (server_name, cfg) in mcp_servers {
             // Validate server name before spawning
@@ -159,6 +194,7 @@ impl McpConnectionManager {
             let startup_timeout = cfg.startup_timeout_sec.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
             let tool_timeout = cfg.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
             tool_filters.insert(server_name.clone(), ToolFilter::from_config(&cfg));
+            call_limits.insert(server_name.clone(), (cfg.cache, cfg.rate_limit));
 
             let resolved_bearer_token = match &cfg.transport {
                 McpServerTransportConfig::StreamableHttp {
@@ -168,8 +204,15 @@ impl McpConnectionManager {
                 _ => Ok(None),
             };
 
+            let codex_linux_sandbox_exe = codex_linux_sandbox_exe.clone();
+            let session_cwd = cwd.clone();
             join_set.spawn(async move {
-                let McpServerConfig { transport, .. } = cfg;
+                let McpServerConfig {
+                    transport,
+                    sandbox,
+                    env_policy,
+                    ..
+                } = cfg;
                 let params = mcp_types::InitializeRequestParams {
                     capabilities: ClientCapabilities {
                         experimental: None,
@@ -202,9 +245,27 @@ impl McpConnectionManager {
                     } => {
                         let command_os: OsString = command.into();
                         let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
-                        match RmcpClient::new_stdio_client(command_os, args_os, env, &env_vars, cwd)
+                        let envs = match env_policy {
+                            Some(policy) => create_env_with_policy(&policy.into(), env, &env_vars),
+                            None => create_env_for_mcp_server(env, &env_vars),
+                        };
+                        let sandbox_cwd = cwd.clone().unwrap_or(session_cwd);
+                        let launcher = sandbox.and_then(stdio_sandbox_policy).map(|policy| {
+                            sandbox_launcher(
+                                codex_linux_sandbox_exe.as_deref(),
+                                &policy,
+                                &sandbox_cwd,
+                            )
+                        });
+                        let client = match launcher.transpose() {
+                            Ok(launcher) => RmcpClient::new_stdio_client_with_launcher(
+                                command_os, args_os, envs, cwd, launcher,
+                            )
                             .await
-                        {
+                            .map_err(anyhow::Error::from),
+                            Err(err) => Err(err),
+                        };
+                        match client {
                             Ok(client) => {
                                 let client = Arc::new(client);
                                 client
@@ -212,7 +273,7 @@ impl McpConnectionManager {
                                     .await
                                     .map(|_| client)
                             }
-                            Err(err) => Err(err.into()),
+                            Err(err) => Err(err),
                         }
                     }
                     McpServerTransportConfig::StreamableHttp {
@@ -263,12 +324,15 @@ impl McpConnectionManager {
 
             match client_res {
                 Ok((client, startup_timeout)) => {
+                    let (cache, rate_limit) = call_limits.remove(&server_name).unwrap_or_default();
                     clients.insert(
                         server_name,
                         ManagedClient {
                             client,
                             startup_timeout,
                             tool_timeout: Some(tool_timeout),
+                            result_cache: cache.map(ToolResultCache::new),
+                            rate_limiter: rate_limit.map(RateLimiter::new),
                         },
                     );
                 }
@@ -435,31 +499,60 @@ impl McpConnectionManager {
         aggregated
     }
 
-    /// Invoke the tool indicated by the (server, tool) pair.
+    /// Invoke the tool indicated by the (server, tool) pair, honoring the
+    /// server's result cache and rate limits.
     pub async fn call_tool(
         &self,
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
-    ) -> Result<mcp_types::CallToolResult> {
+    ) -> McpToolCallOutcome {
+        let uncached = |result| McpToolCallOutcome {
+            result,
+            cached: false,
+            throttled: None,
+        };
         if let Some(filter) = self.tool_filters.get(server)
             && !filter.allows(tool)
         {
-            return Err(anyhow!(
+            return uncached(Err(anyhow!(
                 "tool '{tool}' is disabled for MCP server '{server}'"
-            ));
+            )));
         }
-        let managed = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
-        let client = &managed.client;
-        let timeout = managed.tool_timeout;
+        let Some(managed) = self.clients.get(server) else {
+            return uncached(Err(anyhow!("unknown MCP server '{server}'")));
+        };
 
-        client
-            .call_tool(tool.to_string(), arguments, timeout)
+        if let Some(result) = managed
+            .result_cache
+            .as_ref()
+            .and_then(|cache| cache.get(tool, arguments.as_ref()))
+        {
+            return McpToolCallOutcome {
+                result: Ok(result),
+                cached: true,
+                throttled: None,
+            };
+        }
+
+        let permit = match &managed.rate_limiter {
+            Some(limiter) => Some(limiter.acquire().await),
+            None => None,
+        };
+        let result = managed
+            .client
+            .call_tool(tool.to_string(), arguments.clone(), managed.tool_timeout)
             .await
-            .with_context(|| format!("tool call failed for `{server}/{tool}`"))
+            .with_context(|| format!("tool call failed for `{server}/{tool}`"));
+        if let (Some(cache), Ok(result)) = (&managed.result_cache, &result) {
+            cache.insert(tool, arguments.as_ref(), result);
+        }
+
+        McpToolCallOutcome {
+            result,
+            cached: false,
+            throttled: permit.and_then(|permit| permit.waited),
+        }
     }
 
     /// List resources from the specified server.
@@ -661,6 +754,56 @@ fn is_valid_mcp_server_name(server_name: &str) -> bool {
             .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
 }
 
+/// Environment for a stdio server with an `env_policy`: the policy decides
+/// what is inherited from Codex, then the variables named in `env_vars` are
+/// forwarded and `extra_env` is applied on top.
+fn create_env_with_policy(
+    policy: &ShellEnvironmentPolicy,
+    extra_env: Option<HashMap<String, String>>,
+    env_vars: &[String],
+) -> HashMap<String, String> {
+    let mut envs = create_env(policy);
+    envs.extend(
+        env_vars
+            .iter()
+            .filter_map(|var| env::var(var).ok().map(|value| (var.clone(), value))),
+    );
+    envs.extend(extra_env.unwrap_or_default());
+    envs
+}
+
+fn stdio_sandbox_policy(sandbox: McpServerSandboxConfig) -> Option<SandboxPolicy> {
+    match sandbox.mode {
+        McpServerSandboxMode::ReadOnly => Some(SandboxPolicy::ReadOnly),
+        McpServerSandboxMode::WorkspaceWrite => Some(SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: sandbox.network_access,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+        }),
+        McpServerSandboxMode::None => None,
+    }
+}
+
+/// Runs a stdio server under `codex-linux-sandbox`. A server that asked for a
+/// sandbox is never started unsandboxed, so a missing helper is an error.
+fn sandbox_launcher(
+    codex_linux_sandbox_exe: Option<&Path>,
+    policy: &SandboxPolicy,
+    sandbox_cwd: &Path,
+) -> Result<StdioLauncher> {
+    let Some(codex_linux_sandbox_exe) = codex_linux_sandbox_exe else {
+        return Err(anyhow!(
+            "sandboxed MCP servers require codex-linux-sandbox, which is not available on this platform"
+        ));
+    };
+    Ok(StdioLauncher {
+        program: codex_linux_sandbox_exe.to_path_buf(),
+        arg0: Some("codex-linux-sandbox".to_string()),
+        args: create_linux_sandbox_command_args(Vec::new(), policy, sandbox_cwd),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -819,4 +962,64 @@ mod tests {
         assert_eq!(filtered[0].server_name, "server1");
         assert_eq!(filtered[0].tool_name, "tool_a");
     }
+
+    #[test]
+    fn env_policy_replaces_default_environment() {
+        let policy = ShellEnvironmentPolicy {
+            inherit: crate::config::types::ShellEnvironmentPolicyInherit::None,
+            ..Default::default()
+        };
+        let envs = create_env_with_policy(
+            &policy,
+            Some(HashMap::from([("FOO".to_string(), "bar".to_string())])),
+            &[],
+        );
+        assert_eq!(
+            envs,
+            HashMap::from([("FOO".to_string(), "bar".to_string())])
+        );
+    }
+
+    #[test]
+    fn sandbox_mode_none_runs_unsandboxed() {
+        assert_eq!(
+            stdio_sandbox_policy(McpServerSandboxConfig {
+                mode: McpServerSandboxMode::None,
+                network_access: false,
+            }),
+            None
+        );
+        assert_eq!(
+            stdio_sandbox_policy(McpServerSandboxConfig {
+                mode: McpServerSandboxMode::WorkspaceWrite,
+                network_access: true,
+            }),
+            Some(SandboxPolicy::WorkspaceWrite {
+                writable_roots: Vec::new(),
+                network_access: true,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+            })
+        );
+    }
+
+    #[test]
+    fn sandbox_launcher_wraps_command_with_linux_helper() {
+        let cwd = Path::new("/workspace");
+        let launcher = sandbox_launcher(
+            Some(Path::new("/usr/bin/codex")),
+            &SandboxPolicy::ReadOnly,
+            cwd,
+        )
+        .expect("launcher");
+        assert_eq!(launcher.program, PathBuf::from("/usr/bin/codex"));
+        assert_eq!(launcher.arg0.as_deref(), Some("codex-linux-sandbox"));
+        assert_eq!(
+            launcher.args,
+            create_linux_sandbox_command_args(Vec::new(), &SandboxPolicy::ReadOnly, cwd)
+        );
+        assert_eq!(launcher.args.last().map(String::as_str), Some("--"));
+
+        assert!(sandbox_launcher(None, &SandboxPolicy::ReadOnly, cwd).is_err());
+    }
 }
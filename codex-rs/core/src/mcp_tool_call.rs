@@ -8,6 +8,7 @@ use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::TurnPhase;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 
@@ -56,9 +57,14 @@ pub(crate) async fn handle_mcp_tool_call(
 
     let start = Instant::now();
     // Perform the tool call.
-    let result = sess
+    let outcome = sess
         .call_tool(&server, &tool_name, arguments_value.clone())
-        .await
+        .await;
+    let duration = start.elapsed();
+    sess.record_turn_phase(TurnPhase::McpToolCall, duration)
+        .await;
+    let result = outcome
+        .result
         .map_err(|e| format!("tool call error: {e:?}"));
     if let Err(e) = &result {
         tracing::warn!("MCP tool call error: {e:?}");
@@ -66,8 +72,10 @@ pub(crate) async fn handle_mcp_tool_call(
     let tool_call_end_event = EventMsg::McpToolCallEnd(McpToolCallEndEvent {
         call_id: call_id.clone(),
         invocation,
-        duration: start.elapsed(),
+        duration,
         result: result.clone(),
+        cached: outcome.cached,
+        throttled: outcome.throttled,
     });
 
     notify_mcp_tool_call_event(sess, turn_context, tool_call_end_event.clone()).await;
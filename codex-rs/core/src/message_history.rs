@@ -29,6 +29,11 @@ use tokio::io::AsyncReadExt;
 
 use crate::config::Config;
 use crate::config::types::HistoryPersistence;
+use crate::config::types::SessionEncryption;
+#[cfg(unix)]
+use crate::encryption::decode_line;
+use crate::encryption::shared_cipher;
+use crate::rollout::policy::Redactor;
 
 use codex_protocol::ConversationId;
 #[cfg(unix)]
@@ -55,13 +60,15 @@ fn history_filepath(config: &Config) -> PathBuf {
     path
 }
 
-/// Append a `text` entry associated with `conversation_id` to the history file. Uses
-/// advisory file locking to ensure that concurrent writes do not interleave,
-/// which entails a small amount of blocking I/O internally.
+/// Append a `text` entry associated with `conversation_id` to the history file,
+/// after replacing matches of `redactor`. Uses advisory file locking to ensure
+/// that concurrent writes do not interleave, which entails a small amount of
+/// blocking I/O internally.
 pub(crate) async fn append_entry(
     text: &str,
     conversation_id: &ConversationId,
     config: &Config,
+    redactor: &Redactor,
 ) -> Result<()> {
     match config.history.persistence {
         HistoryPersistence::SaveAll => {
@@ -73,8 +80,6 @@ pub(crate) async fn append_entry(
         }
     }
 
-    // TODO: check `text` for sensitive patterns
-
     // Resolve `~/.codex/history.jsonl` and ensure the parent directory exists.
     let path = history_filepath(config);
     if let Some(parent) = path.parent() {
@@ -91,10 +96,17 @@ pub(crate) async fn append_entry(
     let entry = HistoryEntry {
         session_id: conversation_id.to_string(),
         ts,
-        text: text.to_string(),
+        text: redactor.redact_text(text).into_owned(),
     };
     let mut line = serde_json::to_string(&entry)
         .map_err(|e| std::io::Error::other(format!("failed to serialise history entry: {e}")))?;
+    if config.session_encryption == SessionEncryption::Keyring {
+        let cipher = tokio::task::spawn_blocking(|| shared_cipher(true))
+            .await
+            .map_err(std::io::Error::other)??
+            .ok_or_else(|| std::io::Error::other("no session key available"))?;
+        line = cipher.encrypt_line(&line)?;
+    }
     line.push('\n');
 
     // Open in append-only mode.
@@ -231,6 +243,13 @@ pub(crate) fn lookup(log_id: u64, offset: usize, config: &Config) -> Option<Hist
                     };
 
                     if idx == offset {
+                        let line = match decode_line(&line) {
+                            Ok(line) => line,
+                            Err(e) => {
+                                tracing::warn!(error = %e, "failed to decrypt history entry");
+                                return None;
+                            }
+                        };
                         match serde_json::from_str::<HistoryEntry>(&line) {
                             Ok(entry) => return Some(entry),
                             Err(e) => {
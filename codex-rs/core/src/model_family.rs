@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::Verbosity;
 
+use crate::config::types::ModelFamilyToml;
 use crate::config::types::ReasoningSummaryFormat;
 use crate::tools::handlers::apply_patch::ApplyPatchToolType;
 use crate::tools::spec::ConfigShellToolType;
@@ -200,6 +203,97 @@ pub fn find_family_for_model(slug: &str) -> Option<ModelFamily> {
     }
 }
 
+/// Like [`find_family_for_model`], but applies the best-matching
+/// `[model_families]` entry from config. Returns `None` only when the slug is
+/// unknown and no entry matches it.
+pub fn find_family_for_model_with_overrides(
+    slug: &str,
+    overrides: &HashMap<String, ModelFamilyToml>,
+) -> Option<ModelFamily> {
+    let Some((_, model_family_override)) = find_model_family_override(slug, overrides) else {
+        return find_family_for_model(slug);
+    };
+    let mut family =
+        find_family_for_model(slug).unwrap_or_else(|| derive_default_model_family(slug));
+    apply_model_family_override(&mut family, model_family_override);
+    Some(family)
+}
+
+/// Returns the `[model_families]` entry that applies to `slug`: the longest
+/// key that is a prefix of the slug.
+pub fn find_model_family_override<'a>(
+    slug: &str,
+    overrides: &'a HashMap<String, ModelFamilyToml>,
+) -> Option<(&'a str, &'a ModelFamilyToml)> {
+    overrides
+        .iter()
+        .filter(|(key, _)| slug.starts_with(key.as_str()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(key, value)| (key.as_str(), value))
+}
+
+fn apply_model_family_override(family: &mut ModelFamily, overrides: &ModelFamilyToml) {
+    let ModelFamilyToml {
+        family: family_name,
+        needs_special_apply_patch_instructions,
+        supports_reasoning_summaries,
+        default_reasoning_effort,
+        reasoning_summary_format,
+        supports_parallel_tool_calls,
+        apply_patch_tool_type,
+        base_instructions,
+        experimental_supported_tools,
+        effective_context_window_percent,
+        support_verbosity,
+        default_verbosity,
+        shell_type,
+        // Token limits are not part of `ModelFamily`; see `Config`.
+        context_window: _,
+        max_output_tokens: _,
+        auto_compact_token_limit: _,
+    } = overrides.clone();
+
+    if let Some(family_name) = family_name {
+        family.family = family_name;
+    }
+    if let Some(value) = needs_special_apply_patch_instructions {
+        family.needs_special_apply_patch_instructions = value;
+    }
+    if let Some(value) = supports_reasoning_summaries {
+        family.supports_reasoning_summaries = value;
+    }
+    if let Some(value) = default_reasoning_effort {
+        family.default_reasoning_effort = Some(value);
+    }
+    if let Some(value) = reasoning_summary_format {
+        family.reasoning_summary_format = value;
+    }
+    if let Some(value) = supports_parallel_tool_calls {
+        family.supports_parallel_tool_calls = value;
+    }
+    if let Some(value) = apply_patch_tool_type {
+        family.apply_patch_tool_type = Some(value);
+    }
+    if let Some(value) = base_instructions {
+        family.base_instructions = value;
+    }
+    if let Some(value) = experimental_supported_tools {
+        family.experimental_supported_tools = value;
+    }
+    if let Some(value) = effective_context_window_percent {
+        family.effective_context_window_percent = value;
+    }
+    if let Some(value) = support_verbosity {
+        family.support_verbosity = value;
+    }
+    if let Some(value) = default_verbosity {
+        family.default_verbosity = Some(value);
+    }
+    if let Some(value) = shell_type {
+        family.shell_type = value;
+    }
+}
+
 pub fn derive_default_model_family(model: &str) -> ModelFamily {
     ModelFamily {
         slug: model.to_string(),
@@ -218,3 +312,64 @@ pub fn derive_default_model_family(model: &str) -> ModelFamily {
         default_reasoning_effort: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn longest_matching_override_applies_on_top_of_builtin_family() {
+        let overrides = HashMap::from([
+            (
+                "gpt-5".to_string(),
+                ModelFamilyToml {
+                    support_verbosity: Some(false),
+                    ..Default::default()
+                },
+            ),
+            (
+                "gpt-5-codex".to_string(),
+                ModelFamilyToml {
+                    supports_parallel_tool_calls: Some(true),
+                    shell_type: Some(ConfigShellToolType::ShellCommand),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let family = find_family_for_model_with_overrides("gpt-5-codex-mini", &overrides)
+            .expect("known model slug");
+
+        let mut expected = find_family_for_model("gpt-5-codex-mini").expect("known model slug");
+        expected.supports_parallel_tool_calls = true;
+        expected.shell_type = ConfigShellToolType::ShellCommand;
+        assert_eq!(family, expected);
+    }
+
+    #[test]
+    fn override_declares_unknown_model_family() {
+        let overrides = HashMap::from([(
+            "qwen3-coder".to_string(),
+            ModelFamilyToml {
+                family: Some("qwen3".to_string()),
+                apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+                ..Default::default()
+            },
+        )]);
+
+        assert_eq!(
+            find_family_for_model_with_overrides("llama3", &overrides),
+            None
+        );
+        let family = find_family_for_model_with_overrides("qwen3-coder:30b", &overrides)
+            .expect("declared model family");
+        assert_eq!(family.slug, "qwen3-coder:30b");
+        assert_eq!(family.family, "qwen3");
+        assert_eq!(
+            family.apply_patch_tool_type,
+            Some(ApplyPatchToolType::Function)
+        );
+        assert!(!family.supports_reasoning_summaries);
+    }
+}
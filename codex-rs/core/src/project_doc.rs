@@ -12,6 +12,10 @@
 //!     current working directory (inclusive) and concatenate their contents in
 //!     that order.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! Review mode does not see `AGENTS.md`. Instead it picks up `REVIEW.md`
+//! (or `REVIEW.override.md`) files, discovered with the same rules, so repos
+//! can spell out their own review checks.
 
 use crate::config::Config;
 use dunce::canonicalize as normalize_path;
@@ -23,6 +27,10 @@ use tracing::error;
 pub const DEFAULT_PROJECT_DOC_FILENAME: &str = "AGENTS.md";
 /// Preferred local override for project-level docs.
 pub const LOCAL_PROJECT_DOC_FILENAME: &str = "AGENTS.override.md";
+/// Default filename scanned for repo-level review instructions.
+pub const DEFAULT_REVIEW_DOC_FILENAME: &str = "REVIEW.md";
+/// Preferred local override for repo-level review instructions.
+pub const LOCAL_REVIEW_DOC_FILENAME: &str = "REVIEW.override.md";
 
 /// When both `Config::instructions` and the project doc are present, they will
 /// be concatenated with the following separator.
@@ -53,13 +61,24 @@ pub(crate) async fn get_user_instructions(config: &Config) -> Option<String> {
 /// function returns `Ok(None)`. Unexpected I/O failures bubble up as `Err` so
 /// callers can decide how to handle them.
 pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String>> {
-    let max_total = config.project_doc_max_bytes;
+    if config.project_doc_max_bytes == 0 {
+        return Ok(None);
+    }
+    let paths = discover_project_doc_paths(config)?;
+    read_docs(paths, config.project_doc_max_bytes).await
+}
 
-    if max_total == 0 {
+/// Attempt to locate and load the repo-level review instructions, with the
+/// same search rules and byte budget as `read_project_docs`.
+pub async fn read_review_docs(config: &Config) -> std::io::Result<Option<String>> {
+    if config.project_doc_max_bytes == 0 {
         return Ok(None);
     }
+    let paths = discover_review_doc_paths(config)?;
+    read_docs(paths, config.project_doc_max_bytes).await
+}
 
-    let paths = discover_project_doc_paths(config)?;
+async fn read_docs(paths: Vec<PathBuf>, max_total: usize) -> std::io::Result<Option<String>> {
     if paths.is_empty() {
         return Ok(None);
     }
@@ -111,6 +130,22 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
 /// directory (inclusive). Symlinks are allowed. When `project_doc_max_bytes`
 /// is zero, returns an empty list.
 pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    discover_doc_paths(config, &candidate_filenames(config))
+}
+
+/// Discover `REVIEW.md` files, ordered from repository root to the current
+/// working directory (inclusive).
+pub fn discover_review_doc_paths(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    discover_doc_paths(
+        config,
+        &[LOCAL_REVIEW_DOC_FILENAME, DEFAULT_REVIEW_DOC_FILENAME],
+    )
+}
+
+fn discover_doc_paths(
+    config: &Config,
+    candidate_filenames: &[&str],
+) -> std::io::Result<Vec<PathBuf>> {
     let mut dir = config.cwd.clone();
     if let Ok(canon) = normalize_path(&dir) {
         dir = canon;
@@ -156,9 +191,8 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
     };
 
     let mut found: Vec<PathBuf> = Vec::new();
-    let candidate_filenames = candidate_filenames(config);
     for d in search_dirs {
-        for name in &candidate_filenames {
+        for name in candidate_filenames {
             let candidate = d.join(name);
             match std::fs::symlink_metadata(&candidate) {
                 Ok(md) => {
@@ -447,4 +481,29 @@ mod tests {
                 .eq(DEFAULT_PROJECT_DOC_FILENAME)
         );
     }
+
+    /// REVIEW.md files are discovered like AGENTS.md but kept separate from
+    /// the user instructions.
+    #[tokio::test]
+    async fn review_docs_are_read_separately() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        fs::write(
+            repo.path().join(".git"),
+            "gitdir: /path/to/actual/git/dir\n",
+        )
+        .unwrap();
+        fs::write(repo.path().join("AGENTS.md"), "agents doc").unwrap();
+        fs::write(repo.path().join("REVIEW.md"), "check migrations").unwrap();
+        let nested = repo.path().join("crate_a");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("REVIEW.override.md"), "check unsafe").unwrap();
+
+        let mut cfg = make_config(&repo, 4096, None);
+        cfg.cwd = nested;
+
+        let review = read_review_docs(&cfg).await.expect("read review docs");
+        assert_eq!(review.as_deref(), Some("check migrations\n\ncheck unsafe"));
+        let instructions = get_user_instructions(&cfg).await;
+        assert_eq!(instructions.as_deref(), Some("agents doc"));
+    }
 }
@@ -0,0 +1,151 @@
+//! Detects requests that break the provider's prompt cache.
+//!
+//! Providers cache the longest previously-seen prefix of a request
+//! (instructions, then tools, then input items). As long as each request only
+//! appends to the previous one, everything but the new items is served from
+//! the cache. We keep a cheap fingerprint of the last prompt so we can tell the
+//! user when a request rewrote part of that prefix instead.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use codex_protocol::models::ResponseItem;
+use serde::Serialize;
+
+use crate::client_common::tools::ToolSpec;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PromptFingerprint {
+    model: String,
+    instructions: u64,
+    tools: u64,
+    input: Vec<u64>,
+}
+
+/// The first part of the cached prefix that a request changed.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PrefixBreak {
+    Instructions,
+    Tools,
+    /// Input item `index` differs from (or is missing compared to) the
+    /// previous request.
+    Input {
+        index: usize,
+    },
+}
+
+impl PromptFingerprint {
+    pub(crate) fn new(
+        model: &str,
+        instructions: &str,
+        tools: &[ToolSpec],
+        input: &[ResponseItem],
+    ) -> Self {
+        Self {
+            model: model.to_string(),
+            instructions: hash_of(&instructions),
+            tools: hash_json(&tools),
+            input: input.iter().map(hash_json).collect(),
+        }
+    }
+
+    /// Returns what `next` changed in the prefix sent by `self`. Switching
+    /// models starts a new cache, so that is not reported.
+    pub(crate) fn prefix_break(&self, next: &PromptFingerprint) -> Option<PrefixBreak> {
+        if self.model != next.model {
+            return None;
+        }
+        if self.instructions != next.instructions {
+            return Some(PrefixBreak::Instructions);
+        }
+        if self.tools != next.tools {
+            return Some(PrefixBreak::Tools);
+        }
+        self.input
+            .iter()
+            .enumerate()
+            .find(|(index, hash)| next.input.get(*index) != Some(*hash))
+            .map(|(index, _)| PrefixBreak::Input { index })
+    }
+}
+
+impl PrefixBreak {
+    pub(crate) fn warning_message(&self) -> String {
+        let cause = match self {
+            PrefixBreak::Instructions => "the instructions changed".to_string(),
+            PrefixBreak::Tools => "the set of available tools changed".to_string(),
+            PrefixBreak::Input { index } => {
+                format!("conversation item {index} was rewritten")
+            }
+        };
+        format!(
+            "This request could not fully reuse the prompt cache because {cause} since the previous request; expect higher latency and cost for this turn."
+        )
+    }
+}
+
+fn hash_json<T: Serialize>(value: &T) -> u64 {
+    match serde_json::to_vec(value) {
+        Ok(bytes) => hash_of(&bytes),
+        Err(_) => 0,
+    }
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn appending_items_keeps_the_prefix() {
+        let tools = vec![ToolSpec::LocalShell {}];
+        let first = PromptFingerprint::new("gpt-5", "base", &tools, &[message("a")]);
+        let second = PromptFingerprint::new("gpt-5", "base", &tools, &[message("a"), message("b")]);
+
+        assert_eq!(first.prefix_break(&second), None);
+    }
+
+    #[test]
+    fn reports_the_first_rewritten_part_of_the_prefix() {
+        let tools = vec![ToolSpec::LocalShell {}];
+        let input = [message("a"), message("b")];
+        let previous = PromptFingerprint::new("gpt-5", "base", &tools, &input);
+
+        let rewritten =
+            PromptFingerprint::new("gpt-5", "base", &tools, &[message("a"), message("c")]);
+        assert_eq!(
+            previous.prefix_break(&rewritten),
+            Some(PrefixBreak::Input { index: 1 })
+        );
+
+        let new_tools = PromptFingerprint::new("gpt-5", "base", &[], &input);
+        assert_eq!(previous.prefix_break(&new_tools), Some(PrefixBreak::Tools));
+
+        let new_instructions = PromptFingerprint::new("gpt-5", "other", &[], &[]);
+        assert_eq!(
+            previous.prefix_break(&new_instructions),
+            Some(PrefixBreak::Instructions)
+        );
+
+        let other_model = PromptFingerprint::new("o3", "other", &[], &[]);
+        assert_eq!(previous.prefix_break(&other_model), None);
+    }
+}
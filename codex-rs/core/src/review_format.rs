@@ -17,6 +17,9 @@ fn format_location(item: &ReviewFinding) -> String {
 ///   default to selected.
 /// - When `selection` is `None`, the marker is omitted and a simple bullet is
 ///   rendered ("- Title — path:start-end").
+/// - When any finding carries a rubric `category`, findings are grouped under
+///   a "category:" line per rubric, in order of first appearance. Findings
+///   without a category are listed under "general".
 pub fn format_review_findings_block(
     findings: &[ReviewFinding],
     selection: Option<&[bool]>,
@@ -31,25 +34,128 @@ pub fn format_review_findings_block(
         lines.push("Review comment:".to_string());
     }
 
-    for (idx, item) in findings.iter().enumerate() {
+    if findings.iter().all(|item| item.category.is_none()) {
+        for (idx, item) in findings.iter().enumerate() {
+            push_finding(&mut lines, idx, item, selection);
+        }
+        return lines.join("\n");
+    }
+
+    let mut categories: Vec<&str> = Vec::new();
+    for item in findings {
+        let category = category_of(item);
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    for category in categories {
         lines.push(String::new());
+        lines.push(format!("{category}:"));
+        for (idx, item) in findings.iter().enumerate() {
+            if category_of(item) == category {
+                push_finding(&mut lines, idx, item, selection);
+            }
+        }
+    }
 
-        let title = &item.title;
-        let location = format_location(item);
+    lines.join("\n")
+}
 
-        if let Some(flags) = selection {
-            // Default to selected if index is out of bounds.
-            let checked = flags.get(idx).copied().unwrap_or(true);
-            let marker = if checked { "[x]" } else { "[ ]" };
-            lines.push(format!("- {marker} {title} — {location}"));
-        } else {
-            lines.push(format!("- {title} — {location}"));
-        }
+fn category_of(item: &ReviewFinding) -> &str {
+    item.category
+        .as_deref()
+        .unwrap_or(crate::review_rubrics::GENERAL_REVIEW_CATEGORY)
+}
+
+/// `idx` is the finding's position in the original list, so selections stay
+/// aligned when findings are regrouped.
+fn push_finding(
+    lines: &mut Vec<String>,
+    idx: usize,
+    item: &ReviewFinding,
+    selection: Option<&[bool]>,
+) {
+    lines.push(String::new());
+
+    let title = &item.title;
+    let location = format_location(item);
 
-        for body_line in item.body.lines() {
-            lines.push(format!("  {body_line}"));
+    if let Some(flags) = selection {
+        // Default to selected if index is out of bounds.
+        let checked = flags.get(idx).copied().unwrap_or(true);
+        let marker = if checked { "[x]" } else { "[ ]" };
+        lines.push(format!("- {marker} {title} — {location}"));
+    } else {
+        lines.push(format!("- {title} — {location}"));
+    }
+
+    for body_line in item.body.lines() {
+        lines.push(format!("  {body_line}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, category: Option<&str>) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: format!("{title} body"),
+            confidence_score: 0.5,
+            priority: 1,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 1, end: 2 },
+            },
+            category: category.map(str::to_string),
         }
     }
 
-    lines.join("\n")
+    #[test]
+    fn uncategorized_findings_are_listed_flat() {
+        let block = format_review_findings_block(&[finding("A", None)], None);
+
+        assert_eq!(
+            block,
+            "\nReview comment:\n\n- A — /repo/src/lib.rs:1-2\n  A body"
+        );
+    }
+
+    #[test]
+    fn categorized_findings_are_grouped_and_keep_selection() {
+        let findings = [
+            finding("A", Some("security")),
+            finding("B", None),
+            finding("C", Some("security")),
+        ];
+
+        let block = format_review_findings_block(&findings, Some(&[true, true, false]));
+
+        assert_eq!(
+            block,
+            [
+                "",
+                "Full review comments:",
+                "",
+                "security:",
+                "",
+                "- [x] A — /repo/src/lib.rs:1-2",
+                "  A body",
+                "",
+                "- [ ] C — /repo/src/lib.rs:1-2",
+                "  C body",
+                "",
+                "general:",
+                "",
+                "- [x] B — /repo/src/lib.rs:1-2",
+                "  B body",
+            ]
+            .join("\n")
+        );
+    }
 }
@@ -0,0 +1,42 @@
+use crate::protocol::ReviewRequest;
+
+// Review presets shared by the TUI `/review` popup and `codex exec review`.
+
+/// Review staged, unstaged and untracked changes in the working tree.
+pub fn uncommitted_changes_review_request() -> ReviewRequest {
+    ReviewRequest {
+        prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
+        user_facing_hint: "current changes".to_string(),
+        rubrics: Vec::new(),
+    }
+}
+
+/// Review the changes the current branch would merge into `branch`.
+pub fn base_branch_review_request(branch: &str) -> ReviewRequest {
+    ReviewRequest {
+        prompt: format!(
+            "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
+        ),
+        user_facing_hint: format!("changes against '{branch}'"),
+        rubrics: Vec::new(),
+    }
+}
+
+/// Review the changes introduced by a single commit. The subject is included
+/// in the prompt when known so the model has some context up front.
+pub fn commit_review_request(sha: &str, subject: Option<&str>) -> ReviewRequest {
+    let short = sha.chars().take(7).collect::<String>();
+    let prompt = match subject {
+        Some(subject) => format!(
+            "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
+        ),
+        None => format!(
+            "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings."
+        ),
+    };
+    ReviewRequest {
+        prompt,
+        user_facing_hint: format!("commit {short}"),
+        rubrics: Vec::new(),
+    }
+}
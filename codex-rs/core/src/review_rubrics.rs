@@ -0,0 +1,180 @@
+//! Named review rubrics and assembly of the reviewer's instructions.
+//!
+//! A rubric is a named set of extra checks (security, migration safety, ...)
+//! that a `ReviewRequest` can opt into. Findings raised under a rubric are
+//! tagged with its name in `ReviewFinding::category` so they can be grouped.
+
+use crate::config::Config;
+
+/// Rubrics available without any configuration. `[review_rubrics.<name>]`
+/// entries in config.toml override these by name.
+pub const BUILT_IN_REVIEW_RUBRICS: &[(&str, &str)] = &[
+    (
+        "security",
+        "Look for injection (SQL, shell, path traversal), missing authentication or authorization checks, secrets or credentials in code or logs, unsafe deserialization, and untrusted input reaching privileged operations.",
+    ),
+    (
+        "migrations",
+        "Check that schema and data migrations are safe to run against a live system: they must be reversible or explicitly one-way, must not lock large tables for long, must not drop or rename columns still read by the previous release, and must tolerate being re-run.",
+    ),
+    (
+        "api-compatibility",
+        "Flag breaking changes to public APIs, wire formats, CLI flags, config keys and persisted data: removed or renamed fields, changed defaults or semantics, and new required parameters without a migration path for existing callers.",
+    ),
+];
+
+/// Category given to findings that do not fall under any requested rubric.
+pub const GENERAL_REVIEW_CATEGORY: &str = "general";
+
+const REPO_INSTRUCTIONS_HEADER: &str = "--- repository review instructions ---";
+const RUBRICS_HEADER: &str = "--- review rubrics ---";
+
+/// Names of every rubric a review request may select, sorted.
+pub fn available_review_rubrics(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = BUILT_IN_REVIEW_RUBRICS
+        .iter()
+        .map(|(name, _)| (*name).to_string())
+        .chain(config.review_rubrics.keys().cloned())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Looks up the instructions for each of `names`, preferring config entries
+/// over built-in rubrics. Returns the resolved rubrics and the names that
+/// matched nothing.
+pub fn resolve_review_rubrics(
+    config: &Config,
+    names: &[String],
+) -> (Vec<(String, String)>, Vec<String>) {
+    let mut resolved = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names {
+        let instructions = config
+            .review_rubrics
+            .get(name)
+            .map(|rubric| rubric.instructions.clone())
+            .or_else(|| {
+                BUILT_IN_REVIEW_RUBRICS
+                    .iter()
+                    .find(|(built_in, _)| built_in == name)
+                    .map(|(_, instructions)| (*instructions).to_string())
+            });
+        match instructions {
+            Some(instructions) => resolved.push((name.clone(), instructions)),
+            None => unknown.push(name.clone()),
+        }
+    }
+    (resolved, unknown)
+}
+
+/// Appends the repo's review instructions and the selected rubrics to the
+/// built-in review prompt. Returns `base` unchanged when there is neither.
+pub(crate) fn build_review_instructions(
+    base: &str,
+    repo_instructions: Option<&str>,
+    rubrics: &[(String, String)],
+) -> String {
+    let repo_instructions = repo_instructions
+        .map(str::trim)
+        .filter(|instructions| !instructions.is_empty());
+    if repo_instructions.is_none() && rubrics.is_empty() {
+        return base.to_string();
+    }
+
+    let mut instructions = base.trim_end().to_string();
+    if let Some(repo_instructions) = repo_instructions {
+        instructions.push_str(&format!(
+            "\n\n{REPO_INSTRUCTIONS_HEADER}\n\n{repo_instructions}"
+        ));
+    }
+    if !rubrics.is_empty() {
+        instructions.push_str(&format!(
+            "\n\n{RUBRICS_HEADER}\n\nIn addition to the checks above, apply each of the following rubrics. Set the \"category\" field of every finding to the name of the rubric it falls under, or to \"{GENERAL_REVIEW_CATEGORY}\" if it falls under none of them."
+        ));
+        for (name, rubric) in rubrics {
+            instructions.push_str(&format!("\n\n## {name}\n\n{}", rubric.trim()));
+        }
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use crate::config::types::ReviewRubric;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn config_with_rubric(name: &str, instructions: &str) -> Config {
+        let codex_home = TempDir::new().expect("tempdir");
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("defaults for test should always succeed");
+        config.review_rubrics.insert(
+            name.to_string(),
+            ReviewRubric {
+                instructions: instructions.to_string(),
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn config_rubrics_override_built_ins() {
+        let config = config_with_rubric("security", "Only check the auth module.");
+
+        let (resolved, unknown) = resolve_review_rubrics(
+            &config,
+            &[
+                "security".to_string(),
+                "perf".to_string(),
+                "migrations".to_string(),
+            ],
+        );
+        assert_eq!(
+            resolved,
+            vec![
+                (
+                    "security".to_string(),
+                    "Only check the auth module.".to_string()
+                ),
+                (
+                    "migrations".to_string(),
+                    BUILT_IN_REVIEW_RUBRICS[1].1.to_string()
+                ),
+            ]
+        );
+        assert_eq!(unknown, vec!["perf".to_string()]);
+        assert_eq!(
+            available_review_rubrics(&config),
+            vec!["api-compatibility", "migrations", "security"]
+        );
+    }
+
+    #[test]
+    fn instructions_include_repo_doc_and_rubrics() {
+        let instructions = build_review_instructions(
+            "Base prompt.\n",
+            Some("Never edit generated files.\n"),
+            &[("security".to_string(), "Check auth.".to_string())],
+        );
+
+        assert_eq!(
+            instructions,
+            "Base prompt.\n\n\
+             --- repository review instructions ---\n\n\
+             Never edit generated files.\n\n\
+             --- review rubrics ---\n\n\
+             In addition to the checks above, apply each of the following rubrics. Set the \"category\" field of every finding to the name of the rubric it falls under, or to \"general\" if it falls under none of them.\n\n\
+             ## security\n\n\
+             Check auth."
+        );
+    }
+}
@@ -0,0 +1,690 @@
+//! Portable session bundles.
+//!
+//! A rollout only resumes on the machine that recorded it: it stores absolute
+//! paths, and its ghost snapshots live as unreferenced commits in the local
+//! repository. A bundle is a zstd-compressed tar archive holding the rollout,
+//! the ghost commits it references as a git bundle, and the local images its
+//! messages point at. Importing one rewrites the recorded working directory to
+//! the importer's checkout, restores the ghost commits there and registers the
+//! rollout under `sessions/` so it can be resumed like any other session.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_git::GhostCommit;
+use codex_git::create_ghost_commit_bundle;
+use codex_git::unbundle_ghost_commits;
+use codex_protocol::ConversationId;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression::is_compressed;
+use super::compression::resolve_rollout_path;
+use super::list::find_conversation_path_by_id_str;
+use super::list::parse_timestamp_uuid_from_filename;
+use crate::encryption::decode_text;
+
+/// File extension used for session bundles.
+pub const SESSION_BUNDLE_EXTENSION: &str = "codex-bundle";
+
+/// Directory under `CODEX_HOME` holding images extracted from imported bundles.
+pub const SESSION_ATTACHMENTS_SUBDIR: &str = "session_attachments";
+
+const BUNDLE_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const ROLLOUT_FILE: &str = "rollout.jsonl";
+const SNAPSHOTS_FILE: &str = "snapshots.bundle";
+const IMAGES_DIR: &str = "images";
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleManifest {
+    version: u32,
+    session_id: ConversationId,
+    /// Original rollout file name, which encodes the session start time.
+    rollout_file_name: String,
+    /// Working directory the session was recorded in.
+    cwd: PathBuf,
+    /// Ghost commits stored in the git bundle.
+    ghost_commits: Vec<String>,
+    images: Vec<BundledImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundledImage {
+    /// Reference as it appears in the rollout (a path or `file://` URL).
+    original: String,
+    /// File name inside the bundle's images directory.
+    file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionBundleExport {
+    pub session_id: ConversationId,
+    /// Ghost snapshots written to the bundle.
+    pub ghost_commits: usize,
+    /// Ghost snapshots that could not be bundled: taken outside a git
+    /// repository, or no longer present in it.
+    pub skipped_ghost_commits: usize,
+    pub images: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionBundleImportOptions {
+    /// Checkout that replaces the session's recorded working directory.
+    pub cwd: PathBuf,
+    /// Drop ghost snapshots instead of restoring them into `cwd`.
+    pub skip_snapshots: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionBundleImport {
+    pub session_id: ConversationId,
+    /// Where the imported rollout was registered.
+    pub rollout_path: PathBuf,
+    pub ghost_commits: usize,
+    pub images: usize,
+}
+
+/// Writes the session recorded at `rollout_path` to a bundle at `output`.
+pub async fn export_session_bundle(
+    rollout_path: &Path,
+    output: &Path,
+) -> io::Result<SessionBundleExport> {
+    let rollout_path = resolve_rollout_path(rollout_path).await;
+    let output = output.to_path_buf();
+    tokio::task::spawn_blocking(move || export_blocking(&rollout_path, &output))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Imports the bundle at `bundle` into `codex_home`.
+pub async fn import_session_bundle(
+    codex_home: &Path,
+    bundle: &Path,
+    options: SessionBundleImportOptions,
+) -> io::Result<SessionBundleImport> {
+    let staging = tempfile::tempdir()?;
+    let manifest = {
+        let bundle = bundle.to_path_buf();
+        let staging = staging.path().to_path_buf();
+        tokio::task::spawn_blocking(move || unpack_bundle(&bundle, &staging))
+            .await
+            .map_err(io::Error::other)??
+    };
+
+    if let Some(existing) =
+        find_conversation_path_by_id_str(codex_home, &manifest.session_id.to_string()).await?
+        && existing.file_name().is_some_and(|name| {
+            name.to_string_lossy()
+                .contains(&manifest.session_id.to_string())
+        })
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "session {} already exists at {}",
+                manifest.session_id,
+                existing.display()
+            ),
+        ));
+    }
+
+    let codex_home = codex_home.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        import_blocking(&codex_home, staging.path(), manifest, &options)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+fn export_blocking(rollout_path: &Path, output: &Path) -> io::Result<SessionBundleExport> {
+    let bytes = std::fs::read(rollout_path)?;
+    let bytes = if is_compressed(rollout_path) {
+        zstd::decode_all(bytes.as_slice())?
+    } else {
+        bytes
+    };
+    let text =
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    // Bundles are meant to be shared, so records are stored decrypted.
+    let text = decode_text(text)?;
+    let lines = parse_lines(&text)?;
+
+    let (session_id, cwd) = session_meta(&lines).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("no session meta in {}", rollout_path.display()),
+        )
+    })?;
+    let rollout_file_name = rollout_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".zst").or(Some(name)))
+        .map(str::to_string)
+        .ok_or_else(|| io::Error::other("invalid rollout file name"))?;
+
+    let staging = tempfile::tempdir()?;
+    std::fs::write(staging.path().join(ROLLOUT_FILE), &text)?;
+
+    let ghost_commits = ghost_commits(&lines);
+    let in_repo: Vec<String> = ghost_commits
+        .iter()
+        .filter(|commit| commit.shadow_git_dir().is_none())
+        .map(|commit| commit.id().to_string())
+        .collect();
+    let bundled = if in_repo.is_empty() {
+        Vec::new()
+    } else {
+        create_ghost_commit_bundle(&cwd, &in_repo, &staging.path().join(SNAPSHOTS_FILE))
+            .unwrap_or_else(|e| {
+                warn!(
+                    "failed to bundle ghost snapshots from {}: {e}",
+                    cwd.display()
+                );
+                Vec::new()
+            })
+    };
+
+    let images_dir = staging.path().join(IMAGES_DIR);
+    let mut images = Vec::new();
+    for reference in image_references(&lines) {
+        let Some(source) = local_image_path(&reference) else {
+            continue;
+        };
+        if !source.is_file() {
+            continue;
+        }
+        let file_name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file = format!("{}-{file_name}", images.len());
+        std::fs::create_dir_all(&images_dir)?;
+        std::fs::copy(&source, images_dir.join(&file))?;
+        images.push(BundledImage {
+            original: reference,
+            file,
+        });
+    }
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        session_id,
+        rollout_file_name,
+        cwd,
+        ghost_commits: bundled,
+        images,
+    };
+    std::fs::write(
+        staging.path().join(MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    write_archive(staging.path(), output)?;
+
+    Ok(SessionBundleExport {
+        session_id: manifest.session_id,
+        ghost_commits: manifest.ghost_commits.len(),
+        skipped_ghost_commits: ghost_commits.len() - manifest.ghost_commits.len(),
+        images: manifest.images.len(),
+    })
+}
+
+fn import_blocking(
+    codex_home: &Path,
+    staging: &Path,
+    manifest: BundleManifest,
+    options: &SessionBundleImportOptions,
+) -> io::Result<SessionBundleImport> {
+    ensure_plain_file_name(&manifest.rollout_file_name)?;
+    for image in &manifest.images {
+        ensure_plain_file_name(&image.file)?;
+    }
+    let (timestamp, uuid) = parse_timestamp_uuid_from_filename(&manifest.rollout_file_name)
+        .filter(|_| !manifest.rollout_file_name.ends_with(".zst"))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "invalid rollout file name in bundle: {}",
+                    manifest.rollout_file_name
+                ),
+            )
+        })?;
+    if uuid.to_string() != manifest.session_id.to_string() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "bundle manifest does not match its rollout",
+        ));
+    }
+
+    let restore_snapshots = !options.skip_snapshots && !manifest.ghost_commits.is_empty();
+    if restore_snapshots {
+        unbundle_ghost_commits(&options.cwd, &staging.join(SNAPSHOTS_FILE)).map_err(|e| {
+            io::Error::other(format!(
+                "failed to restore snapshots into {}: {e}; re-run with --skip-snapshots to import without them",
+                options.cwd.display()
+            ))
+        })?;
+    }
+    let kept_snapshots: &[String] = if restore_snapshots {
+        &manifest.ghost_commits
+    } else {
+        &[]
+    };
+
+    let mut replacements = HashMap::new();
+    if !manifest.images.is_empty() {
+        let attachments = codex_home
+            .join(SESSION_ATTACHMENTS_SUBDIR)
+            .join(manifest.session_id.to_string());
+        std::fs::create_dir_all(&attachments)?;
+        for image in &manifest.images {
+            let target = attachments.join(&image.file);
+            std::fs::copy(staging.join(IMAGES_DIR).join(&image.file), &target)?;
+            let replacement = if image.original.starts_with("file://") {
+                format!("file://{}", target.display())
+            } else {
+                target.display().to_string()
+            };
+            replacements.insert(image.original.clone(), replacement);
+        }
+    }
+
+    let text = std::fs::read_to_string(staging.join(ROLLOUT_FILE))?;
+    let mut out = String::with_capacity(text.len());
+    for mut line in parse_lines(&text)? {
+        if let Some(commit) = ghost_commit(&line)
+            && !kept_snapshots.iter().any(|id| id == commit.id())
+        {
+            // The snapshot cannot be restored on this machine.
+            continue;
+        }
+        rewrite_paths(&mut line, &manifest.cwd, &options.cwd, &replacements);
+        out.push_str(&serde_json::to_string(&line)?);
+        out.push('\n');
+    }
+
+    let day_dir = codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(format!("{:04}", timestamp.year()))
+        .join(format!("{:02}", u8::from(timestamp.month())))
+        .join(format!("{:02}", timestamp.day()));
+    std::fs::create_dir_all(&day_dir)?;
+    let rollout_path = day_dir.join(&manifest.rollout_file_name);
+    let tmp = day_dir.join(format!("{}.tmp", manifest.rollout_file_name));
+    std::fs::write(&tmp, out)?;
+    std::fs::rename(&tmp, &rollout_path)?;
+
+    Ok(SessionBundleImport {
+        session_id: manifest.session_id,
+        rollout_path,
+        ghost_commits: kept_snapshots.len(),
+        images: manifest.images.len(),
+    })
+}
+
+/// Rejects manifest file names that are not a single plain file name, so a
+/// crafted bundle cannot read or write files outside its own directories.
+fn ensure_plain_file_name(name: &str) -> io::Result<()> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid file name in bundle: {name}"),
+        )),
+    }
+}
+
+fn write_archive(staging: &Path, output: &Path) -> io::Result<()> {
+    let mut tmp_name = output.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp = PathBuf::from(tmp_name);
+    let encoder = zstd::Encoder::new(File::create(&tmp)?, ZSTD_LEVEL)?;
+    let mut builder = tar::Builder::new(encoder);
+    builder.append_dir_all(".", staging)?;
+    builder.into_inner()?.finish()?;
+    std::fs::rename(&tmp, output)
+}
+
+/// Unpacks `bundle` into `staging`. Bundles come from other machines, so
+/// anything but regular files and directories is rejected: a symlink could
+/// make the import read an arbitrary local file.
+fn unpack_bundle(bundle: &Path, staging: &Path) -> io::Result<BundleManifest> {
+    let decoder = zstd::Decoder::new(File::open(bundle)?)?;
+    let mut archive = tar::Archive::new(decoder);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        if !matches!(
+            entry_type,
+            tar::EntryType::Regular | tar::EntryType::Directory
+        ) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "session bundle entry {} is not a regular file ({entry_type:?})",
+                    entry.path()?.display()
+                ),
+            ));
+        }
+        // Refuses paths that would land outside `staging`.
+        entry.unpack_in(staging)?;
+    }
+    let manifest: BundleManifest =
+        serde_json::from_slice(&std::fs::read(staging.join(MANIFEST_FILE))?)?;
+    if manifest.version != BUNDLE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported session bundle version {}", manifest.version),
+        ));
+    }
+    Ok(manifest)
+}
+
+fn parse_lines(text: &str) -> io::Result<Vec<Value>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(io::Error::from))
+        .collect()
+}
+
+fn session_meta(lines: &[Value]) -> Option<(ConversationId, PathBuf)> {
+    let payload = lines
+        .iter()
+        .find(|line| line["type"] == "session_meta")
+        .map(|line| &line["payload"])?;
+    let id = serde_json::from_value(payload["id"].clone()).ok()?;
+    let cwd = PathBuf::from(payload["cwd"].as_str()?);
+    Some((id, cwd))
+}
+
+fn ghost_commit(line: &Value) -> Option<GhostCommit> {
+    if line["type"] != "response_item" || line["payload"]["type"] != "ghost_snapshot" {
+        return None;
+    }
+    serde_json::from_value(line["payload"]["ghost_commit"].clone()).ok()
+}
+
+fn ghost_commits(lines: &[Value]) -> Vec<GhostCommit> {
+    let mut commits: Vec<GhostCommit> = Vec::new();
+    for commit in lines.iter().filter_map(ghost_commit) {
+        if !commits.iter().any(|existing| existing.id() == commit.id()) {
+            commits.push(commit);
+        }
+    }
+    commits
+}
+
+/// Image references from user messages and `input_image` content items.
+fn image_references(lines: &[Value]) -> Vec<String> {
+    let mut references: Vec<String> = Vec::new();
+    let mut push = |value: &Value| {
+        if let Some(reference) = value.as_str()
+            && !references.iter().any(|existing| existing == reference)
+        {
+            references.push(reference.to_string());
+        }
+    };
+    for line in lines {
+        let payload = &line["payload"];
+        if line["type"] == "event_msg" && payload["type"] == "user_message" {
+            for image in payload["images"].as_array().into_iter().flatten() {
+                push(image);
+            }
+        } else if line["type"] == "response_item" && payload["type"] == "message" {
+            for content in payload["content"].as_array().into_iter().flatten() {
+                if content["type"] == "input_image" {
+                    push(&content["image_url"]);
+                }
+            }
+        }
+    }
+    references
+}
+
+/// Returns the file an image reference points at, or `None` for data and
+/// remote URLs.
+fn local_image_path(reference: &str) -> Option<PathBuf> {
+    let path = Path::new(reference.strip_prefix("file://").unwrap_or(reference));
+    path.is_absolute().then(|| path.to_path_buf())
+}
+
+/// Replaces image references and rebases absolute paths under `from` onto
+/// `to` in every string of `value`.
+fn rewrite_paths(value: &mut Value, from: &Path, to: &Path, images: &HashMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(replacement) = images.get(s.as_str()) {
+                *s = replacement.clone();
+            } else if let Ok(rest) = Path::new(s.as_str()).strip_prefix(from) {
+                let rebased = if rest.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(rest)
+                };
+                *s = rebased.to_string_lossy().into_owned();
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                rewrite_paths(value, from, to, images);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                rewrite_paths(value, from, to, images);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::rollout::session_meta_line;
+    use core_test_support::rollout::write_rollout;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const SESSION_ID: &str = "44444444-4444-4444-8444-444444444444";
+    const FILE_NAME: &str =
+        "rollout-2025-06-01T12-00-00-44444444-4444-4444-8444-444444444444.jsonl";
+
+    fn write_sample_rollout(codex_home: &Path, cwd: &Path, image: &Path) -> io::Result<PathBuf> {
+        let lines = [
+            session_meta_line(SESSION_ID, "2025-06-01T12:00:00.000Z", cwd),
+            json!({
+                "timestamp": "2025-06-01T12:00:01.000Z",
+                "type": "turn_context",
+                "payload": {
+                    "cwd": cwd,
+                    "approval_policy": "on-request",
+                    "sandbox_policy": {
+                        "type": "workspace-write",
+                        "writable_roots": [cwd.join("build")],
+                    },
+                    "model": "gpt-5",
+                    "summary": "auto",
+                }
+            }),
+            json!({
+                "timestamp": "2025-06-01T12:00:02.000Z",
+                "type": "event_msg",
+                "payload": {
+                    "type": "user_message",
+                    "message": "what is in this screenshot?",
+                    "images": [image, "data:image/png;base64,AAAA"],
+                }
+            }),
+            json!({
+                "timestamp": "2025-06-01T12:00:03.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "ghost_snapshot",
+                    "ghost_commit": {
+                        "id": "0123456789abcdef0123456789abcdef01234567",
+                        "parent": null,
+                        "preexisting_untracked_files": [],
+                        "preexisting_untracked_dirs": [],
+                    }
+                }
+            }),
+        ];
+        write_rollout(codex_home, "2025-06-01T12-00-00", SESSION_ID, &lines)
+    }
+
+    #[tokio::test]
+    async fn export_then_import_rewrites_paths_and_images() -> io::Result<()> {
+        let source = TempDir::new()?;
+        let old_cwd = source.path().join("repo");
+        std::fs::create_dir(&old_cwd)?;
+        let image = source.path().join("screenshot.png");
+        std::fs::write(&image, b"png bytes")?;
+        let rollout = write_sample_rollout(source.path(), &old_cwd, &image)?;
+
+        let bundle = source.path().join("session.codex-bundle");
+        let exported = export_session_bundle(&rollout, &bundle).await?;
+        assert_eq!(
+            exported,
+            SessionBundleExport {
+                session_id: serde_json::from_value(json!(SESSION_ID))?,
+                ghost_commits: 0,
+                skipped_ghost_commits: 1,
+                images: 1,
+            }
+        );
+
+        let codex_home = TempDir::new()?;
+        let new_cwd = codex_home.path().join("checkout");
+        let imported = import_session_bundle(
+            codex_home.path(),
+            &bundle,
+            SessionBundleImportOptions {
+                cwd: new_cwd.clone(),
+                skip_snapshots: false,
+            },
+        )
+        .await?;
+        assert_eq!(
+            imported.rollout_path,
+            codex_home
+                .path()
+                .join("sessions/2025/06/01")
+                .join(FILE_NAME)
+        );
+        assert_eq!(imported.ghost_commits, 0);
+
+        let lines = parse_lines(&std::fs::read_to_string(&imported.rollout_path)?)?;
+        assert_eq!(lines.len(), 3, "unbundled snapshot is dropped");
+        assert_eq!(lines[0]["payload"]["cwd"], json!(new_cwd));
+        assert_eq!(
+            lines[1]["payload"]["sandbox_policy"]["writable_roots"],
+            json!([new_cwd.join("build")])
+        );
+        let attachment = codex_home
+            .path()
+            .join(SESSION_ATTACHMENTS_SUBDIR)
+            .join(SESSION_ID)
+            .join("0-screenshot.png");
+        assert_eq!(
+            lines[2]["payload"]["images"],
+            json!([attachment, "data:image/png;base64,AAAA"])
+        );
+        assert_eq!(std::fs::read(&attachment)?, b"png bytes");
+
+        let err = import_session_bundle(
+            codex_home.path(),
+            &bundle,
+            SessionBundleImportOptions {
+                cwd: new_cwd,
+                skip_snapshots: false,
+            },
+        )
+        .await
+        .expect_err("duplicate import");
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        Ok(())
+    }
+
+    #[test]
+    fn import_rejects_file_names_outside_the_bundle() -> io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let staging = TempDir::new()?;
+        let secret = codex_home.path().join("secret.txt");
+        std::fs::write(&secret, "secret")?;
+        let options = SessionBundleImportOptions {
+            cwd: codex_home.path().join("checkout"),
+            skip_snapshots: true,
+        };
+        let manifest = |rollout_file_name: &str, image_file: &str| -> io::Result<BundleManifest> {
+            Ok(BundleManifest {
+                version: BUNDLE_VERSION,
+                session_id: serde_json::from_value(json!(SESSION_ID))?,
+                rollout_file_name: rollout_file_name.to_string(),
+                cwd: PathBuf::from("/repo"),
+                ghost_commits: Vec::new(),
+                images: vec![BundledImage {
+                    original: "/repo/screenshot.png".to_string(),
+                    file: image_file.to_string(),
+                }],
+            })
+        };
+
+        let secret_name = secret.display().to_string();
+        for (rollout_file_name, image_file) in [
+            (FILE_NAME, secret_name.as_str()),
+            (FILE_NAME, "../../secret.txt"),
+            (FILE_NAME, ".."),
+            ("../rollout.jsonl", "0-screenshot.png"),
+        ] {
+            let err = import_blocking(
+                codex_home.path(),
+                staging.path(),
+                manifest(rollout_file_name, image_file)?,
+                &options,
+            )
+            .expect_err("unsafe file name");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        assert!(!codex_home.path().join(SESSION_ATTACHMENTS_SUBDIR).exists());
+        Ok(())
+    }
+    #[test]
+    fn unpack_rejects_links() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let bundle = dir.path().join("session.codex-bundle");
+        let encoder = zstd::Encoder::new(File::create(&bundle)?, 0)?;
+        let mut builder = tar::Builder::new(encoder);
+        let manifest = b"{}";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, MANIFEST_FILE, &manifest[..])?;
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, ROLLOUT_FILE, "/etc/passwd")?;
+        builder.into_inner()?.finish()?;
+
+        let staging = TempDir::new()?;
+        let err = unpack_bundle(&bundle, staging.path()).expect_err("symlink entry");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            std::fs::symlink_metadata(staging.path().join(ROLLOUT_FILE)).is_err(),
+            "the link is not created"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! zstd compression of rollout files.
+//!
+//! Sessions append to `rollout-*.jsonl` while they run. When
+//! `rollout_compression = "zstd"` the writer replaces the file with
+//! `rollout-*.jsonl.zst` once the session shuts down. Readers accept both,
+//! also for `.jsonl` paths handed out before the file was compressed, and
+//! resuming a compressed session decompresses it first so it can be appended
+//! to again.
+
+use std::ffi::OsString;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::encryption::decode_text;
+
+pub(crate) const ROLLOUT_SUFFIX: &str = ".jsonl";
+pub(crate) const COMPRESSED_ROLLOUT_SUFFIX: &str = ".jsonl.zst";
+
+const ZSTD_LEVEL: i32 = 3;
+
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "zst")
+}
+
+/// Strips `.jsonl` or `.jsonl.zst` from a rollout file name.
+pub(crate) fn strip_rollout_suffix(name: &str) -> Option<&str> {
+    name.strip_suffix(COMPRESSED_ROLLOUT_SUFFIX)
+        .or_else(|| name.strip_suffix(ROLLOUT_SUFFIX))
+}
+
+/// Returns `path`, or its `.jsonl.zst` sibling when `path` names a plain
+/// rollout that has since been compressed.
+pub async fn resolve_rollout_path(path: &Path) -> PathBuf {
+    if !is_compressed(path) && !tokio::fs::try_exists(path).await.unwrap_or(true) {
+        let compressed = with_suffix(path, ".zst");
+        if tokio::fs::try_exists(&compressed).await.unwrap_or(false) {
+            return compressed;
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Reads a rollout file, decompressing it if needed.
+pub(crate) async fn read_rollout_bytes(path: &Path) -> io::Result<Vec<u8>> {
+    let path = resolve_rollout_path(path).await;
+    let bytes = tokio::fs::read(&path).await?;
+    if !is_compressed(&path) {
+        return Ok(bytes);
+    }
+    tokio::task::spawn_blocking(move || zstd::decode_all(bytes.as_slice()))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Reads a rollout file as text, decompressing it and decrypting encrypted
+/// records if needed.
+pub(crate) async fn read_rollout_text(path: &Path) -> io::Result<String> {
+    let bytes = read_rollout_bytes(path).await?;
+    let text =
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    decode_text(text)
+}
+
+/// Replaces `path` with a zstd-compressed `<path>.zst` and returns the new
+/// path. The compressed file is written under a temporary name first so a
+/// crash never leaves a truncated rollout behind.
+pub(crate) async fn compress_rollout(path: &Path) -> io::Result<PathBuf> {
+    if is_compressed(path) {
+        return Ok(path.to_path_buf());
+    }
+    let target = with_suffix(path, ".zst");
+    let tmp = with_suffix(&target, ".tmp");
+    let source = path.to_path_buf();
+    let written = target.clone();
+    tokio::task::spawn_blocking(move || -> io::Result<()> {
+        let bytes = std::fs::read(&source)?;
+        let compressed = zstd::encode_all(bytes.as_slice(), ZSTD_LEVEL)?;
+        std::fs::write(&tmp, compressed)?;
+        std::fs::rename(&tmp, &written)?;
+        std::fs::remove_file(&source)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    Ok(target)
+}
+
+/// Inverse of [`compress_rollout`]: restores the plain `.jsonl` file next to
+/// `path`, removes the compressed one and returns the new path.
+pub(crate) async fn decompress_rollout(path: &Path) -> io::Result<PathBuf> {
+    let path = resolve_rollout_path(path).await;
+    if !is_compressed(&path) {
+        return Ok(path);
+    }
+    let target = path.with_extension("");
+    let tmp = with_suffix(&target, ".tmp");
+    let bytes = read_rollout_bytes(&path).await?;
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, &target).await?;
+    tokio::fs::remove_file(&path).await?;
+    Ok(target)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name: OsString = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn compress_and_decompress_round_trip() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let plain = dir.path().join("rollout-2025-01-01T00-00-00-abc.jsonl");
+        tokio::fs::write(&plain, "{\"a\":1}\n{\"b\":2}\n").await?;
+
+        let compressed = compress_rollout(&plain).await?;
+        assert_eq!(
+            compressed,
+            dir.path().join("rollout-2025-01-01T00-00-00-abc.jsonl.zst")
+        );
+        assert!(!plain.exists());
+        assert_eq!(
+            read_rollout_text(&compressed).await?,
+            "{\"a\":1}\n{\"b\":2}\n"
+        );
+
+        // Paths handed out before compression still resolve.
+        assert_eq!(resolve_rollout_path(&plain).await, compressed);
+        assert_eq!(read_rollout_text(&plain).await?, "{\"a\":1}\n{\"b\":2}\n");
+
+        let restored = decompress_rollout(&plain).await?;
+        assert_eq!(restored, plain);
+        assert!(!compressed.exists());
+        assert_eq!(
+            tokio::fs::read_to_string(&plain).await?,
+            "{\"a\":1}\n{\"b\":2}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn strips_both_suffixes() {
+        assert_eq!(strip_rollout_suffix("rollout-x.jsonl"), Some("rollout-x"));
+        assert_eq!(
+            strip_rollout_suffix("rollout-x.jsonl.zst"),
+            Some("rollout-x")
+        );
+        assert_eq!(strip_rollout_suffix("rollout-x.json"), None);
+    }
+}
@@ -0,0 +1,738 @@
+//! Sidecar index of rollout summaries, stored at `~/.codex/sessions/index.json`.
+//!
+//! Listing sessions used to open every rollout file to read its head. The
+//! index keeps one summary per rollout (id, cwd, timestamps, first user
+//! message, model and token totals), grouped by day directory. Each day
+//! remembers the modification time of its directory when it was last scanned,
+//! so a listing only rescans days where rollouts were added, renamed or
+//! removed. The first listing after upgrading scans every day once, which is
+//! how existing rollouts are migrated into the index.
+//!
+//! The recorder also updates its session's entry when the first user message
+//! is written and when the session shuts down, since appending to a rollout
+//! does not change the directory's modification time.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMetaLine;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::TokenUsage;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression::compress_rollout;
+use super::compression::is_compressed;
+use super::compression::read_rollout_bytes;
+use super::compression::resolve_rollout_path;
+use super::list::ConversationItem;
+use super::list::ConversationsPage;
+use super::list::Cursor;
+use super::list::HEAD_RECORD_LIMIT;
+use super::list::ProviderMatcher;
+use super::list::TAIL_RECORD_LIMIT;
+use super::list::build_next_cursor;
+use super::list::collect_dirs_desc;
+use super::list::collect_files;
+use super::list::parse_timestamp_uuid_from_filename;
+use super::list::read_head_and_tail;
+use crate::encryption::decode_text;
+use crate::encryption::is_encrypted_line;
+
+pub const SESSION_INDEX_FILE: &str = "index.json";
+const SESSION_INDEX_VERSION: u32 = 1;
+const FIRST_MESSAGE_MAX_CHARS: usize = 200;
+/// Directory timestamps are only as precise as the filesystem clock, so a day
+/// modified this recently is rescanned on every listing until it settles.
+const MTIME_SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// How long to wait for another process to release the index lock.
+const LOCK_MAX_RETRIES: usize = 50;
+const LOCK_RETRY_SLEEP: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionIndex {
+    version: u32,
+    /// Keyed by the day directory relative to `sessions/`, e.g. `2025/01/31`.
+    days: BTreeMap<String, IndexedDay>,
+}
+
+impl Default for SessionIndex {
+    fn default() -> Self {
+        Self {
+            version: SESSION_INDEX_VERSION,
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexedDay {
+    /// Modification time of the day directory when it was last scanned, in
+    /// nanoseconds since the Unix epoch, or 0 to force a rescan.
+    modified_ns: u64,
+    sessions: Vec<SessionIndexEntry>,
+}
+
+/// Summary of one rollout file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SessionIndexEntry {
+    pub(crate) id: ConversationId,
+    /// Name of the rollout file inside its day directory.
+    pub(crate) file_name: String,
+    pub(crate) created_at: String,
+    /// Timestamp of the last response item, if any.
+    pub(crate) updated_at: Option<String>,
+    pub(crate) cwd: PathBuf,
+    pub(crate) source: SessionSource,
+    pub(crate) model_provider: Option<String>,
+    /// Model of the most recent turn.
+    pub(crate) model: Option<String>,
+    /// Start of the first user message; sessions without one are not listed.
+    pub(crate) first_user_message: Option<String>,
+    pub(crate) token_usage: Option<TokenUsage>,
+    /// Size of the rollout file when it was summarized.
+    #[serde(default)]
+    pub(crate) size: u64,
+    /// Modification time of the rollout file when it was summarized, in
+    /// nanoseconds since the Unix epoch, or 0 to force a rescan.
+    #[serde(default)]
+    pub(crate) modified_ns: u64,
+}
+
+impl SessionIndexEntry {
+    pub(crate) fn new(file_name: String, meta_line: &SessionMetaLine, timestamp: &str) -> Self {
+        let meta = &meta_line.meta;
+        Self {
+            id: meta.id,
+            file_name,
+            created_at: timestamp.to_string(),
+            updated_at: None,
+            cwd: meta.cwd.clone(),
+            source: meta.source.clone(),
+            model_provider: meta.model_provider.clone(),
+            model: None,
+            first_user_message: None,
+            token_usage: None,
+            size: 0,
+            modified_ns: 0,
+        }
+    }
+
+    /// Whether the rollout file is unchanged since this entry summarized it.
+    fn is_current(&self, (size, modified_ns): (u64, u64)) -> bool {
+        self.modified_ns != 0 && self.size == size && self.modified_ns == modified_ns
+    }
+
+    /// Drops the text of the first user message so the index does not keep
+    /// plaintext from an encrypted rollout. The entry still counts as having
+    /// a user message.
+    pub(crate) fn conceal(&mut self) {
+        if let Some(message) = self.first_user_message.as_mut() {
+            message.clear();
+        }
+    }
+
+    /// Folds a line recorded after the session meta into the summary.
+    pub(crate) fn observe(&mut self, line: &RolloutLine) {
+        match &line.item {
+            RolloutItem::ResponseItem(_) => self.updated_at = Some(line.timestamp.clone()),
+            RolloutItem::TurnContext(context) => self.model = Some(context.model.clone()),
+            RolloutItem::EventMsg(EventMsg::UserMessage(event))
+                if self.first_user_message.is_none() =>
+            {
+                self.first_user_message = Some(
+                    event
+                        .message
+                        .chars()
+                        .take(FIRST_MESSAGE_MAX_CHARS)
+                        .collect(),
+                );
+            }
+            RolloutItem::EventMsg(EventMsg::TokenCount(event)) => {
+                if let Some(info) = &event.info {
+                    self.token_usage = Some(info.total_token_usage.clone());
+                }
+            }
+            RolloutItem::SessionMeta(_) | RolloutItem::Compacted(_) | RolloutItem::EventMsg(_) => {}
+        }
+    }
+}
+
+/// Counts reported by [`rebuild_session_index`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SessionIndexStats {
+    pub sessions: usize,
+    pub compressed: usize,
+}
+
+/// Reads the whole rollout at `path` and summarizes it. Returns `None` for
+/// files that do not start with a session meta line yet.
+pub(crate) async fn summarize_rollout(path: &Path) -> io::Result<Option<SessionIndexEntry>> {
+    let path = &resolve_rollout_path(path).await;
+    // Taken before reading so a concurrent append triggers another summary.
+    let (size, modified_ns) = file_fingerprint(path).await?;
+    let bytes = read_rollout_bytes(path).await?;
+    let text =
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let encrypted = text.lines().any(is_encrypted_line);
+    let text = decode_text(text)?;
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| io::Error::other(format!("invalid rollout path {}", path.display())))?
+        .to_string();
+
+    let mut entry: Option<SessionIndexEntry> = None;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(line) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        match (&mut entry, &line.item) {
+            (None, RolloutItem::SessionMeta(meta_line)) => {
+                entry = Some(SessionIndexEntry::new(
+                    file_name.clone(),
+                    meta_line,
+                    &line.timestamp,
+                ));
+            }
+            (Some(entry), _) => entry.observe(&line),
+            (None, _) => {}
+        }
+    }
+    if let Some(entry) = entry.as_mut() {
+        if encrypted {
+            entry.conceal();
+        }
+        entry.size = size;
+        entry.modified_ns = modified_ns;
+    }
+    Ok(entry)
+}
+
+/// Pages through the index, refreshing it first. Only the heads and tails of
+/// the returned rollouts are read from disk.
+pub(crate) async fn get_conversations_from_index(
+    codex_home: &Path,
+    page_size: usize,
+    cursor: Option<&Cursor>,
+    allowed_sources: &[SessionSource],
+    model_providers: Option<&[String]>,
+    default_provider: &str,
+) -> io::Result<ConversationsPage> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let index = {
+        let _lock = SessionIndexLock::acquire(codex_home).await?;
+        let mut index = SessionIndex::load(codex_home).await;
+        if index.refresh(&root).await?
+            && let Err(err) = index.save(codex_home).await
+        {
+            warn!("failed to save session index: {err}");
+        }
+        index
+    };
+
+    let provider_matcher =
+        model_providers.and_then(|filters| ProviderMatcher::new(filters, default_provider));
+    let mut candidates: Vec<_> = index
+        .days
+        .iter()
+        .flat_map(|(key, day)| {
+            day.sessions.iter().filter_map(|entry| {
+                let (ts, id) = parse_timestamp_uuid_from_filename(&entry.file_name)?;
+                Some((ts, id, root.join(key).join(&entry.file_name), entry))
+            })
+        })
+        .collect();
+    candidates.sort_by_key(|(ts, id, _, _)| (Reverse(*ts), Reverse(*id)));
+
+    let mut items: Vec<ConversationItem> = Vec::with_capacity(page_size);
+    let mut scanned_files = 0usize;
+    let mut more_matches_available = false;
+    for (ts, id, path, entry) in candidates {
+        if let Some(cursor) = cursor
+            && !cursor.precedes(ts, id)
+        {
+            continue;
+        }
+        if entry.first_user_message.is_none()
+            || (!allowed_sources.is_empty() && !allowed_sources.contains(&entry.source))
+            || provider_matcher
+                .as_ref()
+                .is_some_and(|matcher| !matcher.matches(entry.model_provider.as_deref()))
+        {
+            continue;
+        }
+        if items.len() == page_size {
+            more_matches_available = true;
+            break;
+        }
+        scanned_files += 1;
+        // The file may have been removed since the day was last scanned.
+        let Ok(summary) = read_head_and_tail(&path, HEAD_RECORD_LIMIT, TAIL_RECORD_LIMIT).await
+        else {
+            continue;
+        };
+        let updated_at = summary.updated_at.or_else(|| summary.created_at.clone());
+        items.push(ConversationItem {
+            path,
+            head: summary.head,
+            tail: summary.tail,
+            created_at: summary.created_at,
+            updated_at,
+        });
+    }
+
+    let next_cursor = if more_matches_available {
+        build_next_cursor(&items)
+    } else {
+        None
+    };
+    Ok(ConversationsPage {
+        items,
+        next_cursor,
+        num_scanned_files: scanned_files,
+        reached_scan_cap: false,
+    })
+}
+
+/// Replaces the entry for `rollout_path`'s session. Does nothing when the
+/// index or the rollout's day is not indexed yet; the next listing picks the
+/// rollout up from disk instead.
+pub(crate) async fn upsert_session_index_entry(
+    codex_home: &Path,
+    rollout_path: &Path,
+    entry: SessionIndexEntry,
+) -> io::Result<()> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let Some(key) = rollout_path.parent().and_then(|dir| day_key(&root, dir)) else {
+        return Ok(());
+    };
+    if !tokio::fs::try_exists(index_path(codex_home)).await? {
+        return Ok(());
+    }
+    let _lock = SessionIndexLock::acquire(codex_home).await?;
+    let mut index = SessionIndex::load(codex_home).await;
+    let Some(day) = index.days.get_mut(&key) else {
+        return Ok(());
+    };
+    day.sessions.retain(|existing| existing.id != entry.id);
+    day.sessions.push(entry);
+    index.save(codex_home).await
+}
+
+/// Rebuilds the index from every rollout under `sessions/`. With
+/// `compress_idle_after`, rollouts that have not been written to for that long
+/// are compressed first; younger ones may still belong to a running session.
+pub(crate) async fn rebuild_session_index(
+    codex_home: &Path,
+    compress_idle_after: Option<Duration>,
+) -> io::Result<SessionIndexStats> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let _lock = SessionIndexLock::acquire(codex_home).await?;
+    let mut index = SessionIndex::default();
+    index.refresh(&root).await?;
+
+    let mut stats = SessionIndexStats::default();
+    if let Some(idle) = compress_idle_after {
+        let cutoff = SystemTime::now().checked_sub(idle).unwrap_or(UNIX_EPOCH);
+        for (key, day) in index.days.iter_mut() {
+            let day_path = root.join(key);
+            let mut renamed = false;
+            for entry in day.sessions.iter_mut() {
+                let path = day_path.join(&entry.file_name);
+                if is_compressed(&path) {
+                    continue;
+                }
+                let modified = tokio::fs::metadata(&path).await?.modified()?;
+                if modified > cutoff {
+                    continue;
+                }
+                let compressed = compress_rollout(&path).await?;
+                if let Some(name) = compressed.file_name().and_then(|name| name.to_str()) {
+                    entry.file_name = name.to_string();
+                }
+                stats.compressed += 1;
+                renamed = true;
+            }
+            if renamed {
+                day.modified_ns = settled_modified_ns(&day_path).await?;
+            }
+        }
+    }
+
+    stats.sessions = index.days.values().map(|day| day.sessions.len()).sum();
+    index.save(codex_home).await?;
+    Ok(stats)
+}
+
+impl SessionIndex {
+    async fn load(codex_home: &Path) -> Self {
+        let path = index_path(codex_home);
+        let Ok(bytes) = tokio::fs::read(&path).await else {
+            return Self::default();
+        };
+        match serde_json::from_slice::<SessionIndex>(&bytes) {
+            Ok(index) if index.version == SESSION_INDEX_VERSION => index,
+            Ok(_) => Self::default(),
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable session index {}: {err}",
+                    path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes the index under a temporary name and renames it into place so
+    /// concurrent readers never see a partial file.
+    async fn save(&self, codex_home: &Path) -> io::Result<()> {
+        let path = index_path(codex_home);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let tmp = path.with_file_name(format!("{SESSION_INDEX_FILE}.{}.tmp", std::process::id()));
+        tokio::fs::write(&tmp, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+
+    /// Rescans the day directories that changed since they were indexed and
+    /// drops days that no longer exist. Returns whether anything changed.
+    async fn refresh(&mut self, root: &Path) -> io::Result<bool> {
+        if !tokio::fs::try_exists(root).await? {
+            let changed = !self.days.is_empty();
+            self.days.clear();
+            return Ok(changed);
+        }
+
+        let mut changed = false;
+        let mut seen: Vec<String> = Vec::new();
+        for (_year, year_path) in collect_dirs_desc(root, |s| s.parse::<u16>().ok()).await? {
+            for (_month, month_path) in
+                collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await?
+            {
+                for (_day, day_path) in
+                    collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+                {
+                    let Some(key) = day_key(root, &day_path) else {
+                        continue;
+                    };
+                    seen.push(key.clone());
+                    // Read the mtime before scanning so a rollout added
+                    // mid-scan triggers another scan next time.
+                    let modified_ns = settled_modified_ns(&day_path).await?;
+                    if modified_ns != 0
+                        && let Some(day) = self.days.get_mut(&key)
+                        && day.modified_ns == modified_ns
+                    {
+                        // Appending to a rollout does not touch its directory.
+                        changed |= rescan_changed_rollouts(&day_path, &mut day.sessions).await;
+                        continue;
+                    }
+                    let previous = self
+                        .days
+                        .remove(&key)
+                        .map(|day| day.sessions)
+                        .unwrap_or_default();
+                    let sessions = scan_day(&day_path, previous).await?;
+                    self.days.insert(
+                        key,
+                        IndexedDay {
+                            modified_ns,
+                            sessions,
+                        },
+                    );
+                    changed = true;
+                }
+            }
+        }
+
+        let before = self.days.len();
+        self.days.retain(|key, _| seen.contains(key));
+        Ok(changed || self.days.len() != before)
+    }
+}
+
+/// Summarizes the rollouts in one day directory, reusing entries from the
+/// previous scan for files that have not changed since.
+async fn scan_day(
+    day_path: &Path,
+    previous: Vec<SessionIndexEntry>,
+) -> io::Result<Vec<SessionIndexEntry>> {
+    let names = collect_files(day_path, |name, _path| {
+        parse_timestamp_uuid_from_filename(name).map(|_| name.to_string())
+    })
+    .await?;
+
+    let mut sessions = Vec::with_capacity(names.len());
+    for name in names {
+        let path = day_path.join(&name);
+        if let Ok(fingerprint) = file_fingerprint(&path).await
+            && let Some(existing) = previous
+                .iter()
+                .find(|entry| entry.file_name == name && entry.is_current(fingerprint))
+        {
+            sessions.push(existing.clone());
+            continue;
+        }
+        match summarize_rollout(&path).await {
+            Ok(Some(entry)) => sessions.push(entry),
+            Ok(None) => {}
+            Err(err) => warn!("failed to index rollout {}: {err}", path.display()),
+        }
+    }
+    Ok(sessions)
+}
+
+/// Summarizes the rollouts of an otherwise unchanged day again when they were
+/// written to since they were indexed. Returns whether any entry changed.
+async fn rescan_changed_rollouts(day_path: &Path, sessions: &mut [SessionIndexEntry]) -> bool {
+    let mut changed = false;
+    for entry in sessions.iter_mut() {
+        let path = day_path.join(&entry.file_name);
+        let Ok(fingerprint) = file_fingerprint(&path).await else {
+            continue;
+        };
+        if entry.is_current(fingerprint) {
+            continue;
+        }
+        match summarize_rollout(&path).await {
+            Ok(Some(summary)) => {
+                *entry = summary;
+                changed = true;
+            }
+            Ok(None) => {}
+            Err(err) => warn!("failed to index rollout {}: {err}", path.display()),
+        }
+    }
+    changed
+}
+
+/// Advisory lock on the index, held across a load-modify-save cycle so
+/// concurrent sessions do not overwrite each other's entries. Released when
+/// dropped.
+struct SessionIndexLock {
+    _file: std::fs::File,
+}
+
+impl SessionIndexLock {
+    async fn acquire(codex_home: &Path) -> io::Result<Self> {
+        let path = index_path(codex_home).with_file_name(format!("{SESSION_INDEX_FILE}.lock"));
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)?;
+            for _ in 0..LOCK_MAX_RETRIES {
+                match file.try_lock() {
+                    Ok(()) => return Ok(Self { _file: file }),
+                    Err(std::fs::TryLockError::WouldBlock) => std::thread::sleep(LOCK_RETRY_SLEEP),
+                    Err(std::fs::TryLockError::Error(err)) => return Err(err),
+                }
+            }
+            Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!("timed out waiting for the lock on {}", path.display()),
+            ))
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+}
+
+fn index_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(SESSIONS_SUBDIR).join(SESSION_INDEX_FILE)
+}
+
+/// `YYYY/MM/DD` for a day directory directly under `root`.
+fn day_key(root: &Path, dir: &Path) -> Option<String> {
+    let relative = dir.strip_prefix(root).ok()?;
+    let parts = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    (parts.len() == 3).then(|| parts.join("/"))
+}
+
+/// Modification time of `path` in nanoseconds, or 0 while it is too recent to
+/// be trusted (see [`MTIME_SETTLE_TIME`]).
+async fn settled_modified_ns(path: &Path) -> io::Result<u64> {
+    let modified = tokio::fs::metadata(path).await?.modified()?;
+    Ok(settled_ns(modified))
+}
+
+/// Size and settled modification time of the file at `path`.
+async fn file_fingerprint(path: &Path) -> io::Result<(u64, u64)> {
+    let metadata = tokio::fs::metadata(path).await?;
+    Ok((metadata.len(), settled_ns(metadata.modified()?)))
+}
+
+fn settled_ns(modified: SystemTime) -> u64 {
+    let settled = SystemTime::now()
+        .duration_since(modified)
+        .is_ok_and(|age| age >= MTIME_SETTLE_TIME);
+    if !settled {
+        return 0;
+    }
+    let nanos = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    u64::try_from(nanos).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::rollout;
+    use core_test_support::rollout::session_meta_line;
+    use core_test_support::rollout::turn_context_line;
+    use core_test_support::rollout::user_message_line;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    const PROVIDER: &str = "openai";
+
+    fn write_rollout(home: &Path, ts: &str, id: Uuid, user_message: Option<&str>) -> PathBuf {
+        let cwd = Path::new("/repo");
+        let mut lines = vec![
+            session_meta_line(id, ts, cwd),
+            turn_context_line(ts, cwd, "gpt-5"),
+        ];
+        lines.extend(user_message.map(|message| user_message_line(ts, message)));
+        rollout::write_rollout(home, ts, id, &lines).unwrap()
+    }
+
+    async fn list(home: &Path, page_size: usize, cursor: Option<&Cursor>) -> ConversationsPage {
+        get_conversations_from_index(home, page_size, cursor, &[], None, PROVIDER)
+            .await
+            .unwrap()
+    }
+
+    fn paths(page: &ConversationsPage) -> Vec<PathBuf> {
+        page.items.iter().map(|item| item.path.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn first_listing_migrates_existing_rollouts_and_pages_newest_first() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let oldest = write_rollout(home, "2025-01-01T10-00-00", Uuid::from_u128(1), Some("a"));
+        let middle = write_rollout(home, "2025-01-01T11-00-00", Uuid::from_u128(2), Some("b"));
+        let newest = write_rollout(home, "2025-02-03T09-00-00", Uuid::from_u128(3), Some("c"));
+        write_rollout(home, "2025-02-03T10-00-00", Uuid::from_u128(4), None);
+
+        let page1 = list(home, 2, None).await;
+        assert!(index_path(home).exists());
+        assert_eq!(paths(&page1), vec![newest, middle]);
+        let page2 = list(home, 2, page1.next_cursor.as_ref()).await;
+        assert_eq!(paths(&page2), vec![oldest]);
+        assert_eq!(page2.next_cursor, None);
+
+        let index = SessionIndex::load(home).await;
+        let entry = &index.days["2025/01/01"].sessions[0];
+        assert_eq!(entry.model.as_deref(), Some("gpt-5"));
+        assert_eq!(entry.cwd, PathBuf::from("/repo"));
+        assert_eq!(index.days["2025/02/03"].sessions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn listing_rescans_days_that_changed() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let first = write_rollout(home, "2025-01-01T10-00-00", Uuid::from_u128(1), Some("a"));
+        assert_eq!(paths(&list(home, 10, None).await), vec![first.clone()]);
+
+        let second = write_rollout(home, "2025-01-01T12-00-00", Uuid::from_u128(2), Some("b"));
+        let compressed = compress_rollout(&first).await.unwrap();
+        assert_eq!(
+            paths(&list(home, 10, None).await),
+            vec![second.clone(), compressed.clone()]
+        );
+
+        std::fs::remove_file(&compressed).unwrap();
+        assert_eq!(paths(&list(home, 10, None).await), vec![second]);
+    }
+
+    #[tokio::test]
+    async fn listing_resummarizes_rollouts_that_were_appended_to() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let path = write_rollout(home, "2025-01-01T10-00-00", Uuid::from_u128(7), None);
+        assert!(list(home, 10, None).await.items.is_empty());
+
+        let line = user_message_line("2025-01-01T10-00-01", "hello");
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str(&format!("{line}\n"));
+        std::fs::write(&path, text).unwrap();
+
+        assert_eq!(paths(&list(home, 10, None).await), vec![path]);
+        let index = SessionIndex::load(home).await;
+        assert_eq!(
+            index.days["2025/01/01"].sessions[0]
+                .first_user_message
+                .as_deref(),
+            Some("hello")
+        );
+    }
+
+    #[tokio::test]
+    async fn upsert_updates_indexed_sessions() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let id = Uuid::from_u128(7);
+        let path = write_rollout(home, "2025-01-01T10-00-00", id, None);
+        assert!(list(home, 10, None).await.items.is_empty());
+
+        let mut entry = summarize_rollout(&path).await.unwrap().unwrap();
+        entry.first_user_message = Some("hello".to_string());
+        upsert_session_index_entry(home, &path, entry)
+            .await
+            .unwrap();
+        let index = SessionIndex::load(home).await;
+        let sessions = &index.days["2025/01/01"].sessions;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].first_user_message.as_deref(), Some("hello"));
+    }
+
+    #[tokio::test]
+    async fn rebuild_compresses_idle_rollouts() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let path = write_rollout(home, "2025-01-01T10-00-00", Uuid::from_u128(1), Some("a"));
+
+        let stats = rebuild_session_index(home, Some(Duration::ZERO))
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            SessionIndexStats {
+                sessions: 1,
+                compressed: 1,
+            }
+        );
+        assert!(!path.exists());
+        let page = list(home, 10, None).await;
+        assert_eq!(page.items.len(), 1);
+        assert!(is_compressed(&page.items[0].path));
+        assert!(!page.items[0].head.is_empty());
+    }
+}
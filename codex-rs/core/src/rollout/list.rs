@@ -13,6 +13,11 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::compression::is_compressed;
+use super::compression::read_rollout_bytes;
+use super::compression::resolve_rollout_path;
+use super::compression::strip_rollout_suffix;
+use crate::encryption::decode_line;
 use crate::protocol::EventMsg;
 use codex_file_search as file_search;
 use codex_protocol::protocol::RolloutItem;
@@ -48,21 +53,21 @@ pub struct ConversationItem {
 }
 
 #[derive(Default)]
-struct HeadTailSummary {
-    head: Vec<serde_json::Value>,
-    tail: Vec<serde_json::Value>,
+pub(super) struct HeadTailSummary {
+    pub(super) head: Vec<serde_json::Value>,
+    pub(super) tail: Vec<serde_json::Value>,
     saw_session_meta: bool,
     saw_user_event: bool,
     source: Option<SessionSource>,
     model_provider: Option<String>,
-    created_at: Option<String>,
-    updated_at: Option<String>,
+    pub(super) created_at: Option<String>,
+    pub(super) updated_at: Option<String>,
 }
 
 /// Hard cap to bound worst‑case work per request.
 const MAX_SCAN_FILES: usize = 10000;
-const HEAD_RECORD_LIMIT: usize = 10;
-const TAIL_RECORD_LIMIT: usize = 10;
+pub(super) const HEAD_RECORD_LIMIT: usize = 10;
+pub(super) const TAIL_RECORD_LIMIT: usize = 10;
 
 /// Pagination cursor identifying a file by timestamp and UUID.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -72,9 +77,15 @@ pub struct Cursor {
 }
 
 impl Cursor {
-    fn new(ts: OffsetDateTime, id: Uuid) -> Self {
+    pub(super) fn new(ts: OffsetDateTime, id: Uuid) -> Self {
         Self { ts, id }
     }
+
+    /// Whether the rollout identified by `ts` and `id` sorts after the cursor,
+    /// i.e. belongs on a later page.
+    pub(super) fn precedes(&self, ts: OffsetDateTime, id: Uuid) -> bool {
+        ts < self.ts || (ts == self.ts && id < self.id)
+    }
 }
 
 impl serde::Serialize for Cursor {
@@ -151,7 +162,7 @@ pub(crate) async fn get_conversation(path: &Path) -> io::Result<String> {
 /// Load conversation file paths from disk using directory traversal.
 ///
 /// Directory layout: `~/.codex/sessions/YYYY/MM/DD/rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl`
-/// Returned newest (latest) first.
+/// (or `.jsonl.zst` once compressed). Returned newest (latest) first.
 async fn traverse_directories_for_paths(
     root: PathBuf,
     page_size: usize,
@@ -185,10 +196,6 @@ async fn traverse_directories_for_paths(
                     break 'outer;
                 }
                 let mut day_files = collect_files(day_path, |name_str, path| {
-                    if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
-                        return None;
-                    }
-
                     parse_timestamp_uuid_from_filename(name_str)
                         .map(|(ts, id)| (ts, id, name_str.to_string(), path.to_path_buf()))
                 })
@@ -287,7 +294,7 @@ pub fn parse_cursor(token: &str) -> Option<Cursor> {
     Some(Cursor::new(ts, uuid))
 }
 
-fn build_next_cursor(items: &[ConversationItem]) -> Option<Cursor> {
+pub(super) fn build_next_cursor(items: &[ConversationItem]) -> Option<Cursor> {
     let last = items.last()?;
     let file_name = last.path.file_name()?.to_string_lossy();
     let (ts, id) = parse_timestamp_uuid_from_filename(&file_name)?;
@@ -296,7 +303,10 @@ fn build_next_cursor(items: &[ConversationItem]) -> Option<Cursor> {
 
 /// Collects immediate subdirectories of `parent`, parses their (string) names with `parse`,
 /// and returns them sorted descending by the parsed key.
-async fn collect_dirs_desc<T, F>(parent: &Path, parse: F) -> io::Result<Vec<(T, PathBuf)>>
+pub(super) async fn collect_dirs_desc<T, F>(
+    parent: &Path,
+    parse: F,
+) -> io::Result<Vec<(T, PathBuf)>>
 where
     T: Ord + Copy,
     F: Fn(&str) -> Option<T>,
@@ -320,7 +330,7 @@ where
 }
 
 /// Collects files in a directory and parses them with `parse`.
-async fn collect_files<T, F>(parent: &Path, parse: F) -> io::Result<Vec<T>>
+pub(super) async fn collect_files<T, F>(parent: &Path, parse: F) -> io::Result<Vec<T>>
 where
     F: Fn(&str, &Path) -> Option<T>,
 {
@@ -341,9 +351,9 @@ where
     Ok(collected)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
-    let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
+pub(super) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, optionally with a trailing .zst
+    let core = strip_rollout_suffix(name.strip_prefix("rollout-")?)?;
 
     // Scan from the right for a '-' such that the suffix parses as a UUID.
     let (sep_idx, uuid) = core
@@ -358,13 +368,13 @@ fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uui
     Some((ts, uuid))
 }
 
-struct ProviderMatcher<'a> {
+pub(super) struct ProviderMatcher<'a> {
     filters: &'a [String],
     matches_default_provider: bool,
 }
 
 impl<'a> ProviderMatcher<'a> {
-    fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
+    pub(super) fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
         if filters.is_empty() {
             return None;
         }
@@ -376,7 +386,7 @@ impl<'a> ProviderMatcher<'a> {
         })
     }
 
-    fn matches(&self, session_provider: Option<&str>) -> bool {
+    pub(super) fn matches(&self, session_provider: Option<&str>) -> bool {
         match session_provider {
             Some(provider) => self.filters.iter().any(|candidate| candidate == provider),
             None => self.matches_default_provider,
@@ -384,15 +394,23 @@ impl<'a> ProviderMatcher<'a> {
     }
 }
 
-async fn read_head_and_tail(
+pub(super) async fn read_head_and_tail(
     path: &Path,
     head_limit: usize,
     tail_limit: usize,
 ) -> io::Result<HeadTailSummary> {
+    use tokio::io::AsyncBufRead;
     use tokio::io::AsyncBufReadExt;
 
-    let file = tokio::fs::File::open(path).await?;
-    let reader = tokio::io::BufReader::new(file);
+    let path = &resolve_rollout_path(path).await;
+    // Compressed rollouts are small enough to decompress in full.
+    let reader: Box<dyn AsyncBufRead + Unpin + Send> = if is_compressed(path) {
+        Box::new(std::io::Cursor::new(read_rollout_bytes(path).await?))
+    } else {
+        Box::new(tokio::io::BufReader::new(
+            tokio::fs::File::open(path).await?,
+        ))
+    };
     let mut lines = reader.lines();
     let mut summary = HeadTailSummary::default();
 
@@ -404,7 +422,10 @@ async fn read_head_and_tail(
             continue;
         }
 
-        let parsed: Result<RolloutLine, _> = serde_json::from_str(trimmed);
+        let Ok(decoded) = decode_line(trimmed) else {
+            continue;
+        };
+        let parsed: Result<RolloutLine, _> = serde_json::from_str(&decoded);
         let Ok(rollout_line) = parsed else { continue };
 
         match rollout_line.item {
@@ -469,6 +490,11 @@ async fn read_tail_records(
     if max_records == 0 {
         return Ok((Vec::new(), None));
     }
+    let path = &resolve_rollout_path(path).await;
+    if is_compressed(path) {
+        let bytes = read_rollout_bytes(path).await?;
+        return Ok(collect_last_response_values(&bytes, max_records));
+    }
 
     const CHUNK_SIZE: usize = 8192;
 
@@ -525,7 +551,10 @@ fn collect_last_response_values(
         if trimmed.is_empty() {
             continue;
         }
-        let parsed: serde_json::Result<RolloutLine> = serde_json::from_str(trimmed);
+        let Ok(decoded) = decode_line(trimmed) else {
+            continue;
+        };
+        let parsed: serde_json::Result<RolloutLine> = serde_json::from_str(&decoded);
         let Ok(rollout_line) = parsed else { continue };
         let RolloutLine { timestamp, item } = rollout_line;
         if let RolloutItem::ResponseItem(item) = item
@@ -544,6 +573,32 @@ fn collect_last_response_values(
     (collected_rev, latest_timestamp)
 }
 
+/// Paths of every rollout file under `codex_home`, newest first. Unlike
+/// [`get_conversations`], sessions without a user message are included.
+pub async fn list_rollout_paths(codex_home: &Path) -> io::Result<Vec<PathBuf>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !tokio::fs::try_exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for (_year, year_path) in collect_dirs_desc(&root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                let mut day_files = collect_files(&day_path, |name_str, path| {
+                    parse_timestamp_uuid_from_filename(name_str)
+                        .map(|(ts, id)| (ts, id, path.to_path_buf()))
+                })
+                .await?;
+                day_files.sort_by_key(|(ts, sid, _path)| (Reverse(*ts), Reverse(*sid)));
+                paths.extend(day_files.into_iter().map(|(_, _, path)| path));
+            }
+        }
+    }
+    Ok(paths)
+}
+
 /// Locate a recorded conversation rollout file by its UUID string using the existing
 /// paginated listing implementation. Returns `Ok(Some(path))` if found, `Ok(None)` if not present
 /// or the id is invalid.
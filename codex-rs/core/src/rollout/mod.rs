@@ -7,12 +7,20 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub mod bundle;
+pub(crate) mod compression;
+pub(crate) mod index;
 pub mod list;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod transcript;
 
 pub use codex_protocol::protocol::SessionMeta;
+pub use compression::resolve_rollout_path;
+pub use index::SESSION_INDEX_FILE;
+pub use index::SessionIndexStats;
 pub use list::find_conversation_path_by_id_str;
+pub use list::list_rollout_paths;
 pub use recorder::RolloutRecorder;
 pub use recorder::RolloutRecorderParams;
 
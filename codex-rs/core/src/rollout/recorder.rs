@@ -5,6 +5,8 @@ use std::fs::{self};
 use std::io::Error as IoError;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use codex_protocol::ConversationId;
 use serde_json::Value;
@@ -19,12 +21,26 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::compression::compress_rollout;
+use super::compression::decompress_rollout;
+use super::compression::read_rollout_text;
+use super::index::SessionIndexEntry;
+use super::index::SessionIndexStats;
+use super::index::get_conversations_from_index;
+use super::index::rebuild_session_index;
+use super::index::summarize_rollout;
+use super::index::upsert_session_index_entry;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
+use super::policy::Redactor;
 use super::policy::is_persisted_response_item;
 use crate::config::Config;
+use crate::config::types::RolloutCompression;
+use crate::config::types::SessionEncryption;
 use crate::default_client::originator;
+use crate::encryption::LineCipher;
+use crate::encryption::shared_cipher;
 use crate::git_info::collect_git_info;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::ResumedHistory;
@@ -92,6 +108,9 @@ impl RolloutRecorderParams {
 
 impl RolloutRecorder {
     /// List conversations (rollout files) under the provided Codex home directory.
+    ///
+    /// Pages through the session index, falling back to scanning the sessions
+    /// directory when the index cannot be read or updated.
     pub async fn list_conversations(
         codex_home: &Path,
         page_size: usize,
@@ -100,7 +119,7 @@ impl RolloutRecorder {
         model_providers: Option<&[String]>,
         default_provider: &str,
     ) -> std::io::Result<ConversationsPage> {
-        get_conversations(
+        match get_conversations_from_index(
             codex_home,
             page_size,
             cursor,
@@ -109,6 +128,31 @@ impl RolloutRecorder {
             default_provider,
         )
         .await
+        {
+            Ok(page) => Ok(page),
+            Err(e) => {
+                warn!("session index unavailable, scanning rollouts instead: {e}");
+                get_conversations(
+                    codex_home,
+                    page_size,
+                    cursor,
+                    allowed_sources,
+                    model_providers,
+                    default_provider,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Rebuild the session index from the rollout files on disk. With
+    /// `compress_idle_after`, rollouts not written to for that long are
+    /// compressed first.
+    pub async fn rebuild_index(
+        codex_home: &Path,
+        compress_idle_after: Option<Duration>,
+    ) -> std::io::Result<SessionIndexStats> {
+        rebuild_session_index(codex_home, compress_idle_after).await
     }
 
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
@@ -151,14 +195,26 @@ impl RolloutRecorder {
                     }),
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
+            RolloutRecorderParams::Resume { path } => {
+                // Compressed rollouts are restored to plain JSONL so the
+                // resumed session can append to them.
+                let path = decompress_rollout(&path).await?;
+                (
+                    tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(&path)
+                        .await?,
+                    path,
+                    None,
+                )
+            }
+        };
+
+        let cipher = match config.session_encryption {
+            SessionEncryption::None => None,
+            SessionEncryption::Keyring => tokio::task::spawn_blocking(|| shared_cipher(true))
+                .await
+                .map_err(IoError::other)??,
         };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
@@ -172,7 +228,19 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(
+            file,
+            rx,
+            meta,
+            cwd,
+            RolloutTarget {
+                path: rollout_path.clone(),
+                codex_home: config.codex_home.clone(),
+                compression: config.rollout_compression,
+                redactor: Redactor::new(&config.redaction_patterns),
+                cipher,
+            },
+        ));
 
         Ok(Self { tx, rollout_path })
     }
@@ -209,7 +277,7 @@ impl RolloutRecorder {
 
     pub async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
+        let text = read_rollout_text(path).await?;
         if text.trim().is_empty() {
             return Err(IoError::other("empty session file"));
         }
@@ -346,13 +414,35 @@ fn create_log_file(
     })
 }
 
+/// Where the writer task records to and what it does with the file once the
+/// session shuts down.
+struct RolloutTarget {
+    path: PathBuf,
+    codex_home: PathBuf,
+    compression: RolloutCompression,
+    redactor: Redactor,
+    cipher: Option<Arc<LineCipher>>,
+}
+
 async fn rollout_writer(
     file: tokio::fs::File,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
+    target: RolloutTarget,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter {
+        file,
+        cipher: target.cipher.clone(),
+    };
+    let file_name = target
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // Summary kept in the session index, seeded from the file when resuming.
+    let mut index_entry: Option<SessionIndexEntry> = None;
 
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
@@ -363,20 +453,52 @@ async fn rollout_writer(
         };
 
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
-        writer
+        let line = writer
             .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
             .await?;
+        if let RolloutItem::SessionMeta(meta_line) = &line.item {
+            index_entry = Some(SessionIndexEntry::new(
+                file_name,
+                meta_line,
+                &line.timestamp,
+            ));
+        }
+    } else {
+        index_entry = summarize_rollout(&target.path).await.unwrap_or_else(|e| {
+            warn!("failed to summarize resumed rollout: {e}");
+            None
+        });
     }
+    let mut indexed_user_message = index_entry
+        .as_ref()
+        .is_some_and(|entry| entry.first_user_message.is_some());
 
     // Process rollout commands
+    let mut compress_on_exit = None;
     while let Some(cmd) = rx.recv().await {
         match cmd {
             RolloutCmd::AddItems(items) => {
                 for item in items {
                     if is_persisted_response_item(&item) {
-                        writer.write_rollout_item(item).await?;
+                        let item = target.redactor.redact_rollout_item(item);
+                        let line = writer.write_rollout_item(item).await?;
+                        if let Some(entry) = index_entry.as_mut() {
+                            entry.observe(&line);
+                            if writer.cipher.is_some() {
+                                entry.conceal();
+                            }
+                        }
                     }
                 }
+                // Sessions are only listed once they have a user message, so
+                // index them as soon as the first one is written.
+                if !indexed_user_message
+                    && let Some(entry) = &index_entry
+                    && entry.first_user_message.is_some()
+                {
+                    indexed_user_message = true;
+                    update_session_index(&target.codex_home, &target.path, entry.clone()).await;
+                }
             }
             RolloutCmd::Flush { ack } => {
                 // Ensure underlying file is flushed and then ack.
@@ -387,20 +509,58 @@ async fn rollout_writer(
                 let _ = ack.send(());
             }
             RolloutCmd::Shutdown { ack } => {
+                if target.compression == RolloutCompression::Zstd {
+                    // Nothing is recorded after shutdown; close the file
+                    // before replacing it.
+                    compress_on_exit = Some(ack);
+                    break;
+                }
+                if let Some(entry) = &index_entry {
+                    update_session_index(&target.codex_home, &target.path, entry.clone()).await;
+                }
                 let _ = ack.send(());
             }
         }
     }
 
+    if let Some(ack) = compress_on_exit {
+        drop(writer);
+        match compress_rollout(&target.path).await {
+            Ok(compressed) => {
+                if let Some(entry) = index_entry.as_mut()
+                    && let Some(name) = compressed.file_name()
+                {
+                    entry.file_name = name.to_string_lossy().into_owned();
+                }
+            }
+            Err(e) => warn!("failed to compress rollout {}: {e}", target.path.display()),
+        }
+        if let Some(entry) = index_entry {
+            update_session_index(&target.codex_home, &target.path, entry).await;
+        }
+        let _ = ack.send(());
+    }
+
     Ok(())
 }
 
+async fn update_session_index(codex_home: &Path, rollout_path: &Path, entry: SessionIndexEntry) {
+    if let Err(e) = upsert_session_index_entry(codex_home, rollout_path, entry).await {
+        warn!("failed to update session index: {e}");
+    }
+}
+
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Encrypts each record when `session_encryption` is enabled.
+    cipher: Option<Arc<LineCipher>>,
 }
 
 impl JsonlWriter {
-    async fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
+    async fn write_rollout_item(
+        &mut self,
+        rollout_item: RolloutItem,
+    ) -> std::io::Result<RolloutLine> {
         let timestamp_format: &[FormatItem] = format_description!(
             "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
         );
@@ -412,10 +572,14 @@ impl JsonlWriter {
             timestamp,
             item: rollout_item,
         };
-        self.write_line(&line).await
+        self.write_line(&line).await?;
+        Ok(line)
     }
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
         let mut json = serde_json::to_string(item)?;
+        if let Some(cipher) = &self.cipher {
+            json = cipher.encrypt_line(&json)?;
+        }
         json.push('\n');
         self.file.write_all(json.as_bytes()).await?;
         self.file.flush().await?;
@@ -12,24 +12,37 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::ConfigToml;
+use crate::encryption::install_shared_cipher;
+use crate::encryption::is_encrypted_line;
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::RolloutRecorder;
+use crate::rollout::RolloutRecorderParams;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::get_conversation;
 use crate::rollout::list::get_conversations;
+use crate::rollout::resolve_rollout_path;
 use anyhow::Result;
+use codex_keyring_store::tests::MockKeyringStore;
 use codex_protocol::ConversationId;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::CompactedItem;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::UserMessageEvent;
+use core_test_support::rollout::user_message_line;
+use core_test_support::rollout::write_rollout;
 
 const NO_SOURCE_FILTER: &[SessionSource] = &[];
 const TEST_PROVIDER: &str = "test-provider";
@@ -71,16 +84,6 @@ fn write_session_file_with_provider(
     let dt = PrimitiveDateTime::parse(ts_str, format)
         .unwrap()
         .assume_utc();
-    let dir = root
-        .join("sessions")
-        .join(format!("{:04}", dt.year()))
-        .join(format!("{:02}", u8::from(dt.month())))
-        .join(format!("{:02}", dt.day()));
-    fs::create_dir_all(&dir)?;
-
-    let filename = format!("rollout-{ts_str}-{uuid}.jsonl");
-    let file_path = dir.join(filename);
-    let mut file = File::create(file_path)?;
 
     let mut payload = serde_json::json!({
         "id": uuid,
@@ -98,32 +101,22 @@ fn write_session_file_with_provider(
         payload["model_provider"] = serde_json::Value::String(provider.to_string());
     }
 
-    let meta = serde_json::json!({
-        "timestamp": ts_str,
-        "type": "session_meta",
-        "payload": payload,
-    });
-    writeln!(file, "{meta}")?;
-
-    // Include at least one user message event to satisfy listing filters
-    let user_event = serde_json::json!({
-        "timestamp": ts_str,
-        "type": "event_msg",
-        "payload": {
-            "type": "user_message",
-            "message": "Hello from user",
-            "kind": "plain"
-        }
-    });
-    writeln!(file, "{user_event}")?;
-
-    for i in 0..num_records {
-        let rec = serde_json::json!({
+    let mut lines = vec![
+        serde_json::json!({
+            "timestamp": ts_str,
+            "type": "session_meta",
+            "payload": payload,
+        }),
+        // Include at least one user message event to satisfy listing filters
+        user_message_line(ts_str, "Hello from user"),
+    ];
+    lines.extend((0..num_records).map(|i| {
+        serde_json::json!({
             "record_type": "response",
             "index": i
-        });
-        writeln!(file, "{rec}")?;
-    }
+        })
+    }));
+    write_rollout(root, ts_str, uuid, &lines)?;
     Ok((dt, uuid))
 }
 
@@ -814,6 +807,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
         timestamp: format!("{ts}-compacted"),
         item: RolloutItem::Compacted(CompactedItem {
             message: "compacted".into(),
+            replacement_history: None,
         }),
     };
     writeln!(file, "{}", serde_json::to_string(&compacted_line)?)?;
@@ -1129,3 +1123,106 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn encrypted_redacted_rollout_can_be_listed_and_resumed() -> Result<()> {
+    let home = TempDir::new()?;
+    install_shared_cipher(&MockKeyringStore::default())?;
+    let cfg: ConfigToml = toml::from_str(
+        r#"
+session_encryption = "keyring"
+
+[redaction]
+builtin = ["keys"]
+"#,
+    )?;
+    let config = Config::load_from_base_config_with_overrides(
+        cfg,
+        ConfigOverrides::default(),
+        home.path().to_path_buf(),
+    )?;
+
+    let secret = "sk-abcdefghijklmnop1234";
+    let call_id = format!("call-{secret}");
+    let conversation_id = ConversationId::new();
+    let recorder = RolloutRecorder::new(
+        &config,
+        RolloutRecorderParams::new(conversation_id, None, SessionSource::Cli),
+    )
+    .await?;
+    recorder
+        .record_items(&[
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: format!("deploy with {secret}"),
+                images: None,
+            })),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: format!(r#"{{"command":["echo","{secret}"]}}"#),
+                call_id: call_id.clone(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: call_id.clone(),
+                output: FunctionCallOutputPayload {
+                    content: secret.to_string(),
+                    ..Default::default()
+                },
+            }),
+        ])
+        .await?;
+    recorder.flush().await?;
+    let rollout_path = recorder.rollout_path.clone();
+    recorder.shutdown().await?;
+
+    let raw = fs::read_to_string(resolve_rollout_path(&rollout_path).await)?;
+    assert!(raw.lines().all(is_encrypted_line));
+    assert!(!raw.contains(secret));
+
+    let page = RolloutRecorder::list_conversations(
+        home.path(),
+        10,
+        None,
+        INTERACTIVE_SESSION_SOURCES,
+        None,
+        &config.model_provider_id,
+    )
+    .await?;
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].path, rollout_path);
+    assert_eq!(
+        page.items[0].head[0]["id"],
+        serde_json::json!(conversation_id)
+    );
+
+    let InitialHistory::Resumed(resumed) =
+        RolloutRecorder::get_rollout_history(&rollout_path).await?
+    else {
+        panic!("expected a resumed history");
+    };
+    assert_eq!(resumed.conversation_id, conversation_id);
+    let calls: Vec<(String, String)> = resumed
+        .history
+        .iter()
+        .filter_map(|item| match item {
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                call_id, arguments, ..
+            }) => Some((call_id.clone(), arguments.clone())),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput { call_id, output }) => {
+                Some((call_id.clone(), output.content.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        calls,
+        vec![
+            (
+                call_id.clone(),
+                r#"{"command":["echo","[REDACTED]"]}"#.to_string()
+            ),
+            (call_id, "[REDACTED]".to_string()),
+        ]
+    );
+    Ok(())
+}
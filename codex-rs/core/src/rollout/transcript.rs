@@ -0,0 +1,296 @@
+//! Readable view of a rollout file: session metadata plus the user and agent
+//! messages, in order. Used by `codex sessions` to list, search and export
+//! recorded conversations.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMeta;
+use serde::Serialize;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+
+use super::compression::is_compressed;
+use super::compression::read_rollout_bytes;
+use super::compression::resolve_rollout_path;
+use super::recorder::RolloutRecorder;
+use crate::encryption::decode_line;
+
+/// Lines [`SessionTranscript::load_head`] reads at most while looking for the
+/// first user message and turn context.
+const HEAD_LINE_LIMIT: usize = 200;
+
+/// Author of a [`TranscriptMessage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptRole {
+    User,
+    Agent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptMessage {
+    pub role: TranscriptRole,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionTranscript {
+    /// Rollout file the transcript was read from.
+    pub path: PathBuf,
+    /// First session meta line in the file.
+    pub meta: SessionMeta,
+    /// Models used by the session's turns, in order of first use.
+    pub models: Vec<String>,
+    /// Providers used by the session's turns, in order of first use. Falls
+    /// back to the session meta provider for rollouts without turn contexts.
+    pub model_providers: Vec<String>,
+    pub messages: Vec<TranscriptMessage>,
+}
+
+impl SessionTranscript {
+    /// Reads the rollout at `path` with [`RolloutRecorder::get_rollout_history`].
+    pub async fn load(path: &Path) -> io::Result<Self> {
+        let path = resolve_rollout_path(path).await;
+        let history = RolloutRecorder::get_rollout_history(&path).await?;
+        Self::from_rollout_items(&path, &history.get_rollout_items())
+    }
+
+    /// Reads only the start of the rollout at `path`, up to the first user
+    /// message and turn context, which is enough for [`Self::title`] and a
+    /// listing. `messages` and `models` only cover that part of the session.
+    pub async fn load_head(path: &Path) -> io::Result<Self> {
+        let resolved = resolve_rollout_path(path).await;
+        // Compressed rollouts are small enough to decompress in full.
+        let reader: Box<dyn AsyncBufRead + Unpin + Send> = if is_compressed(&resolved) {
+            Box::new(io::Cursor::new(read_rollout_bytes(&resolved).await?))
+        } else {
+            Box::new(tokio::io::BufReader::new(
+                tokio::fs::File::open(&resolved).await?,
+            ))
+        };
+        let mut lines = reader.lines();
+        let mut items = Vec::new();
+        let mut saw_user_message = false;
+        let mut saw_turn_context = false;
+        for _ in 0..HEAD_LINE_LIMIT {
+            let Some(line) = lines.next_line().await? else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let decoded = decode_line(&line)?;
+            let Ok(line) = serde_json::from_str::<RolloutLine>(&decoded) else {
+                continue;
+            };
+            match &line.item {
+                RolloutItem::EventMsg(EventMsg::UserMessage(_)) => saw_user_message = true,
+                RolloutItem::TurnContext(_) => saw_turn_context = true,
+                _ => {}
+            }
+            items.push(line.item);
+            if saw_user_message && saw_turn_context {
+                break;
+            }
+        }
+        Self::from_rollout_items(&resolved, &items)
+    }
+
+    pub fn from_rollout_items(path: &Path, items: &[RolloutItem]) -> io::Result<Self> {
+        let mut meta: Option<SessionMeta> = None;
+        let mut models: Vec<String> = Vec::new();
+        let mut model_providers: Vec<String> = Vec::new();
+        let mut messages = Vec::new();
+        for item in items {
+            match item {
+                RolloutItem::SessionMeta(line) => {
+                    if meta.is_none() {
+                        meta = Some(line.meta.clone());
+                    }
+                }
+                RolloutItem::TurnContext(context) => {
+                    push_unique(&mut models, &context.model);
+                    if let Some(provider) = &context.model_provider {
+                        push_unique(&mut model_providers, provider);
+                    }
+                }
+                RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+                    messages.push(TranscriptMessage {
+                        role: TranscriptRole::User,
+                        text: event.message.clone(),
+                    });
+                }
+                RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                    messages.push(TranscriptMessage {
+                        role: TranscriptRole::Agent,
+                        text: event.message.clone(),
+                    });
+                }
+                RolloutItem::EventMsg(_)
+                | RolloutItem::ResponseItem(_)
+                | RolloutItem::Compacted(_) => {}
+            }
+        }
+
+        let meta =
+            meta.ok_or_else(|| io::Error::other(format!("no session meta in {}", path.display())))?;
+        if model_providers.is_empty()
+            && let Some(provider) = &meta.model_provider
+        {
+            model_providers.push(provider.clone());
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            meta,
+            models,
+            model_providers,
+            messages,
+        })
+    }
+
+    /// First non-empty line of the first user message.
+    pub fn title(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .filter(|message| message.role == TranscriptRole::User)
+            .flat_map(|message| message.text.lines())
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: &str) {
+    if !values.iter().any(|existing| existing == value) {
+        values.push(value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::config_types::ReasoningSummary;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::SandboxPolicy;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::TurnContextItem;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn turn_context(model: &str, provider: Option<&str>) -> RolloutItem {
+        RolloutItem::TurnContext(TurnContextItem {
+            cwd: PathBuf::from("/repo"),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            model: model.to_string(),
+            model_provider: provider.map(str::to_string),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+    }
+
+    #[test]
+    fn collects_messages_models_and_providers() {
+        let items = vec![
+            RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: ConversationId::new(),
+                    timestamp: "2025-05-01T10:00:00.000Z".to_string(),
+                    cwd: PathBuf::from("/repo"),
+                    model_provider: Some("openai".to_string()),
+                    ..Default::default()
+                },
+                git: None,
+            }),
+            turn_context("gpt-5", None),
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "\n  fix the build\nplease".to_string(),
+                images: None,
+            })),
+            RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Done.".to_string(),
+            })),
+            turn_context("gpt-5-codex", Some("azure")),
+            turn_context("gpt-5", Some("azure")),
+        ];
+
+        let transcript =
+            SessionTranscript::from_rollout_items(Path::new("/tmp/rollout.jsonl"), &items)
+                .expect("transcript");
+
+        assert_eq!(transcript.models, vec!["gpt-5", "gpt-5-codex"]);
+        assert_eq!(transcript.model_providers, vec!["azure"]);
+        assert_eq!(transcript.title(), Some("fix the build"));
+        assert_eq!(
+            transcript.messages,
+            vec![
+                TranscriptMessage {
+                    role: TranscriptRole::User,
+                    text: "\n  fix the build\nplease".to_string(),
+                },
+                TranscriptMessage {
+                    role: TranscriptRole::Agent,
+                    text: "Done.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn load_head_stops_after_the_first_user_message() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rollout.jsonl");
+        let items = [
+            RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: ConversationId::new(),
+                    timestamp: "2025-05-01T10:00:00.000Z".to_string(),
+                    cwd: PathBuf::from("/repo"),
+                    ..Default::default()
+                },
+                git: None,
+            }),
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "first".to_string(),
+                images: None,
+            })),
+            turn_context("gpt-5", None),
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "second".to_string(),
+                images: None,
+            })),
+            turn_context("gpt-5-codex", None),
+        ];
+        let text: String = items
+            .into_iter()
+            .map(|item| {
+                let line = RolloutLine {
+                    timestamp: "2025-05-01T10:00:00.000Z".to_string(),
+                    item,
+                };
+                serde_json::to_string(&line).map(|line| format!("{line}\n"))
+            })
+            .collect::<Result<_, _>>()?;
+        std::fs::write(&path, text)?;
+
+        let transcript = SessionTranscript::load_head(&path).await?;
+
+        assert_eq!(transcript.title(), Some("first"));
+        assert_eq!(transcript.models, vec!["gpt-5"]);
+        assert_eq!(transcript.messages.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_session_meta_is_an_error() {
+        let err = SessionTranscript::from_rollout_items(Path::new("/tmp/rollout.jsonl"), &[])
+            .expect_err("no meta");
+        assert!(err.to_string().contains("no session meta"));
+    }
+}
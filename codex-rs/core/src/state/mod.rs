@@ -1,7 +1,9 @@
+mod profile;
 mod service;
 mod session;
 mod turn;
 
+pub(crate) use profile::TurnProfiler;
 pub(crate) use service::SessionServices;
 pub(crate) use session::SessionState;
 pub(crate) use turn::ActiveTurn;
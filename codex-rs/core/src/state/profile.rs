@@ -0,0 +1,117 @@
+//! Timing collection for `EventMsg::TurnProfile`.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::protocol::TokenUsage;
+use crate::protocol::TurnPhase;
+use crate::protocol::TurnPhaseTiming;
+use crate::protocol::TurnProfileEvent;
+
+/// Accumulates where a turn spends its time.
+pub(crate) struct TurnProfiler {
+    started_at: Instant,
+    phases: BTreeMap<TurnPhase, PhaseStats>,
+    token_usage: TokenUsage,
+    cost_usd: Option<f64>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct PhaseStats {
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl Default for TurnProfiler {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            phases: BTreeMap::new(),
+            token_usage: TokenUsage::default(),
+            cost_usd: None,
+        }
+    }
+}
+
+impl TurnProfiler {
+    pub(crate) fn record(&mut self, phase: TurnPhase, elapsed: Duration) {
+        let stats = self.phases.entry(phase).or_default();
+        stats.count += 1;
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+    }
+
+    pub(crate) fn record_usage(&mut self, usage: &TokenUsage, cost_usd: Option<f64>) {
+        self.token_usage.add_assign(usage);
+        if let Some(cost_usd) = cost_usd {
+            self.cost_usd = Some(self.cost_usd.unwrap_or(0.0) + cost_usd);
+        }
+    }
+
+    pub(crate) fn to_event(&self) -> TurnProfileEvent {
+        TurnProfileEvent {
+            duration_ms: millis(self.started_at.elapsed()),
+            phases: self
+                .phases
+                .iter()
+                .map(|(phase, stats)| TurnPhaseTiming {
+                    phase: *phase,
+                    count: stats.count,
+                    total_ms: millis(stats.total),
+                    max_ms: millis(stats.max),
+                })
+                .collect(),
+            token_usage: self.token_usage.clone(),
+            cost_usd: self.cost_usd,
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn phases_are_aggregated_in_phase_order() {
+        let mut profiler = TurnProfiler::default();
+        profiler.record(TurnPhase::Hook, Duration::from_millis(30));
+        profiler.record(TurnPhase::ModelTimeToFirstToken, Duration::from_millis(400));
+        profiler.record(TurnPhase::Hook, Duration::from_millis(120));
+        let usage = TokenUsage {
+            input_tokens: 100,
+            output_tokens: 20,
+            total_tokens: 120,
+            ..TokenUsage::default()
+        };
+        profiler.record_usage(&usage, None);
+        profiler.record_usage(&usage, Some(0.5));
+
+        let event = profiler.to_event();
+        assert_eq!(
+            event.phases,
+            vec![
+                TurnPhaseTiming {
+                    phase: TurnPhase::ModelTimeToFirstToken,
+                    count: 1,
+                    total_ms: 400,
+                    max_ms: 400,
+                },
+                TurnPhaseTiming {
+                    phase: TurnPhase::Hook,
+                    count: 2,
+                    total_ms: 150,
+                    max_ms: 120,
+                },
+            ]
+        );
+        assert_eq!(event.token_usage.total_tokens, 240);
+        assert_eq!(event.cost_usd, Some(0.5));
+    }
+}
@@ -1,8 +1,10 @@
 use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::rollout::policy::Redactor;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
@@ -19,4 +21,10 @@ pub(crate) struct SessionServices {
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    /// `model_fallbacks` entry in use, kept across turns so a provider that
+    /// just failed is not tried again first.
+    pub(crate) active_model_fallback: Arc<AtomicUsize>,
+    /// `redaction_patterns`, compiled once for the message history entries
+    /// written during the session.
+    pub(crate) history_redactor: Arc<Redactor>,
 }
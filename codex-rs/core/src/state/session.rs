@@ -4,6 +4,8 @@ use codex_protocol::models::ResponseItem;
 
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::prompt_cache::PromptFingerprint;
+use crate::protocol::PromptCacheStats;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
@@ -13,6 +15,44 @@ pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    spend: SessionSpend,
+    /// Shape of the last prompt sent to the model, used to detect requests
+    /// that rewrite the cached prefix. Cleared whenever the history is
+    /// replaced on purpose (compaction, undo).
+    pub(crate) last_prompt: Option<PromptFingerprint>,
+    /// Messages pinned with `Op::PinMessage`, kept verbatim by compaction.
+    pub(crate) pinned_messages: Vec<String>,
+}
+
+/// Tokens and estimated cost accumulated across every model request made in
+/// this session. Unlike the token info kept with the history, this is never
+/// reset by compaction or rollback, so it can back the session budgets.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SessionSpend {
+    pub(crate) total_tokens: i64,
+    /// `None` until a request is made with a model that has a configured price.
+    pub(crate) cost_usd: Option<f64>,
+    pub(crate) turn_cost_usd: f64,
+    turn_id: String,
+    input_tokens: i64,
+    cached_input_tokens: i64,
+    last_request: Option<TokenUsage>,
+}
+
+impl SessionSpend {
+    /// Prompt cache hit rates for the last request and the whole session.
+    pub(crate) fn prompt_cache(&self) -> Option<PromptCacheStats> {
+        let last_hit_percent = self.last_request.as_ref()?.cache_hit_percent()?;
+        let session = TokenUsage {
+            input_tokens: self.input_tokens,
+            cached_input_tokens: self.cached_input_tokens,
+            ..TokenUsage::default()
+        };
+        Some(PromptCacheStats {
+            last_hit_percent,
+            session_hit_percent: session.cache_hit_percent().unwrap_or_default(),
+        })
+    }
 }
 
 impl SessionState {
@@ -22,9 +62,21 @@ impl SessionState {
             session_configuration,
             history: ContextManager::new(),
             latest_rate_limits: None,
+            spend: SessionSpend::default(),
+            last_prompt: None,
+            pinned_messages: Vec::new(),
         }
     }
 
+    /// Pins `text` unless it is already pinned. Returns whether it was added.
+    pub(crate) fn pin_message(&mut self, text: String) -> bool {
+        if self.pinned_messages.contains(&text) {
+            return false;
+        }
+        self.pinned_messages.push(text);
+        true
+    }
+
     // History helpers
     pub(crate) fn record_items<I>(&mut self, items: I)
     where
@@ -40,6 +92,7 @@ impl SessionState {
 
     pub(crate) fn replace_history(&mut self, items: Vec<ResponseItem>) {
         self.history.replace(items);
+        self.last_prompt = None;
     }
 
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
@@ -69,6 +122,31 @@ impl SessionState {
         (self.token_info(), self.latest_rate_limits.clone())
     }
 
+    pub(crate) fn record_spend(
+        &mut self,
+        turn_id: &str,
+        usage: &TokenUsage,
+        cost_usd: Option<f64>,
+    ) {
+        let spend = &mut self.spend;
+        if spend.turn_id != turn_id {
+            spend.turn_id = turn_id.to_string();
+            spend.turn_cost_usd = 0.0;
+        }
+        spend.total_tokens += usage.total_tokens.max(0);
+        spend.input_tokens += usage.input_tokens.max(0);
+        spend.cached_input_tokens += usage.cached_input();
+        spend.last_request = Some(usage.clone());
+        if let Some(cost_usd) = cost_usd {
+            spend.turn_cost_usd += cost_usd;
+            spend.cost_usd = Some(spend.cost_usd.unwrap_or(0.0) + cost_usd);
+        }
+    }
+
+    pub(crate) fn spend(&self) -> &SessionSpend {
+        &self.spend
+    }
+
     pub(crate) fn set_token_usage_full(&mut self, context_window: i64) {
         self.history.set_token_usage_full(context_window);
     }
@@ -13,6 +13,7 @@ use tokio::sync::oneshot;
 
 use crate::codex::TurnContext;
 use crate::protocol::ReviewDecision;
+use crate::state::TurnProfiler;
 use crate::tasks::SessionTask;
 
 /// Metadata about the currently running turn.
@@ -68,6 +69,7 @@ impl ActiveTurn {
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
     pending_input: Vec<ResponseInputItem>,
+    pub(crate) profile: TurnProfiler,
 }
 
 impl TurnState {
@@ -8,15 +8,33 @@ use codex_git::GitToolingError;
 use codex_git::create_ghost_commit;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
+use codex_utils_cache::sha1_digest;
 use codex_utils_readiness::Readiness;
 use codex_utils_readiness::Token;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Once;
+use std::time::Duration;
+use std::time::SystemTime;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 use tracing::warn;
 
+/// Shadow repositories that have not been used for this long are deleted.
+const SHADOW_REPO_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// File in a shadow repository whose mtime records its last snapshot.
+const SHADOW_LAST_USED_FILE: &str = "codex-last-used";
+
+static PRUNE_SHADOW_REPOS: Once = Once::new();
+
 pub(crate) struct GhostSnapshotTask {
     token: Token,
+    /// Shadow repository used when the cwd is not inside a git repository.
+    shadow_git_dir: Option<PathBuf>,
 }
 
 #[async_trait]
@@ -34,6 +52,7 @@ impl SessionTask for GhostSnapshotTask {
     ) -> Option<String> {
         tokio::task::spawn(async move {
             let token = self.token;
+            let shadow_git_dir = self.shadow_git_dir.clone();
             let ctx_for_task = Arc::clone(&ctx);
             let cancelled = tokio::select! {
                 _ = cancellation_token.cancelled() => true,
@@ -42,7 +61,22 @@ impl SessionTask for GhostSnapshotTask {
                     // Required to run in a dedicated blocking pool.
                     match tokio::task::spawn_blocking(move || {
                         let options = CreateGhostCommitOptions::new(&repo_path);
-                        create_ghost_commit(&options)
+                        match (create_ghost_commit(&options), shadow_git_dir.as_deref()) {
+                            (Err(GitToolingError::NotAGitRepository { .. }), Some(git_dir)) => {
+                                prune_stale_shadow_repos_once(git_dir);
+                                let result = create_ghost_commit(&options.shadow_git_dir(git_dir));
+                                if result.is_ok()
+                                    && let Err(err) = fs::write(
+                                        git_dir.join(SHADOW_LAST_USED_FILE),
+                                        format!("{}\n", chrono::Utc::now().to_rfc3339()),
+                                    )
+                                {
+                                    warn!("failed to mark shadow snapshot repository as used: {err}");
+                                }
+                                result
+                            }
+                            (result, _) => result,
+                        }
                     })
                     .await
                     {
@@ -66,6 +100,9 @@ impl SessionTask for GhostSnapshotTask {
                                     "Snapshots disabled: current directory is not a Git repository."
                                         .to_string()
                                 }
+                                GitToolingError::SnapshotTooLarge { .. } => {
+                                    format!("Snapshot skipped: {err}.")
+                                }
                                 _ => format!("Snapshots disabled after ghost snapshot error: {err}."),
                             };
                             session
@@ -104,7 +141,94 @@ impl SessionTask for GhostSnapshotTask {
 }
 
 impl GhostSnapshotTask {
-    pub(crate) fn new(token: Token) -> Self {
-        Self { token }
+    pub(crate) fn new(token: Token, shadow_git_dir: Option<PathBuf>) -> Self {
+        Self {
+            token,
+            shadow_git_dir,
+        }
+    }
+}
+
+/// Location of the shadow repository for snapshots of `cwd`, which is only
+/// used when `cwd` is not inside a git repository. Snapshotting the whole
+/// file system or home directory is never useful, so those get none.
+pub(crate) fn shadow_git_dir(codex_home: &Path, cwd: &Path) -> Option<PathBuf> {
+    if cwd.parent().is_none() || dirs::home_dir().is_some_and(|home| home == cwd) {
+        return None;
+    }
+    let digest = sha1_digest(cwd.as_os_str().as_encoded_bytes());
+    let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    Some(codex_home.join("snapshots").join(name))
+}
+
+/// Deletes shadow repositories next to `current` that have not been used for
+/// `SHADOW_REPO_MAX_AGE`, at most once per process.
+fn prune_stale_shadow_repos_once(current: &Path) {
+    PRUNE_SHADOW_REPOS.call_once(|| {
+        let Some(snapshots_dir) = current.parent() else {
+            return;
+        };
+        match prune_stale_shadow_repos(snapshots_dir, current, SystemTime::now()) {
+            Ok(0) => {}
+            Ok(pruned) => info!("pruned {pruned} stale shadow snapshot repositories"),
+            Err(err) => warn!("failed to prune shadow snapshot repositories: {err}"),
+        }
+    });
+}
+
+fn prune_stale_shadow_repos(
+    snapshots_dir: &Path,
+    current: &Path,
+    now: SystemTime,
+) -> io::Result<usize> {
+    let mut pruned = 0;
+    for entry in fs::read_dir(snapshots_dir)? {
+        let path = entry?.path();
+        if path == current || !path.is_dir() {
+            continue;
+        }
+        let last_used = fs::metadata(path.join(SHADOW_LAST_USED_FILE))
+            .or_else(|_| fs::metadata(&path))
+            .and_then(|metadata| metadata.modified())?;
+        if now
+            .duration_since(last_used)
+            .is_ok_and(|age| age > SHADOW_REPO_MAX_AGE)
+        {
+            fs::remove_dir_all(&path)?;
+            pruned += 1;
+        }
+    }
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prunes_only_shadow_repos_unused_for_longer_than_max_age() -> io::Result<()> {
+        let snapshots = tempfile::tempdir()?;
+        let current = snapshots.path().join("current");
+        let other = snapshots.path().join("other");
+        fs::create_dir_all(&current)?;
+        fs::create_dir_all(&other)?;
+        fs::write(other.join(SHADOW_LAST_USED_FILE), "")?;
+
+        let now = SystemTime::now();
+        assert_eq!(
+            prune_stale_shadow_repos(snapshots.path(), &current, now)?,
+            0
+        );
+        assert!(other.exists());
+
+        let later = now + SHADOW_REPO_MAX_AGE + Duration::from_secs(60);
+        assert_eq!(
+            prune_stale_shadow_repos(snapshots.path(), &current, later)?,
+            1
+        );
+        assert!(!other.exists());
+        assert!(current.exists());
+        Ok(())
     }
 }
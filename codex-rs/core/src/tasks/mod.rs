@@ -30,9 +30,12 @@ use codex_protocol::user_input::UserInput;
 
 pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
+pub(crate) use ghost_snapshot::shadow_git_dir;
 pub(crate) use regular::RegularTask;
 pub(crate) use review::ReviewTask;
+pub(crate) use undo::RestoreSnapshotTask;
 pub(crate) use undo::UndoTask;
+pub(crate) use undo::snapshot_summaries;
 pub(crate) use user_shell::UserShellCommandTask;
 
 const GRACEFULL_INTERRUPTION_TIMEOUT_MS: u64 = 100;
@@ -162,12 +165,20 @@ impl Session {
         last_agent_message: Option<String>,
     ) {
         let mut active = self.active_turn.lock().await;
+        let profile = match active.as_ref() {
+            Some(at) => Some(at.turn_state.lock().await.profile.to_event()),
+            None => None,
+        };
         if let Some(at) = active.as_mut()
             && at.remove_task(&turn_context.sub_id)
         {
             *active = None;
         }
         drop(active);
+        if let Some(profile) = profile {
+            self.send_event(turn_context.as_ref(), EventMsg::TurnProfile(profile))
+                .await;
+        }
         let event = EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message });
         self.send_event(turn_context.as_ref(), event).await;
     }
@@ -81,8 +81,13 @@ async fn start_review_conversation(
         .disable(crate::features::Feature::WebSearchRequest)
         .disable(crate::features::Feature::ViewImageTool);
 
-    // Set explicit review rubric for the sub-agent
-    sub_agent_config.base_instructions = Some(crate::REVIEW_PROMPT.to_string());
+    // Set explicit review rubric for the sub-agent: the built-in prompt plus
+    // any repo review instructions and requested rubrics.
+    sub_agent_config.base_instructions = Some(
+        ctx.base_instructions
+            .clone()
+            .unwrap_or_else(|| crate::REVIEW_PROMPT.to_string()),
+    );
     (run_codex_conversation_one_shot(
         sub_agent_config,
         session.auth_manager(),
@@ -123,6 +128,10 @@ async fn process_review_events(
             })
             | EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { .. })
             | EventMsg::AgentMessageContentDelta(AgentMessageContentDeltaEvent { .. }) => {}
+            // The parent task reports its own profile when the review finishes.
+            EventMsg::TurnProfile(_) => {}
+            // Pins belong to the parent session.
+            EventMsg::MessagePinned(_) => {}
             EventMsg::TaskComplete(task_complete) => {
                 // Parse review output from the last agent message (if present).
                 let out = task_complete
@@ -1,14 +1,23 @@
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::codex::TurnContext;
+use crate::event_mapping::parse_turn_item;
 use crate::protocol::EventMsg;
+use crate::protocol::SnapshotFileChange;
+use crate::protocol::SnapshotSummary;
 use crate::protocol::UndoCompletedEvent;
 use crate::protocol::UndoStartedEvent;
 use crate::state::TaskKind;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use async_trait::async_trait;
+use codex_git::GhostCommit;
+use codex_git::diff_ghost_commit;
 use codex_git::restore_ghost_commit;
+use codex_git::restore_ghost_commit_paths;
+use codex_protocol::items::TurnItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
 use tokio_util::sync::CancellationToken;
@@ -115,3 +124,145 @@ impl SessionTask for UndoTask {
         None
     }
 }
+
+/// Restores the working tree to any ghost snapshot in the history, optionally
+/// limited to some paths. History is left as is so later snapshots can still
+/// be restored afterwards.
+pub(crate) struct RestoreSnapshotTask {
+    id: String,
+    paths: Vec<PathBuf>,
+}
+
+impl RestoreSnapshotTask {
+    pub(crate) fn new(id: String, paths: Vec<PathBuf>) -> Self {
+        Self { id, paths }
+    }
+}
+
+#[async_trait]
+impl SessionTask for RestoreSnapshotTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let short_id: String = self.id.chars().take(7).collect();
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some(format!("Restoring snapshot {short_id}...")),
+            }),
+        )
+        .await;
+
+        let mut completed = UndoCompletedEvent {
+            success: false,
+            message: None,
+        };
+        if cancellation_token.is_cancelled() {
+            completed.message = Some("Restore cancelled.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+
+        let items = sess.clone_history().await.get_history();
+        let Some(ghost_commit) = ghost_snapshots(&items)
+            .into_iter()
+            .map(|(_, ghost_commit)| ghost_commit)
+            .find(|ghost_commit| ghost_commit.id() == self.id)
+        else {
+            completed.message = Some(format!("No ghost snapshot {short_id} in this session."));
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+
+        let repo_path = ctx.cwd.clone();
+        let paths = self.paths.clone();
+        let restore_result = tokio::task::spawn_blocking(move || {
+            if paths.is_empty() {
+                restore_ghost_commit(&repo_path, &ghost_commit)
+            } else {
+                restore_ghost_commit_paths(&repo_path, &ghost_commit, &paths)
+            }
+        })
+        .await;
+
+        match restore_result {
+            Ok(Ok(())) => {
+                info!(commit_id = self.id, "restored ghost snapshot");
+                completed.success = true;
+                completed.message = Some(match self.paths.len() {
+                    0 => format!("Restored snapshot {short_id}."),
+                    1 => format!("Restored 1 file from snapshot {short_id}."),
+                    n => format!("Restored {n} files from snapshot {short_id}."),
+                });
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to restore snapshot {}: {err}", self.id);
+                warn!("{message}");
+                completed.message = Some(message);
+            }
+            Err(err) => {
+                let message = format!("Failed to restore snapshot {}: {err}", self.id);
+                error!("{message}");
+                completed.message = Some(message);
+            }
+        }
+
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
+
+/// Every ghost snapshot in `items`, oldest first, with the user message of
+/// the turn it was taken for.
+pub(crate) fn ghost_snapshots(items: &[ResponseItem]) -> Vec<(Option<String>, GhostCommit)> {
+    let mut turn_prompt = None;
+    let mut snapshots = Vec::new();
+    for item in items {
+        if let ResponseItem::GhostSnapshot { ghost_commit } = item {
+            snapshots.push((turn_prompt.clone(), ghost_commit.clone()));
+        } else if let Some(TurnItem::UserMessage(user)) = parse_turn_item(item) {
+            turn_prompt = Some(user.message());
+        }
+    }
+    snapshots
+}
+
+/// Summarizes each ghost snapshot in `items` with its diff against the
+/// working tree at `cwd`. Runs git, so call it off the async runtime.
+pub(crate) fn snapshot_summaries(cwd: &Path, items: &[ResponseItem]) -> Vec<SnapshotSummary> {
+    ghost_snapshots(items)
+        .into_iter()
+        .map(|(turn_prompt, ghost_commit)| {
+            let diff = diff_ghost_commit(cwd, &ghost_commit).unwrap_or_else(|err| {
+                warn!("failed to diff ghost snapshot {ghost_commit}: {err}");
+                Default::default()
+            });
+            SnapshotSummary {
+                id: ghost_commit.id().to_string(),
+                turn_prompt,
+                files: diff
+                    .files
+                    .into_iter()
+                    .map(|file| SnapshotFileChange {
+                        path: file.path,
+                        added: u32::try_from(file.added).unwrap_or(u32::MAX),
+                        removed: u32::try_from(file.removed).unwrap_or(u32::MAX),
+                    })
+                    .collect(),
+                diff: diff.patch,
+            }
+        })
+        .collect()
+}
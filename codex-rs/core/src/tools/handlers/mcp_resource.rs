@@ -590,6 +590,8 @@ async fn emit_tool_call_end(
                 invocation,
                 duration,
                 result,
+                cached: false,
+                throttled: None,
             }),
         )
         .await;
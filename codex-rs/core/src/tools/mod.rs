@@ -8,6 +8,7 @@ pub mod router;
 pub mod runtimes;
 pub mod sandboxing;
 pub mod spec;
+pub mod standalone;
 
 use crate::context_manager::MODEL_FORMAT_MAX_BYTES;
 use crate::context_manager::MODEL_FORMAT_MAX_LINES;
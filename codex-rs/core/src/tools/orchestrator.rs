@@ -11,6 +11,7 @@ use crate::error::get_error_message_ui;
 use crate::exec::ExecToolCallOutput;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::EventMsg;
+use crate::protocol::TurnPhase;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ProvidesSandboxRetryData;
@@ -28,6 +29,7 @@ use codex_protocol::protocol::ReviewDecision;
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Instant;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -116,7 +118,14 @@ impl ToolOrchestrator {
         let tool_input_json = serde_json::Value::Object(serde_json::Map::new());
         execute_pre_tool_use_hooks(tool_ctx, turn_ctx, &tool_input_json).await?;
 
-        match tool.run(req, &initial_attempt, tool_ctx).await {
+        let started = Instant::now();
+        let initial_result = tool.run(req, &initial_attempt, tool_ctx).await;
+        tool_ctx
+            .session
+            .record_turn_phase(TurnPhase::Exec, started.elapsed())
+            .await;
+
+        match initial_result {
             Ok(out) => {
                 // Execute PostToolUse hooks
                 let tool_response_json = serde_json::Value::Object(serde_json::Map::new());
@@ -199,7 +208,12 @@ impl ToolOrchestrator {
                 execute_pre_tool_use_hooks(tool_ctx, turn_ctx, &tool_input_json).await?;
 
                 // Second attempt.
+                let started = Instant::now();
                 let result = (*tool).run(req, &escalated_attempt, tool_ctx).await;
+                tool_ctx
+                    .session
+                    .record_turn_phase(TurnPhase::Exec, started.elapsed())
+                    .await;
 
                 // Execute PostToolUse hooks if successful
                 if result.is_ok() {
@@ -267,7 +281,13 @@ async fn execute_pre_tool_use_hooks(
     };
 
     // Execute hooks - if hooks fail to execute (e.g., missing scripts), log and continue
-    let results = match hook_system.execute(HookEvent::PreToolUse, input).await {
+    let started = Instant::now();
+    let outcome = hook_system.execute(HookEvent::PreToolUse, input).await;
+    tool_ctx
+        .session
+        .record_turn_phase(TurnPhase::Hook, started.elapsed())
+        .await;
+    let results = match outcome {
         Ok(results) => results,
         Err(e) => {
             // Hook execution failed (missing script, spawn error, etc.)
@@ -341,7 +361,13 @@ async fn execute_post_tool_use_hooks(
     };
 
     // Execute hooks - if hooks fail to execute, log and continue
-    let results = match hook_system.execute(HookEvent::PostToolUse, input).await {
+    let started = Instant::now();
+    let outcome = hook_system.execute(HookEvent::PostToolUse, input).await;
+    tool_ctx
+        .session
+        .record_turn_phase(TurnPhase::Hook, started.elapsed())
+        .await;
+    let results = match outcome {
         Ok(results) => results,
         Err(e) => {
             // Hook execution failed (missing script, spawn error, etc.)
@@ -1,5 +1,6 @@
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::error::CodexErr;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::model_family::ModelFamily;
@@ -15,7 +16,8 @@ use serde_json::json;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ConfigShellToolType {
     Default,
     Local,
@@ -784,6 +786,45 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values for the Anthropic Messages API's `tools` field. The
+/// API only accepts function tools, so `local_shell` is sent as the `shell`
+/// function and the freeform `apply_patch` tool as its JSON variant; both
+/// names are routed to the same handlers. Any other freeform tool is an error
+/// rather than being silently dropped.
+pub(crate) fn create_tools_json_for_messages_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let mut tools_json = Vec::new();
+    for tool in tools {
+        let tool = match tool {
+            ToolSpec::Function(_) => tool.clone(),
+            ToolSpec::LocalShell {} => create_shell_tool(),
+            ToolSpec::Freeform(tool) if tool.name == "apply_patch" => {
+                create_apply_patch_json_tool()
+            }
+            ToolSpec::Freeform(tool) => {
+                return Err(CodexErr::UnsupportedOperation(format!(
+                    "the freeform tool `{}` is not supported by the Messages API",
+                    tool.name
+                )));
+            }
+            ToolSpec::WebSearch {} => {
+                tracing::warn!("web_search is not supported by the Messages API; omitting it");
+                continue;
+            }
+        };
+        let ToolSpec::Function(tool) = tool else {
+            continue;
+        };
+        tools_json.push(json!({
+            "name": tool.name,
+            "description": tool.description,
+            "input_schema": serde_json::to_value(&tool.parameters)?,
+        }));
+    }
+    Ok(tools_json)
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -930,6 +971,38 @@ fn sanitize_json_schema(value: &mut JsonValue) {
     }
 }
 
+/// Builds the registry served by [`crate::tools::standalone::StandaloneToolHost`]:
+/// the file and shell tools, without plan, MCP or image tools that only make
+/// sense inside a model turn. `apply_patch` uses its JSON form because MCP
+/// tools are described with JSON schemas.
+pub(crate) fn build_standalone_specs() -> ToolRegistryBuilder {
+    use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::GrepFilesHandler;
+    use crate::tools::handlers::ListDirHandler;
+    use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::ShellHandler;
+    use std::sync::Arc;
+
+    let mut builder = ToolRegistryBuilder::new();
+
+    builder.push_spec(create_shell_tool());
+    builder.register_handler("shell", Arc::new(ShellHandler));
+
+    builder.push_spec(create_apply_patch_json_tool());
+    builder.register_handler("apply_patch", Arc::new(ApplyPatchHandler));
+
+    builder.push_spec_with_parallel_support(create_read_file_tool(), true);
+    builder.register_handler("read_file", Arc::new(ReadFileHandler));
+
+    builder.push_spec_with_parallel_support(create_grep_files_tool(), true);
+    builder.register_handler("grep_files", Arc::new(GrepFilesHandler));
+
+    builder.push_spec_with_parallel_support(create_list_dir_tool(), true);
+    builder.register_handler("list_dir", Arc::new(ListDirHandler));
+
+    builder
+}
+
 /// Builds the tool registry builder while collecting tool specs for later serialization.
 pub(crate) fn build_specs(
     config: &ToolsConfig,
@@ -2005,4 +2078,31 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn messages_api_tools_convert_local_shell_and_freeform_apply_patch() {
+        let tools = vec![
+            ToolSpec::LocalShell {},
+            create_apply_patch_freeform_tool(),
+            ToolSpec::WebSearch {},
+        ];
+        let tools_json = create_tools_json_for_messages_api(&tools).expect("convert tools");
+        let names: Vec<&str> = tools_json
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(names, vec!["shell", "apply_patch"]);
+        assert_eq!(tools_json[1]["input_schema"]["type"], json!("object"));
+
+        let unsupported = ToolSpec::Freeform(FreeformTool {
+            name: "custom_grammar".to_string(),
+            description: String::new(),
+            format: crate::client_common::tools::FreeformToolFormat {
+                r#type: "grammar".to_string(),
+                syntax: "lark".to_string(),
+                definition: String::new(),
+            },
+        });
+        assert!(create_tools_json_for_messages_api(&[unsupported]).is_err());
+    }
 }
@@ -0,0 +1,231 @@
+//! Serves Codex's built-in tool handlers without a model loop.
+//!
+//! [`StandaloneToolHost`] is what `codex mcp-server --tools-only` exposes:
+//! `shell`, `apply_patch`, `read_file`, `grep_files` and `list_dir`, executed
+//! through the same handlers, sandbox policy and approval flow as a regular
+//! turn. Every call runs in its own turn context whose `sub_id` is the call
+//! id, so approval requests surface as events with that id and are answered
+//! through [`StandaloneToolHost::notify_approval`].
+
+use std::sync::Arc;
+
+use async_channel::Receiver;
+use codex_protocol::models::ResponseInputItem;
+use tokio::sync::Mutex;
+
+use crate::AuthManager;
+use crate::client_common::tools::ToolSpec;
+use crate::codex::Session;
+use crate::codex::SessionSettingsUpdate;
+use crate::config::Config;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::Event;
+use crate::protocol::ReviewDecision;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ConfiguredToolSpec;
+use crate::tools::registry::ToolRegistry;
+use crate::tools::spec::build_standalone_specs;
+use crate::turn_diff_tracker::TurnDiffTracker;
+
+/// A tool served by [`StandaloneToolHost`], described with a JSON schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandaloneToolSpec {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+/// Result of a single standalone tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandaloneToolOutput {
+    pub content: String,
+    pub success: bool,
+}
+
+pub struct StandaloneToolHost {
+    session: Arc<Session>,
+    registry: ToolRegistry,
+    specs: Vec<ConfiguredToolSpec>,
+    rx_event: Receiver<Event>,
+}
+
+impl StandaloneToolHost {
+    pub async fn new(config: Config, auth_manager: Arc<AuthManager>) -> Self {
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let session = Session::new_standalone(Arc::new(config), auth_manager, tx_event).await;
+        let (specs, registry) = build_standalone_specs().build();
+        Self {
+            session,
+            registry,
+            specs,
+            rx_event,
+        }
+    }
+
+    pub fn tools(&self) -> Vec<StandaloneToolSpec> {
+        self.specs
+            .iter()
+            .filter_map(|configured| match &configured.spec {
+                ToolSpec::Function(tool) => Some(StandaloneToolSpec {
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    input_schema: serde_json::to_value(&tool.parameters).ok()?,
+                }),
+                ToolSpec::LocalShell {} | ToolSpec::WebSearch {} | ToolSpec::Freeform(_) => None,
+            })
+            .collect()
+    }
+
+    /// Runs `tool_name` with JSON `arguments`. Failures the model would have
+    /// seen (bad arguments, rejected approvals, non-zero exits) are reported
+    /// as unsuccessful output rather than errors.
+    pub async fn call_tool(
+        &self,
+        call_id: String,
+        tool_name: &str,
+        arguments: serde_json::Value,
+    ) -> StandaloneToolOutput {
+        let turn = self
+            .session
+            .new_turn_with_sub_id(call_id.clone(), SessionSettingsUpdate::default())
+            .await;
+        let invocation = ToolInvocation {
+            session: Arc::clone(&self.session),
+            turn,
+            tracker: Arc::new(Mutex::new(TurnDiffTracker::new())),
+            call_id,
+            tool_name: tool_name.to_string(),
+            payload: ToolPayload::Function {
+                arguments: arguments.to_string(),
+            },
+        };
+
+        match self.registry.dispatch(invocation).await {
+            Ok(ResponseInputItem::FunctionCallOutput { output, .. }) => StandaloneToolOutput {
+                success: output.success.unwrap_or(true),
+                content: output.content,
+            },
+            Ok(ResponseInputItem::CustomToolCallOutput { output, .. }) => StandaloneToolOutput {
+                content: output,
+                success: true,
+            },
+            Ok(other) => StandaloneToolOutput {
+                content: format!("unexpected tool output: {other:?}"),
+                success: false,
+            },
+            Err(FunctionCallError::RespondToModel(message))
+            | Err(FunctionCallError::Denied(message)) => StandaloneToolOutput {
+                content: message,
+                success: false,
+            },
+            Err(err) => StandaloneToolOutput {
+                content: err.to_string(),
+                success: false,
+            },
+        }
+    }
+
+    /// Next event emitted by a tool call (command begin/end, patch apply,
+    /// approval requests, ...). The event id is the originating call id.
+    pub async fn next_event(&self) -> CodexResult<Event> {
+        self.rx_event
+            .recv()
+            .await
+            .map_err(|_| CodexErr::InternalAgentDied)
+    }
+
+    /// Resolves a pending approval request raised by the call `call_id`.
+    pub async fn notify_approval(&self, call_id: &str, decision: ReviewDecision) {
+        self.session.notify_approval(call_id, decision).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use crate::protocol::AskForApproval;
+    use pretty_assertions::assert_eq;
+
+    async fn host_for(dir: &std::path::Path) -> StandaloneToolHost {
+        let codex_home = tempfile::tempdir().expect("create temp dir");
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                cwd: Some(dir.to_path_buf()),
+                approval_policy: Some(AskForApproval::Never),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load default test config");
+        let auth_manager = AuthManager::shared(
+            config.codex_home.clone(),
+            false,
+            config.cli_auth_credentials_store_mode,
+        );
+        StandaloneToolHost::new(config, auth_manager).await
+    }
+
+    #[tokio::test]
+    async fn exposes_file_and_shell_tools() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let host = host_for(dir.path()).await;
+        let mut names: Vec<String> = host.tools().into_iter().map(|tool| tool.name).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "apply_patch",
+                "grep_files",
+                "list_dir",
+                "read_file",
+                "shell"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_file_runs_without_a_model_turn() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let file = dir.path().join("hello.txt");
+        std::fs::write(&file, "alpha\nbeta\n").expect("write file");
+        let host = host_for(dir.path()).await;
+
+        let output = host
+            .call_tool(
+                "call-1".to_string(),
+                "read_file",
+                serde_json::json!({ "file_path": file, "limit": 1 }),
+            )
+            .await;
+
+        assert_eq!(
+            output,
+            StandaloneToolOutput {
+                content: "L1: alpha".to_string(),
+                success: true,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_tools_are_reported_as_failures() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let host = host_for(dir.path()).await;
+        let output = host
+            .call_tool("call-1".to_string(), "update_plan", serde_json::json!({}))
+            .await;
+        assert_eq!(
+            output,
+            StandaloneToolOutput {
+                content: "unsupported call: update_plan".to_string(),
+                success: false,
+            }
+        );
+    }
+}
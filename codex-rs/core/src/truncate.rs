@@ -155,13 +155,59 @@ fn error_on_double_truncation(content: &str) {
     }
 }
 
-/// Truncate an output string to a maximum number of “tokens”, where tokens are
-/// approximated as individual `char`s. Preserves a prefix and suffix with an
-/// elision marker describing how many tokens were omitted.
+/// Truncate an output string to a maximum number of tokens, counted with
+/// `tokenizer` when one is available and approximated as individual `char`s
+/// otherwise. Preserves a prefix and suffix with an elision marker describing
+/// how many tokens were omitted.
 pub(crate) fn truncate_output_to_tokens(
     output: &str,
     max_tokens: usize,
+    tokenizer: Option<&Tokenizer>,
 ) -> (String, Option<usize>) {
+    match tokenizer {
+        Some(tokenizer) => truncate_output_to_model_tokens(output, max_tokens, tokenizer),
+        None => truncate_output_to_chars(output, max_tokens),
+    }
+}
+
+fn truncate_output_to_model_tokens(
+    output: &str,
+    max_tokens: usize,
+    tokenizer: &Tokenizer,
+) -> (String, Option<usize>) {
+    let tokens = tokenizer.encode(output, false);
+    let total_tokens = tokens.len();
+    if total_tokens <= max_tokens {
+        return (output.to_string(), None);
+    }
+
+    let half = max_tokens / 2;
+    if half == 0 {
+        let message = format!("…{total_tokens} tokens truncated…");
+        return (message, Some(total_tokens));
+    }
+
+    // A token boundary can fall inside a multi-byte character, in which case
+    // the slice does not decode; move the boundary outwards until it does.
+    // No character spans more than four tokens.
+    let head =
+        (0..4).find_map(|shift| tokenizer.decode(&tokens[..half.saturating_sub(shift)]).ok());
+    let tail = (0..4).find_map(|shift| {
+        let start = (total_tokens - half + shift).min(total_tokens);
+        tokenizer.decode(&tokens[start..]).ok()
+    });
+    let (Some(head), Some(tail)) = (head, tail) else {
+        return truncate_output_to_chars(output, max_tokens);
+    };
+
+    let truncated = total_tokens.saturating_sub(half * 2);
+    (
+        format!("{head}…{truncated} tokens truncated…{tail}"),
+        Some(total_tokens),
+    )
+}
+
+fn truncate_output_to_chars(output: &str, max_tokens: usize) -> (String, Option<usize>) {
     if max_tokens == 0 {
         let total_tokens = output.chars().count();
         let message = format!("…{total_tokens} tokens truncated…");
@@ -412,7 +458,7 @@ mod tests {
     #[test]
     fn truncate_output_to_tokens_returns_original_when_under_limit() {
         let s = "short output";
-        let (truncated, original) = truncate_output_to_tokens(s, 100);
+        let (truncated, original) = truncate_output_to_tokens(s, 100, None);
         assert_eq!(truncated, s);
         assert_eq!(original, None);
     }
@@ -420,7 +466,7 @@ mod tests {
     #[test]
     fn truncate_output_to_tokens_reports_truncation_at_zero_limit() {
         let s = "abcdef";
-        let (truncated, original) = truncate_output_to_tokens(s, 0);
+        let (truncated, original) = truncate_output_to_tokens(s, 0, None);
         assert!(truncated.contains("tokens truncated"));
         assert_eq!(original, Some(s.chars().count()));
     }
@@ -429,12 +475,40 @@ mod tests {
     fn truncate_output_to_tokens_preserves_prefix_and_suffix() {
         let s = "abcdefghijklmnopqrstuvwxyz";
         let max_tokens = 10;
-        let (truncated, original) = truncate_output_to_tokens(s, max_tokens);
+        let (truncated, original) = truncate_output_to_tokens(s, max_tokens, None);
         assert!(truncated.starts_with("abcde"));
         assert!(truncated.ends_with("vwxyz"));
         assert_eq!(original, Some(s.chars().count()));
     }
 
+    #[test]
+    fn truncate_output_to_tokens_counts_model_tokens() {
+        let tok = Tokenizer::try_default().expect("load tokenizer");
+        let s = (1..=200).map(|i| format!("line {i}\n")).collect::<String>();
+        let total = tok.encode(&s, false).len();
+
+        let (truncated, original) = truncate_output_to_tokens(&s, 20, Some(&tok));
+        assert_eq!(original, Some(total));
+        assert!(truncated.starts_with("line 1\nline 2\n"));
+        assert!(truncated.ends_with("line 199\nline 200\n"));
+        assert!(truncated.contains(&format!("…{} tokens truncated…", total - 20)));
+
+        let (untouched, original) = truncate_output_to_tokens(&s, total, Some(&tok));
+        assert_eq!(untouched, s);
+        assert_eq!(original, None);
+    }
+
+    #[test]
+    fn truncate_output_to_tokens_keeps_multibyte_characters_intact() {
+        let tok = Tokenizer::try_default().expect("load tokenizer");
+        let s = "日本語のテキスト🚀".repeat(50);
+
+        let (truncated, original) = truncate_output_to_tokens(&s, 15, Some(&tok));
+        assert!(original.is_some());
+        assert!(truncated.contains("tokens truncated"));
+        assert!(truncated.len() < s.len());
+    }
+
     #[test]
     fn format_exec_output_truncates_large_error() {
         let line = "very long execution error line that should trigger truncation\n";
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use codex_utils_tokenizer::Tokenizer;
 use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
@@ -70,7 +71,9 @@ impl UnifiedExecSessionManager {
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let (output, original_token_count) = truncate_output_to_tokens(&text, max_tokens);
+        let tokenizer = Tokenizer::for_model(context.turn.client.get_model().as_str()).ok();
+        let (output, original_token_count) =
+            truncate_output_to_tokens(&text, max_tokens, tokenizer.as_ref());
         let chunk_id = generate_chunk_id();
         let has_exited = session.has_exited();
         let stored_id = self
@@ -175,7 +178,9 @@ impl UnifiedExecSessionManager {
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let (output, original_token_count) = truncate_output_to_tokens(&text, max_tokens);
+        let tokenizer = Tokenizer::for_model(turn_ref.client.get_model().as_str()).ok();
+        let (output, original_token_count) =
+            truncate_output_to_tokens(&text, max_tokens, tokenizer.as_ref());
         let chunk_id = generate_chunk_id();
 
         let status = self.refresh_session_state(session_id).await;
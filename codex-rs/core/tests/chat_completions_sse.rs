@@ -55,6 +55,7 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        stream_include_usage: false,
     };
 
     let codex_home = match TempDir::new() {
@@ -381,6 +382,39 @@ async fn streams_reasoning_before_tool_call() {
     assert_matches!(events[4], ResponseEvent::Completed { .. });
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_usage_sent_after_finish_reason() {
+    skip_if_no_network!();
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}],\"usage\":null}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":1200,\"completion_tokens\":30,\"total_tokens\":1230,\"prompt_tokens_details\":{\"cached_tokens\":1024}}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events = run_stream(sse).await;
+    assert_eq!(events.len(), 4, "unexpected events: {events:?}");
+
+    match &events[2] {
+        ResponseEvent::OutputItemDone(item) => assert_message(item, "hi"),
+        other => panic!("expected terminal message, got {other:?}"),
+    }
+
+    match &events[3] {
+        ResponseEvent::Completed {
+            token_usage: Some(usage),
+            ..
+        } => {
+            assert_eq!(usage.input_tokens, 1200);
+            assert_eq!(usage.cached_input_tokens, 1024);
+            assert_eq!(usage.output_tokens, 30);
+            assert_eq!(usage.total_tokens, 1230);
+        }
+        other => panic!("expected completed with usage, got {other:?}"),
+    }
+}
+
 #[tokio::test]
 #[traced_test]
 async fn chat_sse_emits_failed_on_parse_error() {
@@ -12,6 +12,7 @@ use regex_lite::Regex;
 use assert_cmd::cargo::cargo_bin;
 
 pub mod responses;
+pub mod rollout;
 pub mod test_codex;
 pub mod test_codex_exec;
 
@@ -18,12 +18,15 @@ use crate::test_codex::ApplyPatchModelOutput;
 #[derive(Debug, Clone)]
 pub struct ResponseMock {
     requests: Arc<Mutex<Vec<ResponsesRequest>>>,
+    /// Whether captured bodies are checked against Responses API invariants.
+    validate_responses_body: bool,
 }
 
 impl ResponseMock {
     fn new() -> Self {
         Self {
             requests: Arc::new(Mutex::new(Vec::new())),
+            validate_responses_body: true,
         }
     }
 
@@ -201,7 +204,9 @@ impl Match for ResponseMock {
 
         // Enforce invariant checks on every request body captured by the mock.
         // Panic on orphan tool outputs or calls to catch regressions early.
-        validate_request_body_invariants(request);
+        if self.validate_responses_body {
+            validate_request_body_invariants(request);
+        }
         true
     }
 }
@@ -521,6 +526,117 @@ pub async fn mount_sse_once(server: &MockServer, body: String) -> ResponseMock {
     response_mock
 }
 
+/// Build a Messages API (`wire_api = "messages"`) SSE stream body. Unlike
+/// [`sse`], every event carries its JSON payload, as the Messages API does.
+pub fn sse_messages(events: Vec<Value>) -> String {
+    use std::fmt::Write as _;
+    let mut out = String::new();
+    for ev in events {
+        let kind = ev.get("type").and_then(|v| v.as_str()).unwrap();
+        write!(&mut out, "event: {kind}\ndata: {ev}\n\n").unwrap();
+    }
+    out
+}
+
+/// Messages API: `message_start` for a message with the given id.
+pub fn ev_messages_start(id: &str) -> Value {
+    serde_json::json!({
+        "type": "message_start",
+        "message": {
+            "id": id,
+            "type": "message",
+            "role": "assistant",
+            "content": [],
+            "usage": {"input_tokens": 0, "output_tokens": 0}
+        }
+    })
+}
+
+/// Messages API: a complete `text` content block at `index`.
+pub fn ev_messages_text_block(index: usize, text: &str) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "content_block_start",
+            "index": index,
+            "content_block": {"type": "text", "text": ""}
+        }),
+        serde_json::json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "text_delta", "text": text}
+        }),
+        serde_json::json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+/// Messages API: a complete `thinking` content block at `index`.
+pub fn ev_messages_thinking_block(index: usize, thinking: &str, signature: &str) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "content_block_start",
+            "index": index,
+            "content_block": {"type": "thinking", "thinking": "", "signature": ""}
+        }),
+        serde_json::json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "thinking_delta", "thinking": thinking}
+        }),
+        serde_json::json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "signature_delta", "signature": signature}
+        }),
+        serde_json::json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+/// Messages API: a complete `tool_use` content block at `index`, with the
+/// input JSON streamed as a single fragment.
+pub fn ev_messages_tool_use_block(index: usize, id: &str, name: &str, input: &str) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "content_block_start",
+            "index": index,
+            "content_block": {"type": "tool_use", "id": id, "name": name, "input": {}}
+        }),
+        serde_json::json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "input_json_delta", "partial_json": input}
+        }),
+        serde_json::json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+/// Messages API: the closing `message_delta` and `message_stop` events.
+pub fn ev_messages_stop(stop_reason: &str, output_tokens: i64) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "message_delta",
+            "delta": {"stop_reason": stop_reason, "stop_sequence": null},
+            "usage": {"output_tokens": output_tokens}
+        }),
+        serde_json::json!({"type": "message_stop"}),
+    ]
+}
+
+/// Like [`mount_sse_once`] for POSTs to a Messages API `/messages` endpoint.
+pub async fn mount_messages_sse_once(server: &MockServer, body: String) -> ResponseMock {
+    let response_mock = ResponseMock {
+        validate_responses_body: false,
+        ..ResponseMock::new()
+    };
+    Mock::given(method("POST"))
+        .and(path_regex(".*/messages$"))
+        .and(response_mock.clone())
+        .respond_with(sse_response(body))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+    response_mock
+}
+
 pub async fn start_mock_server() -> MockServer {
     MockServer::builder()
         .body_print_limit(BodyPrintLimit::Limited(80_000))
@@ -0,0 +1,72 @@
+//! Rollout fixtures in the on-disk layout written by `RolloutRecorder`.
+
+use std::fmt::Display;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::Value;
+use serde_json::json;
+
+/// Writes `lines` to `CODEX_HOME/sessions/YYYY/MM/DD/rollout-{filename_ts}-{id}.jsonl`
+/// and returns its path. `filename_ts` uses the `YYYY-MM-DDThh-mm-ss` format.
+pub fn write_rollout(
+    codex_home: &Path,
+    filename_ts: &str,
+    id: impl Display,
+    lines: &[Value],
+) -> io::Result<PathBuf> {
+    let dir = codex_home
+        .join("sessions")
+        .join(&filename_ts[0..4])
+        .join(&filename_ts[5..7])
+        .join(&filename_ts[8..10]);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("rollout-{filename_ts}-{id}.jsonl"));
+    let text: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// A `session_meta` line for a CLI session recorded in `cwd` against the
+/// `openai` provider.
+pub fn session_meta_line(id: impl Display, timestamp: &str, cwd: &Path) -> Value {
+    json!({
+        "timestamp": timestamp,
+        "type": "session_meta",
+        "payload": {
+            "id": id.to_string(),
+            "timestamp": timestamp,
+            "cwd": cwd,
+            "originator": "codex_cli_rs",
+            "cli_version": "0.0.0",
+            "instructions": null,
+            "source": "cli",
+            "model_provider": "openai",
+        }
+    })
+}
+
+/// A `turn_context` line for a read-only turn in `cwd`.
+pub fn turn_context_line(timestamp: &str, cwd: &Path, model: &str) -> Value {
+    json!({
+        "timestamp": timestamp,
+        "type": "turn_context",
+        "payload": {
+            "cwd": cwd,
+            "approval_policy": "on-request",
+            "sandbox_policy": { "type": "read-only" },
+            "model": model,
+            "summary": "auto",
+        }
+    })
+}
+
+/// A `user_message` event line.
+pub fn user_message_line(timestamp: &str, message: &str) -> Value {
+    json!({
+        "timestamp": timestamp,
+        "type": "event_msg",
+        "payload": { "type": "user_message", "message": message, "kind": "plain" }
+    })
+}
@@ -48,6 +48,7 @@ async fn responses_stream_includes_subagent_header_on_review() {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        stream_include_usage: false,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -136,6 +137,7 @@ async fn responses_stream_includes_subagent_header_on_other() {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        stream_include_usage: false,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
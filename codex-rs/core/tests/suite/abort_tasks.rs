@@ -48,6 +48,7 @@ async fn interrupt_long_running_tool_emits_turn_aborted() {
             items: vec![UserInput::Text {
                 text: "start sleep".into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -100,6 +101,7 @@ async fn interrupt_tool_records_history_entries() {
             items: vec![UserInput::Text {
                 text: "start history recording".into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -116,6 +118,7 @@ async fn interrupt_tool_records_history_entries() {
             items: vec![UserInput::Text {
                 text: "follow up".into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -639,3 +639,78 @@ async fn integration_git_info_unit_test() {
 
     println!("✅ Git info serialization test passed!");
 }
+
+/// Records a chat session to a cassette, replays it without a reachable
+/// provider, and checks that a different prompt is rejected on replay.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cassette_record_then_replay_cli() {
+    skip_if_no_network!();
+
+    let server = MockServer::start().await;
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"recorded\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{}}]}\n\n",
+        "data: [DONE]\n\n"
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(sse, "text/event-stream"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let cassette_dir = TempDir::new().unwrap();
+    let cassette = cassette_dir.path().join("session.cassette.jsonl");
+    let run = |base_url: String, cassette_env: &str, prompt: &str| {
+        let home = TempDir::new().unwrap();
+        let provider_override = format!(
+            "model_providers.mock={{ name = \"mock\", base_url = \"{base_url}\", env_key = \"PATH\", wire_api = \"chat\" }}"
+        );
+        let mut cmd = AssertCommand::new(cargo_bin("codex"));
+        cmd.arg("exec")
+            .arg("--skip-git-repo-check")
+            .arg("-c")
+            .arg(&provider_override)
+            .arg("-c")
+            .arg("model_provider=\"mock\"")
+            .arg("-C")
+            .arg(env!("CARGO_MANIFEST_DIR"))
+            .arg(prompt);
+        cmd.env("CODEX_HOME", home.path())
+            .env("OPENAI_API_KEY", "dummy")
+            .env(cassette_env, &cassette);
+        let output = cmd.output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        println!("Stdout:\n{stdout}");
+        println!("Stderr:\n{stderr}");
+        (output.status.success(), stdout, stderr)
+    };
+
+    let (success, stdout, _) = run(
+        format!("{}/v1", server.uri()),
+        "CODEX_RS_RECORD_CASSETTE",
+        "hello?",
+    );
+    assert!(success);
+    assert!(stdout.lines().any(|line| line.trim() == "recorded"));
+    server.verify().await;
+    let recorded = std::fs::read_to_string(&cassette).unwrap();
+    assert_eq!(recorded.lines().count(), 1, "expected one cassette entry");
+
+    // Nothing listens on this port, so any response must come from the cassette.
+    let unreachable = "http://127.0.0.1:9/v1".to_string();
+    let (success, stdout, _) = run(unreachable.clone(), "CODEX_RS_REPLAY_CASSETTE", "hello?");
+    assert!(success);
+    assert!(stdout.lines().any(|line| line.trim() == "recorded"));
+
+    let (_, stdout, stderr) = run(unreachable, "CODEX_RS_REPLAY_CASSETTE", "goodbye?");
+    assert!(
+        format!("{stdout}{stderr}").contains("does not match cassette"),
+        "expected a cassette mismatch error"
+    );
+}
@@ -7,11 +7,13 @@ use codex_core::built_in_model_providers;
 use codex_core::compact::SUMMARIZATION_PROMPT;
 use codex_core::compact::SUMMARY_PREFIX;
 use codex_core::config::Config;
+use codex_core::config::types::Compaction;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::RolloutItem;
 use codex_core::protocol::RolloutLine;
 use codex_core::protocol::WarningEvent;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::user_input::UserInput;
 use core_test_support::load_default_config_for_test;
 use core_test_support::responses::ev_local_shell_call;
@@ -162,6 +164,7 @@ async fn summarize_context_three_requests_and_instructions() {
             items: vec![UserInput::Text {
                 text: "hello world".into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -182,6 +185,7 @@ async fn summarize_context_three_requests_and_instructions() {
             items: vec![UserInput::Text {
                 text: THIRD_USER_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -547,6 +551,7 @@ async fn multiple_auto_compact_per_task_runs_after_token_limit_hit() {
             items: vec![UserInput::Text {
                 text: user_message.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .expect("submit user input");
@@ -1045,6 +1050,7 @@ async fn auto_compact_runs_after_token_limit_hit() {
             items: vec![UserInput::Text {
                 text: FIRST_AUTO_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1056,6 +1062,7 @@ async fn auto_compact_runs_after_token_limit_hit() {
             items: vec![UserInput::Text {
                 text: SECOND_AUTO_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1067,6 +1074,7 @@ async fn auto_compact_runs_after_token_limit_hit() {
             items: vec![UserInput::Text {
                 text: POST_AUTO_USER_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1290,6 +1298,7 @@ async fn auto_compact_persists_rollout_entries() {
             items: vec![UserInput::Text {
                 text: FIRST_AUTO_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1300,6 +1309,7 @@ async fn auto_compact_persists_rollout_entries() {
             items: vec![UserInput::Text {
                 text: SECOND_AUTO_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1391,6 +1401,7 @@ async fn manual_compact_retries_after_context_window_error() {
             items: vec![UserInput::Text {
                 text: "first turn".into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1537,6 +1548,7 @@ async fn manual_compact_twice_preserves_latest_user_messages() {
             items: vec![UserInput::Text {
                 text: first_user_message.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1550,6 +1562,7 @@ async fn manual_compact_twice_preserves_latest_user_messages() {
             items: vec![UserInput::Text {
                 text: second_user_message.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1563,6 +1576,7 @@ async fn manual_compact_twice_preserves_latest_user_messages() {
             items: vec![UserInput::Text {
                 text: final_user_message.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1741,6 +1755,7 @@ async fn auto_compact_allows_multiple_attempts_when_interleaved_with_other_turn_
             items: vec![UserInput::Text {
                 text: MULTI_AUTO_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1854,6 +1869,7 @@ async fn auto_compact_triggers_after_function_call_over_95_percent_usage() {
             items: vec![UserInput::Text {
                 text: FUNCTION_CALL_LIMIT_MSG.into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -1894,3 +1910,112 @@ async fn auto_compact_triggers_after_function_call_over_95_percent_usage() {
         "auto compact request should include the summarization prompt after exceeding 95% (limit {limit})"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manual_compact_drops_old_tool_outputs_and_keeps_pins_without_model() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let request_log = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call(DUMMY_CALL_ID, DUMMY_FUNCTION_NAME, "{}"),
+                ev_completed("r1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m1", FIRST_REPLY),
+                ev_completed("r2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", FINAL_REPLY),
+                ev_completed("r3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m3", FINAL_REPLY),
+                ev_completed("r4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.compaction = Compaction {
+        strategy: CompactionStrategy::DropOldToolOutputs,
+        keep_recent_turns: 1,
+        pinned_files: Vec::new(),
+    };
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    for text in [FUNCTION_CALL_LIMIT_MSG, "second turn"] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text { text: text.into() }],
+                final_output_json_schema: None,
+            })
+            .await
+            .unwrap();
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    }
+    let original_output = request_log.requests()[1].function_call_output_text(DUMMY_CALL_ID);
+    assert!(original_output.is_some_and(|output| !output.is_empty()));
+
+    codex
+        .submit(Op::PinMessage {
+            text: "always run the linter".to_string(),
+        })
+        .await
+        .unwrap();
+    codex.submit(Op::Compact).await.unwrap();
+    let compacted = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::ContextCompacted(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    assert_eq!(compacted.strategy, CompactionStrategy::DropOldToolOutputs);
+    assert_eq!(compacted.pinned_messages, 1);
+    assert!(compacted.tokens_after <= compacted.tokens_before);
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: THIRD_USER_MSG.into(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // Compaction itself made no request; the fourth request is the next turn.
+    let requests = request_log.requests();
+    assert_eq!(requests.len(), 4);
+    let after_compact = &requests[3];
+    assert_eq!(
+        after_compact.function_call_output_text(DUMMY_CALL_ID),
+        Some("[tool output omitted during compaction]".to_string())
+    );
+    assert!(after_compact.has_function_call(DUMMY_CALL_ID));
+    let user_texts = after_compact.message_input_texts("user");
+    assert!(
+        user_texts
+            .iter()
+            .any(|text| text == "always run the linter"),
+        "pinned message should survive compaction: {user_texts:?}"
+    );
+    assert!(
+        user_texts.iter().any(|text| text == "second turn"),
+        "recent turn should be kept: {user_texts:?}"
+    );
+}
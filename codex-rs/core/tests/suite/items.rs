@@ -43,6 +43,7 @@ async fn user_message_item_is_emitted() -> anyhow::Result<()> {
             items: (vec![UserInput::Text {
                 text: "please inspect sample.txt".into(),
             }]),
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -99,6 +100,7 @@ async fn assistant_message_item_is_emitted() -> anyhow::Result<()> {
             items: vec![UserInput::Text {
                 text: "please summarize results".into(),
             }],
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -155,6 +157,7 @@ async fn reasoning_item_is_emitted() -> anyhow::Result<()> {
             items: vec![UserInput::Text {
                 text: "explain your reasoning".into(),
             }],
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -213,6 +216,7 @@ async fn web_search_item_is_emitted() -> anyhow::Result<()> {
             items: vec![UserInput::Text {
                 text: "find the weather".into(),
             }],
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -265,6 +269,7 @@ async fn agent_message_content_delta_has_item_metadata() -> anyhow::Result<()> {
             items: vec![UserInput::Text {
                 text: "please stream text".into(),
             }],
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -330,6 +335,7 @@ async fn reasoning_content_delta_has_item_metadata() -> anyhow::Result<()> {
             items: vec![UserInput::Text {
                 text: "reason through it".into(),
             }],
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -387,6 +393,7 @@ async fn reasoning_raw_content_delta_respects_flag() -> anyhow::Result<()> {
             items: vec![UserInput::Text {
                 text: "show raw reasoning".into(),
             }],
+            final_output_json_schema: None,
         })
         .await?;
 
@@ -0,0 +1,221 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::job_queue::JobQueue;
+use codex_core::job_queue::JobStatus;
+use codex_core::job_queue::JobSummary;
+use codex_core::job_queue::NewJob;
+use codex_core::job_queue::QueueRunOptions;
+use codex_core::job_queue::QueueRunStats;
+use codex_core::job_queue::run_queue;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once_match;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use toml::Value as TomlValue;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::body_string_contains;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn init_repo(repo: &Path) {
+    for args in [
+        &["init", "--initial-branch=main"][..],
+        &["commit", "--allow-empty", "-m", "init"][..],
+    ] {
+        let status = Command::new("git")
+            .current_dir(repo)
+            .args([
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+}
+
+fn completed_turn(id: &str, text: &str) -> String {
+    sse(vec![
+        ev_response_created(id),
+        ev_assistant_message(&format!("msg-{id}"), text),
+        ev_completed(id),
+    ])
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn run_queue_retries_failed_attempts_in_the_same_worktree() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = MockServer::start().await;
+    // The first attempt of the flaky job fails; its retry succeeds.
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("flaky job"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": {"type": "bad_request", "message": "synthetic failure"}
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    mount_sse_once_match(
+        &server,
+        body_string_contains("flaky job"),
+        completed_turn("resp-flaky", "flaky done"),
+    )
+    .await;
+    mount_sse_once_match(
+        &server,
+        body_string_contains("steady job"),
+        completed_turn("resp-steady", "steady done"),
+    )
+    .await;
+
+    let codex_home = TempDir::new()?;
+    let repo = TempDir::new()?;
+    init_repo(repo.path());
+    let queue = JobQueue::new(codex_home.path());
+    let flaky = queue.add(NewJob {
+        prompt: "flaky job".to_string(),
+        cwd: repo.path().to_path_buf(),
+        max_attempts: 2,
+        ..NewJob::default()
+    })?;
+    let steady = queue.add(NewJob {
+        prompt: "steady job".to_string(),
+        cwd: repo.path().to_path_buf(),
+        max_attempts: 1,
+        ..NewJob::default()
+    })?;
+
+    let provider: TomlValue = toml::from_str(&format!(
+        r#"
+name = "mock"
+base_url = "{}/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#,
+        server.uri()
+    ))?;
+    let options = QueueRunOptions {
+        parallel: 2,
+        use_worktrees: true,
+        summary_path: queue.summary_path(),
+        retry_base_delay: Duration::ZERO,
+        wait_for_scheduled: true,
+        cli_overrides: vec![
+            (
+                "model_provider".to_string(),
+                TomlValue::String("mock".to_string()),
+            ),
+            ("model_providers.mock".to_string(), provider),
+        ],
+        codex_linux_sandbox_exe: None,
+    };
+    let conversation_manager = Arc::new(ConversationManager::with_auth(CodexAuth::from_api_key(
+        "dummy",
+    )));
+    let updates = Mutex::new(Vec::new());
+    let stats = run_queue(&queue, conversation_manager, &options, |job| {
+        if let Ok(mut updates) = updates.lock() {
+            updates.push((job.id.clone(), job.status));
+        }
+    })
+    .await?;
+    assert_eq!(
+        stats,
+        QueueRunStats {
+            succeeded: 2,
+            failed: 0,
+            retried: 1,
+        }
+    );
+
+    // Both jobs started before either finished.
+    let updates = updates.into_inner().expect("updates");
+    let mut started: Vec<&str> = updates[..2]
+        .iter()
+        .filter(|(_, status)| *status == JobStatus::Running)
+        .map(|(id, _)| id.as_str())
+        .collect();
+    started.sort_unstable();
+    let mut both = vec![flaky.id.as_str(), steady.id.as_str()];
+    both.sort_unstable();
+    assert_eq!(started, both);
+    assert!(updates.contains(&(flaky.id.clone(), JobStatus::Pending)));
+
+    // Both attempts of the flaky job ran in its worktree, which is gone now
+    // that the job succeeded without changes.
+    let worktree = queue.worktree_path(&flaky.id);
+    let flaky_requests: Vec<String> = server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|request| String::from_utf8_lossy(&request.body).into_owned())
+        .filter(|body| body.contains("flaky job"))
+        .collect();
+    assert_eq!(flaky_requests.len(), 2);
+    for body in &flaky_requests {
+        assert!(body.contains(&worktree.display().to_string()), "{body}");
+    }
+    assert!(!worktree.exists());
+
+    let summary = std::fs::read_to_string(queue.summary_path())?;
+    let mut summaries: Vec<JobSummary> = summary
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    summaries.sort_by_key(|summary| summary.prompt.clone());
+    let results: Vec<_> = summaries
+        .iter()
+        .map(|summary| {
+            (
+                summary.prompt.as_str(),
+                summary.status,
+                summary.attempts,
+                summary.last_agent_message.as_deref(),
+                summary.worktree.is_none() && summary.result_ref.is_none(),
+                summary.rollout_path.is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            (
+                "flaky job",
+                JobStatus::Succeeded,
+                2,
+                Some("flaky done"),
+                true,
+                true
+            ),
+            (
+                "steady job",
+                JobStatus::Succeeded,
+                1,
+                Some("steady done"),
+                true,
+                true
+            ),
+        ]
+    );
+    Ok(())
+}
@@ -104,3 +104,68 @@ async fn codex_returns_json_result(model: String) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn user_input_output_schema_applies_to_one_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let expected_schema: serde_json::Value = serde_json::from_str(SCHEMA)?;
+    let has_schema = move |req: &wiremock::Request| {
+        let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+        body.pointer("/text/format/schema") == Some(&expected_schema)
+    };
+    let no_schema = |req: &wiremock::Request| {
+        let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+        body.pointer("/text/format").is_none()
+    };
+    responses::mount_sse_once_match(
+        &server,
+        has_schema,
+        sse(vec![
+            ev_assistant_message(
+                "m1",
+                r#"{"explanation": "explanation", "final_answer": "final_answer"}"#,
+            ),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+    responses::mount_sse_once_match(
+        &server,
+        no_schema,
+        sse(vec![
+            ev_assistant_message("m2", "plain answer"),
+            ev_completed("r2"),
+        ]),
+    )
+    .await;
+
+    let TestCodex { codex, .. } = test_codex().build(&server).await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "structured".into(),
+            }],
+            final_output_json_schema: Some(serde_json::from_str(SCHEMA)?),
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "plain".into(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+    let message = wait_for_event(&codex, |ev| matches!(ev, EventMsg::AgentMessage(_))).await;
+    let EventMsg::AgentMessage(message) = message else {
+        anyhow::bail!("expected agent message event");
+    };
+    assert_eq!(message.message, "plain answer");
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anyhow::Result;
+use codex_core::WireApi;
+use codex_core::model_family::find_family_for_model;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningEffort;
+use core_test_support::responses::ev_messages_start;
+use core_test_support::responses::ev_messages_stop;
+use core_test_support::responses::ev_messages_text_block;
+use core_test_support::responses::ev_messages_thinking_block;
+use core_test_support::responses::ev_messages_tool_use_block;
+use core_test_support::responses::mount_messages_sse_once;
+use core_test_support::responses::sse_messages;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn messages_api_round_trips_thinking_and_tool_use() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.model = "gpt-5".to_string();
+        config.model_family = find_family_for_model("gpt-5").expect("gpt-5 is a valid model");
+        config.model_reasoning_effort = Some(ReasoningEffort::Low);
+        config.model_provider.wire_api = WireApi::Messages;
+        config.model_provider.experimental_bearer_token = Some("test-key".to_string());
+    });
+    let test = builder.build(&server).await?;
+
+    let call_id = "toolu_01";
+    let args = json!({
+        "command": ["/bin/echo", "messages ok"],
+        "timeout_ms": 1_000,
+    });
+    let first_mock = mount_messages_sse_once(
+        &server,
+        sse_messages(
+            [
+                vec![ev_messages_start("msg_1")],
+                ev_messages_thinking_block(0, "run echo", "sig-1"),
+                ev_messages_tool_use_block(1, call_id, "shell", &serde_json::to_string(&args)?),
+                ev_messages_stop("tool_use", 12),
+            ]
+            .concat(),
+        ),
+    )
+    .await;
+    let second_mock = mount_messages_sse_once(
+        &server,
+        sse_messages(
+            [
+                vec![ev_messages_start("msg_2")],
+                ev_messages_text_block(0, "done"),
+                ev_messages_stop("end_turn", 3),
+            ]
+            .concat(),
+        ),
+    )
+    .await;
+
+    test.submit_turn_with_policies(
+        "run the shell command",
+        AskForApproval::Never,
+        SandboxPolicy::DangerFullAccess,
+    )
+    .await?;
+
+    let first = first_mock.single_request();
+    assert_eq!(first.path(), "/v1/messages");
+    assert_eq!(first.header("x-api-key").as_deref(), Some("test-key"));
+    assert_eq!(
+        first.header("anthropic-version").as_deref(),
+        Some("2023-06-01")
+    );
+    let first_body = first.body_json();
+    assert_eq!(first_body["stream"], json!(true));
+    assert_eq!(
+        first_body["thinking"],
+        json!({"type": "enabled", "budget_tokens": 4_096})
+    );
+    assert_eq!(
+        first_body["system"][0]["cache_control"],
+        json!({"type": "ephemeral"})
+    );
+    assert!(
+        first_body["tools"]
+            .as_array()
+            .is_some_and(|tools| tools.iter().any(|tool| tool["name"] == "shell")),
+        "shell tool missing from {first_body}"
+    );
+
+    let second_body = second_mock.single_request().body_json();
+    let messages = second_body["messages"]
+        .as_array()
+        .expect("messages array")
+        .clone();
+    let assistant = &messages[messages.len() - 2];
+    assert_eq!(assistant["role"], "assistant");
+    assert_eq!(
+        assistant["content"],
+        json!([
+            {"type": "thinking", "thinking": "run echo", "signature": "sig-1"},
+            {"type": "tool_use", "id": call_id, "name": "shell", "input": args},
+        ])
+    );
+
+    let tool_result = &messages[messages.len() - 1]["content"][0];
+    assert_eq!(tool_result["type"], "tool_result");
+    assert_eq!(tool_result["tool_use_id"], call_id);
+    assert_eq!(tool_result["is_error"], Value::Bool(false));
+    assert!(
+        tool_result["content"]
+            .as_str()
+            .is_some_and(|output| output.contains("messages ok")),
+        "unexpected tool output: {tool_result}"
+    );
+
+    Ok(())
+}
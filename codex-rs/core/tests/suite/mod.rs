@@ -30,9 +30,12 @@ mod exec;
 mod fork_conversation;
 mod grep_files;
 mod items;
+mod job_queue;
 mod json_result;
 mod list_dir;
 mod live_cli;
+mod messages_api;
+mod model_fallbacks;
 mod model_overrides;
 mod model_tools;
 mod otel;
@@ -44,6 +47,7 @@ mod review;
 mod rmcp_client;
 mod rollout_list_find;
 mod seatbelt;
+mod session_budget;
 mod shell_serialization;
 mod stream_error_allows_next_turn;
 mod stream_no_completed;
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use codex_core::config::types::ModelFallback;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::RolloutLine;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn quota_error_fails_over_to_next_provider() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let primary = start_mock_server().await;
+    let backup = start_mock_server().await;
+
+    let primary_mock = mount_sse_once(
+        &primary,
+        sse(vec![
+            ev_response_created("resp-1"),
+            json!({
+                "type": "response.failed",
+                "response": {
+                    "id": "resp-1",
+                    "error": {
+                        "code": "insufficient_quota",
+                        "message": "You exceeded your current quota, please check your plan and billing details."
+                    }
+                }
+            }),
+        ]),
+    )
+    .await;
+    let backup_mock = mount_sse_once(
+        &backup,
+        sse(vec![
+            ev_response_created("resp-2"),
+            ev_assistant_message("msg-1", "served by backup"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let backup_uri = backup.uri();
+    let mut builder = test_codex().with_config(move |config| {
+        let mut backup_provider = config.model_provider.clone();
+        backup_provider.name = "backup".to_string();
+        backup_provider.base_url = Some(format!("{backup_uri}/v1"));
+        config.model_fallbacks = vec![ModelFallback {
+            model: "gpt-5".to_string(),
+            model_provider_id: "backup".to_string(),
+            model_provider: backup_provider,
+        }];
+    });
+    let test = builder.build(&primary).await?;
+
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+
+    let mut background_messages = Vec::new();
+    loop {
+        match wait_for_event(&test.codex, |_| true).await {
+            EventMsg::BackgroundEvent(event) => background_messages.push(event.message),
+            EventMsg::Error(err) => panic!("turn should fail over, got error: {}", err.message),
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    assert_eq!(primary_mock.requests().len(), 1);
+    assert_eq!(backup_mock.single_request().body_json()["model"], "gpt-5");
+    assert!(
+        background_messages
+            .iter()
+            .any(|message| message.contains("Switching to gpt-5 (backup)")),
+        "missing failover notice in {background_messages:?}"
+    );
+
+    // The next turn stays on the fallback instead of retrying the primary.
+    mount_sse_once(
+        &backup,
+        sse(vec![
+            ev_response_created("resp-3"),
+            ev_assistant_message("msg-2", "still on backup"),
+            ev_completed("resp-3"),
+        ]),
+    )
+    .await;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "again".into(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let primary_requests = primary.received_requests().await.unwrap_or_default();
+    let backup_requests = backup.received_requests().await.unwrap_or_default();
+    assert_eq!(primary_requests.len(), 1);
+    assert_eq!(backup_requests.len(), 2);
+
+    test.codex.submit(Op::Shutdown).await?;
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::ShutdownComplete)).await;
+
+    let rollout = std::fs::read_to_string(&test.session_configured.rollout_path)?;
+    let recorded_providers: Vec<Option<String>> = rollout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|line| match line.item {
+            RolloutItem::TurnContext(context) => Some(context.model_provider),
+            _ => None,
+        })
+        .collect();
+    assert!(
+        recorded_providers.contains(&Some("backup".to_string())),
+        "rollout should record the fallback provider: {recorded_providers:?}"
+    );
+
+    Ok(())
+}
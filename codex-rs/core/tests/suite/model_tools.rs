@@ -75,6 +75,7 @@ async fn collect_tool_identifiers_for_model(model: &str) -> Vec<String> {
             items: vec![UserInput::Text {
                 text: "hello tools".into(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
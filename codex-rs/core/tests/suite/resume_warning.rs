@@ -16,12 +16,17 @@ use core_test_support::load_default_config_for_test;
 use core_test_support::wait_for_event;
 use tempfile::TempDir;
 
-fn resume_history(config: &codex_core::config::Config, previous_model: &str, rollout_path: &std::path::Path) -> InitialHistory {
+fn resume_history(
+    config: &codex_core::config::Config,
+    previous_model: &str,
+    rollout_path: &std::path::Path,
+) -> InitialHistory {
     let turn_ctx = TurnContextItem {
         cwd: config.cwd.clone(),
         approval_policy: config.approval_policy,
         sandbox_policy: config.sandbox_policy.clone(),
         model: previous_model.to_string(),
+        model_provider: None,
         effort: config.model_reasoning_effort,
         summary: config.model_reasoning_summary,
     };
@@ -7,6 +7,7 @@ use codex_core::REVIEW_PROMPT;
 use codex_core::ResponseItem;
 use codex_core::built_in_model_providers;
 use codex_core::config::Config;
+use codex_core::config::types::ReviewRubric;
 use codex_core::protocol::ENVIRONMENT_CONTEXT_OPEN_TAG;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExitedReviewModeEvent;
@@ -82,6 +83,7 @@ async fn review_op_emits_lifecycle_and_review_output() {
             review_request: ReviewRequest {
                 prompt: "Please review my changes".to_string(),
                 user_facing_hint: "my changes".to_string(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -108,6 +110,7 @@ async fn review_op_emits_lifecycle_and_review_output() {
                 absolute_file_path: PathBuf::from("/tmp/file.rs"),
                 line_range: ReviewLineRange { start: 10, end: 20 },
             },
+            category: None,
         }],
         overall_correctness: "good".to_string(),
         overall_explanation: "All good with some improvements suggested.".to_string(),
@@ -178,6 +181,7 @@ async fn review_op_with_plain_text_emits_review_fallback() {
             review_request: ReviewRequest {
                 prompt: "Plain text review".to_string(),
                 user_facing_hint: "plain text review".to_string(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -236,6 +240,7 @@ async fn review_filters_agent_message_related_events() {
             review_request: ReviewRequest {
                 prompt: "Filter streaming events".to_string(),
                 user_facing_hint: "Filter streaming events".to_string(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -320,6 +325,7 @@ async fn review_does_not_emit_agent_message_on_structured_output() {
             review_request: ReviewRequest {
                 prompt: "check structured".to_string(),
                 user_facing_hint: "check structured".to_string(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -373,6 +379,7 @@ async fn review_uses_custom_review_model_from_config() {
             review_request: ReviewRequest {
                 prompt: "use custom model".to_string(),
                 user_facing_hint: "use custom model".to_string(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -399,6 +406,65 @@ async fn review_uses_custom_review_model_from_config() {
     server.verify().await;
 }
 
+/// Repo REVIEW.md instructions and requested rubrics are appended to the review
+/// prompt; unknown rubrics are skipped with a warning.
+#[cfg_attr(windows, tokio::test(flavor = "multi_thread", worker_threads = 4))]
+#[cfg_attr(not(windows), tokio::test(flavor = "multi_thread", worker_threads = 2))]
+async fn review_appends_repo_instructions_and_rubrics() {
+    skip_if_no_network!();
+
+    let sse_raw = r#"[
+        {"type":"response.completed", "response": {"id": "__ID__"}}
+    ]"#;
+    let server = start_responses_server_with_sse(sse_raw, 1).await;
+    let codex_home = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    std::fs::write(repo.path().join("REVIEW.md"), "Never touch generated code.").unwrap();
+    let repo_path = repo.path().to_path_buf();
+    let codex = new_conversation_for_server(&server, &codex_home, move |cfg| {
+        cfg.cwd = repo_path;
+        cfg.review_rubrics.insert(
+            "perf".to_string(),
+            ReviewRubric {
+                instructions: "Flag quadratic loops.".to_string(),
+            },
+        );
+    })
+    .await;
+
+    codex
+        .submit(Op::Review {
+            review_request: ReviewRequest {
+                prompt: "review with rubrics".to_string(),
+                user_facing_hint: "review with rubrics".to_string(),
+                rubrics: vec!["perf".to_string(), "nope".to_string()],
+            },
+        })
+        .await
+        .unwrap();
+
+    let warning = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Warning(_))).await;
+    let EventMsg::Warning(warning) = warning else {
+        unreachable!("matched warning event");
+    };
+    assert!(
+        warning.message.contains("nope"),
+        "unexpected warning: {}",
+        warning.message
+    );
+    let _complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let request = &server.received_requests().await.unwrap()[0];
+    let body = request.body_json::<serde_json::Value>().unwrap();
+    let instructions = body["instructions"].as_str().expect("instructions string");
+    assert!(instructions.starts_with(REVIEW_PROMPT.trim_end()));
+    assert!(instructions.contains("Never touch generated code."));
+    assert!(instructions.contains("## perf\n\nFlag quadratic loops."));
+    assert!(!instructions.contains("## nope"));
+
+    server.verify().await;
+}
+
 /// When a review session begins, it must not prepend prior chat history from
 /// the parent session. The request `input` should contain only the review
 /// prompt from the user.
@@ -490,6 +556,7 @@ async fn review_input_isolated_from_parent_history() {
             review_request: ReviewRequest {
                 prompt: review_prompt.clone(),
                 user_facing_hint: review_prompt.clone(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -602,6 +669,7 @@ async fn review_history_does_not_leak_into_parent_session() {
             review_request: ReviewRequest {
                 prompt: "Start a review".to_string(),
                 user_facing_hint: "Start a review".to_string(),
+                rubrics: Vec::new(),
             },
         })
         .await
@@ -625,6 +693,7 @@ async fn review_history_does_not_leak_into_parent_session() {
             items: vec![UserInput::Text {
                 text: followup.clone(),
             }],
+            final_output_json_schema: None,
         })
         .await
         .unwrap();
@@ -98,6 +98,10 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             );
         })
@@ -236,6 +240,10 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             );
         })
@@ -432,6 +440,10 @@ async fn stdio_image_completions_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             );
         })
@@ -572,6 +584,10 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             );
         })
@@ -721,6 +737,10 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             );
         })
@@ -902,6 +922,10 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sandbox: None,
+                    env_policy: None,
+                    cache: None,
+                    rate_limit: None,
                 },
             );
         })
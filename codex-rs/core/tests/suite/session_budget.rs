@@ -0,0 +1,71 @@
+use anyhow::Result;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::TurnAbortReason;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn token_budget_aborts_turn_before_next_request() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("call-1", "unknown_tool", "{}"),
+            ev_completed_with_tokens("resp-1", 500),
+        ]),
+    )
+    .await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.max_session_tokens = Some(100);
+    });
+    let test = builder.build(&server).await?;
+
+    for _ in 0..2 {
+        test.codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: "loop forever".into(),
+                }],
+                final_output_json_schema: None,
+            })
+            .await?;
+
+        let mut errors = Vec::new();
+        let reason = loop {
+            match wait_for_event(&test.codex, |_| true).await {
+                EventMsg::Error(err) => errors.push(err.message),
+                EventMsg::TurnAborted(ev) => break ev.reason,
+                EventMsg::TaskComplete(_) => panic!("turn should be aborted"),
+                _ => {}
+            }
+        };
+
+        assert_eq!(reason, TurnAbortReason::BudgetExceeded);
+        assert_eq!(
+            errors,
+            vec![
+                "Session token budget exceeded: used 500 tokens (max_session_tokens = 100)."
+                    .to_string()
+            ]
+        );
+    }
+
+    // The tool output is never sent back, and the second turn is refused outright.
+    assert_eq!(mock.requests().len(), 1);
+
+    Ok(())
+}
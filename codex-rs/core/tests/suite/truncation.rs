@@ -316,6 +316,10 @@ async fn mcp_tool_call_output_exceeds_limit_truncated_for_model() -> Result<()>
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         );
     });
@@ -416,6 +420,10 @@ async fn mcp_image_output_preserves_image_and_no_text_summary() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sandbox: None,
+                env_policy: None,
+                cache: None,
+                rate_limit: None,
             },
         );
     });
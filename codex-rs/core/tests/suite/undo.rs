@@ -2,6 +2,7 @@
 
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
 
@@ -14,6 +15,7 @@ use codex_core::features::Feature;
 use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::SnapshotSummary;
 use codex_core::protocol::UndoCompletedEvent;
 use core_test_support::responses::ev_apply_patch_function_call;
 use core_test_support::responses::ev_assistant_message;
@@ -145,6 +147,40 @@ async fn expect_failed_undo(codex: &Arc<CodexConversation>) -> Result<UndoComple
     Ok(event)
 }
 
+async fn list_snapshots(codex: &Arc<CodexConversation>) -> Result<Vec<SnapshotSummary>> {
+    codex.submit(Op::ListSnapshots).await?;
+    let snapshots = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::ListSnapshotsResponse(ev) => Some(ev.snapshots.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(snapshots)
+}
+
+async fn expect_successful_restore(
+    codex: &Arc<CodexConversation>,
+    id: &str,
+    paths: Vec<PathBuf>,
+) -> Result<UndoCompletedEvent> {
+    codex
+        .submit(Op::RestoreSnapshot {
+            id: id.to_string(),
+            paths,
+        })
+        .await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert!(
+        event.success,
+        "expected restore to succeed but failed with message {:?}",
+        event.message
+    );
+    Ok(event)
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn undo_removes_new_file_created_during_turn() -> Result<()> {
     skip_if_no_network!(Ok(()));
@@ -489,3 +525,89 @@ async fn undo_overwrites_manual_edits_after_turn() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_snapshot_jumps_back_and_forward() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let story = harness.path("story.txt");
+    let notes = harness.path("notes.txt");
+    fs::write(&story, "initial\n")?;
+    fs::write(&notes, "notes\n")?;
+    git(harness.cwd(), &["add", "story.txt", "notes.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "seed"])?;
+
+    run_apply_patch_turn(
+        &harness,
+        "first change",
+        "restore-turn-1",
+        "*** Begin Patch\n*** Update File: story.txt\n@@\n-initial\n+turn one\n*** Update File: notes.txt\n@@\n-notes\n+notes one\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    run_apply_patch_turn(
+        &harness,
+        "second change",
+        "restore-turn-2",
+        "*** Begin Patch\n*** Update File: story.txt\n@@\n-turn one\n+turn two\n*** End Patch",
+        "ok",
+    )
+    .await?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let snapshots = list_snapshots(&codex).await?;
+    assert_eq!(
+        snapshots
+            .iter()
+            .map(|snapshot| snapshot.turn_prompt.as_deref())
+            .collect::<Vec<_>>(),
+        vec![Some("first change"), Some("second change")]
+    );
+    let first = &snapshots[0];
+    let mut changed: Vec<_> = first.files.iter().map(|file| file.path.clone()).collect();
+    changed.sort();
+    assert_eq!(
+        changed,
+        vec![PathBuf::from("notes.txt"), PathBuf::from("story.txt")]
+    );
+    assert!(first.diff.contains("+turn two"), "{}", first.diff);
+
+    expect_successful_restore(&codex, &first.id, vec![PathBuf::from("story.txt")]).await?;
+    assert_eq!(fs::read_to_string(&story)?, "initial\n");
+    assert_eq!(fs::read_to_string(&notes)?, "notes one\n");
+
+    expect_successful_restore(&codex, &first.id, Vec::new()).await?;
+    assert_eq!(fs::read_to_string(&notes)?, "notes\n");
+
+    // History is untouched, so the later snapshot still works as a redo.
+    expect_successful_restore(&codex, &snapshots[1].id, Vec::new()).await?;
+    assert_eq!(fs::read_to_string(&story)?, "turn one\n");
+    assert_eq!(fs::read_to_string(&notes)?, "notes one\n");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_outside_git_repository_uses_shadow_repository() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    let kept = harness.path("kept.txt");
+    fs::write(&kept, "kept\n")?;
+
+    let patch = "*** Begin Patch\n*** Add File: new_file.txt\n+from turn\n*** Update File: kept.txt\n@@\n-kept\n+changed\n*** End Patch";
+    run_apply_patch_turn(&harness, "create file", "shadow-undo", patch, "ok").await?;
+    assert_eq!(fs::read_to_string(&kept)?, "changed\n");
+
+    let codex = Arc::clone(&harness.test().codex);
+    expect_successful_undo(&codex).await?;
+
+    assert!(!harness.path("new_file.txt").exists());
+    assert_eq!(fs::read_to_string(&kept)?, "kept\n");
+    assert!(!harness.cwd().join(".git").exists());
+
+    Ok(())
+}
@@ -820,7 +820,7 @@ async fn exec_command_reports_chunk_and_exit_metadata() -> Result<()> {
 
     let call_id = "uexec-metadata";
     let args = serde_json::json!({
-        "cmd": "printf 'abcdefghijklmnopqrstuvwxyz'",
+        "cmd": "seq 1 100",
         "yield_time_ms": 500,
         "max_output_tokens": 6,
     });
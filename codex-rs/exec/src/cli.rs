@@ -85,6 +85,9 @@ pub struct Cli {
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
     Resume(ResumeArgs),
+
+    /// Review code changes headlessly and print machine-readable findings.
+    Review(ReviewArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -103,6 +106,61 @@ pub struct ResumeArgs {
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+#[command(group(
+    clap::ArgGroup::new("review_target")
+        .required(true)
+        .args(["base", "commit", "uncommitted"]),
+))]
+pub struct ReviewArgs {
+    /// Review the changes the current branch would merge into BRANCH.
+    #[arg(long = "base", value_name = "BRANCH")]
+    pub base: Option<String>,
+
+    /// Review the changes introduced by a single commit.
+    #[arg(long = "commit", value_name = "SHA")]
+    pub commit: Option<String>,
+
+    /// Review staged, unstaged and untracked changes in the working tree.
+    #[arg(long = "uncommitted", default_value_t = false)]
+    pub uncommitted: bool,
+
+    /// Review rubric to apply on top of the default checks; repeatable.
+    /// Built-in rubrics are `security`, `migrations` and `api-compatibility`;
+    /// more can be defined under `[review_rubrics]` in config.toml.
+    #[arg(long = "rubric", value_name = "NAME")]
+    pub rubrics: Vec<String>,
+
+    /// Format of the findings written to stdout.
+    #[arg(long = "format", value_enum, default_value_t = ReviewFormat::Json)]
+    pub format: ReviewFormat,
+
+    /// Also write the findings as JSON to FILE, whatever `--format` is.
+    #[arg(long = "findings-file", value_name = "FILE")]
+    pub findings_file: Option<PathBuf>,
+
+    /// Exit with status 2 when any finding has this priority or a more
+    /// urgent one (0 is the most urgent, 3 the least).
+    #[arg(
+        long = "fail-on-priority",
+        value_name = "PRIORITY",
+        value_parser = clap::value_parser!(i32).range(0..=3)
+    )]
+    pub fail_on_priority: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ReviewFormat {
+    /// The structured review output as a single JSON document.
+    #[default]
+    Json,
+    /// A SARIF 2.1.0 log, e.g. for GitHub code scanning.
+    Sarif,
+    /// GitHub Actions workflow commands (`::warning file=...::`).
+    Github,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
@@ -4,6 +4,7 @@ use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
@@ -21,6 +22,7 @@ use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnProfileEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::num_format::format_with_separators;
@@ -29,6 +31,7 @@ use owo_colors::Style;
 use shlex::try_join;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::time::Instant;
 
 use crate::event_processor::CodexStatus;
@@ -62,6 +65,7 @@ pub(crate) struct EventProcessorWithHumanOutput {
     show_raw_agent_reasoning: bool,
     last_message_path: Option<PathBuf>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsageInfo>,
+    last_cost: Option<codex_core::protocol::TokenCost>,
     final_message: Option<String>,
 }
 
@@ -88,6 +92,7 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_cost: None,
                 final_message: None,
             }
         } else {
@@ -105,6 +110,7 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_cost: None,
                 final_message: None,
             }
         }
@@ -185,6 +191,26 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
+            EventMsg::ContextCompacted(ContextCompactedEvent {
+                strategy,
+                tokens_before,
+                tokens_after,
+                pinned_messages,
+                pinned_files,
+            }) => {
+                let mut message = format!(
+                    "context compacted with {strategy}: {} -> {} tokens",
+                    format_with_separators(tokens_before),
+                    format_with_separators(tokens_after)
+                );
+                if pinned_messages > 0 || !pinned_files.is_empty() {
+                    message.push_str(&format!(
+                        ", kept {pinned_messages} pinned message(s) and {} pinned file(s)",
+                        pinned_files.len()
+                    ));
+                }
+                ts_msg!(self, "{}", message.style(self.dimmed));
+            }
             EventMsg::StreamError(StreamErrorEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
@@ -201,8 +227,12 @@ impl EventProcessor for EventProcessorWithHumanOutput {
 
                 return CodexStatus::InitiateShutdown;
             }
+            EventMsg::TurnProfile(profile) => {
+                ts_msg!(self, "{}", format_turn_profile(&profile).style(self.dimmed));
+            }
             EventMsg::TokenCount(ev) => {
                 self.last_total_token_usage = ev.info;
+                self.last_cost = ev.cost.or(self.last_cost);
             }
 
             EventMsg::AgentReasoningSectionBreak(_) => {
@@ -280,9 +310,22 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     result,
                     invocation,
                     duration,
+                    cached,
+                    throttled,
                 } = tool_call_end_event;
 
-                let duration = format!(" in {}", format_duration(duration));
+                let duration = if cached {
+                    " (cached)".to_string()
+                } else {
+                    format!(" in {}", format_duration(duration))
+                };
+                let duration = match throttled {
+                    Some(waited) => format!(
+                        "{duration} after {} rate limit wait",
+                        format_duration(waited)
+                    ),
+                    None => duration,
+                };
 
                 let status_str = if is_success { "success" } else { "failed" };
                 let title_style = if is_success { self.green } else { self.red };
@@ -512,6 +555,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 TurnAbortReason::ReviewEnded => {
                     ts_msg!(self, "task aborted: review ended");
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    ts_msg!(self, "task aborted: session budget exceeded");
+                    return CodexStatus::InitiateShutdown;
+                }
             },
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::WebSearchBegin(_)
@@ -521,6 +568,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListSnapshotsResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -534,7 +582,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::UndoCompleted(_)
-            | EventMsg::UndoStarted(_) => {}
+            | EventMsg::UndoStarted(_)
+            | EventMsg::MessagePinned(_) => {}
         }
         CodexStatus::Running
     }
@@ -547,6 +596,13 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 format_with_separators(usage_info.total_token_usage.blended_total())
             );
         }
+        if let Some(cost) = &self.last_cost {
+            eprintln!(
+                "{}\n${:.4}",
+                "estimated cost".style(self.magenta).style(self.italic),
+                cost.session_usd
+            );
+        }
 
         // If the user has not piped the final message to a file, they will see
         // it twice: once written to stderr as part of the normal event
@@ -563,6 +619,25 @@ impl EventProcessor for EventProcessorWithHumanOutput {
     }
 }
 
+fn format_turn_profile(profile: &TurnProfileEvent) -> String {
+    let mut message = format!(
+        "turn took {}",
+        format_duration(Duration::from_millis(profile.duration_ms))
+    );
+    for (index, timing) in profile.phases.iter().enumerate() {
+        message.push_str(if index == 0 { ": " } else { ", " });
+        message.push_str(&format!(
+            "{} {}",
+            timing.phase.label(),
+            format_duration(Duration::from_millis(timing.total_ms))
+        ));
+        if timing.count > 1 {
+            message.push_str(&format!(" ({}x)", timing.count));
+        }
+    }
+    message
+}
+
 fn escape_command(command: &[String]) -> String {
     try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
 }
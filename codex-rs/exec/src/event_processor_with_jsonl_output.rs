@@ -30,6 +30,9 @@ use crate::exec_events::TodoItem;
 use crate::exec_events::TodoListItem;
 use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
+use crate::exec_events::TurnPhase;
+use crate::exec_events::TurnPhaseTiming;
+use crate::exec_events::TurnProfile;
 use crate::exec_events::TurnStartedEvent;
 use crate::exec_events::Usage;
 use crate::exec_events::WebSearchItem;
@@ -48,6 +51,8 @@ use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnProfileEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -64,6 +69,8 @@ pub struct EventProcessorWithJsonOutput {
     // Tracks the todo list for the current turn (at most one per turn).
     running_todo_list: Option<RunningTodoList>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsage>,
+    last_cost_usd: Option<f64>,
+    last_turn_profile: Option<TurnProfile>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
 }
@@ -97,6 +104,8 @@ impl EventProcessorWithJsonOutput {
             running_patch_applies: HashMap::new(),
             running_todo_list: None,
             last_total_token_usage: None,
+            last_cost_usd: None,
+            last_turn_profile: None,
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
         }
@@ -119,10 +128,20 @@ impl EventProcessorWithJsonOutput {
                 if let Some(info) = &ev.info {
                     self.last_total_token_usage = Some(info.total_token_usage.clone());
                 }
+                if let Some(cost) = &ev.cost {
+                    self.last_cost_usd = Some(cost.session_usd);
+                }
+                Vec::new()
+            }
+            EventMsg::TurnProfile(ev) => {
+                self.last_turn_profile = Some(convert_turn_profile(ev));
                 Vec::new()
             }
             EventMsg::TaskStarted(ev) => self.handle_task_started(ev),
             EventMsg::TaskComplete(_) => self.handle_task_complete(),
+            EventMsg::TurnAborted(ev) if ev.reason == TurnAbortReason::BudgetExceeded => {
+                self.handle_task_complete()
+            }
             EventMsg::Error(ev) => {
                 let error = ThreadErrorEvent {
                     message: ev.message.clone(),
@@ -429,6 +448,7 @@ impl EventProcessorWithJsonOutput {
 
     fn handle_task_started(&mut self, _: &TaskStartedEvent) -> Vec<ThreadEvent> {
         self.last_critical_error = None;
+        self.last_turn_profile = None;
         vec![ThreadEvent::TurnStarted(TurnStartedEvent {})]
     }
 
@@ -438,6 +458,7 @@ impl EventProcessorWithJsonOutput {
                 input_tokens: u.input_tokens,
                 cached_input_tokens: u.cached_input_tokens,
                 output_tokens: u.output_tokens,
+                cost_usd: self.last_cost_usd,
             }
         } else {
             Usage::default()
@@ -458,13 +479,42 @@ impl EventProcessorWithJsonOutput {
         if let Some(error) = self.last_critical_error.take() {
             items.push(ThreadEvent::TurnFailed(TurnFailedEvent { error }));
         } else {
-            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent { usage }));
+            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent {
+                usage,
+                profile: self.last_turn_profile.take(),
+            }));
         }
 
         items
     }
 }
 
+fn convert_turn_profile(ev: &TurnProfileEvent) -> TurnProfile {
+    let phases = ev
+        .phases
+        .iter()
+        .map(|timing| TurnPhaseTiming {
+            phase: match timing.phase {
+                codex_core::protocol::TurnPhase::ModelTimeToFirstToken => {
+                    TurnPhase::ModelTimeToFirstToken
+                }
+                codex_core::protocol::TurnPhase::ModelStreaming => TurnPhase::ModelStreaming,
+                codex_core::protocol::TurnPhase::Exec => TurnPhase::Exec,
+                codex_core::protocol::TurnPhase::ApprovalWait => TurnPhase::ApprovalWait,
+                codex_core::protocol::TurnPhase::Hook => TurnPhase::Hook,
+                codex_core::protocol::TurnPhase::McpToolCall => TurnPhase::McpToolCall,
+            },
+            count: timing.count,
+            total_ms: timing.total_ms,
+            max_ms: timing.max_ms,
+        })
+        .collect();
+    TurnProfile {
+        duration_ms: ev.duration_ms,
+        phases,
+    }
+}
+
 impl EventProcessor for EventProcessorWithJsonOutput {
     fn print_config_summary(&mut self, _: &Config, _: &str, ev: &SessionConfiguredEvent) {
         self.process_event(Event {
@@ -489,13 +539,17 @@ impl EventProcessor for EventProcessorWithJsonOutput {
 
         let Event { msg, .. } = event;
 
-        if let EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) = msg {
-            if let Some(output_file) = self.last_message_path.as_deref() {
-                handle_last_message(last_agent_message.as_deref(), output_file);
+        match msg {
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                if let Some(output_file) = self.last_message_path.as_deref() {
+                    handle_last_message(last_agent_message.as_deref(), output_file);
+                }
+                CodexStatus::InitiateShutdown
             }
-            CodexStatus::InitiateShutdown
-        } else {
-            CodexStatus::Running
+            EventMsg::TurnAborted(ev) if ev.reason == TurnAbortReason::BudgetExceeded => {
+                CodexStatus::InitiateShutdown
+            }
+            _ => CodexStatus::Running,
         }
     }
 }
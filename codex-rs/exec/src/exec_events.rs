@@ -48,6 +48,10 @@ pub struct TurnStartedEvent {}
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct TurnCompletedEvent {
     pub usage: Usage,
+    /// Where the turn spent its time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub profile: Option<TurnProfile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -64,6 +68,41 @@ pub struct Usage {
     pub cached_input_tokens: i64,
     /// The number of output tokens used during the turn.
     pub output_tokens: i64,
+    /// Estimated cost in USD, when the model has a `model_prices` entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cost_usd: Option<f64>,
+}
+
+/// Timing breakdown of a turn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
+pub struct TurnProfile {
+    /// Wall-clock duration of the turn in milliseconds.
+    pub duration_ms: u64,
+    /// Time spent in each phase. Tool calls can overlap, so the totals may add
+    /// up to more than `duration_ms`.
+    pub phases: Vec<TurnPhaseTiming>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TurnPhaseTiming {
+    pub phase: TurnPhase,
+    /// How many times the phase ran during the turn.
+    pub count: u64,
+    pub total_ms: u64,
+    /// Longest single occurrence.
+    pub max_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnPhase {
+    ModelTimeToFirstToken,
+    ModelStreaming,
+    Exec,
+    ApprovalWait,
+    Hook,
+    McpToolCall,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -9,6 +9,7 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod review_output;
 
 pub use cli::Cli;
 use codex_core::AuthManager;
@@ -23,7 +24,12 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::SessionSource;
+use codex_core::review_prompts;
+use codex_core::review_rubrics::available_review_rubrics;
+use codex_core::review_rubrics::resolve_review_rubrics;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
@@ -45,6 +51,9 @@ use tracing_subscriber::prelude::*;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::review_output::has_findings_at_or_above;
+use crate::review_output::render_json;
+use crate::review_output::render_review_output;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_conversation_path_by_id_str;
 use codex_extensions::StatusLineResult;
@@ -75,66 +84,26 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         config_overrides,
     } = cli;
 
-    // Determine the prompt source (parent or subcommand) and read from stdin if needed.
-    let prompt_arg = match &command {
-        // Allow prompt before the subcommand by falling back to the parent-level prompt
-        // when the Resume subcommand did not provide its own prompt.
-        Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
-        None => prompt,
-    };
-
-    let prompt = match prompt_arg {
-        Some(p) if p != "-" => p,
-        // Either `-` was passed or no positional arg.
-        maybe_dash => {
-            // When no arg (None) **and** stdin is a TTY, bail out early – unless the
-            // user explicitly forced reading via `-`.
-            let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
-
-            if std::io::stdin().is_terminal() && !force_stdin {
-                eprintln!(
-                    "No prompt provided. Either specify one as an argument or pipe the prompt into stdin."
-                );
-                std::process::exit(1);
-            }
-
-            // Ensure the user knows we are waiting on stdin, as they may
-            // have gotten into this state by mistake. If so, and they are not
-            // writing to stdin, Codex will hang indefinitely, so this should
-            // help them debug in that case.
-            if !force_stdin {
-                eprintln!("Reading prompt from stdin...");
-            }
-            let mut buffer = String::new();
-            if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
-                eprintln!("Failed to read prompt from stdin: {e}");
-                std::process::exit(1);
-            } else if buffer.trim().is_empty() {
-                eprintln!("No prompt provided via stdin.");
-                std::process::exit(1);
-            }
-            buffer
-        }
-    };
+    if matches!(command, Some(ExecCommand::Review(_))) && json_mode {
+        eprintln!("`codex exec review` does not support --json; use `review --format` instead.");
+        std::process::exit(1);
+    }
 
-    // Execute UserPromptSubmit hooks on the original prompt
-    let prompt = match execute_user_prompt_submit_hook(&prompt, cwd.as_deref()).await {
-        Ok(updated) => updated,
-        Err(e) => {
-            tracing::warn!("UserPromptSubmit hook failed, continuing: {e}");
-            prompt
-        }
+    let review_request = match &command {
+        Some(ExecCommand::Review(args)) => Some(review_request_from_args(args)),
+        _ => None,
     };
 
-    // Slash command detection and substitution on the (possibly) modified prompt
-    let prompt = match detect_and_substitute_slash_command(&prompt, cwd.as_deref()) {
-        Ok(substituted) => substituted,
-        Err(e) => {
-            tracing::warn!(
-                "Slash command detection failed; using original prompt: {}",
-                e
-            );
-            prompt // Fall back to original prompt if detection fails
+    let prompt = match &review_request {
+        Some(review_request) => review_request.prompt.clone(),
+        None => {
+            let prompt_arg = match &command {
+                // Allow prompt before the subcommand by falling back to the parent-level prompt
+                // when the Resume subcommand did not provide its own prompt.
+                Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
+                _ => prompt,
+            };
+            resolve_prompt(prompt_arg, cwd.as_deref()).await
         }
     };
 
@@ -227,6 +196,19 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
+    // Core only warns about unknown rubrics; in CI a typo should fail loudly.
+    if let Some(review_request) = &review_request {
+        let (_, unknown) = resolve_review_rubrics(&config, &review_request.rubrics);
+        if !unknown.is_empty() {
+            eprintln!(
+                "Unknown review rubric(s): {}. Available rubrics: {}",
+                unknown.join(", "),
+                available_review_rubrics(&config).join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
 
     #[allow(clippy::print_stderr)]
@@ -290,8 +272,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         conversation_id: _,
         conversation,
         session_configured,
-    } = if let Some(ExecCommand::Resume(args)) = command {
-        let resume_path = resolve_resume_path(&config, &args).await?;
+    } = if let Some(ExecCommand::Resume(args)) = &command {
+        let resume_path = resolve_resume_path(&config, args).await?;
 
         if let Some(path) = resume_path {
             conversation_manager
@@ -352,33 +334,40 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         });
     }
 
-    // Package images and prompt into a single user input turn.
-    let mut items: Vec<UserInput> = images
-        .into_iter()
-        .map(|path| UserInput::LocalImage { path })
-        .collect();
-    items.push(UserInput::Text { text: prompt });
-    let initial_prompt_task_id = conversation
-        .submit(Op::UserTurn {
-            items,
-            cwd: default_cwd,
-            approval_policy: default_approval_policy,
-            sandbox_policy: default_sandbox_policy,
-            model: default_model,
-            effort: default_effort,
-            summary: default_summary,
-            final_output_json_schema: output_schema,
-        })
-        .await?;
+    let initial_prompt_task_id = if let Some(review_request) = review_request {
+        conversation.submit(Op::Review { review_request }).await?
+    } else {
+        // Package images and prompt into a single user input turn.
+        let mut items: Vec<UserInput> = images
+            .into_iter()
+            .map(|path| UserInput::LocalImage { path })
+            .collect();
+        items.push(UserInput::Text { text: prompt });
+        conversation
+            .submit(Op::UserTurn {
+                items,
+                cwd: default_cwd.clone(),
+                approval_policy: default_approval_policy,
+                sandbox_policy: default_sandbox_policy,
+                model: default_model,
+                effort: default_effort,
+                summary: default_summary,
+                final_output_json_schema: output_schema,
+            })
+            .await?
+    };
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    let mut review_output: Option<ReviewOutputEvent> = None;
     while let Some(event) = rx.recv().await {
-        if matches!(event.msg, EventMsg::Error(_)) {
-            error_seen = true;
+        match &event.msg {
+            EventMsg::Error(_) => error_seen = true,
+            EventMsg::ExitedReviewMode(exited) => review_output = exited.review_output.clone(),
+            _ => {}
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
@@ -396,9 +385,106 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
+    if let Some(ExecCommand::Review(args)) = &command {
+        let Some(review_output) = review_output else {
+            eprintln!("The review finished without producing any findings output.");
+            std::process::exit(1);
+        };
+        let root = get_git_repo_root(&default_cwd).unwrap_or(default_cwd);
+        #[allow(clippy::print_stdout)]
+        {
+            println!(
+                "{}",
+                render_review_output(&review_output, args.format, &root)
+            );
+        }
+        if let Some(path) = args.findings_file.as_deref()
+            && let Err(err) = std::fs::write(path, render_json(&review_output))
+        {
+            eprintln!("Failed to write findings to {}: {err}", path.display());
+            std::process::exit(1);
+        }
+        if let Some(threshold) = args.fail_on_priority
+            && has_findings_at_or_above(&review_output, threshold)
+        {
+            std::process::exit(2);
+        }
+    }
+
     Ok(())
 }
 
+/// Reads the prompt (from stdin when needed) and applies UserPromptSubmit hooks
+/// and slash command substitution.
+async fn resolve_prompt(prompt_arg: Option<String>, cwd: Option<&Path>) -> String {
+    let prompt = match prompt_arg {
+        Some(p) if p != "-" => p,
+        // Either `-` was passed or no positional arg.
+        maybe_dash => {
+            // When no arg (None) **and** stdin is a TTY, bail out early – unless the
+            // user explicitly forced reading via `-`.
+            let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
+
+            if std::io::stdin().is_terminal() && !force_stdin {
+                eprintln!(
+                    "No prompt provided. Either specify one as an argument or pipe the prompt into stdin."
+                );
+                std::process::exit(1);
+            }
+
+            // Ensure the user knows we are waiting on stdin, as they may
+            // have gotten into this state by mistake. If so, and they are not
+            // writing to stdin, Codex will hang indefinitely, so this should
+            // help them debug in that case.
+            if !force_stdin {
+                eprintln!("Reading prompt from stdin...");
+            }
+            let mut buffer = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
+                eprintln!("Failed to read prompt from stdin: {e}");
+                std::process::exit(1);
+            } else if buffer.trim().is_empty() {
+                eprintln!("No prompt provided via stdin.");
+                std::process::exit(1);
+            }
+            buffer
+        }
+    };
+
+    // Execute UserPromptSubmit hooks on the original prompt
+    let prompt = match execute_user_prompt_submit_hook(&prompt, cwd).await {
+        Ok(updated) => updated,
+        Err(e) => {
+            tracing::warn!("UserPromptSubmit hook failed, continuing: {e}");
+            prompt
+        }
+    };
+
+    // Slash command detection and substitution on the (possibly) modified prompt
+    match detect_and_substitute_slash_command(&prompt, cwd) {
+        Ok(substituted) => substituted,
+        Err(e) => {
+            tracing::warn!(
+                "Slash command detection failed; using original prompt: {}",
+                e
+            );
+            prompt // Fall back to original prompt if detection fails
+        }
+    }
+}
+
+fn review_request_from_args(args: &crate::cli::ReviewArgs) -> ReviewRequest {
+    let mut review_request = if let Some(branch) = args.base.as_deref() {
+        review_prompts::base_branch_review_request(branch)
+    } else if let Some(sha) = args.commit.as_deref() {
+        review_prompts::commit_review_request(sha, None)
+    } else {
+        review_prompts::uncommitted_changes_review_request()
+    };
+    review_request.rubrics = args.rubrics.clone();
+    review_request
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
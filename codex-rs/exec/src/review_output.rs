@@ -0,0 +1,168 @@
+//! Machine-readable renderings of a review for `codex exec review`.
+
+use std::path::Path;
+
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::cli::ReviewFormat;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_INFORMATION_URI: &str = "https://github.com/openai/codex";
+
+/// Renders `output` in `format`. File paths are made relative to `root` (the
+/// repository root) where possible, since that is what both SARIF consumers
+/// and GitHub annotations expect.
+pub(crate) fn render_review_output(
+    output: &ReviewOutputEvent,
+    format: ReviewFormat,
+    root: &Path,
+) -> String {
+    match format {
+        ReviewFormat::Json => render_json(output),
+        ReviewFormat::Sarif => render_sarif(output, root),
+        ReviewFormat::Github => render_github_annotations(output, root),
+    }
+}
+
+pub(crate) fn render_json(output: &ReviewOutputEvent) -> String {
+    serde_json::to_string_pretty(output).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Returns true when at least one finding is at `threshold` or more urgent.
+/// Priorities run from 0 (most urgent) to 3.
+pub(crate) fn has_findings_at_or_above(output: &ReviewOutputEvent, threshold: i32) -> bool {
+    output
+        .findings
+        .iter()
+        .any(|finding| finding.priority <= threshold)
+}
+
+fn render_sarif(output: &ReviewOutputEvent, root: &Path) -> String {
+    let rules: Vec<Value> = (0..=3)
+        .map(|priority| {
+            json!({
+                "id": rule_id(priority),
+                "name": format!("ReviewFindingP{priority}"),
+                "shortDescription": { "text": format!("P{priority} review finding") },
+                "defaultConfiguration": { "level": sarif_level(priority) },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let (start, end) = line_range(finding);
+            json!({
+                "ruleId": rule_id(finding.priority.clamp(0, 3)),
+                "level": sarif_level(finding.priority),
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": relative_path(&finding.code_location.absolute_file_path, root),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": { "startLine": start, "endLine": end },
+                    }
+                }],
+                "properties": {
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                    "category": finding.category,
+                },
+            })
+        })
+        .collect();
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": TOOL_INFORMATION_URI,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+            "properties": {
+                "overallCorrectness": output.overall_correctness,
+                "overallExplanation": output.overall_explanation,
+                "overallConfidence": output.overall_confidence_score,
+            },
+        }],
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// One GitHub Actions workflow command per finding, e.g.
+/// `::error file=src/lib.rs,line=3,endLine=5,title=[P1] Title::Body`.
+fn render_github_annotations(output: &ReviewOutputEvent, root: &Path) -> String {
+    let mut lines: Vec<String> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let (start, end) = line_range(finding);
+            let command = match finding.priority {
+                p if p <= 1 => "error",
+                2 => "warning",
+                _ => "notice",
+            };
+            let file = relative_path(&finding.code_location.absolute_file_path, root);
+            let title = format!("[P{}] {}", finding.priority, finding.title);
+            format!(
+                "::{command} file={},line={start},endLine={end},title={}::{}",
+                escape_property(&file),
+                escape_property(&title),
+                escape_data(&finding.body)
+            )
+        })
+        .collect();
+    if !output.overall_explanation.is_empty() {
+        lines.push(format!(
+            "::notice title=Codex review::{}",
+            escape_data(&output.overall_explanation)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn rule_id(priority: i32) -> String {
+    format!("codex-review/p{priority}")
+}
+
+fn sarif_level(priority: i32) -> &'static str {
+    match priority {
+        p if p <= 1 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
+
+/// SARIF and GitHub both use 1-based lines, and some models report 0.
+fn line_range(finding: &ReviewFinding) -> (u32, u32) {
+    let range = &finding.code_location.line_range;
+    let start = range.start.max(1);
+    (start, range.end.max(start))
+}
+
+fn relative_path(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
@@ -13,6 +13,12 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TokenCost;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
+use codex_core::protocol::TurnPhase as CoreTurnPhase;
+use codex_core::protocol::TurnPhaseTiming as CoreTurnPhaseTiming;
+use codex_core::protocol::TurnProfileEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_exec::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
@@ -39,6 +45,9 @@ use codex_exec::exec_events::TodoItem as ExecTodoItem;
 use codex_exec::exec_events::TodoListItem as ExecTodoListItem;
 use codex_exec::exec_events::TurnCompletedEvent;
 use codex_exec::exec_events::TurnFailedEvent;
+use codex_exec::exec_events::TurnPhase;
+use codex_exec::exec_events::TurnPhaseTiming;
+use codex_exec::exec_events::TurnProfile;
 use codex_exec::exec_events::TurnStartedEvent;
 use codex_exec::exec_events::Usage;
 use codex_exec::exec_events::WebSearchItem;
@@ -236,6 +245,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
             }),
             ThreadEvent::TurnCompleted(TurnCompletedEvent {
                 usage: Usage::default(),
+                profile: None,
             }),
         ]
     );
@@ -286,6 +296,8 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
                 is_error: None,
                 structured_content: None,
             }),
+            cached: false,
+            throttled: None,
         }),
     );
     let end_events = ep.collect_thread_events(&end);
@@ -335,6 +347,8 @@ fn mcp_tool_call_failure_sets_failed_status() {
             invocation,
             duration: Duration::from_millis(5),
             result: Err("tool exploded".to_string()),
+            cached: false,
+            throttled: None,
         }),
     );
     let events = ep.collect_thread_events(&end);
@@ -407,6 +421,8 @@ fn mcp_tool_call_defaults_arguments_and_preserves_structured_content() {
                 is_error: None,
                 structured_content: Some(json!({ "status": "ok" })),
             }),
+            cached: false,
+            throttled: None,
         }),
     );
     let events = ep.collect_thread_events(&end);
@@ -917,6 +933,11 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            cost: Some(TokenCost {
+                turn_usd: 0.01,
+                session_usd: 0.25,
+            }),
+            prompt_cache: None,
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
@@ -936,7 +957,111 @@ fn task_complete_produces_turn_completed_with_usage() {
                 input_tokens: 1200,
                 cached_input_tokens: 200,
                 output_tokens: 345,
+                cost_usd: Some(0.25),
             },
+            profile: None,
+        })]
+    );
+}
+
+#[test]
+fn turn_profile_is_attached_to_turn_completed() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+
+    let profile_event = event(
+        "e1",
+        EventMsg::TurnProfile(TurnProfileEvent {
+            duration_ms: 4200,
+            phases: vec![
+                CoreTurnPhaseTiming {
+                    phase: CoreTurnPhase::ModelTimeToFirstToken,
+                    count: 2,
+                    total_ms: 900,
+                    max_ms: 600,
+                },
+                CoreTurnPhaseTiming {
+                    phase: CoreTurnPhase::Hook,
+                    count: 1,
+                    total_ms: 1500,
+                    max_ms: 1500,
+                },
+            ],
+            token_usage: codex_core::protocol::TokenUsage::default(),
+            cost_usd: None,
+        }),
+    );
+    assert!(ep.collect_thread_events(&profile_event).is_empty());
+
+    let complete_event = event(
+        "e2",
+        EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    );
+    assert_eq!(
+        ep.collect_thread_events(&complete_event),
+        vec![ThreadEvent::TurnCompleted(TurnCompletedEvent {
+            usage: Usage::default(),
+            profile: Some(TurnProfile {
+                duration_ms: 4200,
+                phases: vec![
+                    TurnPhaseTiming {
+                        phase: TurnPhase::ModelTimeToFirstToken,
+                        count: 2,
+                        total_ms: 900,
+                        max_ms: 600,
+                    },
+                    TurnPhaseTiming {
+                        phase: TurnPhase::Hook,
+                        count: 1,
+                        total_ms: 1500,
+                        max_ms: 1500,
+                    },
+                ],
+            }),
+        })]
+    );
+
+    // The profile belongs to a single turn.
+    ep.collect_thread_events(&event(
+        "e3",
+        EventMsg::TaskStarted(codex_core::protocol::TaskStartedEvent {
+            model_context_window: None,
+        }),
+    ));
+    assert_eq!(
+        ep.collect_thread_events(&complete_event),
+        vec![ThreadEvent::TurnCompleted(TurnCompletedEvent {
+            usage: Usage::default(),
+            profile: None,
+        })]
+    );
+}
+
+#[test]
+fn budget_abort_produces_turn_failed() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let message =
+        "Session cost budget exceeded: spent $1.02 (max_session_cost = $1.00).".to_string();
+
+    let error_event = event(
+        "e1",
+        EventMsg::Error(ErrorEvent {
+            message: message.clone(),
+        }),
+    );
+    ep.collect_thread_events(&error_event);
+
+    let aborted_event = event(
+        "e2",
+        EventMsg::TurnAborted(TurnAbortedEvent {
+            reason: TurnAbortReason::BudgetExceeded,
+        }),
+    );
+    assert_eq!(
+        ep.collect_thread_events(&aborted_event),
+        vec![ThreadEvent::TurnFailed(TurnFailedEvent {
+            error: ThreadErrorEvent { message },
         })]
     );
 }
@@ -6,6 +6,7 @@ mod hooks;
 mod originator;
 mod output_schema;
 mod resume;
+mod review;
 mod sandbox;
 mod server_error_exit;
 mod slash_commands;
@@ -0,0 +1,131 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use serde_json::Value;
+use serde_json::json;
+
+fn review_output(file: &std::path::Path, priority: i32) -> String {
+    json!({
+        "findings": [{
+            "title": "Off-by-one in loop",
+            "body": "The loop skips the last element.\nUse `..=` instead.",
+            "confidence_score": 0.8,
+            "priority": priority,
+            "code_location": {
+                "absolute_file_path": file,
+                "line_range": {"start": 3, "end": 5}
+            }
+        }],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "One bug found.",
+        "overall_confidence_score": 0.7
+    })
+    .to_string()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_review_prints_github_annotations_and_fails_on_priority() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let file = test.cwd_path().canonicalize()?.join("src").join("lib.rs");
+
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_response_created("resp1"),
+            responses::ev_assistant_message("m1", &review_output(&file, 1)),
+            responses::ev_completed("resp1"),
+        ]),
+    )
+    .await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("review")
+        .arg("--uncommitted")
+        .arg("--format")
+        .arg("github")
+        .arg("--fail-on-priority")
+        .arg("1")
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        vec![
+            "::error file=src/lib.rs,line=3,endLine=5,title=[P1] Off-by-one in loop::The loop skips the last element.%0AUse `..=` instead.",
+            "::notice title=Codex review::One bug found.",
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_review_writes_sarif_and_json_findings() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let file = test.cwd_path().canonicalize()?.join("src").join("lib.rs");
+    let findings_path = test.cwd_path().join("findings.json");
+
+    let server = responses::start_mock_server().await;
+    let response_mock = responses::mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_response_created("resp1"),
+            responses::ev_assistant_message("m1", &review_output(&file, 2)),
+            responses::ev_completed("resp1"),
+        ]),
+    )
+    .await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("review")
+        .arg("--commit")
+        .arg("abc1234")
+        .arg("--format")
+        .arg("sarif")
+        .arg("--findings-file")
+        .arg(&findings_path)
+        .arg("--fail-on-priority")
+        .arg("1")
+        .output()?;
+
+    assert!(output.status.success(), "{output:?}");
+
+    let request = response_mock.single_request();
+    assert!(
+        request
+            .body_json()
+            .to_string()
+            .contains("Review the code changes introduced by commit abc1234"),
+        "review prompt missing from request"
+    );
+
+    let sarif: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(sarif["version"], "2.1.0");
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "codex-review/p2");
+    assert_eq!(result["level"], "warning");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/lib.rs"
+    );
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["region"],
+        json!({"startLine": 3, "endLine": 5})
+    );
+
+    let findings: Value = serde_json::from_str(&std::fs::read_to_string(&findings_path)?)?;
+    assert_eq!(findings["findings"][0]["priority"], 2);
+    assert_eq!(findings["overall_explanation"], "One bug found.");
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use codex_core::CodexConversation;
+use codex_core::StandaloneToolHost;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use tracing::error;
+
+/// Where the decision from an approval elicitation is delivered: a Codex
+/// conversation (the `codex` tools) or the standalone tool host
+/// (`--tools-only`).
+#[derive(Clone)]
+pub(crate) enum ApprovalTarget {
+    Conversation(Arc<CodexConversation>),
+    ToolHost(Arc<StandaloneToolHost>),
+}
+
+impl ApprovalTarget {
+    pub(crate) async fn exec_decision(&self, event_id: String, decision: ReviewDecision) {
+        match self {
+            ApprovalTarget::Conversation(codex) => {
+                if let Err(err) = codex
+                    .submit(Op::ExecApproval {
+                        id: event_id,
+                        decision,
+                    })
+                    .await
+                {
+                    error!("failed to submit ExecApproval: {err}");
+                }
+            }
+            ApprovalTarget::ToolHost(host) => host.notify_approval(&event_id, decision).await,
+        }
+    }
+
+    pub(crate) async fn patch_decision(&self, event_id: String, decision: ReviewDecision) {
+        match self {
+            ApprovalTarget::Conversation(codex) => {
+                if let Err(err) = codex
+                    .submit(Op::PatchApproval {
+                        id: event_id,
+                        decision,
+                    })
+                    .await
+                {
+                    error!("failed to submit PatchApproval: {err}");
+                }
+            }
+            ApprovalTarget::ToolHost(host) => host.notify_approval(&event_id, decision).await,
+        }
+    }
+}
+
+impl From<Arc<CodexConversation>> for ApprovalTarget {
+    fn from(codex: Arc<CodexConversation>) -> Self {
+        ApprovalTarget::Conversation(codex)
+    }
+}
@@ -57,6 +57,11 @@ pub struct CodexToolCallParam {
     /// Prompt used when compacting the conversation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub compact_prompt: Option<String>,
+
+    /// JSON schema the final assistant message of the initial turn must
+    /// follow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
 }
 
 /// Custom enum mirroring [`AskForApproval`], but has an extra dependency on
@@ -134,12 +139,17 @@ pub(crate) fn create_tool_for_codex_tool_call_param() -> Tool {
 }
 
 impl CodexToolCallParam {
-    /// Returns the initial user prompt to start the Codex conversation and the
-    /// effective Config object generated from the supplied parameters.
+    /// Returns the initial user prompt to start the Codex conversation, the
+    /// output schema for its first turn, and the effective Config object
+    /// generated from the supplied parameters.
     pub async fn into_config(
         self,
         codex_linux_sandbox_exe: Option<PathBuf>,
-    ) -> std::io::Result<(String, codex_core::config::Config)> {
+    ) -> std::io::Result<(
+        String,
+        Option<serde_json::Value>,
+        codex_core::config::Config,
+    )> {
         let Self {
             prompt,
             model,
@@ -151,6 +161,7 @@ impl CodexToolCallParam {
             base_instructions,
             developer_instructions,
             compact_prompt,
+            output_schema,
         } = self;
 
         // Build the `ConfigOverrides` recognized by codex-core.
@@ -182,7 +193,7 @@ impl CodexToolCallParam {
         let cfg =
             codex_core::config::Config::load_with_cli_overrides(cli_overrides, overrides).await?;
 
-        Ok((prompt, cfg))
+        Ok((prompt, output_schema, cfg))
     }
 }
 
@@ -307,6 +318,9 @@ mod tests {
                 "description": "Prompt used when compacting the conversation.",
                 "type": "string"
               },
+              "output-schema": {
+                "description": "JSON schema the final assistant message of the initial turn must follow."
+              },
             },
             "required": [
               "prompt"
@@ -9,6 +9,8 @@ use crate::exec_approval::handle_exec_approval_request;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotificationMeta;
 use crate::patch_approval::handle_patch_approval_request;
+use crate::session_registry::ReserveError;
+use crate::session_registry::SessionRegistry;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
@@ -21,10 +23,12 @@ use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::TurnAbortedEvent;
 use codex_protocol::ConversationId;
 use codex_protocol::user_input::UserInput;
 use mcp_types::CallToolResult;
 use mcp_types::ContentBlock;
+use mcp_types::ProgressToken;
 use mcp_types::RequestId;
 use mcp_types::TextContent;
 use serde_json::json;
@@ -39,11 +43,15 @@ pub(crate) const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
 pub async fn run_codex_tool_session(
     id: RequestId,
     initial_prompt: String,
+    output_schema: Option<serde_json::Value>,
     config: CodexConfig,
     outgoing: Arc<OutgoingMessageSender>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_registry: Arc<SessionRegistry>,
+    progress_token: Option<ProgressToken>,
 ) {
+    let approval_policy = config.approval_policy;
     let NewConversation {
         conversation_id,
         conversation,
@@ -88,12 +96,19 @@ pub async fn run_codex_tool_session(
         .lock()
         .await
         .insert(id.clone(), conversation_id);
+    session_registry
+        .register(&session_configured, Some(approval_policy))
+        .await;
+    session_registry
+        .set_running_request(conversation_id, Some(id.clone()))
+        .await;
     let submission = Submission {
         id: sub_id.clone(),
         op: Op::UserInput {
             items: vec![UserInput::Text {
                 text: initial_prompt.clone(),
             }],
+            final_output_json_schema: output_schema,
         },
     };
 
@@ -101,14 +116,20 @@ pub async fn run_codex_tool_session(
         tracing::error!("Failed to submit initial prompt: {e}");
         // unregister the id so we don't keep it in the map
         running_requests_id_to_codex_uuid.lock().await.remove(&id);
+        session_registry
+            .set_running_request(conversation_id, None)
+            .await;
         return;
     }
 
     run_codex_tool_session_inner(
         conversation,
+        conversation_id,
         outgoing,
         id,
         running_requests_id_to_codex_uuid,
+        session_registry,
+        progress_token,
     )
     .await;
 }
@@ -120,7 +141,35 @@ pub async fn run_codex_tool_session_reply(
     prompt: String,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
     conversation_id: ConversationId,
+    session_registry: Arc<SessionRegistry>,
+    progress_token: Option<ProgressToken>,
 ) {
+    // Claim the session before submitting so a concurrent `codex-reply` or
+    // `codex-undo` cannot interleave with this turn.
+    if let Err(err) = session_registry
+        .try_reserve(conversation_id, request_id.clone())
+        .await
+    {
+        let text = match err {
+            ReserveError::UnknownSession => {
+                format!("Session not found for conversation_id: {conversation_id}")
+            }
+            ReserveError::Busy(running_request_id) => format!(
+                "Session {conversation_id} is busy with request {running_request_id:?}; wait for it to finish or interrupt it first."
+            ),
+        };
+        let result = CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                r#type: "text".to_string(),
+                text,
+                annotations: None,
+            })],
+            is_error: Some(true),
+            structured_content: None,
+        };
+        outgoing.send_response(request_id, result).await;
+        return;
+    }
     running_requests_id_to_codex_uuid
         .lock()
         .await
@@ -128,6 +177,7 @@ pub async fn run_codex_tool_session_reply(
     if let Err(e) = conversation
         .submit(Op::UserInput {
             items: vec![UserInput::Text { text: prompt }],
+            final_output_json_schema: None,
         })
         .await
     {
@@ -137,28 +187,38 @@ pub async fn run_codex_tool_session_reply(
             .lock()
             .await
             .remove(&request_id);
+        session_registry
+            .set_running_request(conversation_id, None)
+            .await;
         return;
     }
 
     run_codex_tool_session_inner(
         conversation,
+        conversation_id,
         outgoing,
         request_id,
         running_requests_id_to_codex_uuid,
+        session_registry,
+        progress_token,
     )
     .await;
 }
 
 async fn run_codex_tool_session_inner(
     codex: Arc<CodexConversation>,
+    conversation_id: ConversationId,
     outgoing: Arc<OutgoingMessageSender>,
     request_id: RequestId,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_registry: Arc<SessionRegistry>,
+    progress_token: Option<ProgressToken>,
 ) {
     let request_id_str = match &request_id {
         RequestId::String(s) => s.clone(),
         RequestId::Integer(n) => n.to_string(),
     };
+    let mut progress = 0.0;
 
     // Stream events until the task needs to pause for user interaction or
     // completes.
@@ -171,6 +231,15 @@ async fn run_codex_tool_session_inner(
                         Some(OutgoingNotificationMeta::new(Some(request_id.clone()))),
                     )
                     .await;
+                session_registry.record_event(conversation_id, &event).await;
+                if let Some(progress_token) = &progress_token
+                    && let Some(message) = progress_message(&event.msg)
+                {
+                    progress += 1.0;
+                    outgoing
+                        .send_progress_notification(progress_token.clone(), progress, Some(message))
+                        .await;
+                }
 
                 match event.msg {
                     EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
@@ -185,7 +254,7 @@ async fn run_codex_tool_session_inner(
                             command,
                             cwd,
                             outgoing.clone(),
-                            codex.clone(),
+                            codex.clone().into(),
                             request_id.clone(),
                             request_id_str.clone(),
                             event.id.clone(),
@@ -202,6 +271,9 @@ async fn run_codex_tool_session_inner(
                             "error": err_event.message,
                         });
                         outgoing.send_response(request_id.clone(), result).await;
+                        session_registry
+                            .set_running_request(conversation_id, None)
+                            .await;
                         break;
                     }
                     EventMsg::Warning(_) => {
@@ -219,7 +291,7 @@ async fn run_codex_tool_session_inner(
                             grant_root,
                             changes,
                             outgoing.clone(),
-                            codex.clone(),
+                            codex.clone().into(),
                             request_id.clone(),
                             request_id_str.clone(),
                             event.id.clone(),
@@ -247,6 +319,32 @@ async fn run_codex_tool_session_inner(
                             .lock()
                             .await
                             .remove(&request_id);
+                        session_registry
+                            .set_running_request(conversation_id, None)
+                            .await;
+                        break;
+                    }
+                    EventMsg::TurnAborted(TurnAbortedEvent { reason }) => {
+                        // Conclude the tool call so callers that interrupted
+                        // the session (e.g. via `codex-interrupt`) are not
+                        // left waiting for a response.
+                        let result = CallToolResult {
+                            content: vec![ContentBlock::TextContent(TextContent {
+                                r#type: "text".to_string(),
+                                text: format!("Codex turn aborted: {reason:?}"),
+                                annotations: None,
+                            })],
+                            is_error: Some(true),
+                            structured_content: None,
+                        };
+                        outgoing.send_response(request_id.clone(), result).await;
+                        running_requests_id_to_codex_uuid
+                            .lock()
+                            .await
+                            .remove(&request_id);
+                        session_registry
+                            .set_running_request(conversation_id, None)
+                            .await;
                         break;
                     }
                     EventMsg::SessionConfigured(_) => {
@@ -264,6 +362,7 @@ async fn run_codex_tool_session_inner(
                     EventMsg::AgentReasoningRawContent(_)
                     | EventMsg::AgentReasoningRawContentDelta(_)
                     | EventMsg::TaskStarted(_)
+                    | EventMsg::TurnProfile(_)
                     | EventMsg::TokenCount(_)
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
@@ -271,10 +370,13 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::ListSnapshotsResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
                     | EventMsg::BackgroundEvent(_)
+                    | EventMsg::ContextCompacted(_)
+                    | EventMsg::MessagePinned(_)
                     | EventMsg::StreamError(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
@@ -283,7 +385,6 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::WebSearchEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
-                    | EventMsg::TurnAborted(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
@@ -320,8 +421,36 @@ async fn run_codex_tool_session_inner(
                     structured_content: None,
                 };
                 outgoing.send_response(request_id.clone(), result).await;
+                // The event stream only fails once the session is gone.
+                session_registry.remove(conversation_id).await;
                 break;
             }
         }
     }
 }
+
+/// Short, human-readable description of the events worth surfacing as MCP
+/// progress notifications. Streaming deltas and bookkeeping events are skipped.
+pub(crate) fn progress_message(msg: &EventMsg) -> Option<String> {
+    match msg {
+        EventMsg::TaskStarted(_) => Some("Turn started".to_string()),
+        EventMsg::ExecCommandBegin(ev) => Some(format!("Running `{}`", ev.command.join(" "))),
+        EventMsg::ExecCommandEnd(ev) => Some(format!("Command exited with {}", ev.exit_code)),
+        EventMsg::PatchApplyBegin(ev) => {
+            Some(format!("Applying patch to {} file(s)", ev.changes.len()))
+        }
+        EventMsg::PatchApplyEnd(ev) if ev.success => Some("Patch applied".to_string()),
+        EventMsg::PatchApplyEnd(_) => Some("Patch failed to apply".to_string()),
+        EventMsg::McpToolCallBegin(ev) => Some(format!(
+            "Calling MCP tool {}/{}",
+            ev.invocation.server, ev.invocation.tool
+        )),
+        EventMsg::WebSearchBegin(_) => Some("Searching the web".to_string()),
+        EventMsg::PlanUpdate(_) => Some("Plan updated".to_string()),
+        EventMsg::AgentMessage(AgentMessageEvent { message }) => Some(message.clone()),
+        EventMsg::ExecApprovalRequest(_) | EventMsg::ApplyPatchApprovalRequest(_) => {
+            Some("Waiting for approval".to_string())
+        }
+        _ => None,
+    }
+}
@@ -1,8 +1,6 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use codex_core::CodexConversation;
-use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SandboxCommandAssessment;
 use codex_protocol::parse_command::ParsedCommand;
@@ -16,6 +14,7 @@ use serde::Serialize;
 use serde_json::json;
 use tracing::error;
 
+use crate::approval_target::ApprovalTarget;
 use crate::codex_tool_runner::INVALID_PARAMS_ERROR_CODE;
 
 /// Conforms to [`mcp_types::ElicitRequestParams`] so that it can be used as the
@@ -56,7 +55,7 @@ pub(crate) async fn handle_exec_approval_request(
     command: Vec<String>,
     cwd: PathBuf,
     outgoing: Arc<crate::outgoing_message::OutgoingMessageSender>,
-    codex: Arc<CodexConversation>,
+    codex: ApprovalTarget,
     request_id: RequestId,
     tool_call_id: String,
     event_id: String,
@@ -125,7 +124,7 @@ pub(crate) async fn handle_exec_approval_request(
 async fn on_exec_approval_response(
     event_id: String,
     receiver: tokio::sync::oneshot::Receiver<mcp_types::Result>,
-    codex: Arc<CodexConversation>,
+    codex: ApprovalTarget,
 ) {
     let response = receiver.await;
     let value = match response {
@@ -146,13 +145,5 @@ async fn on_exec_approval_response(
         }
     });
 
-    if let Err(err) = codex
-        .submit(Op::ExecApproval {
-            id: event_id,
-            decision: response.decision,
-        })
-        .await
-    {
-        error!("failed to submit ExecApproval: {err}");
-    }
+    codex.exec_decision(event_id, response.decision).await;
 }
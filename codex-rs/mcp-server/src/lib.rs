@@ -6,6 +6,8 @@ use std::io::Result as IoResult;
 use std::path::PathBuf;
 
 use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::StandaloneToolHost;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 
@@ -20,6 +22,7 @@ use tracing::error;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+mod approval_target;
 mod codex_tool_config;
 mod codex_tool_runner;
 mod error_code;
@@ -27,6 +30,10 @@ mod exec_approval;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
+mod session_registry;
+mod session_tool_config;
+mod session_tools;
+mod tools_only;
 
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
@@ -38,15 +45,32 @@ pub use crate::exec_approval::ExecApprovalElicitRequestParams;
 pub use crate::exec_approval::ExecApprovalResponse;
 pub use crate::patch_approval::PatchApprovalElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalResponse;
+pub use crate::session_tool_config::CodexEventsParam;
+pub use crate::session_tool_config::CodexListSessionsParam;
+pub use crate::session_tool_config::CodexSessionParam;
+pub use crate::session_tool_config::CodexSetApprovalPolicyParam;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
 /// plenty for an interactive CLI.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// Which set of tools the server exposes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum McpServerMode {
+    /// The `codex` / `codex-reply` agent tools and the session-management
+    /// tools built on top of them.
+    #[default]
+    Codex,
+    /// Codex's built-in tools (`shell`, `apply_patch`, `read_file`,
+    /// `grep_files`, `list_dir`), served directly without a model.
+    ToolsOnly,
+}
+
 pub async fn run_main(
     codex_linux_sandbox_exe: Option<PathBuf>,
     cli_config_overrides: CliConfigOverrides,
+    mode: McpServerMode,
 ) -> IoResult<()> {
     // Install a simple subscriber so `tracing` output is visible.  Users can
     // control the log level with `RUST_LOG`.
@@ -90,12 +114,30 @@ pub async fn run_main(
             format!("error parsing -c overrides: {e}"),
         )
     })?;
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, ConfigOverrides::default())
+    let config_overrides = ConfigOverrides {
+        codex_linux_sandbox_exe: codex_linux_sandbox_exe.clone(),
+        ..Default::default()
+    };
+    let config = Config::load_with_cli_overrides(cli_kv_overrides, config_overrides)
         .await
         .map_err(|e| {
             std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
         })?;
 
+    let standalone_tool_host = match mode {
+        McpServerMode::Codex => None,
+        McpServerMode::ToolsOnly => {
+            let auth_manager = AuthManager::shared(
+                config.codex_home.clone(),
+                false,
+                config.cli_auth_credentials_store_mode,
+            );
+            Some(std::sync::Arc::new(
+                StandaloneToolHost::new(config.clone(), auth_manager).await,
+            ))
+        }
+    };
+
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
         let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
@@ -103,6 +145,7 @@ pub async fn run_main(
             outgoing_message_sender,
             codex_linux_sandbox_exe,
             std::sync::Arc::new(config),
+            standalone_tool_host,
         );
         async move {
             while let Some(msg) = incoming_rx.recv().await {
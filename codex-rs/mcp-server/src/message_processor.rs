@@ -7,11 +7,22 @@ use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::session_registry::SessionRegistry;
+use crate::session_tool_config::EVENTS_TOOL_NAME;
+use crate::session_tool_config::INTERRUPT_TOOL_NAME;
+use crate::session_tool_config::LIST_SESSIONS_TOOL_NAME;
+use crate::session_tool_config::SET_APPROVAL_POLICY_TOOL_NAME;
+use crate::session_tool_config::TURN_DIFF_TOOL_NAME;
+use crate::session_tool_config::UNDO_TOOL_NAME;
+use crate::session_tool_config::create_session_tools;
+use crate::session_tools;
+use crate::tools_only::ToolsOnlyServer;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::SessionSource;
 
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
+use codex_core::StandaloneToolHost;
 use codex_core::config::Config;
 use codex_core::default_client::USER_AGENT_SUFFIX;
 use codex_core::default_client::get_codex_user_agent;
@@ -27,6 +38,7 @@ use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ProgressToken;
 use mcp_types::RequestId;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
@@ -42,6 +54,9 @@ pub(crate) struct MessageProcessor {
     codex_linux_sandbox_exe: Option<PathBuf>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_registry: Arc<SessionRegistry>,
+    /// Set when running with `--tools-only`; replaces the `codex` tools.
+    tools_only: Option<ToolsOnlyServer>,
 }
 
 impl MessageProcessor {
@@ -51,8 +66,11 @@ impl MessageProcessor {
         outgoing: OutgoingMessageSender,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
+        standalone_tool_host: Option<Arc<StandaloneToolHost>>,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
+        let tools_only =
+            standalone_tool_host.map(|host| ToolsOnlyServer::new(host, outgoing.clone()));
         let auth_manager = AuthManager::shared(
             config.codex_home.clone(),
             false,
@@ -66,12 +84,15 @@ impl MessageProcessor {
             codex_linux_sandbox_exe,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            session_registry: Arc::new(SessionRegistry::new()),
+            tools_only,
         }
     }
 
     pub(crate) async fn process_request(&mut self, request: JSONRPCRequest) {
         // Hold on to the ID so we can respond.
         let request_id = request.id.clone();
+        let progress_token = progress_token_from_params(request.params.as_ref());
 
         let client_request = match McpClientRequest::try_from(request) {
             Ok(client_request) => client_request,
@@ -114,7 +135,8 @@ impl MessageProcessor {
                 self.handle_list_tools(request_id, params).await;
             }
             McpClientRequest::CallToolRequest(params) => {
-                self.handle_call_tool(request_id, params).await;
+                self.handle_call_tool(request_id, params, progress_token)
+                    .await;
             }
             McpClientRequest::SetLevelRequest(params) => {
                 self.handle_set_level(params);
@@ -302,11 +324,23 @@ impl MessageProcessor {
         params: <mcp_types::ListToolsRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::trace!("tools/list -> {params:?}");
+        if let Some(tools_only) = &self.tools_only {
+            let result = ListToolsResult {
+                tools: tools_only.tools(),
+                next_cursor: None,
+            };
+            self.send_response::<mcp_types::ListToolsRequest>(id, result)
+                .await;
+            return;
+        }
         let result = ListToolsResult {
-            tools: vec![
+            tools: [
                 create_tool_for_codex_tool_call_param(),
                 create_tool_for_codex_tool_call_reply_param(),
-            ],
+            ]
+            .into_iter()
+            .chain(create_session_tools())
+            .collect(),
             next_cursor: None,
         };
 
@@ -318,16 +352,31 @@ impl MessageProcessor {
         &self,
         id: RequestId,
         params: <mcp_types::CallToolRequest as mcp_types::ModelContextProtocolRequest>::Params,
+        progress_token: Option<ProgressToken>,
     ) {
         tracing::info!("tools/call -> params: {:?}", params);
         let CallToolRequestParams { name, arguments } = params;
 
+        if let Some(tools_only) = &self.tools_only {
+            tools_only.handle_call_tool(id, name, arguments);
+            return;
+        }
+
         match name.as_str() {
-            "codex" => self.handle_tool_call_codex(id, arguments).await,
+            "codex" => {
+                self.handle_tool_call_codex(id, arguments, progress_token)
+                    .await
+            }
             "codex-reply" => {
-                self.handle_tool_call_codex_session_reply(id, arguments)
+                self.handle_tool_call_codex_session_reply(id, arguments, progress_token)
                     .await
             }
+            LIST_SESSIONS_TOOL_NAME
+            | INTERRUPT_TOOL_NAME
+            | EVENTS_TOOL_NAME
+            | TURN_DIFF_TOOL_NAME
+            | SET_APPROVAL_POLICY_TOOL_NAME
+            | UNDO_TOOL_NAME => self.handle_session_tool_call(id, name, arguments),
             _ => {
                 let result = CallToolResult {
                     content: vec![ContentBlock::TextContent(TextContent {
@@ -343,21 +392,103 @@ impl MessageProcessor {
             }
         }
     }
-    async fn handle_tool_call_codex(&self, id: RequestId, arguments: Option<serde_json::Value>) {
-        let (initial_prompt, config): (String, Config) = match arguments {
-            Some(json_val) => match serde_json::from_value::<CodexToolCallParam>(json_val) {
-                Ok(tool_cfg) => match tool_cfg
-                    .into_config(self.codex_linux_sandbox_exe.clone())
-                    .await
-                {
-                    Ok(cfg) => cfg,
+
+    /// Dispatches the session-management tools. Each call runs on its own task
+    /// because `codex-undo` waits on the conversation's event stream.
+    fn handle_session_tool_call(
+        &self,
+        id: RequestId,
+        name: String,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let outgoing = self.outgoing.clone();
+        let conversation_manager = self.conversation_manager.clone();
+        let session_registry = self.session_registry.clone();
+        task::spawn(async move {
+            let result = match name.as_str() {
+                LIST_SESSIONS_TOOL_NAME => session_tools::list_sessions(&session_registry).await,
+                INTERRUPT_TOOL_NAME => match session_tools::parse_arguments(&name, arguments) {
+                    Ok(params) => {
+                        session_tools::interrupt(&conversation_manager, &session_registry, params)
+                            .await
+                    }
+                    Err(result) => result,
+                },
+                EVENTS_TOOL_NAME => match session_tools::parse_arguments(&name, arguments) {
+                    Ok(params) => session_tools::events(&session_registry, params).await,
+                    Err(result) => result,
+                },
+                TURN_DIFF_TOOL_NAME => match session_tools::parse_arguments(&name, arguments) {
+                    Ok(params) => session_tools::turn_diff(&session_registry, params).await,
+                    Err(result) => result,
+                },
+                SET_APPROVAL_POLICY_TOOL_NAME => {
+                    match session_tools::parse_arguments(&name, arguments) {
+                        Ok(params) => {
+                            session_tools::set_approval_policy(
+                                &conversation_manager,
+                                &session_registry,
+                                params,
+                            )
+                            .await
+                        }
+                        Err(result) => result,
+                    }
+                }
+                UNDO_TOOL_NAME => match session_tools::parse_arguments(&name, arguments) {
+                    Ok(params) => {
+                        session_tools::undo(
+                            &conversation_manager,
+                            &session_registry,
+                            id.clone(),
+                            params,
+                        )
+                        .await
+                    }
+                    Err(result) => result,
+                },
+                _ => session_tools::error_result(format!("Unknown tool '{name}'")),
+            };
+            outgoing.send_response(id, result).await;
+        });
+    }
+
+    async fn handle_tool_call_codex(
+        &self,
+        id: RequestId,
+        arguments: Option<serde_json::Value>,
+        progress_token: Option<ProgressToken>,
+    ) {
+        let (initial_prompt, output_schema, config): (String, Option<serde_json::Value>, Config) =
+            match arguments {
+                Some(json_val) => match serde_json::from_value::<CodexToolCallParam>(json_val) {
+                    Ok(tool_cfg) => match tool_cfg
+                        .into_config(self.codex_linux_sandbox_exe.clone())
+                        .await
+                    {
+                        Ok(cfg) => cfg,
+                        Err(e) => {
+                            let result = CallToolResult {
+                                content: vec![ContentBlock::TextContent(TextContent {
+                                    r#type: "text".to_owned(),
+                                    text: format!(
+                                        "Failed to load Codex configuration from overrides: {e}"
+                                    ),
+                                    annotations: None,
+                                })],
+                                is_error: Some(true),
+                                structured_content: None,
+                            };
+                            self.send_response::<mcp_types::CallToolRequest>(id, result)
+                                .await;
+                            return;
+                        }
+                    },
                     Err(e) => {
                         let result = CallToolResult {
                             content: vec![ContentBlock::TextContent(TextContent {
                                 r#type: "text".to_owned(),
-                                text: format!(
-                                    "Failed to load Codex configuration from overrides: {e}"
-                                ),
+                                text: format!("Failed to parse configuration for Codex tool: {e}"),
                                 annotations: None,
                             })],
                             is_error: Some(true),
@@ -368,13 +499,15 @@ impl MessageProcessor {
                         return;
                     }
                 },
-                Err(e) => {
+                None => {
                     let result = CallToolResult {
                         content: vec![ContentBlock::TextContent(TextContent {
-                            r#type: "text".to_owned(),
-                            text: format!("Failed to parse configuration for Codex tool: {e}"),
-                            annotations: None,
-                        })],
+                        r#type: "text".to_string(),
+                        text:
+                            "Missing arguments for codex tool-call; the `prompt` field is required."
+                                .to_string(),
+                        annotations: None,
+                    })],
                         is_error: Some(true),
                         structured_content: None,
                     };
@@ -382,29 +515,13 @@ impl MessageProcessor {
                         .await;
                     return;
                 }
-            },
-            None => {
-                let result = CallToolResult {
-                    content: vec![ContentBlock::TextContent(TextContent {
-                        r#type: "text".to_string(),
-                        text:
-                            "Missing arguments for codex tool-call; the `prompt` field is required."
-                                .to_string(),
-                        annotations: None,
-                    })],
-                    is_error: Some(true),
-                    structured_content: None,
-                };
-                self.send_response::<mcp_types::CallToolRequest>(id, result)
-                    .await;
-                return;
-            }
-        };
+            };
 
         // Clone outgoing and server to move into async task.
         let outgoing = self.outgoing.clone();
         let conversation_manager = self.conversation_manager.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let session_registry = self.session_registry.clone();
 
         // Spawn an async task to handle the Codex session so that we do not
         // block the synchronous message-processing loop.
@@ -413,10 +530,13 @@ impl MessageProcessor {
             crate::codex_tool_runner::run_codex_tool_session(
                 id,
                 initial_prompt,
+                output_schema,
                 config,
                 outgoing,
                 conversation_manager,
                 running_requests_id_to_codex_uuid,
+                session_registry,
+                progress_token,
             )
             .await;
         });
@@ -426,6 +546,7 @@ impl MessageProcessor {
         &self,
         request_id: RequestId,
         arguments: Option<serde_json::Value>,
+        progress_token: Option<ProgressToken>,
     ) {
         tracing::info!("tools/call -> params: {:?}", arguments);
 
@@ -492,6 +613,7 @@ impl MessageProcessor {
         // Clone outgoing to move into async task.
         let outgoing = self.outgoing.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let session_registry = self.session_registry.clone();
 
         let codex = match self
             .conversation_manager
@@ -529,6 +651,8 @@ impl MessageProcessor {
                     prompt,
                     running_requests_id_to_codex_uuid,
                     conversation_id,
+                    session_registry,
+                    progress_token,
                 )
                 .await;
             }
@@ -653,3 +777,38 @@ impl MessageProcessor {
         tracing::info!("notifications/message -> params: {:?}", params);
     }
 }
+
+/// Extracts `_meta.progressToken` from raw request params. `mcp_types` does not
+/// model `_meta`, so this has to happen before the request is converted.
+fn progress_token_from_params(params: Option<&serde_json::Value>) -> Option<ProgressToken> {
+    let token = params?.get("_meta")?.get("progressToken")?;
+    serde_json::from_value(token.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extracts_progress_token_from_meta() {
+        let params = json!({
+            "name": "codex",
+            "arguments": { "prompt": "hi" },
+            "_meta": { "progressToken": "abc" },
+        });
+        assert_eq!(
+            progress_token_from_params(Some(&params)),
+            Some(ProgressToken::String("abc".to_string()))
+        );
+
+        let params = json!({ "_meta": { "progressToken": 7 } });
+        assert_eq!(
+            progress_token_from_params(Some(&params)),
+            Some(ProgressToken::Integer(7))
+        );
+
+        assert_eq!(progress_token_from_params(Some(&json!({}))), None);
+        assert_eq!(progress_token_from_params(None), None);
+    }
+}
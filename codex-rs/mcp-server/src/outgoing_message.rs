@@ -10,6 +10,10 @@ use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ProgressNotification;
+use mcp_types::ProgressNotificationParams;
+use mcp_types::ProgressToken;
 use mcp_types::RequestId;
 use mcp_types::Result;
 use serde::Serialize;
@@ -124,6 +128,32 @@ impl OutgoingMessageSender {
         .await;
     }
 
+    /// Sends a `notifications/progress` message for a request that supplied a
+    /// `progressToken` in its `_meta`.
+    pub(crate) async fn send_progress_notification(
+        &self,
+        progress_token: ProgressToken,
+        progress: f64,
+        message: Option<String>,
+    ) {
+        let params = ProgressNotificationParams {
+            message,
+            progress,
+            progress_token,
+            total: None,
+        };
+        match serde_json::to_value(params) {
+            Ok(params) => {
+                self.send_notification(OutgoingNotification {
+                    method: ProgressNotification::METHOD.to_string(),
+                    params: Some(params),
+                })
+                .await;
+            }
+            Err(err) => warn!("Failed to serialize progress notification: {err}"),
+        }
+    }
+
     pub(crate) async fn send_notification(&self, notification: OutgoingNotification) {
         let outgoing_message = OutgoingMessage::Notification(notification);
         let _ = self.sender.send(outgoing_message);
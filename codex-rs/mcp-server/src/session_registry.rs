@@ -0,0 +1,353 @@
+//! Book-keeping for the Codex sessions hosted by this MCP server.
+//!
+//! The `codex` / `codex-reply` runners are the only consumers of each
+//! conversation's event stream, so they record what they observe here. The
+//! session-management tools (`codex-list-sessions`, `codex-events`, ...) then
+//! answer from this registry without competing for events.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::SessionConfiguredEvent;
+use codex_protocol::ConversationId;
+use mcp_types::RequestId;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+/// Maximum number of events retained per session for `codex-events`.
+pub(crate) const MAX_RECORDED_EVENTS: usize = 256;
+
+/// Summary of a session returned by `codex-list-sessions`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionSummary {
+    pub conversation_id: ConversationId,
+    pub model: String,
+    pub rollout_path: PathBuf,
+    /// `tools/call` request currently driving the session, if any.
+    pub running_request_id: Option<RequestId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<AskForApproval>,
+    pub event_count: usize,
+}
+
+#[derive(Debug)]
+struct SessionState {
+    model: String,
+    rollout_path: PathBuf,
+    running_request_id: Option<RequestId>,
+    approval_policy: Option<AskForApproval>,
+    recent_events: VecDeque<Event>,
+    total_events: usize,
+    last_turn_diff: Option<String>,
+}
+
+/// Why [`SessionRegistry::try_reserve`] refused to reserve a session.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ReserveError {
+    /// The session was never registered or has shut down.
+    UnknownSession,
+    /// Another request is already driving the session.
+    Busy(RequestId),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SessionRegistry {
+    sessions: Mutex<HashMap<ConversationId, SessionState>>,
+}
+
+impl SessionRegistry {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) async fn register(
+        &self,
+        session_configured: &SessionConfiguredEvent,
+        approval_policy: Option<AskForApproval>,
+    ) {
+        let state = SessionState {
+            model: session_configured.model.clone(),
+            rollout_path: session_configured.rollout_path.clone(),
+            running_request_id: None,
+            approval_policy,
+            recent_events: VecDeque::new(),
+            total_events: 0,
+            last_turn_diff: None,
+        };
+        self.sessions
+            .lock()
+            .await
+            .insert(session_configured.session_id, state);
+    }
+
+    pub(crate) async fn set_running_request(
+        &self,
+        conversation_id: ConversationId,
+        request_id: Option<RequestId>,
+    ) {
+        if let Some(state) = self.sessions.lock().await.get_mut(&conversation_id) {
+            state.running_request_id = request_id;
+        }
+    }
+
+    /// Forgets a session once its event stream has ended.
+    pub(crate) async fn remove(&self, conversation_id: ConversationId) {
+        self.sessions.lock().await.remove(&conversation_id);
+    }
+
+    /// Marks `request_id` as driving the session unless another request
+    /// already does. The check and the claim happen under one lock, so two
+    /// callers cannot both start work on the same session.
+    pub(crate) async fn try_reserve(
+        &self,
+        conversation_id: ConversationId,
+        request_id: RequestId,
+    ) -> Result<(), ReserveError> {
+        let mut sessions = self.sessions.lock().await;
+        let Some(state) = sessions.get_mut(&conversation_id) else {
+            return Err(ReserveError::UnknownSession);
+        };
+        match &state.running_request_id {
+            Some(running) => Err(ReserveError::Busy(running.clone())),
+            None => {
+                state.running_request_id = Some(request_id);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) async fn running_request(
+        &self,
+        conversation_id: ConversationId,
+    ) -> Option<RequestId> {
+        self.sessions
+            .lock()
+            .await
+            .get(&conversation_id)
+            .and_then(|state| state.running_request_id.clone())
+    }
+
+    pub(crate) async fn set_approval_policy(
+        &self,
+        conversation_id: ConversationId,
+        approval_policy: AskForApproval,
+    ) {
+        if let Some(state) = self.sessions.lock().await.get_mut(&conversation_id) {
+            state.approval_policy = Some(approval_policy);
+        }
+    }
+
+    /// Record an event observed on the conversation's event stream. The
+    /// session is forgotten once it reports `ShutdownComplete`.
+    pub(crate) async fn record_event(&self, conversation_id: ConversationId, event: &Event) {
+        let mut sessions = self.sessions.lock().await;
+        if matches!(event.msg, EventMsg::ShutdownComplete) {
+            sessions.remove(&conversation_id);
+            return;
+        }
+        let Some(state) = sessions.get_mut(&conversation_id) else {
+            return;
+        };
+        match &event.msg {
+            // Deltas are noisy and already folded into their final events.
+            EventMsg::AgentMessageDelta(_)
+            | EventMsg::AgentReasoningDelta(_)
+            | EventMsg::AgentReasoningRawContentDelta(_)
+            | EventMsg::AgentMessageContentDelta(_)
+            | EventMsg::ReasoningContentDelta(_)
+            | EventMsg::ReasoningRawContentDelta(_)
+            | EventMsg::ExecCommandOutputDelta(_) => return,
+            EventMsg::TurnDiff(diff) => {
+                state.last_turn_diff = Some(diff.unified_diff.clone());
+            }
+            _ => {}
+        }
+        if state.recent_events.len() == MAX_RECORDED_EVENTS {
+            state.recent_events.pop_front();
+        }
+        state.recent_events.push_back(event.clone());
+        state.total_events += 1;
+    }
+
+    pub(crate) async fn list(&self) -> Vec<SessionSummary> {
+        let sessions = self.sessions.lock().await;
+        let mut summaries: Vec<SessionSummary> = sessions
+            .iter()
+            .map(|(conversation_id, state)| SessionSummary {
+                conversation_id: *conversation_id,
+                model: state.model.clone(),
+                rollout_path: state.rollout_path.clone(),
+                running_request_id: state.running_request_id.clone(),
+                approval_policy: state.approval_policy,
+                event_count: state.total_events,
+            })
+            .collect();
+        summaries.sort_by_key(|summary| summary.conversation_id.to_string());
+        summaries
+    }
+
+    /// Returns the last `limit` recorded events, oldest first, or `None` when
+    /// the session is unknown.
+    pub(crate) async fn recent_events(
+        &self,
+        conversation_id: ConversationId,
+        limit: usize,
+    ) -> Option<Vec<Event>> {
+        let sessions = self.sessions.lock().await;
+        let state = sessions.get(&conversation_id)?;
+        let skip = state.recent_events.len().saturating_sub(limit);
+        Some(state.recent_events.iter().skip(skip).cloned().collect())
+    }
+
+    /// Returns the most recent turn diff, `Some(None)` when the session has
+    /// not produced one yet, or `None` when the session is unknown.
+    pub(crate) async fn turn_diff(
+        &self,
+        conversation_id: ConversationId,
+    ) -> Option<Option<String>> {
+        let sessions = self.sessions.lock().await;
+        sessions
+            .get(&conversation_id)
+            .map(|state| state.last_turn_diff.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::BackgroundEventEvent;
+    use codex_core::protocol::TurnDiffEvent;
+    use pretty_assertions::assert_eq;
+
+    fn session_configured(conversation_id: ConversationId) -> SessionConfiguredEvent {
+        SessionConfiguredEvent {
+            session_id: conversation_id,
+            model: "gpt-5-codex".to_string(),
+            reasoning_effort: None,
+            history_log_id: 0,
+            history_entry_count: 0,
+            initial_messages: None,
+            rollout_path: PathBuf::from("/tmp/rollout.jsonl"),
+        }
+    }
+
+    fn background(message: &str) -> Event {
+        Event {
+            id: "1".to_string(),
+            msg: EventMsg::BackgroundEvent(BackgroundEventEvent {
+                message: message.to_string(),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_bounded_event_window_and_last_diff() {
+        let registry = SessionRegistry::new();
+        let conversation_id = ConversationId::new();
+        registry
+            .register(&session_configured(conversation_id), None)
+            .await;
+
+        for i in 0..(MAX_RECORDED_EVENTS + 10) {
+            registry
+                .record_event(conversation_id, &background(&format!("event {i}")))
+                .await;
+        }
+        registry
+            .record_event(
+                conversation_id,
+                &Event {
+                    id: "1".to_string(),
+                    msg: EventMsg::TurnDiff(TurnDiffEvent {
+                        unified_diff: "diff --git a/x b/x".to_string(),
+                    }),
+                },
+            )
+            .await;
+
+        let events = registry
+            .recent_events(conversation_id, 2)
+            .await
+            .expect("session is registered");
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0].msg,
+            EventMsg::BackgroundEvent(ev) if ev.message == format!("event {}", MAX_RECORDED_EVENTS + 9)
+        ));
+        assert_eq!(
+            registry.turn_diff(conversation_id).await,
+            Some(Some("diff --git a/x b/x".to_string()))
+        );
+
+        let summaries = registry.list().await;
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].event_count, MAX_RECORDED_EVENTS + 11);
+        assert_eq!(registry.turn_diff(ConversationId::new()).await, None);
+    }
+
+    #[tokio::test]
+    async fn only_one_request_can_reserve_a_session() {
+        let registry = SessionRegistry::new();
+        let conversation_id = ConversationId::new();
+        registry
+            .register(&session_configured(conversation_id), None)
+            .await;
+
+        assert_eq!(
+            registry
+                .try_reserve(conversation_id, RequestId::Integer(1))
+                .await,
+            Ok(())
+        );
+        assert_eq!(
+            registry
+                .try_reserve(conversation_id, RequestId::Integer(2))
+                .await,
+            Err(ReserveError::Busy(RequestId::Integer(1)))
+        );
+        registry.set_running_request(conversation_id, None).await;
+        assert_eq!(
+            registry
+                .try_reserve(conversation_id, RequestId::Integer(2))
+                .await,
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn forgets_sessions_that_shut_down() {
+        let registry = SessionRegistry::new();
+        let conversation_id = ConversationId::new();
+        assert_eq!(
+            registry
+                .try_reserve(conversation_id, RequestId::Integer(1))
+                .await,
+            Err(ReserveError::UnknownSession)
+        );
+
+        registry
+            .register(&session_configured(conversation_id), None)
+            .await;
+        registry
+            .record_event(
+                conversation_id,
+                &Event {
+                    id: "1".to_string(),
+                    msg: EventMsg::ShutdownComplete,
+                },
+            )
+            .await;
+        assert_eq!(registry.list().await, Vec::new());
+        assert_eq!(
+            registry
+                .try_reserve(conversation_id, RequestId::Integer(1))
+                .await,
+            Err(ReserveError::UnknownSession)
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Parameters and `Tool` definitions for the session-management tools that
+//! complement `codex` / `codex-reply`.
+
+use mcp_types::Tool;
+use mcp_types::ToolInputSchema;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::codex_tool_config::CodexToolCallApprovalPolicy;
+
+pub(crate) const LIST_SESSIONS_TOOL_NAME: &str = "codex-list-sessions";
+pub(crate) const INTERRUPT_TOOL_NAME: &str = "codex-interrupt";
+pub(crate) const EVENTS_TOOL_NAME: &str = "codex-events";
+pub(crate) const TURN_DIFF_TOOL_NAME: &str = "codex-turn-diff";
+pub(crate) const SET_APPROVAL_POLICY_TOOL_NAME: &str = "codex-set-approval-policy";
+pub(crate) const UNDO_TOOL_NAME: &str = "codex-undo";
+
+/// Number of events returned by `codex-events` when `limit` is omitted.
+pub(crate) const DEFAULT_EVENTS_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexListSessionsParam {}
+
+/// Shared parameter for tools that target a single session.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSessionParam {
+    /// The conversation id for this Codex session.
+    pub conversation_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexEventsParam {
+    /// The conversation id for this Codex session.
+    pub conversation_id: String,
+
+    /// Maximum number of events to return, most recent last. Defaults to 20.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSetApprovalPolicyParam {
+    /// The conversation id for this Codex session.
+    pub conversation_id: String,
+
+    /// Approval policy for shell commands generated by the model:
+    /// `untrusted`, `on-failure`, `on-request`, `never`.
+    pub approval_policy: CodexToolCallApprovalPolicy,
+}
+
+fn create_tool<T: JsonSchema>(name: &str, title: &str, description: &str) -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    #[expect(clippy::expect_used)]
+    let schema_value =
+        serde_json::to_value(&schema).expect("session tool schema should serialise to JSON");
+
+    let tool_input_schema =
+        serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
+            panic!("failed to create Tool from schema: {e}");
+        });
+
+    Tool {
+        name: name.to_string(),
+        title: Some(title.to_string()),
+        input_schema: tool_input_schema,
+        output_schema: None,
+        description: Some(description.to_string()),
+        annotations: None,
+    }
+}
+
+/// Builds the `Tool` definitions for every session-management tool.
+pub(crate) fn create_session_tools() -> Vec<Tool> {
+    vec![
+        create_tool::<CodexListSessionsParam>(
+            LIST_SESSIONS_TOOL_NAME,
+            "List Codex Sessions",
+            "List the Codex sessions hosted by this server and whether a turn is running.",
+        ),
+        create_tool::<CodexSessionParam>(
+            INTERRUPT_TOOL_NAME,
+            "Interrupt Codex Session",
+            "Interrupt the turn currently running in a Codex session.",
+        ),
+        create_tool::<CodexEventsParam>(
+            EVENTS_TOOL_NAME,
+            "Codex Session Events",
+            "Return the most recent events emitted by a Codex session.",
+        ),
+        create_tool::<CodexSessionParam>(
+            TURN_DIFF_TOOL_NAME,
+            "Codex Turn Diff",
+            "Return the unified diff of file changes made during the latest turn of a Codex session.",
+        ),
+        create_tool::<CodexSetApprovalPolicyParam>(
+            SET_APPROVAL_POLICY_TOOL_NAME,
+            "Set Codex Approval Policy",
+            "Change the approval policy used for subsequent commands in a Codex session.",
+        ),
+        create_tool::<CodexSessionParam>(
+            UNDO_TOOL_NAME,
+            "Undo Codex Turn",
+            "Revert the file changes made by the most recent turn of an idle Codex session.",
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn verify_codex_events_json_schema() {
+        let tool = create_session_tools()
+            .into_iter()
+            .find(|tool| tool.name == EVENTS_TOOL_NAME)
+            .expect("events tool is defined");
+        let tool_json = serde_json::to_value(&tool).expect("tool serializes");
+        let expected_tool_json = serde_json::json!({
+          "description": "Return the most recent events emitted by a Codex session.",
+          "inputSchema": {
+            "properties": {
+              "conversationId": {
+                "description": "The conversation id for this Codex session.",
+                "type": "string"
+              },
+              "limit": {
+                "description": "Maximum number of events to return, most recent last. Defaults to 20.",
+                "format": "uint32",
+                "minimum": 0.0,
+                "type": "integer"
+              },
+            },
+            "required": [
+              "conversationId",
+            ],
+            "type": "object",
+          },
+          "name": "codex-events",
+          "title": "Codex Session Events",
+        });
+        assert_eq!(expected_tool_json, tool_json);
+    }
+
+    #[test]
+    fn session_tool_names_are_unique() {
+        let mut names: Vec<String> = create_session_tools()
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 6);
+    }
+}
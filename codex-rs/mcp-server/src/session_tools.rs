@@ -0,0 +1,281 @@
+//! Handlers for the session-management tools defined in
+//! `session_tool_config.rs`. Each handler resolves to the `CallToolResult`
+//! that the message processor sends back to the client.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_core::CodexConversation;
+use codex_core::ConversationManager;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::UndoCompletedEvent;
+use codex_protocol::ConversationId;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::RequestId;
+use mcp_types::TextContent;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::session_registry::ReserveError;
+use crate::session_registry::SessionRegistry;
+use crate::session_tool_config::CodexEventsParam;
+use crate::session_tool_config::CodexSessionParam;
+use crate::session_tool_config::CodexSetApprovalPolicyParam;
+use crate::session_tool_config::DEFAULT_EVENTS_LIMIT;
+
+/// How long `codex-undo` waits for `UndoCompleted` before giving up.
+const UNDO_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(crate) fn error_result(text: String) -> CallToolResult {
+    CallToolResult {
+        content: vec![ContentBlock::TextContent(TextContent {
+            r#type: "text".to_string(),
+            text,
+            annotations: None,
+        })],
+        is_error: Some(true),
+        structured_content: None,
+    }
+}
+
+/// Returns `value` both as pretty-printed text (for clients that only read
+/// `content`) and as `structured_content`.
+fn structured_result<T: Serialize>(value: &T) -> CallToolResult {
+    match serde_json::to_value(value) {
+        Ok(structured) => CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                r#type: "text".to_string(),
+                text: serde_json::to_string_pretty(&structured).unwrap_or_default(),
+                annotations: None,
+            })],
+            is_error: None,
+            structured_content: Some(structured),
+        },
+        Err(e) => error_result(format!("Failed to serialize tool result: {e}")),
+    }
+}
+
+pub(crate) fn parse_arguments<T: DeserializeOwned>(
+    tool_name: &str,
+    arguments: Option<serde_json::Value>,
+) -> Result<T, CallToolResult> {
+    let Some(arguments) = arguments else {
+        return Err(error_result(format!(
+            "Missing arguments for {tool_name} tool-call."
+        )));
+    };
+    serde_json::from_value::<T>(arguments).map_err(|e| {
+        error_result(format!(
+            "Failed to parse arguments for {tool_name} tool-call: {e}"
+        ))
+    })
+}
+
+fn parse_conversation_id(conversation_id: &str) -> Result<ConversationId, CallToolResult> {
+    ConversationId::from_string(conversation_id)
+        .map_err(|e| error_result(format!("Failed to parse conversation_id: {e}")))
+}
+
+async fn get_conversation(
+    conversation_manager: &ConversationManager,
+    conversation_id: ConversationId,
+) -> Result<Arc<CodexConversation>, CallToolResult> {
+    conversation_manager
+        .get_conversation(conversation_id)
+        .await
+        .map_err(|_| {
+            error_result(format!(
+                "Session not found for conversation_id: {conversation_id}"
+            ))
+        })
+}
+
+pub(crate) async fn list_sessions(session_registry: &SessionRegistry) -> CallToolResult {
+    let sessions = session_registry.list().await;
+    structured_result(&json!({ "sessions": sessions }))
+}
+
+pub(crate) async fn interrupt(
+    conversation_manager: &ConversationManager,
+    session_registry: &SessionRegistry,
+    params: CodexSessionParam,
+) -> CallToolResult {
+    let conversation_id = match parse_conversation_id(&params.conversation_id) {
+        Ok(id) => id,
+        Err(result) => return result,
+    };
+    let conversation = match get_conversation(conversation_manager, conversation_id).await {
+        Ok(conversation) => conversation,
+        Err(result) => return result,
+    };
+    let Some(running_request_id) = session_registry.running_request(conversation_id).await else {
+        return structured_result(&json!({ "interrupted": false }));
+    };
+    // The runner that owns the running `tools/call` observes the resulting
+    // `TurnAborted` event and concludes that call.
+    if let Err(e) = conversation.submit(Op::Interrupt).await {
+        return error_result(format!("Failed to submit interrupt to Codex: {e}"));
+    }
+    structured_result(&json!({
+        "interrupted": true,
+        "requestId": running_request_id,
+    }))
+}
+
+pub(crate) async fn events(
+    session_registry: &SessionRegistry,
+    params: CodexEventsParam,
+) -> CallToolResult {
+    let conversation_id = match parse_conversation_id(&params.conversation_id) {
+        Ok(id) => id,
+        Err(result) => return result,
+    };
+    let limit = params
+        .limit
+        .map(|limit| limit as usize)
+        .unwrap_or(DEFAULT_EVENTS_LIMIT);
+    match session_registry.recent_events(conversation_id, limit).await {
+        Some(events) => structured_result(&json!({ "events": events })),
+        None => error_result(format!(
+            "Session not found for conversation_id: {conversation_id}"
+        )),
+    }
+}
+
+pub(crate) async fn turn_diff(
+    session_registry: &SessionRegistry,
+    params: CodexSessionParam,
+) -> CallToolResult {
+    let conversation_id = match parse_conversation_id(&params.conversation_id) {
+        Ok(id) => id,
+        Err(result) => return result,
+    };
+    match session_registry.turn_diff(conversation_id).await {
+        Some(unified_diff) => structured_result(&json!({ "unifiedDiff": unified_diff })),
+        None => error_result(format!(
+            "Session not found for conversation_id: {conversation_id}"
+        )),
+    }
+}
+
+pub(crate) async fn set_approval_policy(
+    conversation_manager: &ConversationManager,
+    session_registry: &SessionRegistry,
+    params: CodexSetApprovalPolicyParam,
+) -> CallToolResult {
+    let conversation_id = match parse_conversation_id(&params.conversation_id) {
+        Ok(id) => id,
+        Err(result) => return result,
+    };
+    let conversation = match get_conversation(conversation_manager, conversation_id).await {
+        Ok(conversation) => conversation,
+        Err(result) => return result,
+    };
+    let approval_policy: AskForApproval = params.approval_policy.into();
+    let op = Op::OverrideTurnContext {
+        cwd: None,
+        approval_policy: Some(approval_policy),
+        sandbox_policy: None,
+        model: None,
+        effort: None,
+        summary: None,
+    };
+    if let Err(e) = conversation.submit(op).await {
+        return error_result(format!("Failed to update approval policy: {e}"));
+    }
+    session_registry
+        .set_approval_policy(conversation_id, approval_policy)
+        .await;
+    structured_result(&json!({ "approvalPolicy": approval_policy }))
+}
+
+/// Submits `Op::Undo` to an idle session and waits for `UndoCompleted`.
+///
+/// Undo is refused while a turn is running because the running `tools/call`
+/// owns the event stream. The session stays reserved for `request_id` until
+/// the undo finishes so `codex-reply` cannot start a turn in the meantime.
+pub(crate) async fn undo(
+    conversation_manager: &ConversationManager,
+    session_registry: &SessionRegistry,
+    request_id: RequestId,
+    params: CodexSessionParam,
+) -> CallToolResult {
+    let conversation_id = match parse_conversation_id(&params.conversation_id) {
+        Ok(id) => id,
+        Err(result) => return result,
+    };
+    let conversation = match get_conversation(conversation_manager, conversation_id).await {
+        Ok(conversation) => conversation,
+        Err(result) => return result,
+    };
+    match session_registry
+        .try_reserve(conversation_id, request_id)
+        .await
+    {
+        Ok(()) => {}
+        Err(ReserveError::UnknownSession) => {
+            return error_result(format!(
+                "Session not found for conversation_id: {conversation_id}"
+            ));
+        }
+        Err(ReserveError::Busy(_)) => {
+            return error_result(
+                "Cannot undo while a turn is running; interrupt it first.".to_string(),
+            );
+        }
+    }
+    let result = run_undo(&conversation, session_registry, conversation_id).await;
+    session_registry
+        .set_running_request(conversation_id, None)
+        .await;
+    result
+}
+
+async fn run_undo(
+    conversation: &CodexConversation,
+    session_registry: &SessionRegistry,
+    conversation_id: ConversationId,
+) -> CallToolResult {
+    if let Err(e) = conversation.submit(Op::Undo).await {
+        return error_result(format!("Failed to submit undo to Codex: {e}"));
+    }
+
+    let wait_for_undo = async {
+        loop {
+            let event = match conversation.next_event().await {
+                Ok(event) => event,
+                Err(e) => {
+                    // The event stream only fails once the session is gone.
+                    session_registry.remove(conversation_id).await;
+                    return error_result(format!("Codex runtime error: {e}"));
+                }
+            };
+            session_registry.record_event(conversation_id, &event).await;
+            match event.msg {
+                EventMsg::UndoCompleted(UndoCompletedEvent { success, message }) => {
+                    let result = structured_result(&json!({
+                        "success": success,
+                        "message": message,
+                    }));
+                    return CallToolResult {
+                        is_error: (!success).then_some(true),
+                        ..result
+                    };
+                }
+                EventMsg::Error(err) => return error_result(err.message),
+                _ => {}
+            }
+        }
+    };
+    match tokio::time::timeout(UNDO_TIMEOUT, wait_for_undo).await {
+        Ok(result) => result,
+        Err(_) => error_result(format!(
+            "Timed out after {}s waiting for the undo to finish.",
+            UNDO_TIMEOUT.as_secs()
+        )),
+    }
+}
@@ -0,0 +1,198 @@
+//! `codex mcp-server --tools-only`: serves Codex's built-in tools (`shell`,
+//! `apply_patch`, `read_file`, `grep_files`, `list_dir`) directly as MCP tools
+//! instead of the `codex` agent tools.
+//!
+//! Calls run through [`StandaloneToolHost`], so they honor the configured
+//! `SandboxPolicy` and approval policy. Approval requests are surfaced to the
+//! client as elicitations, exactly like in a `codex` tool-call.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use codex_core::StandaloneToolHost;
+use codex_core::StandaloneToolSpec;
+use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalRequestEvent;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::RequestId;
+use mcp_types::TextContent;
+use mcp_types::Tool;
+use mcp_types::ToolInputSchema;
+use tokio::sync::Mutex;
+
+use crate::approval_target::ApprovalTarget;
+use crate::exec_approval::handle_exec_approval_request;
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotificationMeta;
+use crate::patch_approval::handle_patch_approval_request;
+
+pub(crate) struct ToolsOnlyServer {
+    host: Arc<StandaloneToolHost>,
+    outgoing: Arc<OutgoingMessageSender>,
+    /// Maps the call id handed to the tool host back to the `tools/call`
+    /// request so events and elicitations can be correlated.
+    running_calls: Arc<Mutex<HashMap<String, RequestId>>>,
+}
+
+impl ToolsOnlyServer {
+    /// Creates the server and spawns the task that forwards tool events
+    /// (command output, patch application, approval requests) to the client.
+    pub(crate) fn new(host: Arc<StandaloneToolHost>, outgoing: Arc<OutgoingMessageSender>) -> Self {
+        let running_calls = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(forward_tool_events(
+            host.clone(),
+            outgoing.clone(),
+            running_calls.clone(),
+        ));
+        Self {
+            host,
+            outgoing,
+            running_calls,
+        }
+    }
+
+    pub(crate) fn tools(&self) -> Vec<Tool> {
+        self.host
+            .tools()
+            .into_iter()
+            .filter_map(|spec| match tool_from_spec(spec) {
+                Ok(tool) => Some(tool),
+                Err(err) => {
+                    tracing::error!("failed to describe built-in tool: {err}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn handle_call_tool(
+        &self,
+        id: RequestId,
+        name: String,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let host = self.host.clone();
+        let outgoing = self.outgoing.clone();
+        let running_calls = self.running_calls.clone();
+        tokio::spawn(async move {
+            let call_id = match &id {
+                RequestId::String(s) => s.clone(),
+                RequestId::Integer(n) => n.to_string(),
+            };
+            running_calls
+                .lock()
+                .await
+                .insert(call_id.clone(), id.clone());
+            let output = host
+                .call_tool(
+                    call_id.clone(),
+                    &name,
+                    arguments.unwrap_or_else(|| serde_json::json!({})),
+                )
+                .await;
+            running_calls.lock().await.remove(&call_id);
+
+            let result = CallToolResult {
+                content: vec![ContentBlock::TextContent(TextContent {
+                    r#type: "text".to_string(),
+                    text: output.content,
+                    annotations: None,
+                })],
+                is_error: (!output.success).then_some(true),
+                structured_content: None,
+            };
+            outgoing.send_response(id, result).await;
+        });
+    }
+}
+
+fn tool_from_spec(spec: StandaloneToolSpec) -> Result<Tool, serde_json::Error> {
+    let StandaloneToolSpec {
+        name,
+        description,
+        input_schema,
+    } = spec;
+    let input_schema = serde_json::from_value::<ToolInputSchema>(input_schema)?;
+    Ok(Tool {
+        name,
+        title: None,
+        input_schema,
+        output_schema: None,
+        description: Some(description),
+        annotations: None,
+    })
+}
+
+async fn forward_tool_events(
+    host: Arc<StandaloneToolHost>,
+    outgoing: Arc<OutgoingMessageSender>,
+    running_calls: Arc<Mutex<HashMap<String, RequestId>>>,
+) {
+    loop {
+        let event = match host.next_event().await {
+            Ok(event) => event,
+            Err(err) => {
+                tracing::info!("tool event stream closed: {err}");
+                break;
+            }
+        };
+        let Some(request_id) = running_calls.lock().await.get(&event.id).cloned() else {
+            tracing::warn!("dropping event for unknown tool call {}", event.id);
+            continue;
+        };
+
+        outgoing
+            .send_event_as_notification(
+                &event,
+                Some(OutgoingNotificationMeta::new(Some(request_id.clone()))),
+            )
+            .await;
+
+        match event.msg {
+            EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+                call_id,
+                command,
+                cwd,
+                reason: _,
+                risk,
+                parsed_cmd,
+            }) => {
+                handle_exec_approval_request(
+                    command,
+                    cwd,
+                    outgoing.clone(),
+                    ApprovalTarget::ToolHost(host.clone()),
+                    request_id,
+                    event.id.clone(),
+                    event.id,
+                    call_id,
+                    parsed_cmd,
+                    risk,
+                )
+                .await;
+            }
+            EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                call_id,
+                reason,
+                grant_root,
+                changes,
+            }) => {
+                handle_patch_approval_request(
+                    call_id,
+                    reason,
+                    grant_root,
+                    changes,
+                    outgoing.clone(),
+                    ApprovalTarget::ToolHost(host.clone()),
+                    request_id,
+                    event.id.clone(),
+                    event.id,
+                )
+                .await;
+            }
+            _ => {}
+        }
+    }
+}
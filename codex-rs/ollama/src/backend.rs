@@ -0,0 +1,123 @@
+use std::io;
+
+use crate::client::OllamaClient;
+use crate::openai_compat::OpenAiCompatClient;
+use crate::url::base_url_to_host_root;
+use crate::url::is_openai_compatible_base_url;
+use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::ModelProviderInfo;
+use codex_core::config::Config;
+
+/// A local server backing the `oss` provider.
+pub enum OssBackend {
+    /// Ollama, which can also pull missing models.
+    Ollama(OllamaClient),
+    /// Any other server that speaks the OpenAI API, e.g. llama.cpp's
+    /// `llama-server` or vLLM.
+    OpenAiCompatible(OpenAiCompatClient),
+}
+
+/// Look up the `oss` provider from the Config so that any overrides the user
+/// has in their config.toml are taken into account.
+pub(crate) fn oss_provider(config: &Config) -> io::Result<&ModelProviderInfo> {
+    config
+        .model_providers
+        .get(BUILT_IN_OSS_MODEL_PROVIDER_ID)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Built-in provider {BUILT_IN_OSS_MODEL_PROVIDER_ID} not found",),
+            )
+        })
+}
+
+/// Work out which kind of server the `oss` provider points at. Ollama is
+/// recognized by its native `/api/tags` endpoint; anything else that lists
+/// models at `/v1/models` is treated as a generic OpenAI-compatible server.
+pub async fn detect_oss_backend(config: &Config) -> io::Result<OssBackend> {
+    detect_backend_for_provider(oss_provider(config)?).await
+}
+
+pub(crate) async fn detect_backend_for_provider(
+    provider: &ModelProviderInfo,
+) -> io::Result<OssBackend> {
+    let base_url = provider.base_url.as_deref().unwrap_or_default();
+    if !is_openai_compatible_base_url(base_url) || serves_ollama_api(base_url).await {
+        return OllamaClient::try_from_provider(provider)
+            .await
+            .map(OssBackend::Ollama);
+    }
+
+    let client = OpenAiCompatClient::new(base_url);
+    match client.fetch_models().await {
+        Ok(_) => Ok(OssBackend::OpenAiCompatible(client)),
+        Err(err) => {
+            tracing::warn!("Failed to list models from {base_url}: {err}");
+            Err(io::Error::other(format!(
+                "No local OSS server detected at {base_url}. Start Ollama (`ollama serve`), llama.cpp (`llama-server --jinja`) or vLLM (`vllm serve --enable-auto-tool-choice`), and set CODEX_OSS_BASE_URL or CODEX_OSS_PORT if it is not listening on the default port."
+            )))
+        }
+    }
+}
+
+async fn serves_ollama_api(base_url: &str) -> bool {
+    let url = format!("{}/api/tags", base_url_to_host_root(base_url));
+    let client = reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+    match client.get(url).send().await {
+        Ok(resp) if resp.status().is_success() => resp
+            .json::<serde_json::Value>()
+            .await
+            .is_ok_and(|val| val.get("models").is_some()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[tokio::test]
+    async fn detects_generic_openai_compatible_server() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping detects_generic_openai_compatible_server",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"data": [{"id": "gpt-oss-20b", "owned_by": "llamacpp"}]}),
+            ))
+            .mount(&server)
+            .await;
+
+        let provider =
+            codex_core::create_oss_provider_with_base_url(&format!("{}/v1", server.uri()));
+        let backend = detect_backend_for_provider(&provider)
+            .await
+            .expect("server should be detected");
+        assert_matches!(backend, OssBackend::OpenAiCompatible(_));
+
+        // Once the native Ollama endpoint answers, the same URL is Ollama.
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/tags"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"models": []})),
+            )
+            .mount(&server)
+            .await;
+        let backend = detect_backend_for_provider(&provider)
+            .await
+            .expect("server should be detected");
+        assert_matches!(backend, OssBackend::Ollama(_));
+    }
+}
@@ -5,12 +5,12 @@ use serde_json::Value as JsonValue;
 use std::collections::VecDeque;
 use std::io;
 
+use crate::backend::oss_provider;
 use crate::parser::pull_events_from_value;
 use crate::pull::PullEvent;
 use crate::pull::PullProgressReporter;
 use crate::url::base_url_to_host_root;
 use crate::url::is_openai_compatible_base_url;
-use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ModelProviderInfo;
 use codex_core::WireApi;
 use codex_core::config::Config;
@@ -29,20 +29,7 @@ impl OllamaClient {
     /// and verify that a local Ollama server is reachable. If no server is
     /// detected, returns an error with helpful installation/run instructions.
     pub async fn try_from_oss_provider(config: &Config) -> io::Result<Self> {
-        // Note that we must look up the provider from the Config to ensure that
-        // any overrides the user has in their config.toml are taken into
-        // account.
-        let provider = config
-            .model_providers
-            .get(BUILT_IN_OSS_MODEL_PROVIDER_ID)
-            .ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Built-in provider {BUILT_IN_OSS_MODEL_PROVIDER_ID} not found",),
-                )
-            })?;
-
-        Self::try_from_provider(provider).await
+        Self::try_from_provider(oss_provider(config)?).await
     }
 
     #[cfg(test)]
@@ -52,7 +39,7 @@ impl OllamaClient {
     }
 
     /// Build a client from a provider definition and verify the server is reachable.
-    async fn try_from_provider(provider: &ModelProviderInfo) -> io::Result<Self> {
+    pub(crate) async fn try_from_provider(provider: &ModelProviderInfo) -> io::Result<Self> {
         #![expect(clippy::expect_used)]
         let base_url = provider
             .base_url
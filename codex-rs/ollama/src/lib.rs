@@ -1,10 +1,16 @@
+mod backend;
 mod client;
+mod openai_compat;
 mod parser;
 mod pull;
 mod url;
 
+pub use backend::OssBackend;
+pub use backend::detect_oss_backend;
 pub use client::OllamaClient;
 use codex_core::config::Config;
+pub use openai_compat::OpenAiCompatClient;
+pub use openai_compat::ServedModel;
 pub use pull::CliProgressReporter;
 pub use pull::PullEvent;
 pub use pull::PullProgressReporter;
@@ -15,15 +21,21 @@ pub const DEFAULT_OSS_MODEL: &str = "gpt-oss:20b";
 
 /// Prepare the local OSS environment when `--oss` is selected.
 ///
-/// - Ensures a local Ollama server is reachable.
-/// - Checks if the model exists locally and pulls it if missing.
+/// - Detects whether the `oss` provider points at Ollama or at another
+///   OpenAI-compatible server (llama.cpp, vLLM, ...).
+/// - For Ollama, checks if the model exists locally and pulls it if missing.
+/// - For other servers, checks that the model is served and can call tools.
 pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
+    match detect_oss_backend(config).await? {
+        OssBackend::Ollama(ollama_client) => ensure_ollama_model(config, &ollama_client).await,
+        OssBackend::OpenAiCompatible(client) => ensure_served_model(config, &client).await,
+    }
+}
+
+async fn ensure_ollama_model(config: &Config, ollama_client: &OllamaClient) -> std::io::Result<()> {
     // Only download when the requested model is the default OSS model (or when -m is not provided).
     let model = config.model.as_ref();
 
-    // Verify local Ollama is reachable.
-    let ollama_client = crate::OllamaClient::try_from_oss_provider(config).await?;
-
     // If the model is not present locally, pull it.
     match ollama_client.fetch_models().await {
         Ok(models) => {
@@ -42,3 +54,24 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
 
     Ok(())
 }
+
+async fn ensure_served_model(config: &Config, client: &OpenAiCompatClient) -> std::io::Result<()> {
+    let models = client.fetch_models().await?;
+    let Some(model) = models.iter().find(|m| m.id == config.model) else {
+        let served = models
+            .iter()
+            .map(|m| m.id.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "model `{}` is not served by {} (available: {served}). Pass one of them with `-m <model>`; models cannot be pulled from this server.",
+                config.model,
+                client.base_url()
+            ),
+        ));
+    };
+
+    client.probe_tool_calling(model).await
+}
@@ -0,0 +1,325 @@
+use serde_json::Value as JsonValue;
+use std::io;
+
+/// Name of the throwaway tool offered to the model by [`OpenAiCompatClient::probe_tool_calling`].
+const PROBE_TOOL_NAME: &str = "codex_probe";
+
+/// Client for a generic OpenAI-compatible server such as llama.cpp's
+/// `llama-server` or vLLM. These servers list models and serve chat
+/// completions but cannot pull models on demand.
+pub struct OpenAiCompatClient {
+    client: reqwest::Client,
+    /// Root of the OpenAI-compatible API, e.g. "http://localhost:8080/v1".
+    base_url: String,
+}
+
+/// A model listed by `GET /v1/models`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServedModel {
+    pub id: String,
+    /// Reported owner, e.g. "llamacpp" or "vllm". Used to tailor hints.
+    pub owned_by: Option<String>,
+}
+
+impl OpenAiCompatClient {
+    pub(crate) fn new(base_url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(5))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Return the models served by this server.
+    pub async fn fetch_models(&self) -> io::Result<Vec<ServedModel>> {
+        let url = format!("{}/models", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to list models at {}: HTTP {}",
+                self.base_url,
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(parse_models(&val))
+    }
+
+    /// Ask `model` to call a trivial tool, mirroring how Codex sends tools
+    /// (no `tool_choice`, so the server default of "auto" applies). Returns an
+    /// error explaining how to fix the server when the request is rejected or
+    /// the model answers without calling the tool.
+    pub async fn probe_tool_calling(&self, model: &ServedModel) -> io::Result<()> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let payload = serde_json::json!({
+            "model": model.id,
+            "messages": [{
+                "role": "user",
+                "content": format!("Call the {PROBE_TOOL_NAME} tool with ok set to true."),
+            }],
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": PROBE_TOOL_NAME,
+                    "description": "Connectivity check used by Codex.",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {"ok": {"type": "boolean"}},
+                        "required": ["ok"],
+                    },
+                },
+            }],
+            "max_tokens": 512,
+            "stream": false,
+        });
+        let resp = self
+            .client
+            .post(url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        let body = resp.text().await.map_err(io::Error::other)?;
+        let hint = tool_calling_hint(model.owned_by.as_deref());
+
+        if !status.is_success() {
+            let message = error_message(&body);
+            return Err(io::Error::other(format!(
+                "{} rejected a tool-calling request for model `{}` (HTTP {status}): {message}. {hint}",
+                self.base_url, model.id
+            )));
+        }
+
+        let val = serde_json::from_str::<JsonValue>(&body).map_err(io::Error::other)?;
+        if response_has_tool_call(&val) {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "model `{}` answered without calling a tool, so it cannot drive Codex's tools. {hint}",
+                model.id
+            )))
+        }
+    }
+}
+
+fn parse_models(val: &JsonValue) -> Vec<ServedModel> {
+    val.get("data")
+        .and_then(|data| data.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|model| {
+                    let id = model.get("id").and_then(|id| id.as_str())?;
+                    Some(ServedModel {
+                        id: id.to_string(),
+                        owned_by: model
+                            .get("owned_by")
+                            .and_then(|owner| owner.as_str())
+                            .map(str::to_string),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn response_has_tool_call(val: &JsonValue) -> bool {
+    val.get("choices")
+        .and_then(|choices| choices.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|choice| choice.get("message")?.get("tool_calls")?.as_array())
+        .any(|calls| !calls.is_empty())
+}
+
+/// Extract a human-readable message from an OpenAI-style error body.
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<JsonValue>(body)
+        .ok()
+        .and_then(|val| {
+            let error = val.get("error").unwrap_or(&val);
+            error
+                .get("message")
+                .or_else(|| val.get("message"))
+                .and_then(|message| message.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+fn tool_calling_hint(owned_by: Option<&str>) -> &'static str {
+    match owned_by {
+        Some("llamacpp") => {
+            "Restart llama-server with `--jinja` and a chat template that supports tool calls."
+        }
+        Some("vllm") => {
+            "Start vLLM with `--enable-auto-tool-choice` and a `--tool-call-parser` that matches the model."
+        }
+        _ => {
+            "Enable tool calling on the server (llama.cpp: `llama-server --jinja`; vLLM: `--enable-auto-tool-choice --tool-call-parser <parser>`) or pick a model that supports it."
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn network_disabled(test: &str) -> bool {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping {test}",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return true;
+        }
+        false
+    }
+
+    fn vllm_model() -> ServedModel {
+        ServedModel {
+            id: "Qwen/Qwen3-Coder-30B".to_string(),
+            owned_by: Some("vllm".to_string()),
+        }
+    }
+
+    #[test]
+    fn parses_model_listing() {
+        let val = serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "gpt-oss-20b", "object": "model", "owned_by": "llamacpp"},
+                {"id": "no-owner", "object": "model"},
+            ]
+        });
+
+        assert_eq!(
+            parse_models(&val),
+            vec![
+                ServedModel {
+                    id: "gpt-oss-20b".to_string(),
+                    owned_by: Some("llamacpp".to_string()),
+                },
+                ServedModel {
+                    id: "no-owner".to_string(),
+                    owned_by: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn probe_accepts_tool_call_response() {
+        if network_disabled("probe_accepts_tool_call_response") {
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "choices": [{
+                        "message": {
+                            "role": "assistant",
+                            "tool_calls": [{
+                                "id": "call_1",
+                                "type": "function",
+                                "function": {"name": PROBE_TOOL_NAME, "arguments": "{\"ok\":true}"}
+                            }]
+                        },
+                        "finish_reason": "tool_calls"
+                    }]
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OpenAiCompatClient::new(&format!("{}/v1", server.uri()));
+        client
+            .probe_tool_calling(&vllm_model())
+            .await
+            .expect("tool call should pass the probe");
+    }
+
+    #[tokio::test]
+    async fn probe_explains_rejected_tool_calls() {
+        if network_disabled("probe_explains_rejected_tool_calls") {
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(400).set_body_json(
+                serde_json::json!({
+                    "object": "error",
+                    "message": "\"auto\" tool choice requires --enable-auto-tool-choice and --tool-call-parser to be set",
+                }),
+            ))
+            .mount(&server)
+            .await;
+
+        let client = OpenAiCompatClient::new(&format!("{}/v1", server.uri()));
+        let err = client
+            .probe_tool_calling(&vllm_model())
+            .await
+            .expect_err("rejected tool call should fail the probe");
+
+        let message = err.to_string();
+        assert!(
+            message.contains("requires --enable-auto-tool-choice"),
+            "{message}"
+        );
+        assert!(message.contains("Start vLLM with"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn probe_fails_when_model_skips_tool() {
+        if network_disabled("probe_fails_when_model_skips_tool") {
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "choices": [{
+                        "message": {"role": "assistant", "content": "ok=true"},
+                        "finish_reason": "stop"
+                    }]
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let model = ServedModel {
+            id: "tiny".to_string(),
+            owned_by: Some("llamacpp".to_string()),
+        };
+        let client = OpenAiCompatClient::new(&format!("{}/v1", server.uri()));
+        let err = client
+            .probe_tool_calling(&model)
+            .await
+            .expect_err("plain text answer should fail the probe");
+
+        assert_eq!(
+            err.to_string(),
+            "model `tiny` answered without calling a tool, so it cannot drive Codex's tools. Restart llama-server with `--jinja` and a chat template that supports tool calls."
+        );
+    }
+}
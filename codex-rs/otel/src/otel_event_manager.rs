@@ -367,6 +367,32 @@ impl OtelEventManager {
         );
     }
 
+    pub fn model_failover(
+        &self,
+        from_provider: &str,
+        to_provider: &str,
+        to_model: &str,
+        reason: &str,
+    ) {
+        tracing::event!(
+            tracing::Level::INFO,
+            event.name = "codex.model_failover",
+            event.timestamp = %timestamp(),
+            conversation.id = %self.metadata.conversation_id,
+            app.version = %self.metadata.app_version,
+            auth_mode = self.metadata.auth_mode,
+            user.account_id = self.metadata.account_id,
+            user.email = self.metadata.account_email,
+            terminal.type = %self.metadata.terminal_type,
+            model = %self.metadata.model,
+            slug = %self.metadata.slug,
+            from_provider = %from_provider,
+            to_provider = %to_provider,
+            to_model = %to_model,
+            reason = %reason,
+        );
+    }
+
     pub fn sandbox_assessment(
         &self,
         call_id: &str,
@@ -74,6 +74,24 @@ pub enum Verbosity {
     High,
 }
 
+/// How conversation history is shrunk when it is compacted.
+#[derive(
+    Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum CompactionStrategy {
+    /// Ask the model to summarize the conversation and replace the history
+    /// with the summary and the most recent user messages.
+    #[default]
+    SummarizeAll,
+    /// Replace the output of tool calls older than the most recent turns with
+    /// a placeholder. Does not call the model.
+    DropOldToolOutputs,
+    /// Keep only the most recent turns. Does not call the model.
+    SlidingWindow,
+}
+
 #[derive(
     Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, Display, JsonSchema, TS,
 )]
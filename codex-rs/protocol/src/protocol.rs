@@ -11,6 +11,7 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use crate::ConversationId;
+use crate::config_types::CompactionStrategy;
 use crate::config_types::ReasoningEffort as ReasoningEffortConfig;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
@@ -70,6 +71,11 @@ pub enum Op {
     UserInput {
         /// User input items, see `InputItem`
         items: Vec<UserInput>,
+        /// JSON schema the final assistant message of this turn must follow.
+        /// Applies to this turn only, like the field of the same name on
+        /// [`Op::UserTurn`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        final_output_json_schema: Option<Value>,
     },
 
     /// Similar to [`Op::UserInput`], but contains additional context required
@@ -98,7 +104,9 @@ pub enum Op {
 
         /// Will only be honored if the model is configured to use reasoning.
         summary: ReasoningSummaryConfig,
-        // The JSON schema to use for the final assistant message
+
+        /// JSON schema the final assistant message of this turn must follow.
+        /// Applies to this turn only.
         final_output_json_schema: Option<Value>,
     },
 
@@ -177,9 +185,29 @@ pub enum Op {
     /// to generate a summary which will be returned as an AgentMessage event.
     Compact,
 
+    /// Pin a message so it is kept verbatim whenever the conversation is
+    /// compacted, whatever the compaction strategy. Pins are recorded in the
+    /// rollout, so they survive resume and fork.
+    PinMessage { text: String },
+
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Request the ghost snapshots taken during this session.
+    /// Reply is delivered via `EventMsg::ListSnapshotsResponse`.
+    ListSnapshots,
+
+    /// Restore the working tree to the ghost snapshot `id`. When `paths` is
+    /// non-empty only those files (relative to the session cwd) are restored.
+    /// Unlike `Undo`, the conversation history is left untouched, so later
+    /// snapshots remain available. Progress is reported with `UndoStarted`
+    /// and `UndoCompleted`.
+    RestoreSnapshot {
+        id: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        paths: Vec<PathBuf>,
+    },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -448,6 +476,10 @@ pub enum EventMsg {
     /// Agent has completed all actions
     TaskComplete(TaskCompleteEvent),
 
+    /// Where the task that is about to complete spent its time. Sent right
+    /// before `TaskComplete`.
+    TurnProfile(TurnProfileEvent),
+
     /// Usage update for the current session, including totals and last turn.
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
@@ -507,6 +539,13 @@ pub enum EventMsg {
 
     BackgroundEvent(BackgroundEventEvent),
 
+    /// The conversation history was compacted.
+    ContextCompacted(ContextCompactedEvent),
+
+    /// A message was pinned with `Op::PinMessage`. Recorded in the rollout so
+    /// pins are restored on resume and fork.
+    MessagePinned(MessagePinnedEvent),
+
     UndoStarted(UndoStartedEvent),
 
     UndoCompleted(UndoCompletedEvent),
@@ -533,6 +572,9 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Ghost snapshots taken during the session, oldest first.
+    ListSnapshotsResponse(ListSnapshotsResponseEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -692,6 +734,71 @@ pub struct TaskCompleteEvent {
     pub last_agent_message: Option<String>,
 }
 
+/// Per-turn timing breakdown, used to find out whether a slow turn was
+/// waiting on the model, on commands, on the user or on hooks.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TurnProfileEvent {
+    /// Wall-clock time from the start of the turn until it finished.
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    /// Time spent in each phase, in [`TurnPhase`] order. Phases that never
+    /// ran are omitted. Tool calls can run concurrently, so the totals may
+    /// add up to more than `duration_ms`.
+    pub phases: Vec<TurnPhaseTiming>,
+    /// Tokens used by the model requests made during the turn.
+    pub token_usage: TokenUsage,
+    /// Estimated cost of the turn, present when `model_prices` has an entry
+    /// for the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TurnPhaseTiming {
+    pub phase: TurnPhase,
+    /// How many times the phase ran during the turn.
+    #[ts(type = "number")]
+    pub count: u64,
+    #[ts(type = "number")]
+    pub total_ms: u64,
+    /// Longest single occurrence.
+    #[ts(type = "number")]
+    pub max_ms: u64,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnPhase {
+    /// From sending a model request until the first output arrived.
+    ModelTimeToFirstToken,
+    /// From the first output until the response completed.
+    ModelStreaming,
+    /// Running shell commands and patches, including sandbox setup.
+    Exec,
+    /// Waiting for the user to approve a command or patch.
+    ApprovalWait,
+    /// Running `PreToolUse` and `PostToolUse` hooks.
+    Hook,
+    /// Calls to MCP server tools.
+    McpToolCall,
+}
+
+impl TurnPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            TurnPhase::ModelTimeToFirstToken => "model time to first token",
+            TurnPhase::ModelStreaming => "model streaming",
+            TurnPhase::Exec => "commands",
+            TurnPhase::ApprovalWait => "approval wait",
+            TurnPhase::Hook => "hooks",
+            TurnPhase::McpToolCall => "MCP tool calls",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TaskStartedEvent {
     pub model_context_window: Option<i64>,
@@ -778,6 +885,33 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Estimated spend, present when `model_prices` has an entry for the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cost: Option<TokenCost>,
+    /// Prompt cache hit rates, present once the provider has reported input
+    /// token counts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prompt_cache: Option<PromptCacheStats>,
+}
+
+/// Estimated spend in USD, derived from the configured per-model prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TokenCost {
+    /// Cost of the current turn.
+    pub turn_usd: f64,
+    /// Cost of the whole session so far.
+    pub session_usd: f64,
+}
+
+/// Share of input tokens that were served from the provider's prompt cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PromptCacheStats {
+    /// Percentage (0-100) of the last request's input tokens that were cached.
+    pub last_hit_percent: f64,
+    /// Percentage (0-100) of all input tokens in this session that were cached.
+    pub session_hit_percent: f64,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -814,6 +948,14 @@ impl TokenUsage {
         (self.input_tokens - self.cached_input()).max(0)
     }
 
+    /// Percentage (0-100) of input tokens served from the prompt cache, or
+    /// `None` when no input tokens were reported.
+    pub fn cache_hit_percent(&self) -> Option<f64> {
+        (self.input_tokens > 0).then(|| {
+            (self.cached_input() as f64 / self.input_tokens as f64 * 100.0).clamp(0.0, 100.0)
+        })
+    }
+
     /// Primary count for display as a single absolute value: non-cached input + output.
     pub fn blended_total(&self) -> i64 {
         (self.non_cached_input() + self.output_tokens.max(0)).max(0)
@@ -969,6 +1111,13 @@ pub struct McpToolCallEndEvent {
     pub duration: Duration,
     /// Result of the tool call. Note this could be an error.
     pub result: Result<CallToolResult, String>,
+    /// True when the result was served from the server's result cache.
+    #[serde(default)]
+    pub cached: bool,
+    /// Time spent waiting on the server's rate limit before the call ran.
+    #[serde(default)]
+    #[ts(type = "string | null")]
+    pub throttled: Option<Duration>,
 }
 
 impl McpToolCallEndEvent {
@@ -1120,6 +1269,12 @@ pub enum RolloutItem {
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
 pub struct CompactedItem {
     pub message: String,
+    /// The history as it was after compaction. Present when it cannot be
+    /// rebuilt from `message` alone, e.g. for strategies that do not
+    /// summarize or when pinned messages were kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub replacement_history: Option<Vec<ResponseItem>>,
 }
 
 impl From<CompactedItem> for ResponseItem {
@@ -1140,6 +1295,10 @@ pub struct TurnContextItem {
     pub approval_policy: AskForApproval,
     pub sandbox_policy: SandboxPolicy,
     pub model: String,
+    /// Provider serving `model`; differs from the session's provider after a
+    /// fallback switch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effort: Option<ReasoningEffortConfig>,
     pub summary: ReasoningSummaryConfig,
@@ -1170,6 +1329,10 @@ pub struct GitInfo {
 pub struct ReviewRequest {
     pub prompt: String,
     pub user_facing_hint: String,
+    /// Names of the review rubrics (built-in or `[review_rubrics]` in
+    /// config.toml) the reviewer should apply on top of its default checks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rubrics: Vec<String>,
 }
 
 /// Structured review result produced by a child review session.
@@ -1200,6 +1363,9 @@ pub struct ReviewFinding {
     pub confidence_score: f32,
     pub priority: i32,
     pub code_location: ReviewCodeLocation,
+    /// Rubric the finding falls under, when the review applied rubrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 /// Location of the code related to a review finding.
@@ -1312,6 +1478,24 @@ pub struct DeprecationNoticeEvent {
     pub details: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextCompactedEvent {
+    pub strategy: CompactionStrategy,
+    /// Estimated size of the history before and after compaction, in tokens.
+    pub tokens_before: i64,
+    pub tokens_after: i64,
+    /// Number of pinned messages carried over verbatim.
+    pub pinned_messages: usize,
+    /// Pinned files whose current contents were re-added to the history.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct MessagePinnedEvent {
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct UndoStartedEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1411,6 +1595,34 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListSnapshots`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListSnapshotsResponseEvent {
+    pub snapshots: Vec<SnapshotSummary>,
+}
+
+/// A ghost snapshot and how the working tree has changed since it was taken.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SnapshotSummary {
+    /// Ghost commit id, accepted by `Op::RestoreSnapshot`.
+    pub id: String,
+    /// The user message of the turn the snapshot was taken for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_prompt: Option<String>,
+    /// Files that differ between the snapshot and the working tree.
+    pub files: Vec<SnapshotFileChange>,
+    /// Unified diff from the snapshot to the working tree.
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SnapshotFileChange {
+    /// Path relative to the session cwd.
+    pub path: PathBuf,
+    pub added: u32,
+    pub removed: u32,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of conversation_id for backwards compatibility.
@@ -1496,6 +1708,8 @@ pub enum TurnAbortReason {
     Interrupted,
     Replaced,
     ReviewEnded,
+    /// The session hit `max_session_tokens` or `max_session_cost`.
+    BudgetExceeded,
 }
 
 #[cfg(test)]
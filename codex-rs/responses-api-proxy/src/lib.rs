@@ -7,6 +7,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -20,6 +21,8 @@ use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
 use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
 use tiny_http::Header;
 use tiny_http::Method;
 use tiny_http::Request;
@@ -27,8 +30,17 @@ use tiny_http::Response;
 use tiny_http::Server;
 use tiny_http::StatusCode;
 
+mod audit_log;
 mod read_api_key;
+mod request_policy;
+use audit_log::AuditEntry;
+use audit_log::AuditLog;
+use audit_log::AuditedBody;
+use audit_log::Redactor;
+use audit_log::response_body_value;
 use read_api_key::read_auth_header_from_stdin;
+use request_policy::RequestPolicy;
+use request_policy::Route;
 
 /// CLI arguments for the proxy.
 #[derive(Debug, Clone, Parser)]
@@ -49,6 +61,39 @@ pub struct Args {
     /// Absolute URL the proxy should forward requests to (defaults to OpenAI).
     #[arg(long, default_value = "https://api.openai.com/v1/responses")]
     pub upstream_url: String,
+
+    /// Absolute URL to forward `POST /v1/chat/completions` to. Defaults to
+    /// `--upstream-url` with its trailing `/responses` replaced by
+    /// `/chat/completions`.
+    #[arg(long)]
+    pub chat_upstream_url: Option<String>,
+
+    /// Append redacted request/response pairs to this JSONL file.
+    #[arg(long, value_name = "FILE")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Rotate the audit log once it reaches this many bytes.
+    #[arg(long, default_value_t = 100 * 1024 * 1024, requires = "audit_log")]
+    pub audit_log_max_bytes: u64,
+
+    /// Number of rotated audit log files (`FILE.1` ... `FILE.N`) to keep.
+    #[arg(long, default_value_t = 5, requires = "audit_log")]
+    pub audit_log_max_files: usize,
+
+    /// Additional regex whose matches are replaced with `[REDACTED]` in the
+    /// audit log. May be repeated.
+    #[arg(long = "audit-redact", value_name = "REGEX", requires = "audit_log")]
+    pub audit_redact: Vec<String>,
+
+    /// Only forward requests for this model. May be repeated; if omitted,
+    /// any model is allowed.
+    #[arg(long = "allowed-model", value_name = "MODEL")]
+    pub allowed_models: Vec<String>,
+
+    /// Reject requests asking for more output tokens than this, and set this
+    /// limit on requests that do not specify one.
+    #[arg(long, value_name = "N")]
+    pub max_output_tokens: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -57,27 +102,85 @@ struct ServerInfo {
     pid: u32,
 }
 
-struct ForwardConfig {
-    upstream_url: Url,
+struct Upstream {
+    url: Url,
     host_header: HeaderValue,
 }
 
+impl Upstream {
+    fn parse(url: &str, flag: &str) -> Result<Self> {
+        let url = Url::parse(url).with_context(|| format!("parsing {flag}"))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            _ => return Err(anyhow!("upstream URL must include a host")),
+        };
+        let host_header =
+            HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
+        Ok(Self { url, host_header })
+    }
+}
+
+struct ForwardConfig {
+    responses: Upstream,
+    /// `None` when no chat completions endpoint could be derived, in which
+    /// case `/v1/chat/completions` is rejected like any other path.
+    chat_completions: Option<Upstream>,
+    policy: RequestPolicy,
+    audit_log: Option<Arc<AuditLog>>,
+}
+
+impl ForwardConfig {
+    fn upstream(&self, route: Route) -> Option<&Upstream> {
+        match route {
+            Route::Responses => Some(&self.responses),
+            Route::ChatCompletions => self.chat_completions.as_ref(),
+        }
+    }
+}
+
+/// Derives the chat completions endpoint from a responses endpoint by
+/// swapping the last path segment, keeping any query string (e.g. Azure's
+/// `api-version`).
+fn derive_chat_completions_url(responses_url: &Url) -> Option<Url> {
+    let prefix = responses_url.path().strip_suffix("/responses")?;
+    let mut url = responses_url.clone();
+    url.set_path(&format!("{prefix}/chat/completions"));
+    Some(url)
+}
+
 /// Entry point for the library main, for parity with other crates.
 pub fn run_main(args: Args) -> Result<()> {
     let auth_header = read_auth_header_from_stdin()?;
 
-    let upstream_url = Url::parse(&args.upstream_url).context("parsing --upstream-url")?;
-    let host = match (upstream_url.host_str(), upstream_url.port()) {
-        (Some(host), Some(port)) => format!("{host}:{port}"),
-        (Some(host), None) => host.to_string(),
-        _ => return Err(anyhow!("upstream URL must include a host")),
+    let responses = Upstream::parse(&args.upstream_url, "--upstream-url")?;
+    let chat_completions = match args.chat_upstream_url.as_deref() {
+        Some(url) => Some(Upstream::parse(url, "--chat-upstream-url")?),
+        None => derive_chat_completions_url(&responses.url)
+            .map(|url| Upstream::parse(url.as_str(), "--upstream-url"))
+            .transpose()?,
+    };
+    let audit_log = match args.audit_log {
+        Some(path) => {
+            let redactor = Redactor::new(&args.audit_redact)?;
+            Some(Arc::new(AuditLog::new(
+                path,
+                args.audit_log_max_bytes,
+                args.audit_log_max_files,
+                redactor,
+            )?))
+        }
+        None => None,
     };
-    let host_header =
-        HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
 
     let forward_config = Arc::new(ForwardConfig {
-        upstream_url,
-        host_header,
+        responses,
+        chat_completions,
+        policy: RequestPolicy {
+            allowed_models: args.allowed_models,
+            max_output_tokens: args.max_output_tokens,
+        },
+        audit_log,
     });
 
     let (listener, bound_addr) = bind_listener(args.port)?;
@@ -146,22 +249,49 @@ fn forward_request(
     config: &ForwardConfig,
     mut req: Request,
 ) -> Result<()> {
-    // Only allow POST /v1/responses exactly, no query string.
+    // Only allow POST to the supported endpoints exactly, no query string.
     let method = req.method().clone();
-    let url_path = req.url().to_string();
-    let allow = method == Method::Post && url_path == "/v1/responses";
-
-    if !allow {
+    let route = Route::from_path(req.url());
+    let Some((route, upstream)) = route
+        .filter(|_| method == Method::Post)
+        .and_then(|route| Some((route, config.upstream(route)?)))
+    else {
         let resp = Response::new_empty(StatusCode(403));
         let _ = req.respond(resp);
         return Ok(());
-    }
+    };
+    let started = Instant::now();
 
     // Read request body
     let mut body = Vec::new();
     let mut reader = req.as_reader();
     std::io::Read::read_to_end(&mut reader, &mut body)?;
 
+    // The body only needs to be parsed when it is checked or logged.
+    let mut body_modified = false;
+    let mut request_json = Value::Null;
+    if config.policy.is_active() || config.audit_log.is_some() {
+        request_json = match serde_json::from_slice::<Value>(&body) {
+            Ok(value) => value,
+            Err(_) if !config.policy.is_active() => {
+                Value::String(String::from_utf8_lossy(&body).into_owned())
+            }
+            Err(err) => {
+                let message = format!("request body is not valid JSON: {err}");
+                return reject(config, route, req, Value::Null, started, message);
+            }
+        };
+        if config.policy.is_active() {
+            match config.policy.apply(route, &mut request_json) {
+                Ok(modified) => body_modified = modified,
+                Err(message) => return reject(config, route, req, request_json, started, message),
+            }
+        }
+    }
+    if body_modified {
+        body = serde_json::to_vec(&request_json)?;
+    }
+
     // Build headers for upstream, forwarding everything from the incoming
     // request except Authorization (we replace it below).
     let mut headers = HeaderMap::new();
@@ -171,6 +301,10 @@ fn forward_request(
         if lower.as_str() == "authorization" || lower.as_str() == "host" {
             continue;
         }
+        // reqwest sets the length of the (possibly rewritten) body itself.
+        if body_modified && lower.as_str() == "content-length" {
+            continue;
+        }
 
         let header_name = match HeaderName::from_bytes(lower.as_bytes()) {
             Ok(name) => name,
@@ -187,14 +321,24 @@ fn forward_request(
     auth_header_value.set_sensitive(true);
     headers.insert(AUTHORIZATION, auth_header_value);
 
-    headers.insert(HOST, config.host_header.clone());
+    headers.insert(HOST, upstream.host_header.clone());
 
-    let upstream_resp = client
-        .post(config.upstream_url.clone())
+    let upstream_resp = match client
+        .post(upstream.url.clone())
         .headers(headers)
         .body(body)
         .send()
-        .context("forwarding request to upstream")?;
+    {
+        Ok(resp) => resp,
+        Err(err) => {
+            if let Some(audit_log) = config.audit_log.as_ref() {
+                let mut entry = AuditEntry::new(route.path(), request_json, started);
+                entry.error = Some(err.to_string());
+                audit_log.record(entry);
+            }
+            return Err(err).context("forwarding request to upstream");
+        }
+    };
 
     // We have to create an adapter between a `reqwest::blocking::Response`
     // and a `tiny_http::Response`. Fortunately, `reqwest::blocking::Response`
@@ -224,10 +368,26 @@ fn forward_request(
         }
     });
 
+    // With an audit log, tee the body so the exchange is recorded once the
+    // client has received all of it (or the connection is dropped).
+    let body: Box<dyn std::io::Read> = match config.audit_log.clone() {
+        Some(audit_log) => Box::new(AuditedBody::new(
+            upstream_resp,
+            Box::new(move |captured, truncated| {
+                let mut entry = AuditEntry::new(route.path(), request_json, started);
+                entry.status = Some(status.as_u16());
+                entry.response = response_body_value(&captured);
+                entry.response_truncated = truncated;
+                audit_log.record(entry);
+            }),
+        )),
+        None => Box::new(upstream_resp),
+    };
+
     let response = Response::new(
         StatusCode(status.as_u16()),
         response_headers,
-        upstream_resp,
+        body,
         content_length,
         None,
     );
@@ -235,3 +395,58 @@ fn forward_request(
     let _ = req.respond(response);
     Ok(())
 }
+
+/// Responds with an OpenAI-style `400` error without contacting upstream.
+fn reject(
+    config: &ForwardConfig,
+    route: Route,
+    req: Request,
+    request_json: Value,
+    started: Instant,
+    message: String,
+) -> Result<()> {
+    if let Some(audit_log) = config.audit_log.as_ref() {
+        let mut entry = AuditEntry::new(route.path(), request_json, started);
+        entry.status = Some(400);
+        entry.rejected = Some(message.clone());
+        audit_log.record(entry);
+    }
+
+    let body = json!({
+        "error": {
+            "message": message,
+            "type": "invalid_request_error",
+            "code": "rejected_by_proxy",
+        }
+    });
+    let mut response = Response::from_data(serde_json::to_vec(&body)?).with_status_code(400);
+    if let Ok(header) = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]) {
+        response.add_header(header);
+    }
+    let _ = req.respond(response);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn derives_chat_completions_url_from_responses_url() {
+        let azure = Url::parse(
+            "https://example.openai.azure.com/openai/deployments/gpt/responses?api-version=2025-04-01-preview",
+        )
+        .expect("valid url");
+        assert_eq!(
+            derive_chat_completions_url(&azure).map(String::from),
+            Some(
+                "https://example.openai.azure.com/openai/deployments/gpt/chat/completions?api-version=2025-04-01-preview"
+                    .to_string()
+            )
+        );
+
+        let custom = Url::parse("https://proxy.example.com/generate").expect("valid url");
+        assert_eq!(derive_chat_completions_url(&custom), None);
+    }
+}
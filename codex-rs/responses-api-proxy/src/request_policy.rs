@@ -0,0 +1,142 @@
+use serde_json::Value;
+
+/// Endpoints the proxy forwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Route {
+    Responses,
+    ChatCompletions,
+}
+
+impl Route {
+    pub(crate) fn from_path(path: &str) -> Option<Self> {
+        match path {
+            "/v1/responses" => Some(Self::Responses),
+            "/v1/chat/completions" => Some(Self::ChatCompletions),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn path(self) -> &'static str {
+        match self {
+            Self::Responses => "/v1/responses",
+            Self::ChatCompletions => "/v1/chat/completions",
+        }
+    }
+
+    /// Request fields that bound the number of output tokens. The first one
+    /// is set when the request does not specify any.
+    fn output_token_fields(self) -> &'static [&'static str] {
+        match self {
+            Self::Responses => &["max_output_tokens"],
+            Self::ChatCompletions => &["max_completion_tokens", "max_tokens"],
+        }
+    }
+}
+
+/// Limits applied to every request body before it is forwarded.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestPolicy {
+    /// When non-empty, only these models may be requested.
+    pub(crate) allowed_models: Vec<String>,
+    pub(crate) max_output_tokens: Option<u64>,
+}
+
+impl RequestPolicy {
+    pub(crate) fn is_active(&self) -> bool {
+        !self.allowed_models.is_empty() || self.max_output_tokens.is_some()
+    }
+
+    /// Checks `body` against the policy, adding the output token cap when the
+    /// request does not set one. Returns whether `body` was modified, or the
+    /// reason the request must be rejected.
+    pub(crate) fn apply(&self, route: Route, body: &mut Value) -> Result<bool, String> {
+        let Some(request) = body.as_object_mut() else {
+            return Err("request body must be a JSON object".to_string());
+        };
+
+        if !self.allowed_models.is_empty() {
+            let model = request.get("model").and_then(Value::as_str);
+            if !model.is_some_and(|model| self.allowed_models.iter().any(|m| m == model)) {
+                return Err(format!(
+                    "model `{}` is not allowed by this proxy; allowed models: {}",
+                    model.unwrap_or_default(),
+                    self.allowed_models.join(", ")
+                ));
+            }
+        }
+
+        let Some(cap) = self.max_output_tokens else {
+            return Ok(false);
+        };
+        let mut capped = false;
+        for field in route.output_token_fields() {
+            let Some(value) = request.get(*field) else {
+                continue;
+            };
+            match value.as_u64() {
+                Some(requested) if requested <= cap => capped = true,
+                _ => {
+                    return Err(format!(
+                        "`{field}` must be at most {cap} (requested {value})"
+                    ));
+                }
+            }
+        }
+        if capped {
+            return Ok(false);
+        }
+        let field = route.output_token_fields()[0];
+        request.insert(field.to_string(), Value::from(cap));
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn policy() -> RequestPolicy {
+        RequestPolicy {
+            allowed_models: vec!["gpt-5".to_string(), "gpt-5-codex".to_string()],
+            max_output_tokens: Some(4096),
+        }
+    }
+
+    #[test]
+    fn rejects_models_outside_the_allowlist() {
+        let mut body = json!({"model": "o3", "input": []});
+
+        assert_eq!(
+            policy().apply(Route::Responses, &mut body),
+            Err(
+                "model `o3` is not allowed by this proxy; allowed models: gpt-5, gpt-5-codex"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn caps_output_tokens_when_unset() {
+        let mut body = json!({"model": "gpt-5-codex", "messages": []});
+
+        assert_eq!(policy().apply(Route::ChatCompletions, &mut body), Ok(true));
+        assert_eq!(
+            body,
+            json!({"model": "gpt-5-codex", "messages": [], "max_completion_tokens": 4096})
+        );
+    }
+
+    #[test]
+    fn rejects_output_tokens_above_cap() {
+        let mut within = json!({"model": "gpt-5", "max_output_tokens": 1024});
+        assert_eq!(policy().apply(Route::Responses, &mut within), Ok(false));
+
+        let mut above = json!({"model": "gpt-5", "max_tokens": 100000});
+        assert_eq!(
+            policy().apply(Route::ChatCompletions, &mut above),
+            Err("`max_tokens` must be at most 4096 (requested 100000)".to_string())
+        );
+    }
+}
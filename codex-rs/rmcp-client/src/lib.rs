@@ -18,3 +18,5 @@ pub(crate) use oauth::load_oauth_tokens;
 pub use oauth::save_oauth_tokens;
 pub use perform_oauth_login::perform_oauth_login;
 pub use rmcp_client::RmcpClient;
+pub use rmcp_client::StdioLauncher;
+pub use utils::create_env_for_mcp_server;
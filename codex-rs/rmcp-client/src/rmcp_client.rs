@@ -77,6 +77,16 @@ enum ClientState {
     },
 }
 
+/// Wraps the command of a stdio MCP server: `program` is executed with
+/// `args`, followed by the resolved server command and its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdioLauncher {
+    pub program: PathBuf,
+    /// `argv[0]` for `program`, for multi-call binaries that dispatch on it.
+    pub arg0: Option<String>,
+    pub args: Vec<String>,
+}
+
 /// MCP client implemented on top of the official `rmcp` SDK.
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
@@ -91,15 +101,44 @@ impl RmcpClient {
         env_vars: &[String],
         cwd: Option<PathBuf>,
     ) -> io::Result<Self> {
-        let program_name = program.to_string_lossy().into_owned();
-
         // Build environment for program resolution and subprocess
         let envs = create_env_for_mcp_server(env, env_vars);
+        Self::new_stdio_client_with_launcher(program, args, envs, cwd, None).await
+    }
+
+    /// Like [`RmcpClient::new_stdio_client`], but the caller provides the
+    /// complete environment for the server and may wrap the server command in
+    /// a [`StdioLauncher`] (e.g. a sandbox helper).
+    pub async fn new_stdio_client_with_launcher(
+        program: OsString,
+        args: Vec<OsString>,
+        envs: HashMap<String, String>,
+        cwd: Option<PathBuf>,
+        launcher: Option<StdioLauncher>,
+    ) -> io::Result<Self> {
+        let program_name = program.to_string_lossy().into_owned();
 
         // Resolve program to executable path (platform-specific)
         let resolved_program = program_resolver::resolve(program, &envs)?;
 
-        let mut command = Command::new(resolved_program);
+        let mut command = match launcher {
+            Some(StdioLauncher {
+                program: launcher_program,
+                arg0,
+                args: launcher_args,
+            }) => {
+                let mut command = Command::new(launcher_program);
+                #[cfg(unix)]
+                if let Some(arg0) = arg0 {
+                    command.arg0(arg0);
+                }
+                #[cfg(not(unix))]
+                let _ = arg0;
+                command.args(launcher_args).arg(resolved_program);
+                command
+            }
+            None => Command::new(resolved_program),
+        };
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -72,7 +72,9 @@ where
     serde_json::from_value(json).map_err(|err| anyhow!(err))
 }
 
-pub(crate) fn create_env_for_mcp_server(
+/// Environment for a stdio MCP server: a small set of core variables from the
+/// current process, the variables named in `env_vars`, and `extra_env`.
+pub fn create_env_for_mcp_server(
     extra_env: Option<HashMap<String, String>>,
     env_vars: &[String],
 ) -> HashMap<String, String> {
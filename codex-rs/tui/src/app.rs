@@ -13,6 +13,7 @@ use crate::pager_overlay::Overlay;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::Renderable;
 use crate::resume_picker::ResumeSelection;
+use crate::snapshot_timeline;
 use crate::tui;
 use crate::tui::TuiEvent;
 use crate::update_action::UpdateAction;
@@ -23,7 +24,6 @@ use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::config::edit::ConfigEditsBuilder;
-use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::FinalOutput;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
@@ -120,7 +120,7 @@ async fn handle_model_migration_prompt_if_needed(
                     migration_config: "hide_gpt5_1_migration_prompt".to_string(),
                 });
                 config.model = target_model.to_string();
-                if let Some(family) = find_family_for_model(&target_model) {
+                if let Some(family) = config.model_family_for(&target_model) {
                     config.model_family = family;
                 }
 
@@ -166,6 +166,8 @@ pub(crate) struct App {
     /// Config is stored here so we can recreate ChatWidgets as needed.
     pub(crate) config: Config,
     pub(crate) active_profile: Option<String>,
+    /// JSON schema from `--output-schema`, applied to every turn.
+    pub(crate) output_schema: Option<serde_json::Value>,
 
     pub(crate) file_search: FileSearchManager,
 
@@ -200,6 +202,7 @@ impl App {
         active_profile: Option<String>,
         initial_prompt: Option<String>,
         initial_images: Vec<PathBuf>,
+        output_schema: Option<serde_json::Value>,
         resume_selection: ResumeSelection,
         feedback: codex_feedback::CodexFeedback,
     ) -> Result<AppExitInfo> {
@@ -228,6 +231,7 @@ impl App {
                     app_event_tx: app_event_tx.clone(),
                     initial_prompt: initial_prompt.clone(),
                     initial_images: initial_images.clone(),
+                    output_schema: output_schema.clone(),
                     enhanced_keys_supported,
                     auth_manager: auth_manager.clone(),
                     feedback: feedback.clone(),
@@ -251,6 +255,7 @@ impl App {
                     app_event_tx: app_event_tx.clone(),
                     initial_prompt: initial_prompt.clone(),
                     initial_images: initial_images.clone(),
+                    output_schema: output_schema.clone(),
                     enhanced_keys_supported,
                     auth_manager: auth_manager.clone(),
                     feedback: feedback.clone(),
@@ -274,6 +279,7 @@ impl App {
             auth_manager: auth_manager.clone(),
             config,
             active_profile,
+            output_schema,
             file_search,
             enhanced_keys_supported,
             transcript_cells: Vec::new(),
@@ -399,6 +405,7 @@ impl App {
                     app_event_tx: self.app_event_tx.clone(),
                     initial_prompt: None,
                     initial_images: Vec::new(),
+                    output_schema: self.output_schema.clone(),
                     enhanced_keys_supported: self.enhanced_keys_supported,
                     auth_manager: self.auth_manager.clone(),
                     feedback: self.feedback.clone(),
@@ -502,7 +509,7 @@ impl App {
             AppEvent::UpdateModel(model) => {
                 self.chat_widget.set_model(&model);
                 self.config.model = model.clone();
-                if let Some(family) = find_family_for_model(&model) {
+                if let Some(family) = self.config.model_family_for(&model) {
                     self.config.model_family = family;
                 }
             }
@@ -696,6 +703,20 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::OpenSnapshot(snapshot) => {
+                self.chat_widget.show_snapshot_actions(snapshot);
+            }
+            AppEvent::OpenSnapshotFiles(snapshot) => {
+                self.chat_widget.show_snapshot_files(snapshot);
+            }
+            AppEvent::ShowSnapshotDiff(snapshot) => {
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_static_with_lines(
+                    snapshot_timeline::snapshot_diff_lines(&snapshot),
+                    "S N A P S H O T".to_string(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -880,6 +901,7 @@ mod tests {
             auth_manager,
             config,
             active_profile: None,
+            output_schema: None,
             file_search,
             transcript_cells: Vec::new(),
             overlay: None,
@@ -4,6 +4,7 @@ use codex_common::approval_presets::ApprovalPreset;
 use codex_common::model_presets::ModelPreset;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::SnapshotSummary;
 use codex_file_search::FileMatch;
 
 use crate::bottom_pane::ApprovalRequest;
@@ -141,6 +142,15 @@ pub(crate) enum AppEvent {
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt,
 
+    /// Open the actions for a snapshot picked from the `/timeline` popup.
+    OpenSnapshot(SnapshotSummary),
+
+    /// Open the per-file restore picker for a snapshot.
+    OpenSnapshotFiles(SnapshotSummary),
+
+    /// Show the diff between a snapshot and the working tree in a pager.
+    ShowSnapshotDiff(SnapshotSummary),
+
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
@@ -16,6 +16,7 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
@@ -26,19 +27,24 @@ use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::ListSnapshotsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PromptCacheStats;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::SnapshotSummary;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::TokenCost;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnProfileEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::UserMessageEvent;
@@ -48,6 +54,7 @@ use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_extensions::execute_user_prompt_submit_hooks;
 use codex_protocol::ConversationId;
+use codex_protocol::num_format::format_with_separators;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
@@ -100,6 +107,7 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
+use crate::snapshot_timeline;
 use crate::status::RateLimitSnapshotDisplay;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
@@ -123,6 +131,7 @@ use codex_core::ConversationManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::review_prompts;
 use codex_file_search::FileMatch;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use strum::IntoEnumIterator;
@@ -232,6 +241,7 @@ pub(crate) struct ChatWidgetInit {
     pub(crate) app_event_tx: AppEventSender,
     pub(crate) initial_prompt: Option<String>,
     pub(crate) initial_images: Vec<PathBuf>,
+    pub(crate) output_schema: Option<serde_json::Value>,
     pub(crate) enhanced_keys_supported: bool,
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) feedback: codex_feedback::CodexFeedback,
@@ -255,6 +265,16 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    // Estimated spend from the latest TokenCount event, if the model is priced.
+    token_cost: Option<TokenCost>,
+    // Prompt cache hit rates from the latest TokenCount event.
+    prompt_cache: Option<PromptCacheStats>,
+    // Timing breakdown of the last completed turn, shown by `/profile`.
+    last_turn_profile: Option<TurnProfileEvent>,
+    // Schema every turn's final message must follow (`--output-schema`). When
+    // set, agent messages are rendered as structured output instead of being
+    // streamed as markdown.
+    output_schema: Option<serde_json::Value>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     rate_limit_warnings: RateLimitWarningState,
     rate_limit_switch_prompt: RateLimitSwitchPromptState,
@@ -281,6 +301,8 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
+    // Text of the latest user message the session recorded, for /pin.
+    last_user_message: Option<String>,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
     // Simple review mode flag; used to adjust layout and banners.
@@ -464,6 +486,15 @@ impl ChatWidget {
     }
 
     fn on_agent_message(&mut self, message: String) {
+        if let Some(schema) = self.active_output_schema() {
+            let cell = history_cell::new_structured_output(&message, schema);
+            self.flush_active_cell();
+            self.add_final_message_separator_if_needed();
+            self.add_to_history(cell);
+            self.handle_stream_finished();
+            self.request_redraw();
+            return;
+        }
         // If we have a stream_controller, then the final agent message is redundant and will be a
         // duplicate of what has already been streamed.
         if self.stream_controller.is_none() {
@@ -475,6 +506,10 @@ impl ChatWidget {
     }
 
     fn on_agent_message_delta(&mut self, delta: String) {
+        // Structured output is rendered in full once the message completes.
+        if self.active_output_schema().is_some() {
+            return;
+        }
         self.handle_streaming_delta(delta);
     }
 
@@ -796,6 +831,45 @@ impl ChatWidget {
         self.set_status_header(message);
     }
 
+    fn on_context_compacted(&mut self, event: ContextCompactedEvent) {
+        let ContextCompactedEvent {
+            strategy,
+            tokens_before,
+            tokens_after,
+            pinned_messages,
+            pinned_files,
+        } = event;
+        let message = format!(
+            "Context compacted with {strategy}: {} → {} tokens",
+            format_with_separators(tokens_before),
+            format_with_separators(tokens_after)
+        );
+        let hint = (pinned_messages > 0 || !pinned_files.is_empty()).then(|| {
+            format!(
+                "Kept {pinned_messages} pinned message{} and {} pinned file{}",
+                if pinned_messages == 1 { "" } else { "s" },
+                pinned_files.len(),
+                if pinned_files.len() == 1 { "" } else { "s" }
+            )
+        });
+        self.add_info_message(message, hint);
+    }
+
+    fn pin_last_user_message(&mut self) {
+        let Some(text) = self.last_user_message.clone() else {
+            self.add_info_message(
+                "Nothing to pin yet.".to_string(),
+                Some("/pin pins your last message so compaction keeps it.".to_string()),
+            );
+            return;
+        };
+        self.submit_op(Op::PinMessage { text });
+        self.add_info_message(
+            "Pinned your last message.".to_string(),
+            Some("It will be kept verbatim whenever the conversation is compacted.".to_string()),
+        );
+    }
+
     fn on_undo_started(&mut self, event: UndoStartedEvent) {
         self.bottom_pane.ensure_status_indicator();
         self.bottom_pane.set_interrupt_hint_visible(false);
@@ -875,20 +949,30 @@ impl ChatWidget {
         self.flush_interrupt_queue();
     }
 
+    fn add_final_message_separator_if_needed(&mut self) {
+        if self.needs_final_message_separator {
+            let elapsed_seconds = self
+                .bottom_pane
+                .status_widget()
+                .map(super::status_indicator_widget::StatusIndicatorWidget::elapsed_seconds);
+            self.add_to_history(history_cell::FinalMessageSeparator::new(elapsed_seconds));
+            self.needs_final_message_separator = false;
+        }
+    }
+
+    /// Schema the current turn's answer must follow. Review turns are never
+    /// submitted with one.
+    fn active_output_schema(&self) -> Option<&serde_json::Value> {
+        self.output_schema.as_ref().filter(|_| !self.is_review_mode)
+    }
+
     #[inline]
     fn handle_streaming_delta(&mut self, delta: String) {
         // Before streaming agent content, flush any active exec cell group.
         self.flush_active_cell();
 
         if self.stream_controller.is_none() {
-            if self.needs_final_message_separator {
-                let elapsed_seconds = self
-                    .bottom_pane
-                    .status_widget()
-                    .map(super::status_indicator_widget::StatusIndicatorWidget::elapsed_seconds);
-                self.add_to_history(history_cell::FinalMessageSeparator::new(elapsed_seconds));
-                self.needs_final_message_separator = false;
-            }
+            self.add_final_message_separator_if_needed();
             self.stream_controller = Some(StreamController::new(
                 self.last_rendered_width.get().map(|w| w.saturating_sub(2)),
             ));
@@ -1059,6 +1143,7 @@ impl ChatWidget {
             invocation,
             duration,
             result,
+            ..
         } = ev;
 
         let extra_cell = match self
@@ -1092,6 +1177,7 @@ impl ChatWidget {
             app_event_tx,
             initial_prompt,
             initial_images,
+            output_schema,
             enhanced_keys_supported,
             auth_manager,
             feedback,
@@ -1121,6 +1207,10 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            token_cost: None,
+            last_turn_profile: None,
+            prompt_cache: None,
+            output_schema,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             rate_limit_switch_prompt: RateLimitSwitchPromptState::default(),
@@ -1134,6 +1224,7 @@ impl ChatWidget {
             retry_status_header: None,
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            last_user_message: None,
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
             pending_notification: None,
@@ -1157,6 +1248,7 @@ impl ChatWidget {
             app_event_tx,
             initial_prompt,
             initial_images,
+            output_schema,
             enhanced_keys_supported,
             auth_manager,
             feedback,
@@ -1188,6 +1280,10 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            token_cost: None,
+            last_turn_profile: None,
+            prompt_cache: None,
+            output_schema,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             rate_limit_switch_prompt: RateLimitSwitchPromptState::default(),
@@ -1201,6 +1297,7 @@ impl ChatWidget {
             retry_status_header: None,
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            last_user_message: None,
             show_welcome_banner: true,
             suppress_session_configured_redraw: true,
             pending_notification: None,
@@ -1338,6 +1435,9 @@ impl ChatWidget {
                 self.clear_token_usage();
                 self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
             }
+            SlashCommand::Pin => {
+                self.pin_last_user_message();
+            }
             SlashCommand::Review => {
                 self.open_review_popup();
             }
@@ -1362,6 +1462,9 @@ impl ChatWidget {
             SlashCommand::Undo => {
                 self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
             }
+            SlashCommand::Timeline => {
+                self.submit_op(Op::ListSnapshots);
+            }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
@@ -1385,6 +1488,11 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Profile => {
+                self.add_to_history(history_cell::new_turn_profile_output(
+                    self.last_turn_profile.as_ref(),
+                ));
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
@@ -1530,7 +1638,10 @@ impl ChatWidget {
 
         if text.is_empty() {
             self.codex_op_tx
-                .send(Op::UserInput { items })
+                .send(Op::UserInput {
+                    items,
+                    final_output_json_schema: self.output_schema.clone(),
+                })
                 .unwrap_or_else(|e| {
                     tracing::error!("failed to send message: {e}");
                 });
@@ -1549,6 +1660,7 @@ impl ChatWidget {
 
         let items_for_async = items.clone();
         let text_for_async = text.clone();
+        let output_schema = self.output_schema.clone();
 
         let spawn_result = Handle::try_current().map(|handle| {
             handle.spawn(async move {
@@ -1602,6 +1714,7 @@ impl ChatWidget {
 
                 if let Err(e) = op_tx.send(Op::UserInput {
                     items: updated_items,
+                    final_output_json_schema: output_schema,
                 }) {
                     tracing::error!("failed to send message: {e}");
                 }
@@ -1619,7 +1732,10 @@ impl ChatWidget {
 
         if spawn_result.is_err() {
             tracing::warn!("Skipping UserPromptSubmit hooks: no Tokio runtime available");
-            if let Err(e) = self.codex_op_tx.send(Op::UserInput { items }) {
+            if let Err(e) = self.codex_op_tx.send(Op::UserInput {
+                items,
+                final_output_json_schema: self.output_schema.clone(),
+            }) {
                 tracing::error!("failed to send message: {e}");
             }
             self.codex_op_tx
@@ -1686,8 +1802,15 @@ impl ChatWidget {
             EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
                 self.on_task_complete(last_agent_message)
             }
+            EventMsg::TurnProfile(ev) => self.last_turn_profile = Some(ev),
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
+                if ev.cost.is_some() {
+                    self.token_cost = ev.cost;
+                }
+                if ev.prompt_cache.is_some() {
+                    self.prompt_cache = ev.prompt_cache;
+                }
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
@@ -1702,6 +1825,8 @@ impl ChatWidget {
                 TurnAbortReason::ReviewEnded => {
                     self.on_interrupted_turn(ev.reason);
                 }
+                // The preceding Error event already explained which budget ran out.
+                TurnAbortReason::BudgetExceeded => self.finalize_turn(),
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => {
@@ -1724,16 +1849,19 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListSnapshotsResponse(ev) => self.on_list_snapshots(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
+            EventMsg::ContextCompacted(ev) => self.on_context_compacted(ev),
             EventMsg::UndoStarted(ev) => self.on_undo_started(ev),
             EventMsg::UndoCompleted(ev) => self.on_undo_completed(ev),
             EventMsg::StreamError(StreamErrorEvent { message }) => self.on_stream_error(message),
             EventMsg::UserMessage(ev) => {
+                self.last_user_message = Some(ev.message.clone());
                 if from_replay {
                     self.on_user_message_event(ev);
                 }
@@ -1742,7 +1870,9 @@ impl ChatWidget {
                 self.on_entered_review_mode(review_request)
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
-            EventMsg::RawResponseItem(_)
+            // `/pin` already confirmed the pin when it was submitted.
+            EventMsg::MessagePinned(_)
+            | EventMsg::RawResponseItem(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -1884,6 +2014,8 @@ impl ChatWidget {
             self.auth_manager.as_ref(),
             total_usage,
             context_usage,
+            self.token_cost.as_ref(),
+            self.prompt_cache.as_ref(),
             &self.conversation_id,
             self.rate_limit_snapshot.as_ref(),
             Local::now(),
@@ -2743,16 +2875,11 @@ impl ChatWidget {
 
         items.push(SelectionItem {
             name: "Review uncommitted changes".to_string(),
-            actions: vec![Box::new(
-                move |tx: &AppEventSender| {
-                    tx.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
-                            user_facing_hint: "current changes".to_string(),
-                        },
-                    }));
-                },
-            )],
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::Review {
+                    review_request: review_prompts::uncommitted_changes_review_request(),
+                }));
+            })],
             dismiss_on_select: true,
             ..Default::default()
         });
@@ -2800,12 +2927,7 @@ impl ChatWidget {
                 name: format!("{current_branch} -> {branch}"),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: format!(
-                                "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
-                            ),
-                            user_facing_hint: format!("changes against '{branch}'"),
-                        },
+                        review_request: review_prompts::base_branch_review_request(&branch),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2824,6 +2946,28 @@ impl ChatWidget {
         });
     }
 
+    fn on_list_snapshots(&mut self, ev: ListSnapshotsResponseEvent) {
+        if ev.snapshots.is_empty() {
+            self.add_info_message(
+                "No snapshots in this session yet.".to_string(),
+                Some("Codex snapshots the working tree at the start of each turn.".to_string()),
+            );
+            return;
+        }
+        self.bottom_pane
+            .show_selection_view(snapshot_timeline::timeline_params(ev.snapshots));
+    }
+
+    pub(crate) fn show_snapshot_actions(&mut self, snapshot: SnapshotSummary) {
+        self.bottom_pane
+            .show_selection_view(snapshot_timeline::snapshot_actions_params(snapshot));
+    }
+
+    pub(crate) fn show_snapshot_files(&mut self, snapshot: SnapshotSummary) {
+        self.bottom_pane
+            .show_selection_view(snapshot_timeline::snapshot_files_params(snapshot));
+    }
+
     pub(crate) async fn show_review_commit_picker(&mut self, cwd: &Path) {
         let commits = codex_core::git_info::recent_commits(cwd, 100).await;
 
@@ -2831,21 +2975,13 @@ impl ChatWidget {
         for entry in commits {
             let subject = entry.subject.clone();
             let sha = entry.sha.clone();
-            let short = sha.chars().take(7).collect::<String>();
             let search_val = format!("{subject} {sha}");
 
             items.push(SelectionItem {
                 name: subject.clone(),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
-                    let hint = format!("commit {short}");
-                    let prompt = format!(
-                        "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                    );
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt,
-                            user_facing_hint: hint,
-                        },
+                        review_request: review_prompts::commit_review_request(&sha, Some(&subject)),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2879,6 +3015,7 @@ impl ChatWidget {
                     review_request: ReviewRequest {
                         prompt: trimmed.clone(),
                         user_facing_hint: trimmed,
+                        rubrics: Vec::new(),
                     },
                 }));
             }),
@@ -3067,6 +3204,7 @@ pub(crate) fn show_review_commit_picker_with_entries(
                     review_request: ReviewRequest {
                         prompt,
                         user_facing_hint: hint,
+                        rubrics: Vec::new(),
                     },
                 }));
             })],
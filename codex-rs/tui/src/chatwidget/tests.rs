@@ -19,6 +19,7 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -27,6 +28,7 @@ use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::ListSnapshotsResponseEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
@@ -44,6 +46,7 @@ use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
@@ -210,6 +213,7 @@ fn entered_review_mode_uses_request_hint() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             prompt: "Review the latest changes".to_string(),
             user_facing_hint: "feature branch".to_string(),
+            rubrics: Vec::new(),
         }),
     });
 
@@ -229,6 +233,7 @@ fn entered_review_mode_defaults_to_current_changes_banner() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             prompt: "Review the current changes".to_string(),
             user_facing_hint: "current changes".to_string(),
+            rubrics: Vec::new(),
         }),
     });
 
@@ -254,6 +259,7 @@ fn exited_review_mode_emits_results_and_finishes() {
                 absolute_file_path: PathBuf::from("src/lib.rs"),
                 line_range: ReviewLineRange { start: 10, end: 12 },
             },
+            category: None,
         }],
         overall_correctness: "needs work".to_string(),
         overall_explanation: "Investigate the failure".to_string(),
@@ -292,6 +298,7 @@ async fn helpers_are_available_and_do_not_panic() {
         app_event_tx: tx,
         initial_prompt: None,
         initial_images: Vec::new(),
+        output_schema: None,
         enhanced_keys_supported: false,
         auth_manager,
         feedback: codex_feedback::CodexFeedback::new(),
@@ -330,6 +337,10 @@ fn make_chatwidget_manual() -> (
         session_header: SessionHeader::new(cfg.model),
         initial_user_message: None,
         token_info: None,
+        token_cost: None,
+        last_turn_profile: None,
+        prompt_cache: None,
+        output_schema: None,
         rate_limit_snapshot: None,
         rate_limit_warnings: RateLimitWarningState::default(),
         rate_limit_switch_prompt: RateLimitSwitchPromptState::default(),
@@ -345,6 +356,7 @@ fn make_chatwidget_manual() -> (
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
+        last_user_message: None,
         suppress_session_configured_redraw: false,
         pending_notification: None,
         is_review_mode: false,
@@ -1066,6 +1078,89 @@ fn slash_undo_sends_op() {
     }
 }
 
+#[test]
+fn slash_timeline_requests_snapshots() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Timeline);
+
+    assert_matches!(op_rx.try_recv(), Ok(Op::ListSnapshots));
+}
+
+#[test]
+fn empty_snapshot_list_reports_info_message() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::ListSnapshotsResponse(ListSnapshotsResponseEvent {
+            snapshots: Vec::new(),
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    let text = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        text.contains("No snapshots in this session yet."),
+        "unexpected history: {text}"
+    );
+}
+
+#[test]
+fn slash_pin_pins_last_user_message() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Pin);
+    assert!(op_rx.try_recv().is_err(), "nothing should be pinned yet");
+    let _ = drain_insert_history(&mut rx);
+
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::UserMessage(UserMessageEvent {
+            message: "never touch the generated code".to_string(),
+            images: None,
+        }),
+    });
+    chat.dispatch_command(SlashCommand::Pin);
+
+    assert_matches!(
+        op_rx.try_recv(),
+        Ok(Op::PinMessage { text }) if text == "never touch the generated code"
+    );
+}
+
+#[test]
+fn context_compacted_reports_strategy_and_pins() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::ContextCompacted(ContextCompactedEvent {
+            strategy: CompactionStrategy::DropOldToolOutputs,
+            tokens_before: 120_000,
+            tokens_after: 40_000,
+            pinned_messages: 1,
+            pinned_files: vec![PathBuf::from("NOTES.md")],
+        }),
+    });
+
+    let text = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        text.contains("Context compacted with drop-old-tool-outputs: 120,000 → 40,000 tokens"),
+        "unexpected history: {text}"
+    );
+    assert!(
+        text.contains("Kept 1 pinned message and 1 pinned file"),
+        "unexpected history: {text}"
+    );
+}
+
 #[test]
 fn slash_rollout_displays_current_path() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -2759,6 +2854,42 @@ fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
     assert!(first_idx < second_idx, "messages out of order: {combined}");
 }
 
+#[test]
+fn output_schema_renders_agent_message_as_structured_output() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.output_schema = Some(serde_json::json!({
+        "type": "object",
+        "required": ["summary"]
+    }));
+
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "{\"summary\":".into(),
+        }),
+    });
+    assert!(chat.stream_controller.is_none());
+
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "{\"summary\": \"done\"}".into(),
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one structured output cell");
+    let rendered = lines_to_single_string(&cells[0]);
+    assert!(
+        rendered.contains("Structured output ✓ matches output schema"),
+        "structured output cell missing status: {rendered}"
+    );
+    assert!(
+        rendered.contains("\"summary\": \"done\""),
+        "structured output cell missing JSON: {rendered}"
+    );
+}
+
 #[test]
 fn final_reasoning_then_message_without_deltas_are_rendered() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
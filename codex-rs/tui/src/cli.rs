@@ -33,8 +33,8 @@ pub struct Cli {
     pub model: Option<String>,
 
     /// Convenience flag to select the local open source model provider.
-    /// Equivalent to -c model_provider=oss; verifies a local Ollama server is
-    /// running.
+    /// Equivalent to -c model_provider=oss; verifies a local Ollama, llama.cpp
+    /// or vLLM server is running and that the model can call tools.
     #[arg(long = "oss", default_value_t = false)]
     pub oss: bool,
 
@@ -77,6 +77,10 @@ pub struct Cli {
     #[arg(long = "add-dir", value_name = "DIR", value_hint = ValueHint::DirPath)]
     pub add_dir: Vec<PathBuf>,
 
+    /// Path to a JSON Schema file that the final response of every turn must follow.
+    #[arg(long = "output-schema", value_name = "FILE")]
+    pub output_schema: Option<PathBuf>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
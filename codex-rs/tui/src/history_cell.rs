@@ -22,6 +22,7 @@ use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use crate::wrapping::word_wrap_lines;
 use base64::Engine;
+use codex_common::elapsed::format_duration;
 use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
@@ -30,7 +31,9 @@ use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TurnProfileEvent;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::num_format::format_with_separators;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -1238,6 +1241,80 @@ pub(crate) fn new_mcp_tools_output(
 
     PlainHistoryCell { lines }
 }
+
+/// Render the timing breakdown of the last completed turn for `/profile`.
+pub(crate) fn new_turn_profile_output(profile: Option<&TurnProfileEvent>) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/profile".magenta().into(), "".into()];
+
+    let Some(profile) = profile else {
+        lines.push("  • No turn has completed yet.".italic().into());
+        lines.push("".into());
+        return PlainHistoryCell { lines };
+    };
+
+    lines.push(
+        vec![
+            "⏱  ".into(),
+            "Last turn".bold(),
+            format!(" took {}", format_millis(profile.duration_ms)).into(),
+        ]
+        .into(),
+    );
+    lines.push("".into());
+
+    if profile.phases.is_empty() {
+        lines.push("  • No timed phases ran.".italic().into());
+    }
+    let label_width = profile
+        .phases
+        .iter()
+        .map(|timing| timing.phase.label().width())
+        .max()
+        .unwrap_or(0);
+    for timing in &profile.phases {
+        let label = timing.phase.label();
+        let padding = " ".repeat(label_width - label.width());
+        let mut line: Vec<Span<'static>> = vec![
+            "  • ".into(),
+            format!("{label}{padding}  ").into(),
+            format_millis(timing.total_ms).into(),
+        ];
+        if timing.count > 1 {
+            line.push(
+                format!(
+                    " ({} times, longest {})",
+                    timing.count,
+                    format_millis(timing.max_ms)
+                )
+                .dim(),
+            );
+        }
+        lines.push(line.into());
+    }
+
+    let usage = &profile.token_usage;
+    if usage.total_tokens > 0 {
+        let mut summary = format!(
+            "{} tokens ({} input, {} output)",
+            format_with_separators(usage.blended_total()),
+            format_with_separators(usage.non_cached_input()),
+            format_with_separators(usage.output_tokens)
+        );
+        if let Some(cost_usd) = profile.cost_usd {
+            summary.push_str(&format!(" · ${cost_usd:.4}"));
+        }
+        lines.push("".into());
+        lines.push(vec!["  ".into(), summary.dim()].into());
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
+fn format_millis(ms: u64) -> String {
+    format_duration(Duration::from_millis(ms))
+}
+
 pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHistoryCell {
     let mut line = vec!["• ".dim(), message.into()];
     if let Some(hint) = hint {
@@ -1409,6 +1486,92 @@ pub(crate) fn new_reasoning_summary_block(
     ))
 }
 
+/// Lines of structured output shown before the rest is collapsed into the
+/// transcript (ctrl + t).
+const STRUCTURED_OUTPUT_MAX_LINES: usize = 12;
+
+/// A final answer produced under `--output-schema`, shown as pretty-printed
+/// JSON along with the result of validating it against the schema.
+#[derive(Debug)]
+pub(crate) struct StructuredOutputCell {
+    json_lines: Vec<String>,
+    /// Schema violations, or why the message could not be parsed as JSON.
+    problems: Vec<String>,
+    is_json: bool,
+}
+
+pub(crate) fn new_structured_output(
+    message: &str,
+    schema: &serde_json::Value,
+) -> StructuredOutputCell {
+    match serde_json::from_str::<serde_json::Value>(message) {
+        Ok(value) => {
+            let pretty = serde_json::to_string_pretty(&value).unwrap_or_else(|_| message.into());
+            StructuredOutputCell {
+                json_lines: pretty.lines().map(str::to_string).collect(),
+                problems: crate::output_schema::validate(schema, &value),
+                is_json: true,
+            }
+        }
+        Err(err) => StructuredOutputCell {
+            json_lines: message.lines().map(str::to_string).collect(),
+            problems: vec![err.to_string()],
+            is_json: false,
+        },
+    }
+}
+
+impl StructuredOutputCell {
+    fn lines(&self, max_json_lines: usize) -> Vec<Line<'static>> {
+        let status = if !self.is_json {
+            "✗ not valid JSON".red()
+        } else if self.problems.is_empty() {
+            "✓ matches output schema".green()
+        } else {
+            let count = self.problems.len();
+            let noun = if count == 1 {
+                "violation"
+            } else {
+                "violations"
+            };
+            format!("✗ {count} schema {noun}").red()
+        };
+        let mut lines: Vec<Line<'static>> =
+            vec![vec!["• ".dim(), "Structured output".bold(), " ".into(), status].into()];
+
+        lines.extend(prefix_lines(
+            self.problems
+                .iter()
+                .map(|problem| Line::from(problem.clone().red()))
+                .collect(),
+            "  └ ".dim(),
+            "    ".into(),
+        ));
+
+        let shown = self.json_lines.len().min(max_json_lines);
+        lines.extend(
+            self.json_lines[..shown]
+                .iter()
+                .map(|line| Line::from(format!("  {line}"))),
+        );
+        let omitted = self.json_lines.len() - shown;
+        if omitted > 0 {
+            lines.push(vec![format!("  … +{omitted} lines").dim()].into());
+        }
+        lines
+    }
+}
+
+impl HistoryCell for StructuredOutputCell {
+    fn display_lines(&self, _width: u16) -> Vec<Line<'static>> {
+        self.lines(STRUCTURED_OUTPUT_MAX_LINES)
+    }
+
+    fn transcript_lines(&self, _width: u16) -> Vec<Line<'static>> {
+        self.lines(usize::MAX)
+    }
+}
+
 #[derive(Debug)]
 pub struct FinalMessageSeparator {
     elapsed_seconds: Option<u64>,
@@ -1510,6 +1673,61 @@ mod tests {
         render_lines(&cell.transcript_lines(u16::MAX))
     }
 
+    #[test]
+    fn turn_profile_output_lists_phases() {
+        use codex_core::protocol::TokenUsage;
+        use codex_core::protocol::TurnPhase;
+        use codex_core::protocol::TurnPhaseTiming;
+
+        let profile = TurnProfileEvent {
+            duration_ms: 12_400,
+            phases: vec![
+                TurnPhaseTiming {
+                    phase: TurnPhase::ModelTimeToFirstToken,
+                    count: 2,
+                    total_ms: 1_500,
+                    max_ms: 900,
+                },
+                TurnPhaseTiming {
+                    phase: TurnPhase::Hook,
+                    count: 1,
+                    total_ms: 250,
+                    max_ms: 250,
+                },
+            ],
+            token_usage: TokenUsage {
+                input_tokens: 1_200,
+                cached_input_tokens: 200,
+                output_tokens: 300,
+                reasoning_output_tokens: 0,
+                total_tokens: 1_500,
+            },
+            cost_usd: Some(0.0123),
+        };
+
+        let cell = new_turn_profile_output(Some(&profile));
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec![
+                "/profile",
+                "",
+                "⏱  Last turn took 12.40s",
+                "",
+                "  • model time to first token  1.50s (2 times, longest 900ms)",
+                "  • hooks                      250ms",
+                "",
+                "  1,300 tokens (1,000 input, 300 output) · $0.0123",
+                "",
+            ]
+        );
+
+        let empty = new_turn_profile_output(None);
+        assert_eq!(
+            render_lines(&empty.display_lines(80)),
+            vec!["/profile", "", "  • No turn has completed yet.", ""]
+        );
+    }
+
     #[test]
     fn mcp_tools_output_masks_sensitive_values() {
         let mut config = test_config();
@@ -1528,6 +1746,10 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sandbox: None,
+            env_policy: None,
+            cache: None,
+            rate_limit: None,
         };
         config.mcp_servers.insert("docs".to_string(), stdio_config);
 
@@ -1547,6 +1769,10 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sandbox: None,
+            env_policy: None,
+            cache: None,
+            rate_limit: None,
         };
         config.mcp_servers.insert("http".to_string(), http_config);
 
@@ -1595,6 +1821,45 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn structured_output_reports_schema_violations() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "ok": { "type": "boolean" } },
+            "required": ["ok"]
+        });
+
+        let cell = new_structured_output(r#"{"ok": "yes"}"#, &schema);
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec![
+                "• Structured output ✗ 1 schema violation",
+                "  └ /ok: expected boolean, found string",
+                "  {",
+                "    \"ok\": \"yes\"",
+                "  }",
+            ]
+        );
+
+        let cell = new_structured_output("not json", &schema);
+        assert_eq!(
+            render_lines(&cell.display_lines(80))[0],
+            "• Structured output ✗ not valid JSON"
+        );
+    }
+
+    #[test]
+    fn structured_output_collapses_long_values_outside_the_transcript() {
+        let items: Vec<u32> = (0..20).collect();
+        let cell = new_structured_output(&json!({ "items": items }).to_string(), &json!({}));
+
+        let display = render_lines(&cell.display_lines(80));
+        assert_eq!(display[0], "• Structured output ✓ matches output schema");
+        assert_eq!(display.len(), STRUCTURED_OUTPUT_MAX_LINES + 2);
+        assert_eq!(display.last().map(String::as_str), Some("  … +12 lines"));
+        assert_eq!(render_transcript(&cell).len(), 25);
+    }
+
     #[test]
     fn empty_agent_message_cell_transcript() {
         let cell = AgentMessageCell::new(vec![Line::default()], false);
@@ -56,6 +56,7 @@ mod markdown_render;
 mod markdown_stream;
 mod model_migration;
 pub mod onboarding;
+mod output_schema;
 mod pager_overlay;
 pub mod public_widgets;
 mod render;
@@ -64,6 +65,7 @@ mod selection_list;
 mod session_log;
 mod shimmer;
 mod slash_command;
+mod snapshot_timeline;
 mod status;
 mod status_indicator_widget;
 mod streaming;
@@ -85,6 +87,7 @@ pub mod test_backend;
 use crate::onboarding::WSL_INSTRUCTIONS;
 use crate::onboarding::onboarding_screen::OnboardingScreenArgs;
 use crate::onboarding::onboarding_screen::run_onboarding_app;
+use crate::output_schema::load_output_schema;
 use crate::tui::Tui;
 pub use cli::Cli;
 pub use markdown_render::render_markdown_text;
@@ -182,6 +185,18 @@ pub async fn run_main(
         }
     }
 
+    let output_schema = match cli.output_schema.as_deref().map(load_output_schema) {
+        Some(Ok(schema)) => Some(schema),
+        Some(Err(err)) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        }
+        None => None,
+    };
+
     #[allow(clippy::print_stderr)]
     if let Err(err) = enforce_login_restrictions(&config).await {
         eprintln!("{err}");
@@ -272,6 +287,7 @@ pub async fn run_main(
         overrides,
         cli_kv_overrides,
         active_profile,
+        output_schema,
         feedback,
     )
     .await
@@ -284,6 +300,7 @@ async fn run_ratatui_app(
     overrides: ConfigOverrides,
     cli_kv_overrides: Vec<(String, toml::Value)>,
     active_profile: Option<String>,
+    output_schema: Option<serde_json::Value>,
     feedback: codex_feedback::CodexFeedback,
 ) -> color_eyre::Result<AppExitInfo> {
     color_eyre::install()?;
@@ -460,6 +477,7 @@ async fn run_ratatui_app(
         active_profile,
         prompt,
         images,
+        output_schema,
         resume_selection,
         feedback,
     )
@@ -0,0 +1,281 @@
+//! Validation of structured final answers against the `--output-schema`.
+//!
+//! The model is asked to follow the schema, but nothing guarantees that it
+//! does (and providers other than OpenAI may ignore it entirely), so the TUI
+//! checks the final message itself. Only the subset of JSON Schema used by
+//! structured outputs is supported: `type`, `enum`, `const`, `properties`,
+//! `required`, `additionalProperties`, `items`, `anyOf`/`oneOf` and local
+//! `$ref`s. Unknown keywords are ignored.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+/// Reads and parses the JSON schema at `path`.
+pub(crate) fn load_output_schema(path: &Path) -> Result<Value, String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        format!(
+            "Failed to read output schema file {}: {err}",
+            path.display()
+        )
+    })?;
+    serde_json::from_str(&contents).map_err(|err| {
+        format!(
+            "Output schema file {} is not valid JSON: {err}",
+            path.display()
+        )
+    })
+}
+
+/// Returns one message per way `value` violates `schema`, each prefixed with
+/// the JSON pointer of the offending value.
+pub(crate) fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, schema, value, "", &mut errors);
+    errors
+}
+
+fn validate_at(root: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts everything; `false` accepts nothing.
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{}: no value is allowed here", display_path(path)));
+        }
+        return;
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+        {
+            Some(target) => validate_at(root, target, value, path, errors),
+            None => errors.push(format!(
+                "{}: cannot resolve schema reference `{reference}`",
+                display_path(path)
+            )),
+        }
+    }
+
+    if let Some(expected) = schema.get("type")
+        && !type_matches(expected, value)
+    {
+        errors.push(format!(
+            "{}: expected {}, found {}",
+            display_path(path),
+            describe_type(expected),
+            type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        let allowed = allowed
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        errors.push(format!(
+            "{}: {value} is not one of {allowed}",
+            display_path(path)
+        ));
+    }
+
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        errors.push(format!(
+            "{}: expected {expected}, found {value}",
+            display_path(path)
+        ));
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(keyword).and_then(Value::as_array) {
+            let matches = variants
+                .iter()
+                .filter(|variant| validate(&with_root(root, variant), value).is_empty())
+                .count();
+            let ok = if keyword == "oneOf" {
+                matches == 1
+            } else {
+                matches > 0
+            };
+            if !ok {
+                let quantifier = if keyword == "oneOf" {
+                    "exactly one"
+                } else {
+                    "any"
+                };
+                errors.push(format!(
+                    "{}: does not match {quantifier} of the schemas in `{keyword}`",
+                    display_path(path)
+                ));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        errors.push(format!(
+                            "{}: missing required property `{name}`",
+                            display_path(path)
+                        ));
+                    }
+                }
+            }
+            for (name, child) in object {
+                let child_path = format!("{path}/{}", escape_pointer(name));
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(child_schema) => {
+                        validate_at(root, child_schema, child, &child_path, errors)
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => errors.push(format!(
+                            "{}: unexpected property `{name}`",
+                            display_path(path)
+                        )),
+                        Some(additional) => {
+                            validate_at(root, additional, child, &child_path, errors)
+                        }
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(root, item_schema, item, &format!("{path}/{index}"), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `anyOf`/`oneOf` variants are validated on their own, but may still refer
+/// to definitions in the root schema.
+fn with_root(root: &Value, variant: &Value) -> Value {
+    match (root.as_object(), variant.as_object()) {
+        (Some(root), Some(variant)) => {
+            let mut merged = variant.clone();
+            for keyword in ["$defs", "definitions"] {
+                if let Some(definitions) = root.get(keyword) {
+                    merged.entry(keyword).or_insert_with(|| definitions.clone());
+                }
+            }
+            Value::Object(merged)
+        }
+        _ => variant.clone(),
+    }
+}
+
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => single_type_matches(name, value),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| single_type_matches(name, value)),
+        _ => true,
+    }
+}
+
+fn single_type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "status": { "enum": ["ok", "failed"] },
+                "files": { "type": "array", "items": { "$ref": "#/$defs/file" } }
+            },
+            "required": ["status", "files"],
+            "additionalProperties": false,
+            "$defs": {
+                "file": {
+                    "type": "object",
+                    "properties": { "path": { "type": "string" } },
+                    "required": ["path"]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_conforming_values() {
+        let value = json!({"status": "ok", "files": [{"path": "src/lib.rs"}]});
+
+        assert_eq!(validate(&schema(), &value), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let value = json!({"status": "maybe", "files": [{"path": 3}, {}], "extra": true});
+
+        assert_eq!(
+            validate(&schema(), &value),
+            vec![
+                "/status: \"maybe\" is not one of \"ok\", \"failed\"".to_string(),
+                "/files/0/path: expected string, found number".to_string(),
+                "/files/1: missing required property `path`".to_string(),
+                "/: unexpected property `extra`".to_string(),
+            ]
+        );
+    }
+}
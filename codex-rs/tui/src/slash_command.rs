@@ -18,10 +18,13 @@ pub enum SlashCommand {
     New,
     Init,
     Compact,
+    Pin,
     Undo,
+    Timeline,
     Diff,
     Mention,
     Status,
+    Profile,
     Mcp,
     Logout,
     Quit,
@@ -39,12 +42,15 @@ impl SlashCommand {
             SlashCommand::New => "start a new chat during a conversation",
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Pin => "pin your last message so compaction keeps it",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Undo => "ask Codex to undo a turn",
+            SlashCommand::Timeline => "browse and restore snapshots from this session",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Profile => "show where the last turn spent its time",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::Mcp => "list configured MCP tools",
@@ -67,13 +73,16 @@ impl SlashCommand {
             | SlashCommand::Init
             | SlashCommand::Compact
             | SlashCommand::Undo
+            | SlashCommand::Timeline
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::Review
             | SlashCommand::Logout => false,
-            SlashCommand::Diff
+            SlashCommand::Pin
+            | SlashCommand::Diff
             | SlashCommand::Mention
             | SlashCommand::Status
+            | SlashCommand::Profile
             | SlashCommand::Mcp
             | SlashCommand::Feedback
             | SlashCommand::Quit
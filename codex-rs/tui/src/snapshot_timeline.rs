@@ -0,0 +1,339 @@
+//! `/timeline`: browse the ghost snapshots Codex took during the session,
+//! preview how the working tree changed since each one and restore it.
+//!
+//! Snapshots are listed newest first. Picking one opens its actions: preview
+//! the diff in a pager, restore every file, or restore individual files.
+//! Restoring does not rewrite the conversation, so a newer snapshot can be
+//! restored afterwards to redo.
+
+use codex_core::protocol::Op;
+use codex_core::protocol::SnapshotSummary;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::text_formatting::truncate_text;
+
+const PROMPT_MAX_GRAPHEMES: usize = 60;
+
+/// Popup listing every snapshot, newest first.
+pub(crate) fn timeline_params(snapshots: Vec<SnapshotSummary>) -> SelectionViewParams {
+    let count = snapshots.len();
+    let items = snapshots
+        .into_iter()
+        .enumerate()
+        .rev()
+        .map(|(index, snapshot)| {
+            let search_value = format!(
+                "{} {}",
+                snapshot.id,
+                snapshot.turn_prompt.as_deref().unwrap_or_default()
+            );
+            SelectionItem {
+                name: format!("{}. {}", index + 1, snapshot_title(&snapshot)),
+                description: Some(change_stats(&snapshot)),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::OpenSnapshot(snapshot.clone()));
+                })],
+                dismiss_on_select: false,
+                search_value: Some(search_value),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    SelectionViewParams {
+        title: Some("Snapshot timeline".to_string()),
+        subtitle: Some(format!(
+            "{count} snapshot{} this session, compared with the working tree",
+            if count == 1 { "" } else { "s" }
+        )),
+        footer_hint: Some(standard_popup_hint_line()),
+        items,
+        is_searchable: true,
+        search_placeholder: Some("Type to search prompts".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Popup with the actions available for one snapshot.
+pub(crate) fn snapshot_actions_params(snapshot: SnapshotSummary) -> SelectionViewParams {
+    let mut items = Vec::new();
+    let has_changes = !snapshot.files.is_empty();
+
+    items.push(SelectionItem {
+        name: "Preview diff".to_string(),
+        description: Some("Changes since this snapshot".to_string()),
+        actions: vec![Box::new({
+            let snapshot = snapshot.clone();
+            move |tx: &AppEventSender| {
+                tx.send(AppEvent::ShowSnapshotDiff(snapshot.clone()));
+            }
+        })],
+        dismiss_on_select: false,
+        ..Default::default()
+    });
+    if has_changes {
+        items.push(SelectionItem {
+            name: "Restore all files".to_string(),
+            description: Some("Undo every change made since this snapshot".to_string()),
+            actions: vec![Box::new({
+                let id = snapshot.id.clone();
+                move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::RestoreSnapshot {
+                        id: id.clone(),
+                        paths: Vec::new(),
+                    }));
+                }
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+        items.push(SelectionItem {
+            name: "Restore selected files".to_string(),
+            description: Some("Pick files to restore one at a time".to_string()),
+            actions: vec![Box::new({
+                let snapshot = snapshot.clone();
+                move |tx: &AppEventSender| {
+                    tx.send(AppEvent::OpenSnapshotFiles(snapshot.clone()));
+                }
+            })],
+            dismiss_on_select: false,
+            ..Default::default()
+        });
+    }
+
+    SelectionViewParams {
+        title: Some(format!(
+            "Snapshot {}: {}",
+            short_id(&snapshot.id),
+            snapshot_title(&snapshot)
+        )),
+        subtitle: Some(change_stats(&snapshot)),
+        footer_hint: Some(standard_popup_hint_line()),
+        items,
+        ..Default::default()
+    }
+}
+
+/// Popup listing the files changed since the snapshot. Each selection
+/// restores that file and leaves the popup open for further picks.
+pub(crate) fn snapshot_files_params(snapshot: SnapshotSummary) -> SelectionViewParams {
+    let items = snapshot
+        .files
+        .iter()
+        .map(|file| {
+            let id = snapshot.id.clone();
+            let path = file.path.clone();
+            SelectionItem {
+                name: file.path.display().to_string(),
+                description: Some(format!("+{} -{}", file.added, file.removed)),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::RestoreSnapshot {
+                        id: id.clone(),
+                        paths: vec![path.clone()],
+                    }));
+                })],
+                dismiss_on_select: false,
+                search_value: Some(file.path.display().to_string()),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    SelectionViewParams {
+        title: Some(format!(
+            "Restore files from snapshot {}",
+            short_id(&snapshot.id)
+        )),
+        subtitle: Some("Enter restores the highlighted file".to_string()),
+        footer_hint: Some(standard_popup_hint_line()),
+        items,
+        is_searchable: true,
+        search_placeholder: Some("Type to search files".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Pager lines for the diff between the snapshot and the working tree.
+pub(crate) fn snapshot_diff_lines(snapshot: &SnapshotSummary) -> Vec<Line<'static>> {
+    if snapshot.diff.trim().is_empty() {
+        return vec!["No changes since this snapshot.".italic().into()];
+    }
+    snapshot
+        .diff
+        .lines()
+        .map(|line| {
+            let line = line.to_string();
+            if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+                line.bold().into()
+            } else if line.starts_with('+') {
+                line.green().into()
+            } else if line.starts_with('-') {
+                line.red().into()
+            } else if line.starts_with("@@") {
+                line.cyan().into()
+            } else {
+                line.into()
+            }
+        })
+        .collect()
+}
+
+fn snapshot_title(snapshot: &SnapshotSummary) -> String {
+    let prompt = snapshot
+        .turn_prompt
+        .as_deref()
+        .and_then(|prompt| prompt.lines().find(|line| !line.trim().is_empty()))
+        .map(str::trim);
+    match prompt {
+        Some(prompt) => truncate_text(prompt, PROMPT_MAX_GRAPHEMES),
+        None => "(no prompt)".to_string(),
+    }
+}
+
+fn change_stats(snapshot: &SnapshotSummary) -> String {
+    if snapshot.files.is_empty() {
+        return "no changes since".to_string();
+    }
+    let added: u64 = snapshot
+        .files
+        .iter()
+        .map(|file| u64::from(file.added))
+        .sum();
+    let removed: u64 = snapshot
+        .files
+        .iter()
+        .map(|file| u64::from(file.removed))
+        .sum();
+    let count = snapshot.files.len();
+    format!(
+        "+{added} -{removed} in {count} file{}",
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+fn short_id(id: &str) -> String {
+    id.chars().take(7).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::SnapshotFileChange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn snapshot(id: &str, prompt: &str, files: &[(&str, u32, u32)]) -> SnapshotSummary {
+        SnapshotSummary {
+            id: id.to_string(),
+            turn_prompt: Some(prompt.to_string()),
+            files: files
+                .iter()
+                .map(|(path, added, removed)| SnapshotFileChange {
+                    path: PathBuf::from(path),
+                    added: *added,
+                    removed: *removed,
+                })
+                .collect(),
+            diff: String::new(),
+        }
+    }
+
+    #[test]
+    fn timeline_lists_newest_first_with_stats() {
+        let params = timeline_params(vec![
+            snapshot("aaaaaaaaaa", "first change", &[("a.rs", 1, 2)]),
+            snapshot(
+                "bbbbbbbbbb",
+                "\nsecond change\nwith details",
+                &[("a.rs", 3, 0), ("b.rs", 4, 1)],
+            ),
+            snapshot("cccccccccc", "third change", &[]),
+        ]);
+
+        let rows: Vec<(String, Option<String>)> = params
+            .items
+            .iter()
+            .map(|item| (item.name.clone(), item.description.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "3. third change".to_string(),
+                    Some("no changes since".to_string())
+                ),
+                (
+                    "2. second change".to_string(),
+                    Some("+7 -1 in 2 files".to_string())
+                ),
+                (
+                    "1. first change".to_string(),
+                    Some("+1 -2 in 1 file".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn actions_restore_whole_snapshot_or_single_files() {
+        let summary = snapshot("abcdef123456", "edit", &[("src/lib.rs", 1, 1)]);
+        let (tx, mut rx) = unbounded_channel();
+        let tx = AppEventSender::new(tx);
+
+        let actions = snapshot_actions_params(summary.clone());
+        assert_eq!(actions.title.as_deref(), Some("Snapshot abcdef1: edit"));
+        let names: Vec<&str> = actions
+            .items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Preview diff",
+                "Restore all files",
+                "Restore selected files"
+            ]
+        );
+        (actions.items[1].actions[0])(&tx);
+        match rx.try_recv() {
+            Ok(AppEvent::CodexOp(Op::RestoreSnapshot { id, paths })) => {
+                assert_eq!(id, "abcdef123456");
+                assert!(paths.is_empty());
+            }
+            other => panic!("expected RestoreSnapshot, got {other:?}"),
+        }
+
+        let files = snapshot_files_params(summary);
+        assert_eq!(files.items.len(), 1);
+        (files.items[0].actions[0])(&tx);
+        match rx.try_recv() {
+            Ok(AppEvent::CodexOp(Op::RestoreSnapshot { id, paths })) => {
+                assert_eq!(id, "abcdef123456");
+                assert_eq!(paths, vec![PathBuf::from("src/lib.rs")]);
+            }
+            other => panic!("expected RestoreSnapshot, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unchanged_snapshot_only_offers_preview() {
+        let actions = snapshot_actions_params(snapshot("abc", "noop", &[]));
+        let names: Vec<&str> = actions
+            .items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Preview diff"]);
+
+        let lines = snapshot_diff_lines(&snapshot("abc", "noop", &[]));
+        assert_eq!(lines.len(), 1);
+    }
+}
@@ -7,7 +7,9 @@ use chrono::DateTime;
 use chrono::Local;
 use codex_common::create_config_summary_entries;
 use codex_core::config::Config;
+use codex_core::protocol::PromptCacheStats;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::TokenCost;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::ConversationId;
 use ratatui::prelude::*;
@@ -61,6 +63,8 @@ struct StatusHistoryCell {
     account: Option<StatusAccountDisplay>,
     session_id: Option<String>,
     token_usage: StatusTokenUsageData,
+    cost: Option<TokenCost>,
+    prompt_cache: Option<PromptCacheStats>,
     rate_limits: StatusRateLimitData,
 }
 
@@ -69,6 +73,8 @@ pub(crate) fn new_status_output(
     auth_manager: &AuthManager,
     total_usage: &TokenUsage,
     context_usage: Option<&TokenUsage>,
+    cost: Option<&TokenCost>,
+    prompt_cache: Option<&PromptCacheStats>,
     session_id: &Option<ConversationId>,
     rate_limits: Option<&RateLimitSnapshotDisplay>,
     now: DateTime<Local>,
@@ -79,6 +85,8 @@ pub(crate) fn new_status_output(
         auth_manager,
         total_usage,
         context_usage,
+        cost,
+        prompt_cache,
         session_id,
         rate_limits,
         now,
@@ -93,6 +101,7 @@ impl StatusHistoryCell {
         auth_manager: &AuthManager,
         total_usage: &TokenUsage,
         context_usage: Option<&TokenUsage>,
+        cost: Option<&TokenCost>,
         session_id: &Option<ConversationId>,
         rate_limits: Option<&RateLimitSnapshotDisplay>,
         now: DateTime<Local>,
@@ -138,6 +147,8 @@ impl StatusHistoryCell {
             account,
             session_id,
             token_usage,
+            cost: cost.copied(),
+            prompt_cache: prompt_cache.copied(),
             rate_limits,
         }
     }
@@ -160,6 +171,27 @@ impl StatusHistoryCell {
         ]
     }
 
+    fn cost_spans(&self) -> Option<Vec<Span<'static>>> {
+        let cost = self.cost.as_ref()?;
+        Some(vec![
+            Span::from(format!("${:.2}", cost.session_usd)),
+            Span::from(" session").dim(),
+            Span::from(" (").dim(),
+            Span::from(format!("${:.2}", cost.turn_usd)).dim(),
+            Span::from(" this turn)").dim(),
+        ])
+    }
+
+    fn prompt_cache_spans(&self) -> Option<Vec<Span<'static>>> {
+        let cache = self.prompt_cache.as_ref()?;
+        Some(vec![
+            Span::from(format!("{:.0}% hit", cache.last_hit_percent)),
+            Span::from(" (").dim(),
+            Span::from(format!("{:.0}%", cache.session_hit_percent)).dim(),
+            Span::from(" this session)").dim(),
+        ])
+    }
+
     fn context_window_spans(&self) -> Option<Vec<Span<'static>>> {
         let context = self.token_usage.context_window.as_ref()?;
         let percent = context.percent_remaining;
@@ -308,6 +340,12 @@ impl HistoryCell for StatusHistoryCell {
             push_label(&mut labels, &mut seen, "Session");
         }
         push_label(&mut labels, &mut seen, "Token usage");
+        if self.cost.is_some() {
+            push_label(&mut labels, &mut seen, "Cost");
+        }
+        if self.prompt_cache.is_some() {
+            push_label(&mut labels, &mut seen, "Prompt cache");
+        }
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
@@ -362,6 +400,14 @@ impl HistoryCell for StatusHistoryCell {
             lines.push(formatter.line("Token usage", self.token_usage_spans()));
         }
 
+        if let Some(spans) = self.cost_spans() {
+            lines.push(formatter.line("Cost", spans));
+        }
+
+        if let Some(spans) = self.prompt_cache_spans() {
+            lines.push(formatter.line("Prompt cache", spans));
+        }
+
         if let Some(spans) = self.context_window_spans() {
             lines.push(formatter.line("Context window", spans));
         }
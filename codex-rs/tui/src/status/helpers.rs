@@ -32,6 +32,13 @@ pub(crate) fn compose_model_display(
         }
     }
 
+    if let Some((slug, _)) = config.model_family_override(&config.model) {
+        details.push(format!(
+            "family {} from model_families.{slug}",
+            config.model_family.family
+        ));
+    }
+
     (config.model.clone(), details)
 }
 
@@ -8,9 +8,11 @@ use codex_core::AuthManager;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use codex_core::protocol::PromptCacheStats;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::TokenCost;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -126,6 +128,8 @@ fn status_snapshot_includes_reasoning_details() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         Some(&rate_display),
         captured_at,
@@ -176,6 +180,8 @@ fn status_snapshot_includes_monthly_limit() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         Some(&rate_display),
         captured_at,
@@ -216,6 +222,8 @@ fn status_card_token_usage_excludes_cached_tokens() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         None,
         now,
@@ -266,6 +274,8 @@ fn status_snapshot_truncates_in_narrow_terminal() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         Some(&rate_display),
         captured_at,
@@ -307,6 +317,8 @@ fn status_snapshot_shows_missing_limits_message() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         None,
         now,
@@ -352,6 +364,8 @@ fn status_snapshot_shows_empty_limits_message() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         Some(&rate_display),
         captured_at,
@@ -406,6 +420,8 @@ fn status_snapshot_shows_stale_limits_message() {
         &auth_manager,
         &usage,
         Some(&usage),
+        None,
+        None,
         &None,
         Some(&rate_display),
         now,
@@ -452,6 +468,8 @@ fn status_context_window_uses_last_usage() {
         &auth_manager,
         &total_usage,
         Some(&last_usage),
+        None,
+        None,
         &None,
         None,
         now,
@@ -471,3 +489,121 @@ fn status_context_window_uses_last_usage() {
         "context line should not use total aggregated tokens, got: {context_line}"
     );
 }
+
+#[test]
+fn status_model_line_shows_configured_model_family() {
+    let temp_home = TempDir::new().expect("temp home");
+    let cfg = toml::from_str::<ConfigToml>(
+        r#"
+model = "qwen3-coder:30b"
+
+[model_families.qwen3-coder]
+family = "qwen3"
+"#,
+    )
+    .expect("parse config");
+    let config = Config::load_from_base_config_with_overrides(
+        cfg,
+        ConfigOverrides::default(),
+        temp_home.path().to_path_buf(),
+    )
+    .expect("load config");
+    let auth_manager = test_auth_manager(&config);
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 6, 1, 12, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        &TokenUsage::default(),
+        None,
+        None,
+        None,
+        &None,
+        None,
+        now,
+    );
+    let model_line = render_lines(&composite.display_lines(120))
+        .into_iter()
+        .find(|line| line.contains("Model:"))
+        .expect("model line");
+
+    assert!(
+        model_line.contains("family qwen3 from model_families.qwen3-coder"),
+        "expected model line to show the configured family, got: {model_line}"
+    );
+}
+
+#[test]
+fn status_shows_session_and_turn_cost() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home);
+    let auth_manager = test_auth_manager(&config);
+    let cost = TokenCost {
+        turn_usd: 0.05,
+        session_usd: 1.234,
+    };
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 6, 1, 12, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        &TokenUsage::default(),
+        None,
+        Some(&cost),
+        None,
+        &None,
+        None,
+        now,
+    );
+    let cost_line = render_lines(&composite.display_lines(80))
+        .into_iter()
+        .find(|line| line.contains("Cost:"))
+        .expect("cost line");
+
+    assert!(
+        cost_line.contains("$1.23 session ($0.05 this turn)"),
+        "expected cost line to show session and turn cost, got: {cost_line}"
+    );
+}
+
+#[test]
+fn status_shows_prompt_cache_hit_rate() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home);
+    let auth_manager = test_auth_manager(&config);
+    let prompt_cache = PromptCacheStats {
+        last_hit_percent: 92.4,
+        session_hit_percent: 71.6,
+    };
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 6, 1, 12, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        &TokenUsage::default(),
+        None,
+        None,
+        Some(&prompt_cache),
+        &None,
+        None,
+        now,
+    );
+    let cache_line = render_lines(&composite.display_lines(80))
+        .into_iter()
+        .find(|line| line.contains("Prompt cache:"))
+        .expect("prompt cache line");
+
+    assert!(
+        cache_line.contains("92% hit (72% this session)"),
+        "expected prompt cache line to show hit rates, got: {cache_line}"
+    );
+}
@@ -0,0 +1,210 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+
+/// Namespace for the temporary refs that name ghost commits while a bundle is
+/// written. Ghost commits are otherwise unreferenced, and `git bundle` only
+/// packs objects reachable from refs.
+const BUNDLE_REF_PREFIX: &str = "refs/codex/bundle/";
+
+/// Writes the ghost commits in `commit_ids` to a git bundle at `output`.
+///
+/// Each commit's parent is left out of the bundle, so the repository that
+/// unbundles it must already contain the commits the snapshots were taken on.
+/// Commits that no longer exist (for example after `git gc`) are skipped.
+/// Returns the ids that were written; no file is created when that is empty.
+pub fn create_ghost_commit_bundle(
+    repo_path: &Path,
+    commit_ids: &[String],
+    output: &Path,
+) -> Result<Vec<String>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+
+    let mut included = Vec::new();
+    for id in commit_ids {
+        if !included.contains(id) && commit_exists(&repo_root, id) {
+            included.push(id.clone());
+        }
+    }
+    if included.is_empty() {
+        return Ok(included);
+    }
+
+    for id in &included {
+        run_git_for_status(
+            &repo_root,
+            [
+                OsString::from("update-ref"),
+                OsString::from(format!("{BUNDLE_REF_PREFIX}{id}")),
+                OsString::from(id),
+            ],
+            None,
+        )?;
+    }
+
+    let mut args = vec![
+        OsString::from("bundle"),
+        OsString::from("create"),
+        output.as_os_str().to_owned(),
+    ];
+    for id in &included {
+        args.push(OsString::from(format!("{BUNDLE_REF_PREFIX}{id}")));
+        if commit_exists(&repo_root, &format!("{id}^")) {
+            args.push(OsString::from(format!("^{id}^")));
+        }
+    }
+    let result = run_git_for_status(&repo_root, args, None);
+
+    // Always drop the temporary refs so they never keep snapshots alive.
+    for id in &included {
+        let _ = run_git_for_status(
+            &repo_root,
+            [
+                OsString::from("update-ref"),
+                OsString::from("-d"),
+                OsString::from(format!("{BUNDLE_REF_PREFIX}{id}")),
+            ],
+            None,
+        );
+    }
+
+    result.map(|()| included)
+}
+
+/// Adds the objects of a bundle written by [`create_ghost_commit_bundle`] to
+/// the repository containing `repo_path`. No refs are created, so the ghost
+/// commits stay as unreferenced as they were in the original repository.
+pub fn unbundle_ghost_commits(repo_path: &Path, bundle: &Path) -> Result<(), GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    // `verify` reports missing prerequisite commits with a readable message.
+    run_git_for_status(
+        &repo_root,
+        [
+            OsString::from("bundle"),
+            OsString::from("verify"),
+            OsString::from("--quiet"),
+            bundle.as_os_str().to_owned(),
+        ],
+        None,
+    )?;
+    run_git_for_status(
+        &repo_root,
+        [
+            OsString::from("bundle"),
+            OsString::from("unbundle"),
+            bundle.as_os_str().to_owned(),
+        ],
+        None,
+    )
+}
+
+fn commit_exists(repo_root: &Path, rev: &str) -> bool {
+    run_git_for_status(
+        repo_root,
+        [
+            OsString::from("cat-file"),
+            OsString::from("-e"),
+            OsString::from(format!("{rev}^{{commit}}")),
+        ],
+        None,
+    )
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CreateGhostCommitOptions;
+    use crate::create_ghost_commit;
+    use crate::operations::run_git_for_stdout;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn init_committed_repo(repo: &Path) {
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        run_git_in(repo, &["config", "core.autocrlf", "false"]);
+        std::fs::write(repo.join("tracked.txt"), "initial\n").expect("write");
+        run_git_in(repo, &["add", "tracked.txt"]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+    }
+
+    #[test]
+    fn ghost_commits_round_trip_through_a_bundle() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let source = temp.path().join("source");
+        std::fs::create_dir(&source)?;
+        init_committed_repo(&source);
+
+        std::fs::write(source.join("tracked.txt"), "snapshot\n")?;
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(&source))?;
+        let missing = "0123456789abcdef0123456789abcdef01234567".to_string();
+
+        let bundle = temp.path().join("snapshots.bundle");
+        let included = create_ghost_commit_bundle(
+            &source,
+            &[ghost.id().to_string(), missing, ghost.id().to_string()],
+            &bundle,
+        )?;
+        assert_eq!(included, vec![ghost.id().to_string()]);
+        let refs = run_git_for_stdout(
+            &source,
+            [OsString::from("for-each-ref"), OsString::from("refs/codex")],
+            None,
+        )?;
+        assert_eq!(refs, "");
+
+        let target = temp.path().join("target");
+        // A local clone would hardlink every object, ghost commits included.
+        run_git_in(
+            temp.path(),
+            &[
+                "clone",
+                "--quiet",
+                "--no-local",
+                source.to_str().expect("utf-8 path"),
+                "target",
+            ],
+        );
+        assert!(!commit_exists(&target, ghost.id()));
+        unbundle_ghost_commits(&target, &bundle)?;
+        assert!(commit_exists(&target, ghost.id()));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_selection_writes_no_bundle() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        init_committed_repo(temp.path());
+        let bundle = temp.path().join("snapshots.bundle");
+        let included = create_ghost_commit_bundle(temp.path(), &[], &bundle)?;
+        assert!(included.is_empty());
+        assert!(!bundle.exists());
+        Ok(())
+    }
+}
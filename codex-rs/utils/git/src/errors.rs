@@ -22,6 +22,14 @@ pub enum GitToolingError {
     },
     #[error("{path:?} is not a git repository")]
     NotAGitRepository { path: PathBuf },
+    #[error(
+        "{path:?} has more than {max_files} files or {max_mib} MiB to snapshot outside a git repository"
+    )]
+    SnapshotTooLarge {
+        path: PathBuf,
+        max_files: usize,
+        max_mib: u64,
+    },
     #[error("path {path:?} must be relative to the repository root")]
     NonRelativePath { path: PathBuf },
     #[error("path {path:?} escapes the repository root")]
@@ -22,11 +22,29 @@ use crate::operations::run_git_for_stdout_all;
 /// Default commit message used for ghost commits when none is provided.
 const DEFAULT_COMMIT_MESSAGE: &str = "codex snapshot";
 
+/// Shadow snapshots of directories with more files than this are skipped.
+const SHADOW_MAX_FILES: usize = 10_000;
+
+/// Shadow snapshots of directories larger than this many MiB are skipped.
+const SHADOW_MAX_MIB: u64 = 256;
+
+/// Ignore rules for new shadow repositories. Directories outside git rarely
+/// have a `.gitignore`, so skip the usual dependency and build directories.
+const SHADOW_EXCLUDES: &str = "\
+.cache/
+.venv/
+__pycache__/
+node_modules/
+target/
+venv/
+";
+
 /// Options to control ghost commit creation.
 pub struct CreateGhostCommitOptions<'a> {
     pub repo_path: &'a Path,
     pub message: Option<&'a str>,
     pub force_include: Vec<PathBuf>,
+    pub shadow_git_dir: Option<&'a Path>,
 }
 
 impl<'a> CreateGhostCommitOptions<'a> {
@@ -36,6 +54,7 @@ impl<'a> CreateGhostCommitOptions<'a> {
             repo_path,
             message: None,
             force_include: Vec::new(),
+            shadow_git_dir: None,
         }
     }
 
@@ -62,19 +81,132 @@ impl<'a> CreateGhostCommitOptions<'a> {
         self.force_include.push(path.into());
         self
     }
+
+    /// Stores the snapshot in a shadow repository at `git_dir` whose work tree
+    /// is `repo_path`, so directories that are not git repositories can be
+    /// snapshotted too. The shadow repository is created on first use.
+    pub fn shadow_git_dir(mut self, git_dir: &'a Path) -> Self {
+        self.shadow_git_dir = Some(git_dir);
+        self
+    }
+}
+
+/// Per-file line counts between a ghost commit and the working tree.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GhostCommitFileDiff {
+    /// Path relative to the directory the snapshot was taken in.
+    pub path: PathBuf,
+    /// Lines added since the snapshot. Zero for binary files.
+    pub added: usize,
+    /// Lines removed since the snapshot. Zero for binary files.
+    pub removed: usize,
+}
+
+/// Difference between a ghost commit and the current working tree.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GhostCommitDiff {
+    pub files: Vec<GhostCommitFileDiff>,
+    /// Unified diff from the snapshot to the working tree.
+    pub patch: String,
+}
+
+/// Where git commands for a snapshot run: a regular repository, optionally
+/// scoped to a subdirectory, or a shadow repository whose work tree is the
+/// snapshotted directory.
+struct SnapshotRepo {
+    root: PathBuf,
+    prefix: Option<PathBuf>,
+    env: Vec<(OsString, OsString)>,
+    shadow: bool,
+}
+
+impl SnapshotRepo {
+    fn open(repo_path: &Path, shadow_git_dir: Option<&Path>) -> Result<Self, GitToolingError> {
+        let Some(git_dir) = shadow_git_dir else {
+            ensure_git_repository(repo_path)?;
+            let root = resolve_repository_root(repo_path)?;
+            let prefix = repo_subdir(root.as_path(), repo_path);
+            return Ok(Self {
+                root,
+                prefix,
+                env: Vec::new(),
+                shadow: false,
+            });
+        };
+
+        let env = vec![
+            (
+                OsString::from("GIT_DIR"),
+                git_dir.as_os_str().to_os_string(),
+            ),
+            (
+                OsString::from("GIT_WORK_TREE"),
+                repo_path.as_os_str().to_os_string(),
+            ),
+        ];
+        if !git_dir.join("HEAD").exists() {
+            fs::create_dir_all(git_dir)?;
+            run_git_for_status(
+                repo_path,
+                vec![OsString::from("init"), OsString::from("--quiet")],
+                Some(env.as_slice()),
+            )?;
+            let info_dir = git_dir.join("info");
+            fs::create_dir_all(&info_dir)?;
+            fs::write(info_dir.join("exclude"), SHADOW_EXCLUDES)?;
+        }
+        Ok(Self {
+            root: repo_path.to_path_buf(),
+            prefix: None,
+            env,
+            shadow: true,
+        })
+    }
+
+    fn env(&self) -> Option<&[(OsString, OsString)]> {
+        if self.env.is_empty() {
+            None
+        } else {
+            Some(self.env.as_slice())
+        }
+    }
+
+    /// Environment for commands that must use the throwaway index at `index_path`.
+    fn env_with_index(&self, index_path: &Path) -> Vec<(OsString, OsString)> {
+        let mut env = self.env.clone();
+        env.push((
+            OsString::from("GIT_INDEX_FILE"),
+            OsString::from(index_path.as_os_str()),
+        ));
+        env
+    }
+
+    /// Pathspec covering everything the snapshot captures.
+    fn scope(&self) -> OsString {
+        match self.prefix.as_deref() {
+            Some(prefix) => prefix.as_os_str().to_os_string(),
+            None => OsString::from("."),
+        }
+    }
 }
 
 /// Create a ghost commit capturing the current state of the repository's working tree.
 pub fn create_ghost_commit(
     options: &CreateGhostCommitOptions<'_>,
 ) -> Result<GhostCommit, GitToolingError> {
-    ensure_git_repository(options.repo_path)?;
-
-    let repo_root = resolve_repository_root(options.repo_path)?;
-    let repo_prefix = repo_subdir(repo_root.as_path(), options.repo_path);
-    let parent = resolve_head(repo_root.as_path())?;
-    let existing_untracked =
-        capture_existing_untracked(repo_root.as_path(), repo_prefix.as_deref())?;
+    let repo = SnapshotRepo::open(options.repo_path, options.shadow_git_dir)?;
+    // Shadow repositories never get a HEAD; every snapshot is a root commit.
+    let parent = if repo.shadow {
+        None
+    } else {
+        resolve_head(repo.root.as_path())?
+    };
+    // Nothing is ever staged in a shadow repository, so every file would look
+    // untracked. Only ignored entries need to be remembered there.
+    if repo.shadow {
+        ensure_shadow_snapshot_size(&repo)?;
+    }
+    let existing_untracked = capture_existing_untracked(&repo, repo.shadow)?;
 
     let normalized_force = options
         .force_include
@@ -82,47 +214,13 @@ pub fn create_ghost_commit(
         .map(|path| normalize_relative_path(path))
         .collect::<Result<Vec<_>, _>>()?;
     let force_include =
-        apply_repo_prefix_to_force_include(repo_prefix.as_deref(), &normalized_force);
+        apply_repo_prefix_to_force_include(repo.prefix.as_deref(), &normalized_force);
     let index_tempdir = Builder::new().prefix("codex-git-index-").tempdir()?;
-    let index_path = index_tempdir.path().join("index");
-    let base_env = vec![(
-        OsString::from("GIT_INDEX_FILE"),
-        OsString::from(index_path.as_os_str()),
-    )];
+    let base_env = repo.env_with_index(&index_tempdir.path().join("index"));
 
     // Pre-populate the temporary index with HEAD so unchanged tracked files
     // are included in the snapshot tree.
-    if let Some(parent_sha) = parent.as_deref() {
-        run_git_for_status(
-            repo_root.as_path(),
-            vec![OsString::from("read-tree"), OsString::from(parent_sha)],
-            Some(base_env.as_slice()),
-        )?;
-    }
-
-    let mut add_args = vec![OsString::from("add"), OsString::from("--all")];
-    if let Some(prefix) = repo_prefix.as_deref() {
-        add_args.extend([OsString::from("--"), prefix.as_os_str().to_os_string()]);
-    }
-
-    run_git_for_status(repo_root.as_path(), add_args, Some(base_env.as_slice()))?;
-    if !force_include.is_empty() {
-        let mut args = Vec::with_capacity(force_include.len() + 2);
-        args.push(OsString::from("add"));
-        args.push(OsString::from("--force"));
-        args.extend(
-            force_include
-                .iter()
-                .map(|path| OsString::from(path.as_os_str())),
-        );
-        run_git_for_status(repo_root.as_path(), args, Some(base_env.as_slice()))?;
-    }
-
-    let tree_id = run_git_for_stdout(
-        repo_root.as_path(),
-        vec![OsString::from("write-tree")],
-        Some(base_env.as_slice()),
-    )?;
+    let tree_id = write_worktree_tree(&repo, &base_env, parent.as_deref(), &force_include)?;
 
     let mut commit_env = base_env;
     commit_env.extend(default_commit_identity());
@@ -138,81 +236,287 @@ pub fn create_ghost_commit(
 
     // Retrieve commit ID.
     let commit_id = run_git_for_stdout(
-        repo_root.as_path(),
+        repo.root.as_path(),
         commit_args,
         Some(commit_env.as_slice()),
     )?;
 
-    Ok(GhostCommit::new(
+    let ghost = GhostCommit::new(
         commit_id,
         parent,
         existing_untracked.files,
         existing_untracked.dirs,
-    ))
+    );
+    Ok(match options.shadow_git_dir {
+        Some(git_dir) => ghost.with_shadow_git_dir(git_dir.to_path_buf()),
+        None => ghost,
+    })
+}
+
+/// Writes a tree object matching the working tree using the temporary index
+/// configured in `index_env`. The index starts from `base` when given, so
+/// paths tracked there stay tracked even if they are ignored.
+fn write_worktree_tree(
+    repo: &SnapshotRepo,
+    index_env: &[(OsString, OsString)],
+    base: Option<&str>,
+    force_include: &[PathBuf],
+) -> Result<String, GitToolingError> {
+    if let Some(base) = base {
+        run_git_for_status(
+            repo.root.as_path(),
+            vec![OsString::from("read-tree"), OsString::from(base)],
+            Some(index_env),
+        )?;
+    }
+
+    let mut add_args = vec![OsString::from("add"), OsString::from("--all")];
+    if let Some(prefix) = repo.prefix.as_deref() {
+        add_args.extend([OsString::from("--"), prefix.as_os_str().to_os_string()]);
+    }
+
+    run_git_for_status(repo.root.as_path(), add_args, Some(index_env))?;
+    if !force_include.is_empty() {
+        let mut args = Vec::with_capacity(force_include.len() + 2);
+        args.push(OsString::from("add"));
+        args.push(OsString::from("--force"));
+        args.extend(
+            force_include
+                .iter()
+                .map(|path| OsString::from(path.as_os_str())),
+        );
+        run_git_for_status(repo.root.as_path(), args, Some(index_env))?;
+    }
+
+    run_git_for_stdout(
+        repo.root.as_path(),
+        vec![OsString::from("write-tree")],
+        Some(index_env),
+    )
+}
+
+/// Fails with `SnapshotTooLarge` when the files a shadow snapshot would add
+/// exceed `SHADOW_MAX_FILES` or `SHADOW_MAX_MIB`.
+fn ensure_shadow_snapshot_size(repo: &SnapshotRepo) -> Result<(), GitToolingError> {
+    let output = run_git_for_stdout_all(
+        repo.root.as_path(),
+        vec![
+            OsString::from("ls-files"),
+            OsString::from("--others"),
+            OsString::from("--exclude-standard"),
+            OsString::from("-z"),
+        ],
+        repo.env(),
+    )?;
+    let max_bytes = SHADOW_MAX_MIB * 1024 * 1024;
+    let mut files = 0;
+    let mut bytes = 0;
+    for path in output.split('\0').filter(|path| !path.is_empty()) {
+        files += 1;
+        bytes += fs::symlink_metadata(repo.root.join(path)).map_or(0, |metadata| metadata.len());
+        if files > SHADOW_MAX_FILES || bytes > max_bytes {
+            return Err(GitToolingError::SnapshotTooLarge {
+                path: repo.root.clone(),
+                max_files: SHADOW_MAX_FILES,
+                max_mib: SHADOW_MAX_MIB,
+            });
+        }
+    }
+    Ok(())
 }
 
 /// Restore the working tree to match the provided ghost commit.
 pub fn restore_ghost_commit(repo_path: &Path, commit: &GhostCommit) -> Result<(), GitToolingError> {
-    ensure_git_repository(repo_path)?;
-
-    let repo_root = resolve_repository_root(repo_path)?;
-    let repo_prefix = repo_subdir(repo_root.as_path(), repo_path);
-    let current_untracked =
-        capture_existing_untracked(repo_root.as_path(), repo_prefix.as_deref())?;
-    restore_to_commit_inner(repo_root.as_path(), repo_prefix.as_deref(), commit.id())?;
+    let repo = SnapshotRepo::open(repo_path, commit.shadow_git_dir())?;
+    let current_untracked = capture_existing_untracked(&repo, false)?;
+    restore_to_commit_inner(&repo, commit.id(), vec![repo.scope()])?;
+
+    let mut preserved_files = commit.preexisting_untracked_files().to_vec();
+    if repo.shadow {
+        // Files restored from a shadow snapshot are still "untracked".
+        preserved_files.extend(list_commit_files(&repo, commit.id())?);
+    }
     remove_new_untracked(
-        repo_root.as_path(),
-        commit.preexisting_untracked_files(),
+        repo.root.as_path(),
+        &preserved_files,
         commit.preexisting_untracked_dirs(),
         current_untracked,
     )
 }
 
+/// Restore only `paths` (relative to `repo_path`) to their state in the ghost
+/// commit. Paths that did not exist when the snapshot was taken are deleted.
+pub fn restore_ghost_commit_paths(
+    repo_path: &Path,
+    commit: &GhostCommit,
+    paths: &[PathBuf],
+) -> Result<(), GitToolingError> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let repo = SnapshotRepo::open(repo_path, commit.shadow_git_dir())?;
+
+    let mut restore = Vec::new();
+    for path in paths {
+        let relative = normalize_relative_path(path)?;
+        let relative = match repo.prefix.as_deref() {
+            Some(prefix) => prefix.join(relative),
+            None => relative,
+        };
+        if commit_contains_path(&repo, commit.id(), &relative)? {
+            restore.push(relative.into_os_string());
+        } else {
+            remove_path(&repo.root.join(&relative))?;
+        }
+    }
+    if restore.is_empty() {
+        return Ok(());
+    }
+    restore_to_commit_inner(&repo, commit.id(), restore)
+}
+
+/// Diff the ghost commit against the current working tree, including
+/// untracked files. Paths are relative to `repo_path`.
+pub fn diff_ghost_commit(
+    repo_path: &Path,
+    commit: &GhostCommit,
+) -> Result<GhostCommitDiff, GitToolingError> {
+    let repo = SnapshotRepo::open(repo_path, commit.shadow_git_dir())?;
+    let index_tempdir = Builder::new().prefix("codex-git-index-").tempdir()?;
+    let index_env = repo.env_with_index(&index_tempdir.path().join("index"));
+    // Start from the snapshot so force-included files it captured are compared too.
+    let tree_id = write_worktree_tree(&repo, &index_env, Some(commit.id()), &[])?;
+
+    let diff_args = |extra: &[&str]| {
+        let mut args = vec![
+            OsString::from("diff"),
+            OsString::from("--no-color"),
+            OsString::from("--no-ext-diff"),
+            OsString::from("--no-renames"),
+        ];
+        args.extend(extra.iter().map(OsString::from));
+        if let Some(prefix) = repo.prefix.as_deref() {
+            let mut relative = OsString::from("--relative=");
+            relative.push(prefix.as_os_str());
+            args.push(relative);
+        }
+        args.extend([OsString::from(commit.id()), OsString::from(&tree_id)]);
+        args
+    };
+
+    let numstat = run_git_for_stdout_all(
+        repo.root.as_path(),
+        diff_args(&["--numstat", "-z"]),
+        Some(index_env.as_slice()),
+    )?;
+    let files = numstat
+        .split('\0')
+        .filter_map(|entry| {
+            let mut parts = entry.splitn(3, '\t');
+            let added = parts.next()?;
+            let removed = parts.next()?;
+            let path = parts.next().filter(|path| !path.is_empty())?;
+            Some(GhostCommitFileDiff {
+                path: PathBuf::from(path),
+                added: added.parse().unwrap_or(0),
+                removed: removed.parse().unwrap_or(0),
+            })
+        })
+        .collect();
+    let patch = run_git_for_stdout_all(
+        repo.root.as_path(),
+        diff_args(&[]),
+        Some(index_env.as_slice()),
+    )?;
+
+    Ok(GhostCommitDiff { files, patch })
+}
+
 /// Restore the working tree to match the given commit ID.
 pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToolingError> {
-    ensure_git_repository(repo_path)?;
-
-    let repo_root = resolve_repository_root(repo_path)?;
-    let repo_prefix = repo_subdir(repo_root.as_path(), repo_path);
-    restore_to_commit_inner(repo_root.as_path(), repo_prefix.as_deref(), commit_id)
+    let repo = SnapshotRepo::open(repo_path, None)?;
+    restore_to_commit_inner(&repo, commit_id, vec![repo.scope()])
 }
 
-/// Restores the working tree and index to the given commit using `git restore`.
-/// The repository root and optional repository-relative prefix limit the restore scope.
+/// Restores `pathspecs` in the working tree (and, outside shadow repositories,
+/// the index) to the given commit using `git restore`.
 fn restore_to_commit_inner(
-    repo_root: &Path,
-    repo_prefix: Option<&Path>,
+    repo: &SnapshotRepo,
     commit_id: &str,
+    pathspecs: Vec<OsString>,
 ) -> Result<(), GitToolingError> {
     let mut restore_args = vec![
         OsString::from("restore"),
         OsString::from("--source"),
         OsString::from(commit_id),
         OsString::from("--worktree"),
-        OsString::from("--staged"),
-        OsString::from("--"),
     ];
-    if let Some(prefix) = repo_prefix {
-        restore_args.push(prefix.as_os_str().to_os_string());
-    } else {
-        restore_args.push(OsString::from("."));
+    // The shadow index stays empty so untracked detection keeps working.
+    if !repo.shadow {
+        restore_args.push(OsString::from("--staged"));
     }
+    restore_args.push(OsString::from("--"));
+    restore_args.extend(pathspecs);
 
-    run_git_for_status(repo_root, restore_args, None)?;
+    run_git_for_status(repo.root.as_path(), restore_args, repo.env())?;
     Ok(())
 }
 
+/// Returns whether `path` (relative to the repository root) exists in the commit.
+fn commit_contains_path(
+    repo: &SnapshotRepo,
+    commit_id: &str,
+    path: &Path,
+) -> Result<bool, GitToolingError> {
+    let output = run_git_for_stdout(
+        repo.root.as_path(),
+        vec![
+            OsString::from("ls-tree"),
+            OsString::from("--name-only"),
+            OsString::from(commit_id),
+            OsString::from("--"),
+            path.as_os_str().to_os_string(),
+        ],
+        repo.env(),
+    )?;
+    Ok(!output.is_empty())
+}
+
+/// Lists every file in the commit, relative to the repository root.
+fn list_commit_files(
+    repo: &SnapshotRepo,
+    commit_id: &str,
+) -> Result<Vec<PathBuf>, GitToolingError> {
+    let output = run_git_for_stdout_all(
+        repo.root.as_path(),
+        vec![
+            OsString::from("ls-tree"),
+            OsString::from("-r"),
+            OsString::from("-z"),
+            OsString::from("--name-only"),
+            OsString::from(commit_id),
+        ],
+        repo.env(),
+    )?;
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
 #[derive(Default)]
 struct UntrackedSnapshot {
     files: Vec<PathBuf>,
     dirs: Vec<PathBuf>,
 }
 
-/// Captures the untracked and ignored entries under `repo_root`, optionally limited by `repo_prefix`.
+/// Captures the untracked and ignored entries in the snapshot's scope, or only
+/// the ignored ones when `ignored_only` is set.
 /// Returns the result as an `UntrackedSnapshot`.
 fn capture_existing_untracked(
-    repo_root: &Path,
-    repo_prefix: Option<&Path>,
+    repo: &SnapshotRepo,
+    ignored_only: bool,
 ) -> Result<UntrackedSnapshot, GitToolingError> {
     // Ask git for the zero-delimited porcelain status so we can enumerate
     // every untracked or ignored path (including ones filtered by prefix).
@@ -223,12 +527,12 @@ fn capture_existing_untracked(
         OsString::from("--ignored=matching"),
         OsString::from("--untracked-files=all"),
     ];
-    if let Some(prefix) = repo_prefix {
+    if let Some(prefix) = repo.prefix.as_deref() {
         args.push(OsString::from("--"));
         args.push(prefix.as_os_str().to_os_string());
     }
 
-    let output = run_git_for_stdout_all(repo_root, args, None)?;
+    let output = run_git_for_stdout_all(repo.root.as_path(), args, repo.env())?;
     if output.is_empty() {
         return Ok(UntrackedSnapshot::default());
     }
@@ -246,7 +550,7 @@ fn capture_existing_untracked(
         let (Some(code), Some(path_part)) = (code, path_part) else {
             continue;
         };
-        if code != "?" && code != "!" {
+        if code != "!" && (ignored_only || code != "?") {
             continue;
         }
         if path_part.is_empty() {
@@ -254,7 +558,7 @@ fn capture_existing_untracked(
         }
 
         let normalized = normalize_relative_path(Path::new(path_part))?;
-        let absolute = repo_root.join(&normalized);
+        let absolute = repo.root.join(&normalized);
         let is_dir = absolute.is_dir();
         if is_dir {
             snapshot.dirs.push(normalized);
@@ -706,4 +1010,141 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    /// Snapshots outside a git repository round-trip through a shadow repository.
+    fn shadow_repository_snapshot_roundtrip() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let shadow = tempfile::tempdir()?;
+        let workdir = temp.path();
+        let git_dir = shadow.path().join("snapshots");
+        std::fs::write(workdir.join("notes.txt"), "before\n")?;
+        std::fs::create_dir_all(workdir.join("nested"))?;
+        std::fs::write(workdir.join("nested").join("data.txt"), "data\n")?;
+
+        let ghost =
+            create_ghost_commit(&CreateGhostCommitOptions::new(workdir).shadow_git_dir(&git_dir))?;
+        assert_eq!(ghost.shadow_git_dir(), Some(git_dir.as_path()));
+        assert!(ghost.parent().is_none());
+        assert!(!workdir.join(".git").exists());
+
+        std::fs::write(workdir.join("notes.txt"), "after\n")?;
+        std::fs::remove_file(workdir.join("nested").join("data.txt"))?;
+        std::fs::write(workdir.join("new.txt"), "new\n")?;
+
+        let diff = diff_ghost_commit(workdir, &ghost)?;
+        let mut paths: Vec<_> = diff.files.iter().map(|file| file.path.clone()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("nested/data.txt"),
+                PathBuf::from("new.txt"),
+                PathBuf::from("notes.txt"),
+            ]
+        );
+
+        restore_ghost_commit(workdir, &ghost)?;
+
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("notes.txt"))?,
+            "before\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("nested").join("data.txt"))?,
+            "data\n"
+        );
+        assert!(!workdir.join("new.txt").exists());
+        assert_eq!(
+            diff_ghost_commit(workdir, &ghost)?,
+            GhostCommitDiff::default()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    /// Shadow snapshots skip directories that are too large and honor the
+    /// default ignore rules.
+    fn shadow_repository_snapshot_size_limit() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let shadow = tempfile::tempdir()?;
+        let workdir = temp.path();
+        let git_dir = shadow.path().join("snapshots");
+        let too_large = (SHADOW_MAX_MIB + 1) * 1024 * 1024;
+        std::fs::create_dir_all(workdir.join("node_modules"))?;
+        std::fs::File::create(workdir.join("node_modules").join("ignored.bin"))?
+            .set_len(too_large)?;
+        std::fs::write(workdir.join("notes.txt"), "notes\n")?;
+
+        create_ghost_commit(&CreateGhostCommitOptions::new(workdir).shadow_git_dir(&git_dir))?;
+
+        std::fs::File::create(workdir.join("large.bin"))?.set_len(too_large)?;
+        let result =
+            create_ghost_commit(&CreateGhostCommitOptions::new(workdir).shadow_git_dir(&git_dir));
+        assert_matches!(result, Err(GitToolingError::SnapshotTooLarge { .. }));
+
+        Ok(())
+    }
+
+    #[test]
+    /// Restoring selected paths leaves other files untouched.
+    fn restore_selected_paths_only() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::write(repo.join("a.txt"), "a1\n")?;
+        std::fs::write(repo.join("b.txt"), "b1\n")?;
+
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+
+        std::fs::write(repo.join("a.txt"), "a2\n")?;
+        std::fs::write(repo.join("b.txt"), "b2\n")?;
+        std::fs::write(repo.join("c.txt"), "c2\n")?;
+        std::fs::write(repo.join("d.txt"), "d2\n")?;
+
+        restore_ghost_commit_paths(
+            repo,
+            &ghost,
+            &[PathBuf::from("a.txt"), PathBuf::from("c.txt")],
+        )?;
+
+        assert_eq!(std::fs::read_to_string(repo.join("a.txt"))?, "a1\n");
+        assert_eq!(std::fs::read_to_string(repo.join("b.txt"))?, "b2\n");
+        assert!(!repo.join("c.txt").exists());
+        assert!(repo.join("d.txt").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    /// Diffs are scoped to, and relative to, the snapshotted subdirectory.
+    fn diff_from_subdirectory_reports_relative_paths() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        let workspace = repo.join("workspace");
+        std::fs::create_dir_all(&workspace)?;
+        std::fs::write(repo.join("root.txt"), "root\n")?;
+        std::fs::write(workspace.join("lib.rs"), "one\ntwo\n")?;
+
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(&workspace))?;
+
+        std::fs::write(repo.join("root.txt"), "root changed\n")?;
+        std::fs::write(workspace.join("lib.rs"), "one\nthree\nfour\n")?;
+
+        let diff = diff_ghost_commit(&workspace, &ghost)?;
+        assert_eq!(
+            diff.files,
+            vec![GhostCommitFileDiff {
+                path: PathBuf::from("lib.rs"),
+                added: 2,
+                removed: 1,
+            }]
+        );
+        assert!(diff.patch.contains("+three"), "{}", diff.patch);
+        assert!(!diff.patch.contains("root.txt"), "{}", diff.patch);
+
+        Ok(())
+    }
 }
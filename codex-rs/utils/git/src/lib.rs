@@ -1,11 +1,14 @@
 use std::fmt;
+use std::path::Path;
 use std::path::PathBuf;
 
 mod apply;
+mod bundle;
 mod errors;
 mod ghost_commits;
 mod operations;
 mod platform;
+mod worktree;
 
 pub use apply::ApplyGitRequest;
 pub use apply::ApplyGitResult;
@@ -13,16 +16,26 @@ pub use apply::apply_git_patch;
 pub use apply::extract_paths_from_patch;
 pub use apply::parse_git_apply_output;
 pub use apply::stage_paths;
+pub use bundle::create_ghost_commit_bundle;
+pub use bundle::unbundle_ghost_commits;
 pub use errors::GitToolingError;
 pub use ghost_commits::CreateGhostCommitOptions;
+pub use ghost_commits::GhostCommitDiff;
+pub use ghost_commits::GhostCommitFileDiff;
 pub use ghost_commits::create_ghost_commit;
+pub use ghost_commits::diff_ghost_commit;
 pub use ghost_commits::restore_ghost_commit;
+pub use ghost_commits::restore_ghost_commit_paths;
 pub use ghost_commits::restore_to_commit;
 pub use platform::create_symlink;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use ts_rs::TS;
+pub use worktree::DetachedWorktree;
+pub use worktree::create_detached_worktree;
+pub use worktree::pin_worktree_head;
+pub use worktree::remove_worktree;
 
 type CommitID = String;
 
@@ -33,6 +46,11 @@ pub struct GhostCommit {
     parent: Option<CommitID>,
     preexisting_untracked_files: Vec<PathBuf>,
     preexisting_untracked_dirs: Vec<PathBuf>,
+    /// Git directory of the shadow repository holding this snapshot, for
+    /// snapshots taken outside a git repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    shadow_git_dir: Option<PathBuf>,
 }
 
 impl GhostCommit {
@@ -48,9 +66,16 @@ impl GhostCommit {
             parent,
             preexisting_untracked_files,
             preexisting_untracked_dirs,
+            shadow_git_dir: None,
         }
     }
 
+    /// Marks the snapshot as stored in the shadow repository at `git_dir`.
+    pub fn with_shadow_git_dir(mut self, git_dir: PathBuf) -> Self {
+        self.shadow_git_dir = Some(git_dir);
+        self
+    }
+
     /// Commit ID for the snapshot.
     pub fn id(&self) -> &str {
         &self.id
@@ -70,6 +95,11 @@ impl GhostCommit {
     pub fn preexisting_untracked_dirs(&self) -> &[PathBuf] {
         &self.preexisting_untracked_dirs
     }
+
+    /// Git directory of the shadow repository, if the snapshot was taken outside a git repository.
+    pub fn shadow_git_dir(&self) -> Option<&Path> {
+        self.shadow_git_dir.as_deref()
+    }
 }
 
 impl fmt::Display for GhostCommit {
@@ -0,0 +1,205 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::repo_subdir;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout;
+
+/// A worktree created by [`create_detached_worktree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetachedWorktree {
+    /// Root of the new worktree.
+    pub root: PathBuf,
+    /// Directory inside the worktree that corresponds to the path the
+    /// worktree was created from.
+    pub cwd: PathBuf,
+    /// Commit the worktree was checked out at.
+    pub head: String,
+}
+
+/// Adds a worktree at `worktree_path` with the current `HEAD` of the
+/// repository containing `repo_path` checked out in detached mode, so no
+/// branch is created or locked. Uncommitted changes are not carried over.
+pub fn create_detached_worktree(
+    repo_path: &Path,
+    worktree_path: &Path,
+) -> Result<DetachedWorktree, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    run_git_for_status(
+        &repo_root,
+        [
+            OsString::from("worktree"),
+            OsString::from("add"),
+            OsString::from("--detach"),
+            worktree_path.as_os_str().to_owned(),
+            OsString::from("HEAD"),
+        ],
+        None,
+    )?;
+    let head = run_git_for_stdout(
+        worktree_path,
+        [OsString::from("rev-parse"), OsString::from("HEAD")],
+        None,
+    )?;
+    let cwd = match repo_subdir(&repo_root, repo_path) {
+        Some(subdir) => worktree_path.join(subdir),
+        None => worktree_path.to_path_buf(),
+    };
+    Ok(DetachedWorktree {
+        root: worktree_path.to_path_buf(),
+        cwd,
+        head,
+    })
+}
+
+/// Points `reference` at the commit checked out in the worktree when it moved
+/// away from `base`, so commits made on the detached `HEAD` stay reachable
+/// once the worktree is removed. Returns whether the reference was written.
+pub fn pin_worktree_head(
+    worktree_path: &Path,
+    base: &str,
+    reference: &str,
+) -> Result<bool, GitToolingError> {
+    ensure_git_repository(worktree_path)?;
+    let head = run_git_for_stdout(
+        worktree_path,
+        [OsString::from("rev-parse"), OsString::from("HEAD")],
+        None,
+    )?;
+    if head == base {
+        return Ok(false);
+    }
+    run_git_for_status(
+        worktree_path,
+        [
+            OsString::from("update-ref"),
+            OsString::from(reference),
+            OsString::from(head),
+        ],
+        None,
+    )?;
+    Ok(true)
+}
+
+/// Removes a worktree created by [`create_detached_worktree`]. Unless `force`
+/// is set, git refuses to remove a worktree that has uncommitted changes,
+/// which is reported as an error rather than discarding them.
+pub fn remove_worktree(worktree_path: &Path, force: bool) -> Result<(), GitToolingError> {
+    ensure_git_repository(worktree_path)?;
+    let mut args = vec![OsString::from("worktree"), OsString::from("remove")];
+    if force {
+        args.push(OsString::from("--force"));
+    }
+    args.push(worktree_path.as_os_str().to_owned());
+    run_git_for_status(worktree_path, args, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn commit_all(repo_path: &Path, message: &str) {
+        run_git_in(repo_path, &["add", "."]);
+        run_git_in(
+            repo_path,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                message,
+            ],
+        );
+    }
+
+    #[test]
+    fn worktree_mirrors_the_subdirectory_it_was_created_from() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join("crates/app"))?;
+        run_git_in(&repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("crates/app/lib.rs"), "fn main() {}\n")?;
+        commit_all(&repo, "init");
+        // Uncommitted edits stay in the original checkout.
+        std::fs::write(repo.join("crates/app/lib.rs"), "dirty\n")?;
+
+        let path = temp.path().join("worktrees").join("job-1");
+        let worktree = create_detached_worktree(&repo.join("crates/app"), &path)?;
+        let head = run_git_for_stdout(
+            &repo,
+            [OsString::from("rev-parse"), OsString::from("HEAD")],
+            None,
+        )?;
+        assert_eq!(
+            worktree,
+            DetachedWorktree {
+                root: path.clone(),
+                cwd: path.join("crates/app"),
+                head,
+            }
+        );
+        assert_eq!(
+            std::fs::read_to_string(worktree.cwd.join("lib.rs"))?,
+            "fn main() {}\n"
+        );
+
+        std::fs::write(worktree.cwd.join("lib.rs"), "changed\n")?;
+        assert!(remove_worktree(&path, false).is_err());
+        remove_worktree(&path, true)?;
+        assert!(!path.exists());
+        Ok(())
+    }
+    #[test]
+    fn pinned_head_keeps_worktree_commits_reachable() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(&repo)?;
+        run_git_in(&repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("README.md"), "hello\n")?;
+        commit_all(&repo, "init");
+
+        let path = temp.path().join("worktrees").join("job-1");
+        let worktree = create_detached_worktree(&repo, &path)?;
+        let reference = "refs/codex/queue/job-1";
+        assert!(!pin_worktree_head(&path, &worktree.head, reference)?);
+
+        std::fs::write(path.join("README.md"), "hello again\n")?;
+        commit_all(&path, "work");
+        let head = run_git_for_stdout(
+            &path,
+            [OsString::from("rev-parse"), OsString::from("HEAD")],
+            None,
+        )?;
+        assert!(pin_worktree_head(&path, &worktree.head, reference)?);
+
+        remove_worktree(&path, false)?;
+        let pinned = run_git_for_stdout(
+            &repo,
+            [OsString::from("rev-parse"), OsString::from(reference)],
+            None,
+        )?;
+        assert_eq!(pinned, head);
+        Ok(())
+    }
+}
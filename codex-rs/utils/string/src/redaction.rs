@@ -0,0 +1,23 @@
+//! Regular expressions for secrets that are replaced with `[REDACTED]` before
+//! text is written to disk. Shared by the `[redaction]` config rules and the
+//! responses API proxy's audit log so both scrub the same secrets.
+
+/// Bearer credentials, JWTs and GitHub/Slack tokens.
+pub const TOKEN_PATTERNS: &[&str] = &[
+    r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]{16,}=*",
+    r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+    r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+    r"\bxox[abprs]-[A-Za-z0-9-]{10,}\b",
+];
+
+/// `sk-`/`pk-`/`rk-` API keys, AWS access key ids, Google API keys and PEM
+/// private keys.
+pub const KEY_PATTERNS: &[&str] = &[
+    r"\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}",
+    r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+    r"\bAIza[0-9A-Za-z_-]{35}\b",
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+];
+
+/// Email addresses.
+pub const EMAIL_PATTERNS: &[&str] = &[r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b"];
@@ -25,12 +25,45 @@ export type Usage = {
   cached_input_tokens: number;
   /** The number of output tokens used during the turn. */
   output_tokens: number;
+  /** Estimated cost in USD, when the model has a `model_prices` entry. */
+  cost_usd?: number;
+};
+
+export type TurnPhase =
+  | "model_time_to_first_token"
+  | "model_streaming"
+  | "exec"
+  | "approval_wait"
+  | "hook"
+  | "mcp_tool_call";
+
+/** Time spent in one phase of a turn. */
+export type TurnPhaseTiming = {
+  phase: TurnPhase;
+  /** How many times the phase ran during the turn. */
+  count: number;
+  total_ms: number;
+  /** Longest single occurrence. */
+  max_ms: number;
+};
+
+/** Timing breakdown of a turn. */
+export type TurnProfile = {
+  /** Wall-clock duration of the turn in milliseconds. */
+  duration_ms: number;
+  /**
+   * Time spent in each phase. Tool calls can overlap, so the totals may add
+   * up to more than `duration_ms`.
+   */
+  phases: TurnPhaseTiming[];
 };
 
 /** Emitted when a turn is completed. Typically right after the assistant's response. */
 export type TurnCompletedEvent = {
   type: "turn.completed";
   usage: Usage;
+  /** Where the turn spent its time. */
+  profile?: TurnProfile;
 };
 
 /** Indicates that a turn failed with an error. */
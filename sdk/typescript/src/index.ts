@@ -10,6 +10,9 @@ export type {
   ThreadError,
   ThreadErrorEvent,
   Usage,
+  TurnProfile,
+  TurnPhase,
+  TurnPhaseTiming,
 } from "./events";
 export type {
   ThreadItem,